@@ -73,6 +73,7 @@ namespace SteamUtility.Backends
                         Percent = hasPercent ? percent : null,
                         ProtectedAchievement = (flags & StatFlags.Protected) != 0,
                         Flags = flags.ToString(),
+                        Progress = def.Progress,
                     };
                 })
                 .ToList();
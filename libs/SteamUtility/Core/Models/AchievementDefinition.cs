@@ -18,5 +18,8 @@ namespace SteamUtility.Core.Models
         public string IconNormal { get; init; } = "";
         public string IconLocked { get; init; } = "";
         public bool Hidden { get; init; }
+
+        // Null unless the bit carries a "statvalue" progress block - see AchievementProgress.
+        public AchievementProgress? Progress { get; init; }
     }
 }
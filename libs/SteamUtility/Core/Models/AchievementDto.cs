@@ -48,5 +48,11 @@ namespace SteamUtility.Core.Models
 
         [JsonPropertyName("flags")]
         public string Flags { get; init; } = "";
+
+        // Straight from the schema (AchievementDefinition.Progress), so both backends fill it the
+        // same way. Omitted from the JSON for the usual achievement with no stat threshold.
+        [JsonPropertyName("progress")]
+        [JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]
+        public AchievementProgress? Progress { get; init; }
     }
 }
@@ -0,0 +1,21 @@
+using System.Text.Json.Serialization;
+
+namespace SteamUtility.Core.Models
+{
+    // A progress-based achievement's stat threshold, from its schema bit's "progress" block: the
+    // achievement unlocks once stat StatId (a stat's string id, as in StatDto.Id) reaches MaxValue.
+    // Only the "statvalue" operation is ever parsed - the only one Steamworks' partner site offers
+    // for achievement progress. Serialized inside AchievementDto, so the Rust side can walk the stat
+    // up before forcing the unlock (achievements::progress) without a schema of its own.
+    public sealed class AchievementProgress
+    {
+        [JsonPropertyName("statId")]
+        public string StatId { get; init; } = "";
+
+        [JsonPropertyName("minValue")]
+        public float MinValue { get; init; }
+
+        [JsonPropertyName("maxValue")]
+        public float MaxValue { get; init; }
+    }
+}
@@ -78,6 +78,7 @@ namespace SteamUtility.Core.SchemaParsing
                                 IconNormal = bit["display"]["icon"].AsString(""),
                                 IconLocked = bit["display"]["icon_gray"].AsString(""),
                                 Hidden = bit["display"]["hidden"].AsBoolean(false),
+                                Progress = ParseProgress(bit["progress"]),
                             }
                         );
                     }
@@ -152,6 +153,35 @@ namespace SteamUtility.Core.SchemaParsing
             return result;
         }
 
+        // <bit> > progress > { min_val, max_val, value > { operation, operand1 } } - only the
+        // "statvalue" operation, where operand1 names the stat. Anything else (or a malformed
+        // block) is null, and the achievement is just treated as a plain one.
+        private static AchievementProgress? ParseProgress(KeyValue progress)
+        {
+            var value = progress["value"];
+            if (
+                !value["operation"]
+                    .AsString("")
+                    .Equals("statvalue", StringComparison.OrdinalIgnoreCase)
+            )
+            {
+                return null;
+            }
+
+            var statId = value["operand1"].AsString("");
+            if (statId.Length == 0 || !progress["max_val"].Valid)
+            {
+                return null;
+            }
+
+            return new AchievementProgress
+            {
+                StatId = statId,
+                MinValue = progress["min_val"].AsFloat(0f),
+                MaxValue = progress["max_val"].AsFloat(0f),
+            };
+        }
+
         // Newer schemas dropped the explicit type_int field in favor of either a "type" string
         // (INT/FLOAT/AVGRATE/ACHIEVEMENT) or, failing that, structural detection (bits child =>
         // achievement, else infer int/float/avgrate from the min/max/default value types and
@@ -213,6 +213,7 @@ namespace SteamUtility.Daemon.Bot
                         Percent = null,
                         ProtectedAchievement = (flags & StatFlags.Protected) != 0,
                         Flags = flags.ToString(),
+                        Progress = def.Progress,
                     };
                 })
                 .ToList();
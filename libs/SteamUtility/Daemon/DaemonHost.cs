@@ -1,6 +1,9 @@
 using System;
 using System.Collections.Generic;
+using System.IO;
+using System.IO.Pipes;
 using System.Linq;
+using System.Text;
 using System.Text.Json;
 using System.Threading.Tasks;
 using SteamKit2;
@@ -33,9 +36,22 @@ namespace SteamUtility.Daemon
         private OwnershipManager _ownershipManager = null!;
         private FreeLicenseManager _freeLicenseManager = null!;
 
+        // Non-null in detached mode (`agent --listen <endpoint>`): the daemon outlives the host that
+        // spawned it, serving one host connection at a time over a named pipe (Windows) or Unix
+        // domain socket (everything else - .NET's NamedPipeServerStream uses one under the hood,
+        // and takes a rooted pipe name as the literal socket path). See
+        // src-tauri/src/steam_agent/detached.rs for the Rust half.
+        private string? _listenEndpoint;
+
         public int Run(string[] args)
         {
-            Log.Info("Daemon", "Starting daemon (agent) mode");
+            _listenEndpoint = ParseListenEndpoint(args);
+            Log.Info(
+                "Daemon",
+                _listenEndpoint == null
+                    ? "Starting daemon (agent) mode"
+                    : $"Starting detached daemon (agent) mode on {_listenEndpoint}"
+            );
 
             _bot = new SteamBot();
             _authFlow = new AuthFlow(_bot);
@@ -82,8 +98,31 @@ namespace SteamUtility.Daemon
 
             _bot.Start();
 
+            if (_listenEndpoint != null)
+            {
+                return RunListening(_listenEndpoint);
+            }
+
+            ReadLines(Console.In);
+            return 0;
+        }
+
+        private static string? ParseListenEndpoint(string[] args)
+        {
+            for (var i = 0; i < args.Length - 1; i++)
+            {
+                if (string.Equals(args[i], "--listen", StringComparison.OrdinalIgnoreCase))
+                {
+                    return args[i + 1];
+                }
+            }
+            return null;
+        }
+
+        private void ReadLines(TextReader reader)
+        {
             string? line;
-            while ((line = Console.In.ReadLine()) != null)
+            while ((line = reader.ReadLine()) != null)
             {
                 if (string.IsNullOrWhiteSpace(line))
                 {
@@ -93,8 +132,53 @@ namespace SteamUtility.Daemon
                 var capturedLine = line;
                 _ = Task.Run(() => HandleLineAsync(capturedLine));
             }
+        }
 
-            return 0;
+        // Accept loop for detached mode: one host at a time (a second concurrent connect is
+        // refused by maxNumberOfServerInstances: 1), and the SteamBot session - logon, idle set,
+        // presence - carries on untouched across a host disconnecting and a new one attaching.
+        // Only `logout`/`shutdown` end the process. CurrentUserOnly restricts the pipe/socket to
+        // the user that started the daemon, since whoever connects can drive the signed-in account.
+        private int RunListening(string endpoint)
+        {
+            while (true)
+            {
+                using var pipe = new NamedPipeServerStream(
+                    endpoint,
+                    PipeDirection.InOut,
+                    1,
+                    PipeTransmissionMode.Byte,
+                    PipeOptions.Asynchronous | PipeOptions.CurrentUserOnly
+                );
+
+                try
+                {
+                    pipe.WaitForConnection();
+                }
+                catch (IOException ex)
+                {
+                    Log.Warn("Daemon", $"Detached host connection failed: {ex.Message}");
+                    continue;
+                }
+
+                Log.Info("Daemon", "Host attached");
+                var encoding = new UTF8Encoding(encoderShouldEmitUTF8Identifier: false);
+                IpcServer.Attach(new StreamWriter(pipe, encoding, leaveOpen: true));
+                try
+                {
+                    using var reader = new StreamReader(pipe, encoding, leaveOpen: true);
+                    ReadLines(reader);
+                }
+                catch (IOException ex)
+                {
+                    Log.Info("Daemon", $"Host connection closed: {ex.Message}");
+                }
+                finally
+                {
+                    IpcServer.Detach();
+                }
+                Log.Info("Daemon", "Host detached, session still running");
+            }
         }
 
         private async Task HandleLineAsync(string line)
@@ -162,6 +246,36 @@ namespace SteamUtility.Daemon
                     case "logout":
                         _bot.Stop();
                         IpcServer.SendResponse(request.Id, true);
+                        // A detached daemon exists only to keep one signed-in session alive - with
+                        // the session gone there's nothing left for a later host to reattach to.
+                        if (_listenEndpoint != null)
+                        {
+                            Environment.Exit(0);
+                        }
+                        break;
+
+                    case "shutdown":
+                        _bot.Stop();
+                        IpcServer.SendResponse(request.Id, true);
+                        Environment.Exit(0);
+                        break;
+
+                    // Reattach handshake - lets a freshly-launched host adopt a detached daemon's
+                    // still-live session (see AgentManager::login_with_token) instead of logging in
+                    // again. Also harmless in stdio mode, where the host already knows all of this.
+                    case "hello":
+                        IpcServer.SendResponse(
+                            request.Id,
+                            true,
+                            new
+                            {
+                                loggedOn = _bot.IsLoggedOn,
+                                steamId = _bot.SteamID != null
+                                    ? ((ulong)_bot.SteamID).ToString()
+                                    : null,
+                                appIds = _idlingManager.CurrentAppIds,
+                            }
+                        );
                         break;
 
                     case "get_web_session":
@@ -1,4 +1,5 @@
 using System;
+using System.IO;
 using SteamUtility.Core.Json;
 
 namespace SteamUtility.Daemon.Ipc
@@ -7,6 +8,29 @@ namespace SteamUtility.Daemon.Ipc
     {
         private static readonly object WriteLock = new();
 
+        // Where envelope lines go. Console.Out in the default stdio mode; in `--listen` mode (see
+        // DaemonHost.RunListening) it's the currently-attached host's pipe/socket stream, or null
+        // while no host is attached - responses/events produced with nobody listening are dropped,
+        // the same as a line written to a closed stdout would be. The Rust host re-reads anything it
+        // needs (logon state, idle set) via `hello` on reattach rather than relying on a backlog.
+        private static TextWriter? s_writer = Console.Out;
+
+        public static void Attach(TextWriter writer)
+        {
+            lock (WriteLock)
+            {
+                s_writer = writer;
+            }
+        }
+
+        public static void Detach()
+        {
+            lock (WriteLock)
+            {
+                s_writer = null;
+            }
+        }
+
         public static void SendResponse(
             string? id,
             bool ok,
@@ -26,8 +50,22 @@ namespace SteamUtility.Daemon.Ipc
         {
             lock (WriteLock)
             {
-                Console.Out.WriteLine(json);
-                Console.Out.Flush();
+                if (s_writer == null)
+                {
+                    return;
+                }
+
+                try
+                {
+                    s_writer.WriteLine(json);
+                    s_writer.Flush();
+                }
+                catch (IOException)
+                {
+                    // The attached host went away mid-write (app closed) - stop writing to it until
+                    // the next host attaches, instead of throwing out of every later SendEvent.
+                    s_writer = null;
+                }
             }
         }
     }
@@ -0,0 +1,357 @@
+//! Aggregate import-timings: the same per-profile lookup as [`import_timings`], run across many
+//! profiles and combined into a single saved [`AchievementOrder`], so one player's odd pacing (a
+//! months-long break, a burst of unlocks from a cheat tool) doesn't become the plan.
+//!
+//! **Profiles** come from an explicit list, or - [`TimingSource::TopCompletionists`] - from the
+//! signed-in account's Steam friends, ranked by how many of the game's achievements each has
+//! unlocked. Steam has no public per-game completionist ranking, so the friend list is the only
+//! candidate pool the Web API can actually enumerate. Any profile that can't be used (private,
+//! no timestamps, not found) is reported back by its `AppError::code`, never fatal on its own.
+//!
+//! **Aggregation** ([`build_order`]) works per achievement across every usable profile:
+//! - *Order* - each achievement's position in a profile's unlock history, normalized to `0..=1` so
+//!   profiles with different unlock counts weigh the same, aggregated and sorted ascending.
+//! - *Delay* - the gap (minutes) between a profile unlocking the achievement and its previous
+//!   unlock, i.e. how long it took to get. Saved as the *preceding* entry's `delay_next_unlock`,
+//!   which is where the unlocker waits for it.
+//! - Both use [`AggregationMethod`], after dropping Tukey-fence outliers (outside 1.5x the
+//!   interquartile range) once there are enough samples for quartiles to mean anything.
+
+use std::collections::HashMap;
+
+use futures::stream::{self, StreamExt};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::error::{AppError, AppResult};
+use crate::steam_web_api::resolve_api_key;
+
+use super::import_timings::{fetch_player_timings, resolve_steam_id64, AchievementTiming};
+use super::order::{self, AchievementOrder, AchievementOrderEntry};
+
+/// Friends whose achievements are fetched when picking top completionists - bounds a large
+/// friend list to a predictable number of Web API calls.
+const MAX_FRIENDS_SCANNED: usize = 100;
+
+/// Concurrent `GetPlayerAchievements` requests.
+const FETCH_CONCURRENCY: usize = 8;
+
+/// Share of samples dropped from each end by [`AggregationMethod::TrimmedMean`].
+const TRIM_FRACTION: f64 = 0.1;
+
+/// Fewer samples than this skip outlier removal - quartiles of two or three values aren't a fence.
+const MIN_SAMPLES_FOR_OUTLIERS: usize = 4;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum TimingSource {
+    /// Steam IDs, profile URLs or vanity names - anything `import_timings` accepts.
+    Profiles { profiles: Vec<String> },
+    /// The `count` friends with the most of the game's achievements unlocked.
+    TopCompletionists { count: usize },
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AggregationMethod {
+    #[default]
+    Median,
+    TrimmedMean,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedProfile {
+    /// The profile as given, or a friend's SteamID64.
+    pub profile: String,
+    /// `AppError::code` - `player_profile_private`, `player_no_timestamps`, ...
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregateTimingsReport {
+    /// The saved order - `None` when no profile was usable, in which case nothing was saved and
+    /// any existing order is untouched.
+    pub order: Option<AchievementOrder>,
+    /// SteamID64s whose timings went into `order`.
+    pub used_profiles: Vec<String>,
+    pub skipped: Vec<SkippedProfile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GetFriendListResponse {
+    friendslist: FriendsList,
+}
+
+#[derive(Debug, Deserialize)]
+struct FriendsList {
+    #[serde(default)]
+    friends: Vec<Friend>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Friend {
+    steamid: String,
+}
+
+/// Drops values outside the Tukey fences. `sorted` must be sorted ascending; stays sorted.
+fn remove_outliers(sorted: &mut Vec<f64>) {
+    if sorted.len() < MIN_SAMPLES_FOR_OUTLIERS {
+        return;
+    }
+    let q1 = quantile(sorted, 0.25);
+    let q3 = quantile(sorted, 0.75);
+    let fence = 1.5 * (q3 - q1);
+    sorted.retain(|v| *v >= q1 - fence && *v <= q3 + fence);
+}
+
+/// Linear-interpolated quantile of an ascending, non-empty slice.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let (lower, upper) = (pos.floor() as usize, pos.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (pos - lower as f64)
+}
+
+fn aggregate(mut values: Vec<f64>, method: AggregationMethod) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    remove_outliers(&mut values);
+    Some(match method {
+        AggregationMethod::Median => quantile(&values, 0.5),
+        AggregationMethod::TrimmedMean => {
+            let trim = (values.len() as f64 * TRIM_FRACTION).floor() as usize;
+            let kept = &values[trim..values.len() - trim];
+            kept.iter().sum::<f64>() / kept.len() as f64
+        }
+    })
+}
+
+/// Combines every profile's ascending timings into one order - see this module's doc comment.
+fn build_order(profiles: &[Vec<AchievementTiming>], method: AggregationMethod) -> AchievementOrder {
+    let mut positions: HashMap<&str, Vec<f64>> = HashMap::new();
+    let mut gaps_before: HashMap<&str, Vec<f64>> = HashMap::new();
+    for timings in profiles {
+        let last = timings.len().saturating_sub(1).max(1) as f64;
+        for (index, timing) in timings.iter().enumerate() {
+            positions
+                .entry(timing.id.as_str())
+                .or_default()
+                .push(index as f64 / last);
+            if index > 0 {
+                let gap_secs = timing.unlock_time - timings[index - 1].unlock_time;
+                gaps_before
+                    .entry(timing.id.as_str())
+                    .or_default()
+                    .push(gap_secs as f64 / 60.0);
+            }
+        }
+    }
+
+    let mut ordered: Vec<(&str, f64)> = positions
+        .into_iter()
+        .filter_map(|(id, values)| Some((id, aggregate(values, method)?)))
+        .collect();
+    ordered.sort_by(|(a_id, a), (b_id, b)| {
+        a.partial_cmp(b)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a_id.cmp(b_id))
+    });
+
+    let achievements = ordered
+        .iter()
+        .enumerate()
+        .map(|(index, (id, _))| AchievementOrderEntry {
+            id: id.to_string(),
+            skip: false,
+            delay_next_unlock: ordered
+                .get(index + 1)
+                .and_then(|(next, _)| gaps_before.remove(next))
+                .and_then(|gaps| aggregate(gaps, method))
+                .map(|minutes| (minutes * 10.0).round() / 10.0),
+        })
+        .collect();
+    AchievementOrder {
+        achievements,
+        delay_before_first_unlock: None,
+    }
+}
+
+async fn fetch_friend_ids(client: &Client, key: &str, steam_id: &str) -> AppResult<Vec<String>> {
+    let url = format!(
+        "https://api.steampowered.com/ISteamUser/GetFriendList/v0001/?key={key}&steamid={steam_id}&relationship=friend"
+    );
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| AppError::SteamApiRequest(e.to_string()))?;
+    // A private friend list is a 401, not an empty list.
+    if !response.status().is_success() {
+        return Err(AppError::FriendListUnavailable(format!(
+            "HTTP {}",
+            response.status()
+        )));
+    }
+    let body: GetFriendListResponse = response
+        .json()
+        .await
+        .map_err(|e| AppError::SteamApiResponse(e.to_string()))?;
+    Ok(body
+        .friendslist
+        .friends
+        .into_iter()
+        .map(|f| f.steamid)
+        .collect())
+}
+
+/// Fetches timings for every `(label, steam_id)` candidate concurrently, splitting successes
+/// (keyed by SteamID64) from failures.
+async fn fetch_all(
+    client: &Client,
+    key: &str,
+    app_id: u32,
+    candidates: Vec<(String, AppResult<String>)>,
+) -> (Vec<(String, Vec<AchievementTiming>)>, Vec<SkippedProfile>) {
+    let results: Vec<_> = stream::iter(candidates)
+        .map(|(label, resolved)| async move {
+            let result = match resolved {
+                Ok(steam_id) => fetch_player_timings(client, key, app_id, &steam_id)
+                    .await
+                    .map(|timings| (steam_id, timings)),
+                Err(e) => Err(e),
+            };
+            (label, result)
+        })
+        .buffer_unordered(FETCH_CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut usable = Vec::new();
+    let mut skipped = Vec::new();
+    for (label, result) in results {
+        match result {
+            Ok(profile) => usable.push(profile),
+            Err(e) => skipped.push(SkippedProfile {
+                profile: label,
+                reason: e.code(),
+            }),
+        }
+    }
+    (usable, skipped)
+}
+
+/// Runs an aggregate import for `app_id` and saves the result as `steam_id`'s order for it.
+pub async fn aggregate_and_save(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    app_id: u32,
+    source: TimingSource,
+    method: AggregationMethod,
+    api_key: Option<String>,
+) -> AppResult<AggregateTimingsReport> {
+    let key = resolve_api_key(api_key)?;
+    let client = Client::new();
+
+    let (mut usable, mut skipped) = match source {
+        TimingSource::Profiles { profiles } => {
+            let mut candidates = Vec::with_capacity(profiles.len());
+            for profile in profiles {
+                let resolved = resolve_steam_id64(&client, &key, &profile).await;
+                candidates.push((profile, resolved));
+            }
+            fetch_all(&client, &key, app_id, candidates).await
+        }
+        TimingSource::TopCompletionists { count } => {
+            let candidates = fetch_friend_ids(&client, &key, steam_id)
+                .await?
+                .into_iter()
+                .take(MAX_FRIENDS_SCANNED)
+                .map(|id| (id.clone(), Ok(id)))
+                .collect();
+            let (mut usable, skipped) = fetch_all(&client, &key, app_id, candidates).await;
+            usable.sort_by(|(a_id, a), (b_id, b)| b.len().cmp(&a.len()).then(a_id.cmp(b_id)));
+            usable.truncate(count);
+            (usable, skipped)
+        }
+    };
+    // `buffer_unordered` finishes in any order - keep the report stable.
+    usable.sort_by(|(a, _), (b, _)| a.cmp(b));
+    skipped.sort_by(|a, b| a.profile.cmp(&b.profile));
+
+    let timings: Vec<Vec<AchievementTiming>> = usable.iter().map(|(_, t)| t.clone()).collect();
+    let order = if timings.is_empty() {
+        None
+    } else {
+        let built = build_order(&timings, method);
+        Some(order::save(app_handle, steam_id, app_id, built).await?)
+    };
+    tracing::info!(
+        app_id,
+        used = usable.len(),
+        skipped = skipped.len(),
+        "achievement unlocker: aggregated import-timings"
+    );
+    Ok(AggregateTimingsReport {
+        order,
+        used_profiles: usable.into_iter().map(|(id, _)| id).collect(),
+        skipped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timings(unlocks: &[(&str, u64)]) -> Vec<AchievementTiming> {
+        unlocks
+            .iter()
+            .map(|(id, unlock_time)| AchievementTiming {
+                id: id.to_string(),
+                unlock_time: *unlock_time,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn outliers_are_dropped_before_aggregating() {
+        // 600 is far outside 1.5x the IQR of the rest.
+        let values = vec![10.0, 11.0, 12.0, 13.0, 14.0, 600.0];
+        assert_eq!(
+            aggregate(values.clone(), AggregationMethod::Median),
+            Some(12.0)
+        );
+        assert_eq!(
+            aggregate(values, AggregationMethod::TrimmedMean),
+            Some(12.0)
+        );
+        assert_eq!(
+            aggregate(vec![5.0, 500.0], AggregationMethod::Median),
+            Some(252.5)
+        );
+        assert_eq!(aggregate(vec![], AggregationMethod::Median), None);
+    }
+
+    #[test]
+    fn order_follows_typical_position_and_delays_follow_next_achievement() {
+        let profiles = vec![
+            timings(&[("a", 0), ("b", 600), ("c", 1_800)]),
+            timings(&[("a", 0), ("b", 1_200), ("c", 2_400)]),
+            // Odd player who got `c` before `b` - outvoted on order.
+            timings(&[("a", 0), ("c", 60), ("b", 120)]),
+        ];
+        let order = build_order(&profiles, AggregationMethod::Median);
+        let ids: Vec<&str> = order.achievements.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+        // Gaps before `b`: 10, 20, 1 minutes -> median 10. Before `c`: 20, 20, 1 -> median 20.
+        let delays: Vec<Option<f64>> = order
+            .achievements
+            .iter()
+            .map(|e| e.delay_next_unlock)
+            .collect();
+        assert_eq!(delays, vec![Some(10.0), Some(20.0), None]);
+    }
+}
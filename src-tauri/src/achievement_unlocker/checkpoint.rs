@@ -0,0 +1,294 @@
+//! Crash/restart checkpoint for a running unlocker session - one file per account
+//! (`achievement_unlocker_checkpoint.json`), rewritten every [`CHECKPOINT_INTERVAL`] by
+//! `manager::run_loop` from the session's own `AchievementUnlockerState`: each active game's full
+//! projected unlock schedule (`ActiveGameProgress::schedule` - absolute timestamps, the same values
+//! `upcoming` shows the first few of) plus the `completed` list.
+//!
+//! **Resuming** (`AchievementUnlockerManager::resume`) starts an ordinary session seeded from it:
+//! the queue is still re-scanned (achievements unlocked meanwhile drop out as usual), but a game
+//! that was active skips the worker's `INITIAL_DELAY` and its own `delay_before_first_unlock`,
+//! waits only until its next saved deadline (not at all if that already passed), and keeps the
+//! saved gaps between its remaining unlocks ([`resumed_delays`]) - so a restart neither re-runs the
+//! grace period nor re-rolls the pacing. The schedule window is still honored exactly as before:
+//! a deadline that falls outside it waits for the window like any other unlock.
+//!
+//! **Lifetime** - written while a session runs and once more when it ends without the queue
+//! emptying (a forced stop, e.g. a concurrent-login kick); deleted when the queue genuinely empties
+//! or the user stops the run themselves (`commands::stop_achievement_unlocker`'s
+//! `discard_checkpoint`, the Idling page's "Stop All"). An app exit, a crash, a sign-out or Steam
+//! closing never reaches either, which is exactly the case it exists for. Whether a found checkpoint
+//! is resumed automatically or offered first is `settings::AchievementUnlockerSettings::
+//! auto_resume`, applied by the frontend's `useAchievementUnlockerResume` the first time each
+//! account becomes active (for a CLI-mode account, once Steam is running) - except a detached-agent
+//! reattach (`steam_agent::detached::restore_after_reattach`), which always resumes, since it
+//! already restarts whatever was running. `sgi unlocker resume` is the headless equivalent.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tokio::sync::Mutex;
+
+use crate::async_utils::wait_ticking;
+use crate::error::{AppError, AppResult};
+use crate::fs_utils::atomic_write_json;
+use crate::platform;
+
+use super::manager::ScannedGame;
+use super::{AchievementUnlockerState, CompletedUnlock};
+
+const CHECKPOINT_FILE_NAME: &str = "achievement_unlocker_checkpoint.json";
+
+/// How often a running session rewrites its checkpoint - at most this much progress (never more
+/// than one unlock at the default `interval`) is lost to a crash.
+pub(super) const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(30);
+
+static WRITE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+/// One achievement's projected unlock time - see `ActiveGameProgress::schedule`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledUnlock {
+    pub id: String,
+    pub unlock_at_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameCheckpoint {
+    pub app_id: u32,
+    pub name: String,
+    /// Every remaining achievement's projected unlock time, in unlock order.
+    pub schedule: Vec<ScheduledUnlock>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionCheckpoint {
+    pub saved_at_ms: i64,
+    /// The session's live worker count when saved - what the frontend offers to resume with.
+    pub max_concurrent_games: u32,
+    pub games: Vec<GameCheckpoint>,
+    pub completed: Vec<CompletedUnlock>,
+}
+
+fn checkpoint_file_path(app_handle: &AppHandle, steam_id: &str) -> AppResult<PathBuf> {
+    Ok(platform::cache_dir(app_handle)?
+        .join(steam_id)
+        .join(CHECKPOINT_FILE_NAME))
+}
+
+/// `None` when there's nothing to resume - including an unparseable file, which is logged and
+/// treated as absent rather than failing the resume prompt over a stale checkpoint.
+pub async fn load(app_handle: &AppHandle, steam_id: &str) -> AppResult<Option<SessionCheckpoint>> {
+    let _guard = WRITE_LOCK.lock().await;
+    let path = checkpoint_file_path(app_handle, steam_id)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(&path)
+        .map_err(|e| AppError::AchievementUnlockerCheckpointIo(e.to_string()))?;
+    match serde_json::from_str(&contents) {
+        Ok(checkpoint) => Ok(Some(checkpoint)),
+        Err(_) => {
+            tracing::warn!(
+                steam_id,
+                "achievement unlocker: checkpoint failed to parse, ignoring it"
+            );
+            Ok(None)
+        }
+    }
+}
+
+async fn save(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    checkpoint: &SessionCheckpoint,
+) -> AppResult<()> {
+    let _guard = WRITE_LOCK.lock().await;
+    let path = checkpoint_file_path(app_handle, steam_id)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| AppError::AchievementUnlockerCheckpointIo(e.to_string()))?;
+    }
+    atomic_write_json(&path, checkpoint)
+        .map_err(|e| AppError::AchievementUnlockerCheckpointIo(e.to_string()))
+}
+
+/// Idempotent - a missing file is already cleared.
+pub async fn clear(app_handle: &AppHandle, steam_id: &str) -> AppResult<()> {
+    let _guard = WRITE_LOCK.lock().await;
+    let path = checkpoint_file_path(app_handle, steam_id)?;
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(AppError::AchievementUnlockerCheckpointIo(e.to_string())),
+    }
+}
+
+/// A game still in its worker's `INITIAL_DELAY` has no schedule yet and isn't recorded - resuming
+/// it is the same as starting it fresh.
+fn snapshot(
+    state: &AchievementUnlockerState,
+    max_concurrent_games: u32,
+    now_ms: i64,
+) -> SessionCheckpoint {
+    SessionCheckpoint {
+        saved_at_ms: now_ms,
+        max_concurrent_games,
+        games: state
+            .active
+            .iter()
+            .filter(|g| !g.schedule.is_empty())
+            .map(|g| GameCheckpoint {
+                app_id: g.app_id,
+                name: g.name.clone(),
+                schedule: g.schedule.clone(),
+            })
+            .collect(),
+        completed: state.completed.clone(),
+    }
+}
+
+/// Snapshots `state` to disk once - the periodic tick and `run_loop`'s final write both go through
+/// here. Never fails the session: a failed write is logged and the next one tries again.
+pub(super) async fn write(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    state: &Mutex<AchievementUnlockerState>,
+    max_concurrent_games: u32,
+) {
+    let checkpoint = {
+        let s = state.lock().await;
+        snapshot(
+            &s,
+            max_concurrent_games,
+            chrono::Utc::now().timestamp_millis(),
+        )
+    };
+    if let Err(e) = save(app_handle, steam_id, &checkpoint).await {
+        tracing::warn!(steam_id, error = %e.code(), "achievement unlocker: failed to write checkpoint");
+    }
+}
+
+/// `run_loop`'s background checkpoint writer - aborted by `run_loop` itself once the session ends.
+pub(super) async fn run_periodic(
+    app_handle: AppHandle,
+    steam_id: String,
+    state: Arc<Mutex<AchievementUnlockerState>>,
+    max_concurrent_games: Arc<AtomicU32>,
+    stopped: Arc<AtomicBool>,
+) {
+    while !wait_ticking(CHECKPOINT_INTERVAL, &stopped).await {
+        write(
+            &app_handle,
+            &steam_id,
+            &state,
+            max_concurrent_games.load(Ordering::SeqCst),
+        )
+        .await;
+    }
+}
+
+fn unlock_times(resume: &GameCheckpoint) -> HashMap<&str, i64> {
+    resume
+        .schedule
+        .iter()
+        .map(|s| (s.id.as_str(), s.unlock_at_ms))
+        .collect()
+}
+
+/// How long a resumed game waits before its first unlock - until its saved deadline, or not at all
+/// if that already passed (or its first achievement wasn't scheduled, e.g. newly un-skipped).
+pub(super) fn first_wait_ms(game: &ScannedGame, resume: &GameCheckpoint, now_ms: i64) -> u64 {
+    game.achievements
+        .first()
+        .and_then(|a| unlock_times(resume).get(a.id.as_str()).copied())
+        .map(|at| (at - now_ms).max(0) as u64)
+        .unwrap_or(0)
+}
+
+/// `drawn` (a fresh `manager::resolve_delays`) with every gap between two adjacent achievements that
+/// both have a saved time replaced by the difference of those times - so each keeps its original
+/// offset from the one before it even when something in between was unlocked by hand meanwhile.
+/// Anything without a saved time (newly un-skipped, say) keeps its fresh draw.
+pub(super) fn resumed_delays(
+    game: &ScannedGame,
+    resume: &GameCheckpoint,
+    mut drawn: Vec<u64>,
+) -> Vec<u64> {
+    let times = unlock_times(resume);
+    for (index, pair) in game.achievements.windows(2).enumerate() {
+        if let (Some(a), Some(b)) = (
+            times.get(pair[0].id.as_str()),
+            times.get(pair[1].id.as_str()),
+        ) {
+            drawn[index] = (b - a).max(0) as u64;
+        }
+    }
+    drawn
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::manager::ScannedAchievement;
+    use super::*;
+
+    fn game(ids: &[&str]) -> ScannedGame {
+        ScannedGame {
+            app_id: 1,
+            name: "game".to_string(),
+            achievements: ids
+                .iter()
+                .map(|id| ScannedAchievement {
+                    id: id.to_string(),
+                    name: id.to_string(),
+                    icon_locked: String::new(),
+                    percent: None,
+                    delay_next_unlock_ms: None,
+                })
+                .collect(),
+            delay_before_first_unlock_ms: 0,
+            achievement_count: ids.len() as u32,
+            resume: None,
+            stat_progress: HashMap::new(),
+        }
+    }
+
+    fn checkpoint(schedule: &[(&str, i64)]) -> GameCheckpoint {
+        GameCheckpoint {
+            app_id: 1,
+            name: "game".to_string(),
+            schedule: schedule
+                .iter()
+                .map(|(id, at)| ScheduledUnlock {
+                    id: id.to_string(),
+                    unlock_at_ms: *at,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn first_wait_runs_until_the_saved_deadline_or_not_at_all() {
+        let resume = checkpoint(&[("a", 10_000), ("b", 50_000)]);
+        assert_eq!(first_wait_ms(&game(&["a", "b"]), &resume, 4_000), 6_000);
+        assert_eq!(first_wait_ms(&game(&["a", "b"]), &resume, 90_000), 0);
+        assert_eq!(first_wait_ms(&game(&["new", "a"]), &resume, 4_000), 0);
+    }
+
+    #[test]
+    fn saved_gaps_replace_fresh_draws_only_between_adjacent_scheduled_pairs() {
+        let resume = checkpoint(&[("a", 0), ("b", 60_000), ("c", 200_000)]);
+        // "b" was unlocked by hand meanwhile - "c" still lands at its saved offset from "a".
+        let delays = resumed_delays(&game(&["a", "c", "d"]), &resume, vec![1, 2, 3]);
+        assert_eq!(delays, vec![200_000, 2, 3]);
+        let delays = resumed_delays(&game(&["b", "c", "d"]), &resume, vec![1, 2, 3]);
+        assert_eq!(delays, vec![140_000, 2, 3]);
+    }
+}
@@ -1,17 +1,25 @@
+use std::collections::HashMap;
+use std::path::Path;
+
 use tauri::{AppHandle, State};
 
-use crate::error::AppResult;
+use crate::achievements::{self, AchievementDto};
+use crate::error::{AppError, AppResult};
 use crate::games::commands::{resolve_steam_id, GamesAccount};
 use crate::local_steam::commands::require_steam_running;
 use crate::steam_agent::AgentManager;
 
 use super::{
-    cache, import_timings, order, settings, AchievementUnlockerEntry, AchievementUnlockerManager,
-    AchievementUnlockerState,
+    aggregate_timings, cache, checkpoint, import_timings, natural, order, plan_file, settings,
+    simulate, AchievementUnlockerEntry, AchievementUnlockerManager, AchievementUnlockerState,
 };
+use aggregate_timings::{AggregateTimingsReport, AggregationMethod, TimingSource};
+use checkpoint::SessionCheckpoint;
 use import_timings::AchievementTiming;
 use order::AchievementOrder;
+use plan_file::{AchievementOrderPlan, PlanImportReport};
 use settings::AchievementUnlockerSettings;
+use simulate::UnlockSimulation;
 
 #[tauri::command]
 pub async fn get_achievement_unlocker_queue(
@@ -141,6 +149,87 @@ pub async fn save_achievement_order(
     super::order::save(&app_handle, &steam_id, app_id, order).await
 }
 
+/// Writes `app_id`'s saved order to `path` (from the frontend's save dialog) as a shareable plan -
+/// see `plan_file`. Fails with `AchievementOrderNotSaved` rather than exporting an empty plan.
+#[tauri::command]
+pub async fn export_achievement_order(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    account: GamesAccount,
+    app_id: u32,
+    path: String,
+) -> AppResult<()> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    let order = order::get(&app_handle, &steam_id, app_id)
+        .await?
+        .ok_or(AppError::AchievementOrderNotSaved(app_id))?;
+    let plan = AchievementOrderPlan::new(app_id, order, chrono::Utc::now().timestamp_millis());
+    plan_file::write(&plan, Path::new(&path))
+}
+
+/// Reads a plan from `path` and validates its ids against `app_id`'s live schema. Saved as this
+/// account's order only if nothing mismatched, or if `force` is set (unknown/duplicate ids are then
+/// left out) - otherwise the report comes back with `saved: false` for the user to review. A plan
+/// exported for a different game is rejected outright.
+#[tauri::command]
+pub async fn import_achievement_order(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    account: GamesAccount,
+    app_id: u32,
+    path: String,
+    force: Option<bool>,
+) -> AppResult<PlanImportReport> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    let plan = plan_file::read(Path::new(&path))?;
+    if plan.app_id != app_id {
+        return Err(AppError::AchievementOrderPlanInvalid(format!(
+            "plan is for app {}, not {app_id}",
+            plan.app_id
+        )));
+    }
+    let data =
+        achievements::commands::fetch_achievement_data(&agent_manager, account, app_id, "english")
+            .await?;
+    let mut report = plan_file::validate(&plan, &data.achievements);
+    if force.unwrap_or(false) || !report.has_mismatches() {
+        report.order = order::save(&app_handle, &steam_id, app_id, report.order).await?;
+        report.saved = true;
+    }
+    Ok(report)
+}
+
+/// Generates a fresh natural unlock plan for `app_id` (see `natural`'s module doc comment) and saves
+/// it over any existing order, returning it for review in the order editor. Works regardless of
+/// `settings.ordering` - that setting only decides whether the unlocker generates one on its own.
+#[tauri::command]
+pub async fn generate_natural_achievement_order(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    account: GamesAccount,
+    app_id: u32,
+) -> AppResult<AchievementOrder> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    let unlocker_settings = settings::get(&app_handle, &steam_id).await?;
+    // Same filter `manager::scan_game` applies, so the plan covers exactly what would be unlocked.
+    let data =
+        achievements::commands::fetch_achievement_data(&agent_manager, account, app_id, "english")
+            .await?;
+    let eligible: Vec<&AchievementDto> = data
+        .achievements
+        .iter()
+        .filter(|a| !a.achieved && (!unlocker_settings.hidden || !a.hidden))
+        .collect();
+    Ok(natural::generate_and_save(
+        &app_handle,
+        &steam_id,
+        app_id,
+        &eligible,
+        &unlocker_settings,
+    )
+    .await)
+}
+
 /// Fetches a target Steam profile's real achievement unlock timestamps for `app_id`, used by the
 /// (gamer-tier gated, frontend-only) import-timings feature to derive realistic per-achievement
 /// delays. Not account-scoped - `steam_input` names an arbitrary profile, unrelated to the signed-in
@@ -156,6 +245,32 @@ pub async fn import_achievement_timings(
     import_timings::get_player_achievement_timings(app_id, steam_input, api_key).await
 }
 
+/// Aggregates many profiles' unlock timings for `app_id` into one order and saves it for
+/// `account` - see `aggregate_timings`'s module doc comment. Unlike [`import_achievement_timings`]
+/// this is account-scoped: the result is saved straight to the account's order, and
+/// `TopCompletionists` draws on the account's own friend list.
+#[tauri::command]
+pub async fn aggregate_achievement_timings(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    account: GamesAccount,
+    app_id: u32,
+    source: TimingSource,
+    method: Option<AggregationMethod>,
+) -> AppResult<AggregateTimingsReport> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    let api_key = crate::credential_store::load_web_api_key()?;
+    aggregate_timings::aggregate_and_save(
+        &app_handle,
+        &steam_id,
+        app_id,
+        source,
+        method.unwrap_or_default(),
+        api_key,
+    )
+    .await
+}
+
 /// Starts an unlock automation session for `account`: repeats scan-then-unlock passes over the
 /// queue until it's empty, per `manager.rs`'s doc comment. Idempotent - calling this while a
 /// session is already running for the account just returns its current state. `max_concurrent_games`
@@ -180,6 +295,30 @@ pub async fn start_achievement_unlocker(
         .await
 }
 
+/// Dry-run of [`start_achievement_unlocker`] - projects the whole run (every unlock's timestamp,
+/// every game's finish) without idling, unlocking or calling Steam. `achievement_data` is whatever
+/// achievement lists the frontend already has, keyed by app id - see `simulate`'s module doc
+/// comment for why it isn't fetched here.
+#[tauri::command]
+pub async fn simulate_achievement_unlocker(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    unlocker_manager: State<'_, AchievementUnlockerManager>,
+    account: GamesAccount,
+    max_concurrent_games: u32,
+    achievement_data: HashMap<u32, Vec<AchievementDto>>,
+) -> AppResult<UnlockSimulation> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    unlocker_manager
+        .simulate(
+            &app_handle,
+            &steam_id,
+            achievement_data,
+            max_concurrent_games,
+        )
+        .await
+}
+
 /// Live-corrects an already-running session's worker count - called by the frontend's
 /// `useAchievementUnlockerConcurrencyGuard` whenever the subscription tier changes, since
 /// `start_achievement_unlocker`'s `max_concurrent_games` is otherwise only ever resolved once at
@@ -199,16 +338,71 @@ pub async fn update_achievement_unlocker_concurrency(
     Ok(())
 }
 
+/// [`start_achievement_unlocker`], picking up an interrupted run from its saved checkpoint - see
+/// `checkpoint`'s module doc comment. Starts fresh if there's no checkpoint to resume.
+#[tauri::command]
+pub async fn resume_achievement_unlocker(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    unlocker_manager: State<'_, AchievementUnlockerManager>,
+    account: GamesAccount,
+    max_concurrent_games: u32,
+) -> AppResult<AchievementUnlockerState> {
+    if matches!(account, GamesAccount::Local { .. }) {
+        require_steam_running()?;
+    }
+
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    unlocker_manager
+        .resume(&app_handle, steam_id, account, max_concurrent_games)
+        .await
+}
+
+/// The interrupted run waiting to be resumed, if any - `None` while a session is running, so the
+/// frontend never offers to resume a run that's still going.
+#[tauri::command]
+pub async fn get_achievement_unlocker_checkpoint(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    unlocker_manager: State<'_, AchievementUnlockerManager>,
+    account: GamesAccount,
+) -> AppResult<Option<SessionCheckpoint>> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    if unlocker_manager.state(&steam_id).await.is_running {
+        return Ok(None);
+    }
+    checkpoint::load(&app_handle, &steam_id).await
+}
+
+/// Declines the resume offer - the next start is a fresh run.
+#[tauri::command]
+pub async fn discard_achievement_unlocker_checkpoint(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    account: GamesAccount,
+) -> AppResult<()> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    checkpoint::clear(&app_handle, &steam_id).await
+}
+
 /// Stops `account`'s unlock automation session if one is running - idempotent (a no-op if nothing
-/// is tracked).
+/// is tracked). `discard_checkpoint` marks a deliberate stop (the run page's own stop button), which
+/// isn't an interruption worth offering to resume; the sign-out/Steam-closed stops leave it unset
+/// and keep the checkpoint.
 #[tauri::command]
 pub async fn stop_achievement_unlocker(
+    app_handle: AppHandle,
     agent_manager: State<'_, AgentManager>,
     unlocker_manager: State<'_, AchievementUnlockerManager>,
     account: GamesAccount,
+    discard_checkpoint: Option<bool>,
 ) -> AppResult<()> {
     let steam_id = resolve_steam_id(&account, &agent_manager).await?;
-    unlocker_manager.stop(&steam_id).await
+    unlocker_manager.stop(&steam_id).await?;
+    if discard_checkpoint.unwrap_or(false) {
+        checkpoint::clear(&app_handle, &steam_id).await?;
+    }
+    Ok(())
 }
 
 /// The account's current unlock automation state - a default/not-running state if nothing is
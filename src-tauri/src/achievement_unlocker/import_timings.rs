@@ -63,7 +63,7 @@ struct RawAchievement {
 /// Resolves `steam_input` to a SteamID64: a literal 17-digit id, a `/profiles/<id>` URL, a
 /// `/id/<vanity>` URL, or a bare vanity name (resolved via `ResolveVanityURL`) - mirrors `main`'s
 /// exact parsing precedence.
-async fn resolve_steam_id64(
+pub(super) async fn resolve_steam_id64(
     client: &Client,
     api_key: &str,
     steam_input: &str,
@@ -118,7 +118,17 @@ pub async fn get_player_achievement_timings(
     let client = Client::new();
 
     let steam_id = resolve_steam_id64(&client, &key, &steam_input).await?;
+    fetch_player_timings(&client, &key, app_id, &steam_id).await
+}
 
+/// [`get_player_achievement_timings`] for an already-resolved SteamID64 - shared with
+/// `aggregate_timings`, which resolves (or discovers) its profiles itself.
+pub(super) async fn fetch_player_timings(
+    client: &Client,
+    key: &str,
+    app_id: u32,
+    steam_id: &str,
+) -> AppResult<Vec<AchievementTiming>> {
     let url = format!(
         "https://api.steampowered.com/ISteamUserStats/GetPlayerAchievements/v0001/?appid={app_id}&key={key}&steamid={steam_id}"
     );
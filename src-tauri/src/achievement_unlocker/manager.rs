@@ -29,26 +29,26 @@ use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
-use chrono::Timelike;
-use rand::Rng;
 use tauri::{AppHandle, Emitter, Manager};
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
 
-use crate::achievements::{self, AchievementDto};
+use crate::achievements::{self, AchievementDto, StatDto, StatUpdate};
 use crate::async_utils::wait_ticking;
 use crate::auto_idle;
 use crate::card_farming;
 use crate::error::AppResult;
 use crate::games::commands::GamesAccount;
 use crate::idling::{self, IdleTarget, IdlingManager};
+use crate::local_steam::stats_schema;
 use crate::steam_agent::AgentManager;
+use crate::webhooks::{self, WebhookEvent};
 
-use super::settings::ScheduleTime;
+use super::settings::{ScheduleWindow, UnlockOrdering};
 use super::{
-    cache, order, settings, AchievementUnlockerEntry, AchievementUnlockerState, ActiveGameProgress,
-    CompletedUnlock, CompletedUnlockReason, ScanProgress, UpcomingAchievement,
-    ACHIEVEMENT_UNLOCKER_STATE_EVENT,
+    cache, checkpoint, natural, order, schedule, settings, simulate, AchievementUnlockerEntry,
+    AchievementUnlockerState, ActiveGameProgress, CompletedUnlock, CompletedUnlockReason,
+    ScanProgress, UpcomingAchievement, ACHIEVEMENT_UNLOCKER_STATE_EVENT,
 };
 
 /// Mirrors `idling`'s own concurrent-games cap (see `card_farming::manager`'s identical constant
@@ -57,14 +57,14 @@ pub const MAX_CONCURRENT_GAMES: u32 = 32;
 
 /// Grace period before a worker starts unlocking its first game for the session - matches `main`'s
 /// hardcoded 10s.
-const INITIAL_DELAY: Duration = Duration::from_secs(10);
+pub(super) const INITIAL_DELAY: Duration = Duration::from_secs(10);
 
 /// Wait between finishing one game and starting the next *when running single-game (worker_count
 /// == 1) and the next game has no `delayBeforeFirstUnlock` of its own* - matches `main`'s
 /// hardcoded 2 minutes. Skipped entirely in multi-game mode (each worker's games already overlap
 /// in time, so there's no "switching" to pace) and skipped when the next game already has its own
 /// configured delay (redundant otherwise).
-const INTER_GAME_DELAY: Duration = Duration::from_secs(120);
+pub(super) const INTER_GAME_DELAY: Duration = Duration::from_secs(120);
 
 const MAX_UNLOCK_ATTEMPTS: usize = 3;
 const RETRY_BACKOFF: [Duration; 2] = [Duration::from_secs(2), Duration::from_secs(5)];
@@ -73,6 +73,10 @@ const RETRY_BACKOFF: [Duration; 2] = [Duration::from_secs(2), Duration::from_sec
 /// matches `main`'s `buildUpcomingQueue` default `limit`.
 const UPCOMING_LIMIT: usize = 5;
 
+/// How many `update_stats` writes walk a progress stat up to one achievement's threshold - see
+/// [`wait_advancing_stats`].
+const STAT_PROGRESS_STEPS: usize = 4;
+
 struct UnlockerSession {
     handle: JoinHandle<()>,
     stopped: Arc<AtomicBool>,
@@ -110,6 +114,33 @@ impl AchievementUnlockerManager {
         steam_id: String,
         account: GamesAccount,
         max_concurrent_games: u32,
+    ) -> AppResult<AchievementUnlockerState> {
+        self.start_session(app_handle, steam_id, account, max_concurrent_games, None)
+            .await
+    }
+
+    /// [`Self::start`], seeded from `steam_id`'s saved checkpoint if there is one (a plain start
+    /// otherwise) - see `checkpoint`'s module doc comment. Same idempotency: if a session is
+    /// already running, the checkpoint is left alone and its state returned.
+    pub async fn resume(
+        &self,
+        app_handle: &AppHandle,
+        steam_id: String,
+        account: GamesAccount,
+        max_concurrent_games: u32,
+    ) -> AppResult<AchievementUnlockerState> {
+        let resume = checkpoint::load(app_handle, &steam_id).await?;
+        self.start_session(app_handle, steam_id, account, max_concurrent_games, resume)
+            .await
+    }
+
+    async fn start_session(
+        &self,
+        app_handle: &AppHandle,
+        steam_id: String,
+        account: GamesAccount,
+        max_concurrent_games: u32,
+        resume: Option<checkpoint::SessionCheckpoint>,
     ) -> AppResult<AchievementUnlockerState> {
         {
             let sessions = self.sessions.lock().await;
@@ -120,6 +151,10 @@ impl AchievementUnlockerManager {
 
         let queue = cache::read(app_handle, &steam_id).await?;
         if queue.is_empty() {
+            // Nothing left to resume either - emptied by hand since the checkpoint was written.
+            if resume.is_some() {
+                checkpoint::clear(app_handle, &steam_id).await?;
+            }
             return Ok(AchievementUnlockerState::default());
         }
 
@@ -128,10 +163,15 @@ impl AchievementUnlockerManager {
             steam_id,
             queue_len = queue.len(),
             worker_count,
+            resumed_games = resume.as_ref().map(|r| r.games.len()),
             "achievement unlocker: session started"
         );
         let state = Arc::new(Mutex::new(AchievementUnlockerState {
             is_running: true,
+            completed: resume
+                .as_ref()
+                .map(|r| r.completed.clone())
+                .unwrap_or_default(),
             ..Default::default()
         }));
         let stopped = Arc::new(AtomicBool::new(false));
@@ -148,6 +188,7 @@ impl AchievementUnlockerManager {
             stopped.clone(),
             idling_apps.clone(),
             excluded_app_ids.clone(),
+            resume,
         ));
 
         let snapshot = state.lock().await.clone();
@@ -178,6 +219,17 @@ impl AchievementUnlockerManager {
         }
     }
 
+    /// `steam_id`'s running session's current worker count, or `None` if no session is running -
+    /// read by `steam_agent::detached::detach_on_exit` so a reattached session restarts with the
+    /// same value, including any live [`Self::set_max_concurrent_games`] change.
+    pub async fn max_concurrent_games(&self, steam_id: &str) -> Option<u32> {
+        self.sessions
+            .lock()
+            .await
+            .get(steam_id)
+            .map(|session| session.max_concurrent_games.load(Ordering::SeqCst))
+    }
+
     /// Stops `steam_id`'s session if one is running - idempotent. Sets a flag and awaits the task's
     /// own cleanup rather than aborting it outright, same reasoning as `CardFarmingManager::stop`:
     /// aborting mid-`set_idle_games` call could leave a game idling with nothing left to stop it.
@@ -198,6 +250,25 @@ impl AchievementUnlockerManager {
         }
     }
 
+    /// Projects a run of `steam_id`'s current queue without starting one - see `simulate`'s module
+    /// doc comment. Independent of any running session: `max_concurrent_games` is clamped exactly
+    /// like [`Self::start`]'s, and nothing here is tracked in `sessions`.
+    pub async fn simulate(
+        &self,
+        app_handle: &AppHandle,
+        steam_id: &str,
+        achievement_data: HashMap<u32, Vec<AchievementDto>>,
+        max_concurrent_games: u32,
+    ) -> AppResult<simulate::UnlockSimulation> {
+        simulate::simulate(
+            app_handle,
+            steam_id,
+            achievement_data,
+            max_concurrent_games.clamp(1, MAX_CONCURRENT_GAMES),
+        )
+        .await
+    }
+
     /// Excludes `app_id` from idling for the rest of a running session for `steam_id` - the
     /// Idling page's per-card "stop" toggle already releases the actual idling claim via
     /// `idling::claims::IdleClaimsRegistry::release_app_id` (this session finds out its game
@@ -249,27 +320,33 @@ impl AchievementUnlockerManager {
 /// A schema achievement, already filtered/ordered/annotated - what survives [`scan_game`] and
 /// feeds [`unlock_game`]. `delay_next_unlock_ms` is `Some` only when a custom per-achievement delay
 /// was configured (`order::AchievementOrderEntry::delay_next_unlock`); otherwise a fresh random
-/// delay (from `settings.interval`) is drawn once per game in [`unlock_game`], matching `main`'s
+/// delay (`natural::draw_delay_minutes`, within `settings.interval`) is drawn once per game in [`unlock_game`], matching `main`'s
 /// `delayMap` (computed once per game, reused for both the real wait and the displayed projection).
 #[derive(Clone)]
-struct ScannedAchievement {
-    id: String,
-    name: String,
-    icon_locked: String,
-    percent: Option<f32>,
-    delay_next_unlock_ms: Option<u64>,
+pub(super) struct ScannedAchievement {
+    pub(super) id: String,
+    pub(super) name: String,
+    pub(super) icon_locked: String,
+    pub(super) percent: Option<f32>,
+    pub(super) delay_next_unlock_ms: Option<u64>,
 }
 
-struct ScannedGame {
-    app_id: u32,
-    name: String,
-    achievements: Vec<ScannedAchievement>,
-    delay_before_first_unlock_ms: u64,
+pub(super) struct ScannedGame {
+    pub(super) app_id: u32,
+    pub(super) name: String,
+    pub(super) achievements: Vec<ScannedAchievement>,
+    pub(super) delay_before_first_unlock_ms: u64,
     /// The initial `ActiveGameProgress::achievement_count` value - the per-game max-unlocks
     /// override if one exists, otherwise every achievement found. Deliberately `unwrap_or`, not
     /// `main`'s `maxAchievementUnlocks || achievements.length` - a JS-falsy `0` override would
     /// silently fall back to "no override" there; `Option::unwrap_or` doesn't have that gotcha.
-    achievement_count: u32,
+    pub(super) achievement_count: u32,
+    /// This game's saved schedule when the session is resuming it - attached by [`run_loop`] after
+    /// its first scan pass, never by scanning itself. See `checkpoint`'s module doc comment.
+    pub(super) resume: Option<checkpoint::GameCheckpoint>,
+    /// Per progress-based achievement id, the stat writes leading up to its unlock - filled by
+    /// [`scan_game`] when `settings.stat_progress` is on. See `achievements::progress`.
+    pub(super) stat_progress: HashMap<String, Vec<StatUpdate>>,
 }
 
 /// Reason the outer loop in [`run_loop`] ended - only [`EndReason::QueueEmpty`] triggers next-task
@@ -310,6 +387,7 @@ async fn add_active(
         is_waiting_for_schedule: false,
         achievement_count,
         upcoming: Vec::new(),
+        schedule: Vec::new(),
     });
 }
 
@@ -320,7 +398,11 @@ async fn remove_active(state: &Mutex<AchievementUnlockerState>, app_id: u32) {
 
 /// Records a game's outcome for this session's "finished" summary - see [`CompletedUnlock`]'s doc
 /// comment for which exit paths call this (and which deliberately don't).
+/// Also the `unlockCompleted` webhook's trigger, so every recorded entry is announced exactly once.
+#[allow(clippy::too_many_arguments)]
 async fn add_completed(
+    app_handle: &AppHandle,
+    steam_id: &str,
     state: &Mutex<AchievementUnlockerState>,
     app_id: u32,
     name: &str,
@@ -328,14 +410,19 @@ async fn add_completed(
     total: u32,
     reason: CompletedUnlockReason,
 ) {
-    let mut s = state.lock().await;
-    s.completed.push(CompletedUnlock {
+    let completed = CompletedUnlock {
         app_id,
         name: name.to_string(),
         unlocked,
         total,
         reason,
-    });
+    };
+    state.lock().await.completed.push(completed.clone());
+    webhooks::notify(
+        app_handle,
+        steam_id,
+        WebhookEvent::UnlockCompleted(completed),
+    );
 }
 
 async fn update_game(
@@ -353,7 +440,10 @@ async fn update_game(
 /// when each will unlock, matching `main`'s `buildUpcomingQueue`: the first projected item lands at
 /// `now + initial_delay_ms`, each subsequent one adds the *previous* achievement's own resolved
 /// delay (`delays[i - 1]`) - the same values actually used to pace the real unlocks, so the
-/// projection and the real wait can never disagree.
+/// projection and the real wait can never disagree. With schedule `windows`, a time that falls outside
+/// every window moves to its [`schedule::next_opening`] before the next delay is added on, the same
+/// way the real schedule wait would hold it. The same projection continues past the limit into
+/// `ActiveGameProgress::schedule` for `checkpoint`.
 async fn update_upcoming(
     state: &Mutex<AchievementUnlockerState>,
     app_id: u32,
@@ -361,68 +451,106 @@ async fn update_upcoming(
     delays: &[u64],
     start_index: usize,
     initial_delay_ms: u64,
+    windows: Option<&[ScheduleWindow]>,
 ) {
-    let now = chrono::Utc::now().timestamp_millis();
-    let mut cumulative = initial_delay_ms as i64;
+    let open = |at_ms: i64| -> i64 {
+        windows
+            .zip(chrono::DateTime::from_timestamp_millis(at_ms))
+            .and_then(|(windows, at)| schedule::next_opening(windows, at))
+            .map(|t| t.timestamp_millis())
+            .unwrap_or(at_ms)
+    };
+    let mut unlock_at_ms = open(chrono::Utc::now().timestamp_millis() + initial_delay_ms as i64);
     let mut upcoming = Vec::new();
+    let mut projected = Vec::new();
     for i in start_index..achievements.len() {
-        if upcoming.len() >= UPCOMING_LIMIT {
-            break;
-        }
         if i > start_index {
-            cumulative += delays[i - 1] as i64;
+            unlock_at_ms = open(unlock_at_ms + delays[i - 1] as i64);
         }
         let achievement = &achievements[i];
-        upcoming.push(UpcomingAchievement {
+        projected.push(checkpoint::ScheduledUnlock {
             id: achievement.id.clone(),
-            name: achievement.name.clone(),
-            icon_locked: achievement.icon_locked.clone(),
-            percent: achievement.percent,
-            unlock_at_ms: now + cumulative,
+            unlock_at_ms,
         });
+        if upcoming.len() < UPCOMING_LIMIT {
+            upcoming.push(UpcomingAchievement {
+                id: achievement.id.clone(),
+                name: achievement.name.clone(),
+                icon_locked: achievement.icon_locked.clone(),
+                percent: achievement.percent,
+                unlock_at_ms,
+            });
+        }
     }
-    update_game(state, app_id, |g| g.upcoming = upcoming).await;
+    update_game(state, app_id, |g| {
+        g.upcoming = upcoming;
+        g.schedule = projected;
+    })
+    .await;
+}
+
+/// Whether now falls inside `windows` - `None` (schedule off) always does.
+fn is_within_schedule(windows: Option<&[ScheduleWindow]>) -> bool {
+    windows.is_none_or(|w| schedule::is_within(w, chrono::Utc::now()))
 }
 
-fn random_delay_ms(min_minutes: u32, max_minutes: u32) -> u64 {
-    let min_ms = min_minutes as u64 * 60_000;
-    let max_ms = max_minutes as u64 * 60_000;
-    if max_ms <= min_ms {
-        return min_ms;
+/// Blocks until [`schedule::is_within`] is true, re-checking every 60s (ticked via
+/// [`wait_ticking`] so a stop takes effect within ~1s, not up to a full minute late) - returns
+/// `true` if a stop was detected while waiting.
+async fn wait_until_in_schedule(windows: &[ScheduleWindow], stopped: &AtomicBool) -> bool {
+    while !schedule::is_within(windows, chrono::Utc::now()) {
+        if wait_ticking(Duration::from_secs(60), stopped).await {
+            return true;
+        }
     }
-    min_ms + rand::thread_rng().gen_range(0..=(max_ms - min_ms))
+    false
 }
 
-/// Whether the current local wall-clock time falls within `[from, to)`, wrapping past midnight if
-/// `to` is earlier than `from` (an overnight schedule, e.g. 22:00 to 06:00) - ported from `main`'s
-/// `isWithinSchedule` (`src/shared/utils/handleAutomation.ts`), just against `chrono::Local` instead
-/// of `@internationalized/date`'s `Time`.
-fn is_within_schedule(from: ScheduleTime, to: ScheduleTime) -> bool {
-    let now = chrono::Local::now();
-    let current = (now.hour(), now.minute());
-    let from_t = (from.hour as u32, from.minute as u32);
-    let to_t = (to.hour as u32, to.minute as u32);
-    if to_t < from_t {
-        current >= from_t || current < to_t
-    } else {
-        current >= from_t && current < to_t
+/// One stat-progress write through `achievements::commands::write_stats`. Best-effort: a failure is
+/// logged and the achievement still gets its direct unlock.
+async fn advance_stats(
+    app_handle: &AppHandle,
+    account: &GamesAccount,
+    app_id: u32,
+    update: StatUpdate,
+) {
+    let stat_id = update.name.clone();
+    let value = update.value;
+    if let Err(e) =
+        achievements::commands::write_stats(app_handle, account.clone(), app_id, vec![update]).await
+    {
+        tracing::warn!(app_id, stat_id, value, error = %e.code(), "achievement unlocker: stat progress write failed");
     }
 }
 
-/// Blocks until `is_within_schedule(from, to)` is true, re-checking every 60s (ticked via
-/// [`wait_ticking`] so a stop takes effect within ~1s, not up to a full minute late) - returns
-/// `true` if a stop was detected while waiting.
-async fn wait_until_in_schedule(
-    from: ScheduleTime,
-    to: ScheduleTime,
+/// Waits `wait_ms` before an achievement's unlock, applying every step of its stat progress but the
+/// last evenly across the wait (only while inside the schedule, same as the unlocks themselves) -
+/// the last step, the threshold itself, is written by [`unlock_game`] right before the unlock.
+/// Without steps this is just [`wait_ticking`]. Returns `true` if a stop was detected.
+async fn wait_advancing_stats(
+    app_handle: &AppHandle,
+    account: &GamesAccount,
+    app_id: u32,
+    wait_ms: u64,
+    steps: &[StatUpdate],
+    windows: Option<&[ScheduleWindow]>,
     stopped: &AtomicBool,
 ) -> bool {
-    while !is_within_schedule(from, to) {
-        if wait_ticking(Duration::from_secs(60), stopped).await {
+    let intermediate = steps.len().saturating_sub(1);
+    let chunk_ms = wait_ms / steps.len().max(1) as u64;
+    for step in &steps[..intermediate] {
+        if wait_ticking(Duration::from_millis(chunk_ms), stopped).await {
             return true;
         }
+        if is_within_schedule(windows) {
+            advance_stats(app_handle, account, app_id, step.clone()).await;
+        }
     }
-    false
+    wait_ticking(
+        Duration::from_millis(wait_ms - chunk_ms * intermediate as u64),
+        stopped,
+    )
+    .await
 }
 
 /// Claims or releases `app_id` in this session's own desired idling set, then re-announces this
@@ -477,7 +605,8 @@ async fn set_game_idling(
 /// achievements (and, if `settings.hidden` is on, hidden ones too), applies a saved custom order if
 /// one exists (dropping skipped achievements, sorting the rest by saved position with unordered
 /// achievements falling back to percent-descending at the end - matches `main`'s
-/// `fetchAchievements`), or falls back to plain percent-descending. A game with a schema that
+/// `fetchAchievements`), or falls back to plain percent-descending - unless `settings.ordering` is
+/// `Natural`, which generates and saves a plan first and then applies it like any saved order. A game with a schema that
 /// reports any `protected_achievement` is treated as having nothing to unlock (matches `main`'s
 /// same "can't safely automate this game" treatment). Never hard-errors - any failure (fetch,
 /// settings, order) also resolves to "nothing to unlock", matching `main`'s catch-all behavior of
@@ -494,6 +623,8 @@ async fn scan_game(
         achievements: Vec::new(),
         delay_before_first_unlock_ms: 0,
         achievement_count,
+        resume: None,
+        stat_progress: HashMap::new(),
     };
 
     let unlocker_settings = match settings::get(app_handle, steam_id).await {
@@ -510,11 +641,11 @@ async fn scan_game(
     let agent_manager = app_handle.state::<AgentManager>();
     // Backend automation loop, no frontend locale to follow - the fetched name/description are
     // only ever used in `tracing::` log lines below, not shown to the user, so "english" is fine.
-    let data = match achievements::commands::get_achievement_data(
-        agent_manager,
+    let data = match achievements::commands::fetch_achievement_data(
+        &agent_manager,
         account.clone(),
         entry.app_id,
-        "english".to_string(),
+        "english",
     )
     .await
     {
@@ -525,21 +656,32 @@ async fn scan_game(
         }
     };
 
-    if data.achievements.iter().any(|a| a.protected_achievement) {
+    let Some(eligible) = eligible_achievements(&data.achievements, &unlocker_settings) else {
         tracing::info!(app_id = entry.app_id, name = %entry.name, "achievement unlocker: game has protected achievements, removing from queue");
         return empty(0);
-    }
-
-    let eligible: Vec<&AchievementDto> = data
-        .achievements
-        .iter()
-        .filter(|a| !a.achieved && (!unlocker_settings.hidden || !a.hidden))
-        .collect();
+    };
 
-    let saved_order = order::get(app_handle, steam_id, entry.app_id)
+    let mut saved_order = order::get(app_handle, steam_id, entry.app_id)
         .await
         .ok()
         .flatten();
+    // Generated once and saved, then read back like any hand-made order on every later scan - see
+    // `natural`'s module doc comment.
+    if saved_order.is_none()
+        && unlocker_settings.ordering == UnlockOrdering::Natural
+        && !eligible.is_empty()
+    {
+        saved_order = Some(
+            natural::generate_and_save(
+                app_handle,
+                steam_id,
+                entry.app_id,
+                &eligible,
+                &unlocker_settings,
+            )
+            .await,
+        );
+    }
     tracing::info!(
         app_id = entry.app_id,
         name = %entry.name,
@@ -547,7 +689,65 @@ async fn scan_game(
         "achievement unlocker: scanned game"
     );
 
-    let (achievements, delay_before_first_unlock_ms) = if let Some(saved) = &saved_order {
+    let mut game = build_scanned_game(entry, eligible, saved_order.as_ref(), max_unlocks);
+    if unlocker_settings.stat_progress {
+        game.stat_progress = plan_stat_progress(&game, &data.achievements, &data.stats);
+    }
+    game
+}
+
+/// [`ScannedGame::stat_progress`] for `game` - thresholds from the fetched schema
+/// (`achievements::progress::links`), in either sign-in mode. Only when none of `achievements`
+/// carries one (data from a SteamUtility build before `AchievementDto::progress`) does this fall
+/// back to the local Steam client's cached schema (`local_steam::stats_schema`). Empty when neither
+/// ties any achievement to a stat, in which case every achievement just gets the direct unlock.
+fn plan_stat_progress(
+    game: &ScannedGame,
+    achievements: &[AchievementDto],
+    stats: &[StatDto],
+) -> HashMap<String, Vec<StatUpdate>> {
+    let mut links = achievements::progress::links(achievements);
+    if links.is_empty() {
+        links = match stats_schema::progress_links(game.app_id) {
+            Ok(links) => links,
+            Err(e) => {
+                tracing::warn!(app_id = game.app_id, error = %e.code(), "achievement unlocker: failed to read cached stats schema, unlocking progress achievements directly");
+                return HashMap::new();
+            }
+        };
+    }
+    let order: Vec<&str> = game.achievements.iter().map(|a| a.id.as_str()).collect();
+    achievements::progress::plan(&order, stats, &links, STAT_PROGRESS_STEPS)
+}
+
+/// What the unlocker would consider unlocking out of a game's full achievement list - not yet
+/// achieved, and not hidden when `settings.hidden` is on. `None` when the schema reports any
+/// `protected_achievement` (see [`scan_game`]'s doc comment).
+pub(super) fn eligible_achievements<'a>(
+    achievements: &'a [AchievementDto],
+    unlocker_settings: &settings::AchievementUnlockerSettings,
+) -> Option<Vec<&'a AchievementDto>> {
+    if achievements.iter().any(|a| a.protected_achievement) {
+        return None;
+    }
+    Some(
+        achievements
+            .iter()
+            .filter(|a| !a.achieved && (!unlocker_settings.hidden || !a.hidden))
+            .collect(),
+    )
+}
+
+/// The pure half of [`scan_game`]: orders `eligible` by `saved_order` (or percent-descending) and
+/// resolves its custom delays and max-unlocks count - shared with `simulate`, which supplies the
+/// same inputs without fetching anything.
+pub(super) fn build_scanned_game(
+    entry: &AchievementUnlockerEntry,
+    eligible: Vec<&AchievementDto>,
+    saved_order: Option<&order::AchievementOrder>,
+    max_unlocks: Option<u32>,
+) -> ScannedGame {
+    let (achievements, delay_before_first_unlock_ms) = if let Some(saved) = saved_order {
         let position: HashMap<&str, usize> = saved
             .achievements
             .iter()
@@ -631,6 +831,8 @@ async fn scan_game(
         achievements,
         delay_before_first_unlock_ms,
         achievement_count,
+        resume: None,
+        stat_progress: HashMap::new(),
     }
 }
 
@@ -704,6 +906,8 @@ async fn run_scan_phase(
                         tracing::info!(app_id = entry.app_id, "achievement unlocker: removed over-max-playtime game from queue");
                     }
                     add_completed(
+                        &app_handle,
+                        &steam_id,
                         &state,
                         entry.app_id,
                         &entry.name,
@@ -743,6 +947,8 @@ async fn run_scan_phase(
                         tracing::warn!(app_id = entry.app_id, error = %e.code(), "achievement unlocker: failed to remove exhausted game from queue");
                     }
                     add_completed(
+                        &app_handle,
+                        &steam_id,
                         &state,
                         entry.app_id,
                         &entry.name,
@@ -776,6 +982,14 @@ async fn run_scan_phase(
     ready
 }
 
+/// `game.achievements[index]`'s planned stat progress - empty past the end or without a plan.
+fn stat_steps(game: &ScannedGame, index: usize) -> &[StatUpdate] {
+    game.achievements
+        .get(index)
+        .and_then(|a| game.stat_progress.get(&a.id))
+        .map_or(&[], Vec::as_slice)
+}
+
 /// Unlocks every achievement in `game`, one at a time, in order - schedule-waiting, idling, retrying
 /// failed unlocks up to [`MAX_UNLOCK_ATTEMPTS`] times, and stopping early once either every
 /// achievement is unlocked or the per-game max-unlocks override is reached (matches `main`'s
@@ -800,6 +1014,7 @@ async fn unlock_game(
     let max_unlocks = settings::get_max_unlocks(app_handle, steam_id, app_id)
         .await
         .unwrap_or(None);
+    let active_schedule = unlocker_settings.active_schedule();
 
     // Baseline for the per-achievement max-playtime check below - read once per game, not per
     // achievement, same reasoning as `run_scan_phase`'s own playtime lookup (a cache read, not a
@@ -816,15 +1031,15 @@ async fn unlock_game(
 
     // Resolved once per game, not per achievement, so the projected `upcoming` queue and the real
     // wait always agree - matches `main`'s `delayMap`.
-    let delays: Vec<u64> = game
-        .achievements
-        .iter()
-        .map(|a| {
-            a.delay_next_unlock_ms.unwrap_or_else(|| {
-                random_delay_ms(unlocker_settings.interval[0], unlocker_settings.interval[1])
-            })
-        })
-        .collect();
+    // A resumed game keeps its saved deadlines instead - see `checkpoint`'s module doc comment.
+    let mut delays = resolve_delays(game, &unlocker_settings, &mut rand::thread_rng());
+    let delay_before_first_unlock_ms = match &game.resume {
+        Some(resume) => {
+            delays = checkpoint::resumed_delays(game, resume, delays);
+            checkpoint::first_wait_ms(game, resume, chrono::Utc::now().timestamp_millis())
+        }
+        None => game.delay_before_first_unlock_ms,
+    };
 
     // Idling is a side effect of the game being *active*, not of it having actually started
     // unlocking - starting it here (rather than only once the achievement loop below runs) means a
@@ -835,18 +1050,17 @@ async fn unlock_game(
     let mut is_idling = false;
     if unlocker_settings.idle
         && !excluded_app_ids.lock().await.contains(&app_id)
-        && (!unlocker_settings.schedule
-            || is_within_schedule(unlocker_settings.schedule_from, unlocker_settings.schedule_to))
+        && is_within_schedule(active_schedule.as_deref())
     {
         set_game_idling(idling_apps, app_handle, account, app_id, &game.name, true).await;
         is_idling = true;
     }
 
-    if game.delay_before_first_unlock_ms > 0 {
+    if delay_before_first_unlock_ms > 0 {
         tracing::info!(
             app_id,
             name = %game.name,
-            delay_minutes = game.delay_before_first_unlock_ms / 60_000,
+            delay_minutes = delay_before_first_unlock_ms / 60_000,
             "achievement unlocker: waiting before first unlock"
         );
         update_upcoming(
@@ -855,12 +1069,18 @@ async fn unlock_game(
             &game.achievements,
             &delays,
             0,
-            game.delay_before_first_unlock_ms,
+            delay_before_first_unlock_ms,
+            active_schedule.as_deref(),
         )
         .await;
         emit_state(app_handle, steam_id, state).await;
-        if wait_ticking(
-            Duration::from_millis(game.delay_before_first_unlock_ms),
+        if wait_advancing_stats(
+            app_handle,
+            account,
+            app_id,
+            delay_before_first_unlock_ms,
+            stat_steps(game, 0),
+            active_schedule.as_deref(),
             stopped,
         )
         .await
@@ -871,7 +1091,16 @@ async fn unlock_game(
             return;
         }
     } else {
-        update_upcoming(state, app_id, &game.achievements, &delays, 0, 0).await;
+        update_upcoming(
+            state,
+            app_id,
+            &game.achievements,
+            &delays,
+            0,
+            0,
+            active_schedule.as_deref(),
+        )
+        .await;
     }
 
     let total = game.achievements.len() as u32;
@@ -909,6 +1138,8 @@ async fn unlock_game(
                 tracing::warn!(app_id, error = %e.code(), "achievement unlocker: failed to remove over-max-playtime game from queue");
             }
             add_completed(
+                app_handle,
+                steam_id,
                 state,
                 app_id,
                 &game.name,
@@ -920,11 +1151,9 @@ async fn unlock_game(
             break;
         }
 
-        if unlocker_settings.schedule
-            && !is_within_schedule(
-                unlocker_settings.schedule_from,
-                unlocker_settings.schedule_to,
-            )
+        if let Some(windows) = active_schedule
+            .as_deref()
+            .filter(|w| !schedule::is_within(w, chrono::Utc::now()))
         {
             if is_idling {
                 set_game_idling(idling_apps, app_handle, account, app_id, &game.name, false).await;
@@ -933,13 +1162,7 @@ async fn unlock_game(
             update_game(state, app_id, |g| g.is_waiting_for_schedule = true).await;
             emit_state(app_handle, steam_id, state).await;
 
-            if wait_until_in_schedule(
-                unlocker_settings.schedule_from,
-                unlocker_settings.schedule_to,
-                stopped,
-            )
-            .await
-            {
+            if wait_until_in_schedule(windows, stopped).await {
                 break;
             }
             update_game(state, app_id, |g| g.is_waiting_for_schedule = false).await;
@@ -956,6 +1179,12 @@ async fn unlock_game(
             break;
         }
 
+        // Forcing the achievement alone would leave its progress stat short of the threshold -
+        // land the stat on it first. See `achievements::progress`.
+        if let Some(threshold) = stat_steps(game, index).last() {
+            advance_stats(app_handle, account, app_id, threshold.clone()).await;
+        }
+
         let mut succeeded = false;
         for attempt in 0..MAX_UNLOCK_ATTEMPTS {
             if stopped.load(Ordering::SeqCst) {
@@ -963,6 +1192,7 @@ async fn unlock_game(
             }
             let agent_manager = app_handle.state::<AgentManager>();
             succeeded = achievements::commands::set_achievement(
+                app_handle.clone(),
                 agent_manager,
                 account.clone(),
                 app_id,
@@ -1020,7 +1250,10 @@ async fn unlock_game(
                 } else {
                     CompletedUnlockReason::MaxUnlocksReached
                 };
-                add_completed(state, app_id, &game.name, processed, total, reason).await;
+                add_completed(
+                    app_handle, steam_id, state, app_id, &game.name, processed, total, reason,
+                )
+                .await;
                 update_game(state, app_id, |g| g.upcoming.clear()).await;
                 emit_state(app_handle, steam_id, state).await;
                 return;
@@ -1037,10 +1270,21 @@ async fn unlock_game(
             &delays,
             index + 1,
             delay_ms,
+            active_schedule.as_deref(),
         )
         .await;
         emit_state(app_handle, steam_id, state).await;
-        if wait_ticking(Duration::from_millis(delay_ms), stopped).await {
+        if wait_advancing_stats(
+            app_handle,
+            account,
+            app_id,
+            delay_ms,
+            stat_steps(game, index + 1),
+            active_schedule.as_deref(),
+            stopped,
+        )
+        .await
+        {
             break;
         }
     }
@@ -1050,12 +1294,32 @@ async fn unlock_game(
     }
 }
 
+/// Each achievement's wait after unlocking it, in ms - its custom delay if one is configured,
+/// otherwise a fresh `natural::draw_delay_minutes` draw. Shared with `simulate`, so a projected
+/// timeline paces exactly the way a real run would.
+pub(super) fn resolve_delays(
+    game: &ScannedGame,
+    unlocker_settings: &settings::AchievementUnlockerSettings,
+    rng: &mut impl rand::Rng,
+) -> Vec<u64> {
+    game.achievements
+        .iter()
+        .map(|a| {
+            a.delay_next_unlock_ms.unwrap_or_else(|| {
+                let minutes = natural::draw_delay_minutes(unlocker_settings, rng);
+                (minutes * 60_000.0) as u64
+            })
+        })
+        .collect()
+}
+
 /// Phase 2 of one pass: unlocks every game in `ready` across `worker_count` workers, pulled from a
 /// shared queue (work-stealing, matching `main`'s `unlockWorker`). The first game a given worker
 /// picks up always gets the fixed [`INITIAL_DELAY`] grace period; later ones get
 /// [`INTER_GAME_DELAY`] only in single-worker mode and only when the game has no
 /// `delayBeforeFirstUnlock` of its own (matches `main`'s `gameHasPreDelay` check, reusing the value
-/// already computed during scanning instead of re-reading the order file a second time).
+/// already computed during scanning instead of re-reading the order file a second time). A game
+/// being resumed from a checkpoint gets neither.
 #[allow(clippy::too_many_arguments)]
 async fn run_unlock_phase(
     app_handle: &AppHandle,
@@ -1090,7 +1354,7 @@ async fn run_unlock_phase(
                 let game = { queue.lock().await.pop_front() };
                 let Some(game) = game else { break };
 
-                if is_first {
+                if is_first && game.resume.is_none() {
                     add_active(
                         &state,
                         game.app_id,
@@ -1111,7 +1375,9 @@ async fn run_unlock_phase(
                     })
                     .await;
                 } else {
-                    let has_pre_delay = game.delay_before_first_unlock_ms > 0;
+                    // A resumed game already sat through its grace period before the restart.
+                    let has_pre_delay =
+                        game.delay_before_first_unlock_ms > 0 || game.resume.is_some();
                     if !has_pre_delay && worker_count == 1 {
                         tracing::info!(
                             next_app_id = game.app_id,
@@ -1244,7 +1510,8 @@ fn maybe_start_next_task<'a>(
 }
 
 /// The session itself: repeats scan-then-unlock passes until the queue is genuinely empty or a stop
-/// is requested, then releases any idling this session claimed, clears the tracked state, chains
+/// is requested, checkpointing its state in the background as it goes (`resume`, if any, seeds the
+/// first pass), then releases any idling this session claimed, clears the tracked state, chains
 /// into the next task if configured (only on a genuine empty-queue ending), and deregisters itself
 /// from the manager.
 #[allow(clippy::too_many_arguments)]
@@ -1257,8 +1524,16 @@ async fn run_loop(
     stopped: Arc<AtomicBool>,
     idling_apps: Arc<Mutex<HashMap<u32, String>>>,
     excluded_app_ids: Arc<Mutex<HashSet<u32>>>,
+    mut resume: Option<checkpoint::SessionCheckpoint>,
 ) {
     let end_reason;
+    let checkpoint_task = tokio::spawn(checkpoint::run_periodic(
+        app_handle.clone(),
+        steam_id.clone(),
+        state.clone(),
+        max_concurrent_games.clone(),
+        stopped.clone(),
+    ));
 
     loop {
         if stopped.load(Ordering::SeqCst) {
@@ -1285,7 +1560,7 @@ async fn run_loop(
             .load(Ordering::SeqCst)
             .clamp(1, MAX_CONCURRENT_GAMES);
 
-        let ready = run_scan_phase(
+        let mut ready = run_scan_phase(
             &app_handle,
             &account,
             &steam_id,
@@ -1302,6 +1577,19 @@ async fn run_loop(
         if ready.is_empty() {
             continue;
         }
+        // Only the first pass resumes anything - checkpointed games go first, in their saved
+        // order, so they're the ones picked up straight away.
+        if let Some(checkpoint) = resume.take() {
+            let mut saved: HashMap<u32, checkpoint::GameCheckpoint> = checkpoint
+                .games
+                .into_iter()
+                .map(|g| (g.app_id, g))
+                .collect();
+            for game in &mut ready {
+                game.resume = saved.remove(&game.app_id);
+            }
+            ready.sort_by_key(|game| game.resume.is_none());
+        }
 
         run_unlock_phase(
             &app_handle,
@@ -1317,6 +1605,24 @@ async fn run_loop(
         .await;
     }
 
+    // A genuinely empty queue leaves nothing to resume; anything else (a stop, a read error) keeps
+    // a final snapshot, taken before `active` is cleared below. A user-initiated stop discards it
+    // afterwards - see `commands::stop_achievement_unlocker`'s `discard_checkpoint`.
+    checkpoint_task.abort();
+    if matches!(end_reason, EndReason::QueueEmpty) {
+        if let Err(e) = checkpoint::clear(&app_handle, &steam_id).await {
+            tracing::warn!(steam_id, error = %e.code(), "achievement unlocker: failed to clear checkpoint");
+        }
+    } else {
+        checkpoint::write(
+            &app_handle,
+            &steam_id,
+            &state,
+            max_concurrent_games.load(Ordering::SeqCst),
+        )
+        .await;
+    }
+
     let had_idling = {
         let mut apps = idling_apps.lock().await;
         let had = !apps.is_empty();
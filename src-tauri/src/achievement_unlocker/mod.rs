@@ -1,12 +1,16 @@
 //! Per-account achievement-unlocker queue (`cache`/`commands` - which games are lined up for
 //! automatic achievement unlocking, add/remove/reorder), settings (`settings` - unlock pacing/
 //! scheduling/toggles plus per-game max-unlocks overrides), per-game achievement ordering
-//! (`order` - custom order/skip/per-achievement delay, one file per game), the import-timings
-//! lookup (`import_timings` - deriving delays from a real player's unlock history), and the actual
-//! unlock automation loop (`manager` - combines all three of the above into a running background
-//! task: idle up to [`manager::MAX_CONCURRENT_GAMES`] queued games concurrently, unlock their
+//! (`order` - custom order/skip/per-achievement delay, one file per game, shareable as
+//! self-describing plan files via `plan_file`), the import-timings
+//! lookup (`import_timings` - deriving delays from a real player's unlock history, or from many at
+//! once via `aggregate_timings`), generated
+//! rarity-aware orders and delay distributions (`natural`), and the actual unlock automation loop
+//! (`manager` - combines all three of the above into a running background task: idle up to [`manager::MAX_CONCURRENT_GAMES`] queued games concurrently, unlock their
 //! achievements one at a time with real pacing/scheduling, backfilling from the queue as games
-//! finish, until the queue empties).
+//! finish, until the queue empties), plus a dry-run projection of that loop against a virtual clock
+//! (`simulate`), the checkpoint a running session keeps on disk so a restart can resume it
+//! (`checkpoint`), and the weekday-aware schedule windows unlocking is limited to (`schedule`).
 //!
 //! Its own vertical module (own cache files, own commands) rather than a shared "custom lists"
 //! abstraction spanning favorites/card-farming-queue/achievement-unlocker-queue/auto-idle-queue via
@@ -15,12 +19,18 @@
 //! underlying problem (a per-account ordered list of app ids) is identical; `order` departs from
 //! that shape deliberately since it's genuinely per-game, not per-account.
 
-mod cache;
+pub mod aggregate_timings;
+pub(crate) mod cache;
+pub mod checkpoint;
 pub mod commands;
 pub mod import_timings;
 pub mod manager;
+mod natural;
 pub mod order;
+pub mod plan_file;
+pub mod schedule;
 pub mod settings;
+pub mod simulate;
 
 use serde::{Deserialize, Serialize};
 
@@ -75,6 +85,10 @@ pub struct ActiveGameProgress {
     /// mirrors `main`'s `achievementCount` display field.
     pub achievement_count: u32,
     pub upcoming: Vec<UpcomingAchievement>,
+    /// Every remaining achievement's projected unlock time, not just the first few `upcoming`
+    /// shows - kept only for `checkpoint`, never sent to the frontend.
+    #[serde(skip)]
+    pub schedule: Vec<checkpoint::ScheduledUnlock>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -90,7 +104,7 @@ pub struct ScanProgress {
 /// [`CompletedUnlockReason::MaxPlaytime`] or [`CompletedUnlockReason::NothingToUnlock`] on the very
 /// first scan pass (the most common way a session ends in well under a second) is exactly the case
 /// that used to leave no visible trace at all.
-#[derive(Debug, Clone, Copy, Serialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum CompletedUnlockReason {
     /// Genuinely ran out of achievements to unlock - `remaining` hit zero.
@@ -113,7 +127,7 @@ pub enum CompletedUnlockReason {
 /// vanishing from `active` once `cache::remove` dequeues it. Mirrors `card_farming::GameWithDrops`'s
 /// identical role in `FarmingState::completed`. Not populated for a user-initiated stop - matches
 /// `card_farming::poll_active`'s same distinction (a manual stop isn't "this game is done").
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CompletedUnlock {
     pub app_id: u32,
@@ -0,0 +1,230 @@
+//! "Natural" unlock plans and the configurable delay distribution. A plan is an ordinary
+//! [`order::AchievementOrder`] - generated once per game (by `manager::scan_game` the first time
+//! it scans a game with `settings::UnlockOrdering::Natural` on and no saved order, or on demand via
+//! `commands::generate_natural_achievement_order`), saved through [`order::save`], and from then on
+//! indistinguishable from one the user built by hand: the order editor can review and tweak it, and
+//! a regenerate just overwrites it.
+//!
+//! **Ordering** is global unlock percentage descending (`AchievementDto::percent`, from
+//! `achievements::web_api::fetch_global_percentages`) - common first, rare last, the shape a real
+//! playthrough has - with each percentage jittered by up to [`PERCENT_JITTER`] before sorting, so
+//! achievements of similar rarity don't always come out in the same strict order the plain
+//! percent-descending fallback uses. An achievement with no known percentage sorts as the rarest.
+//!
+//! **Delays** ([`draw_delay_minutes`]) follow `settings::DelayDistribution`, still bounded by
+//! `settings.interval`: `Uniform` is the original flat draw; `LogNormal` clusters most delays near
+//! the interval's geometric middle with an occasional long gap, which is what real unlock
+//! timestamps look like; `Empirical` resamples `settings.empirical_delays` (e.g. gaps taken from
+//! an import-timings lookup). The same draw is used for every delay the unlocker picks itself, not
+//! just for generated plans.
+
+use rand::Rng;
+
+use crate::achievements::AchievementDto;
+
+use super::order::{self, AchievementOrder, AchievementOrderEntry};
+use super::settings::{AchievementUnlockerSettings, DelayDistribution};
+
+/// Maximum relative jitter applied to an achievement's unlock percentage before sorting - `0.15`
+/// lets a 40% achievement land anywhere a 34-46% one would.
+const PERCENT_JITTER: f32 = 0.15;
+
+/// z-score of the 95th percentile - a log-normal fitted so `interval` spans its middle 90%.
+const Z_95: f64 = 1.645;
+
+/// One delay in minutes, drawn from `settings.delay_distribution`. Every distribution is clamped to
+/// `interval` so a configured range still means what it says; `Empirical` with no samples falls
+/// back to `Uniform`.
+pub fn draw_delay_minutes(settings: &AchievementUnlockerSettings, rng: &mut impl Rng) -> f64 {
+    let min = f64::from(settings.interval[0]);
+    let max = f64::from(settings.interval[1]).max(min);
+    if max <= min {
+        return min;
+    }
+    let drawn = match settings.delay_distribution {
+        DelayDistribution::Uniform => rng.gen_range(min..=max),
+        DelayDistribution::LogNormal => log_normal(min, max, rng),
+        DelayDistribution::Empirical => {
+            let samples: Vec<f64> = settings
+                .empirical_delays
+                .iter()
+                .copied()
+                .filter(|d| d.is_finite() && *d >= 0.0)
+                .collect();
+            if samples.is_empty() {
+                rng.gen_range(min..=max)
+            } else {
+                samples[rng.gen_range(0..samples.len())]
+            }
+        }
+    };
+    drawn.clamp(min, max)
+}
+
+/// Log-normal with its median at the geometric mean of `[min, max]` and `[min, max]` as its middle
+/// 90% - via Box-Muller, since `rand` alone has no normal distribution. A zero `min` is nudged up
+/// to a minute so the log stays finite.
+fn log_normal(min: f64, max: f64, rng: &mut impl Rng) -> f64 {
+    let (ln_min, ln_max) = (min.max(1.0).ln(), max.max(1.0).ln());
+    let mu = (ln_min + ln_max) / 2.0;
+    let sigma = (ln_max - ln_min) / (2.0 * Z_95);
+    let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+    let u2: f64 = rng.gen();
+    let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+    (mu + sigma * z).exp()
+}
+
+/// A natural plan for `eligible` (already filtered to what the unlocker would unlock) - see this
+/// module's doc comment. Every entry gets its own drawn delay, so the saved plan shows exactly the
+/// pacing the unlocker will use.
+pub fn plan(
+    eligible: &[&AchievementDto],
+    settings: &AchievementUnlockerSettings,
+    rng: &mut impl Rng,
+) -> AchievementOrder {
+    let mut keyed: Vec<(f32, &AchievementDto)> = eligible
+        .iter()
+        .map(|a| {
+            let jitter = 1.0 + rng.gen_range(-PERCENT_JITTER..=PERCENT_JITTER);
+            (a.percent.unwrap_or(0.0) * jitter, *a)
+        })
+        .collect();
+    keyed.sort_by(|(a, _), (b, _)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+
+    AchievementOrder {
+        achievements: keyed
+            .into_iter()
+            .map(|(_, a)| AchievementOrderEntry {
+                id: a.id.clone(),
+                skip: false,
+                delay_next_unlock: Some(round_tenth(draw_delay_minutes(settings, rng))),
+            })
+            .collect(),
+        delay_before_first_unlock: None,
+    }
+}
+
+/// Plans are edited in the order editor's `step={0.1}` minute inputs - keep saved values on that
+/// grid.
+fn round_tenth(minutes: f64) -> f64 {
+    (minutes * 10.0).round() / 10.0
+}
+
+/// Generates and saves a plan for `app_id` - the shared body behind `manager::scan_game`'s
+/// first-scan generation and the regenerate command. Saving can fail without losing the plan: the
+/// caller still gets it back to use for this run.
+pub async fn generate_and_save(
+    app_handle: &tauri::AppHandle,
+    steam_id: &str,
+    app_id: u32,
+    eligible: &[&AchievementDto],
+    settings: &AchievementUnlockerSettings,
+) -> AchievementOrder {
+    let generated = plan(eligible, settings, &mut rand::thread_rng());
+    match order::save(app_handle, steam_id, app_id, generated.clone()).await {
+        Ok(saved) => {
+            tracing::info!(
+                app_id,
+                achievements = saved.achievements.len(),
+                "achievement unlocker: saved natural unlock plan"
+            );
+            saved
+        }
+        Err(e) => {
+            tracing::warn!(app_id, error = %e.code(), "achievement unlocker: failed to save natural unlock plan, using it unsaved");
+            generated
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    fn achievement(id: &str, percent: Option<f32>) -> AchievementDto {
+        AchievementDto {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            icon_normal: String::new(),
+            icon_locked: String::new(),
+            permission: 0,
+            hidden: false,
+            achieved: false,
+            percent,
+            protected_achievement: false,
+            flags: String::new(),
+            progress: None,
+        }
+    }
+
+    fn settings(distribution: DelayDistribution) -> AchievementUnlockerSettings {
+        AchievementUnlockerSettings {
+            interval: [30, 130],
+            delay_distribution: distribution,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn plan_orders_common_first_rare_last() {
+        let list = [
+            achievement("rare", Some(2.0)),
+            achievement("unknown", None),
+            achievement("common", Some(80.0)),
+            achievement("mid", Some(30.0)),
+        ];
+        let eligible: Vec<&AchievementDto> = list.iter().collect();
+        let mut rng = StdRng::seed_from_u64(7);
+        let order = plan(&eligible, &settings(DelayDistribution::LogNormal), &mut rng);
+        let ids: Vec<&str> = order.achievements.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["common", "mid", "rare", "unknown"]);
+        assert!(order.achievements.iter().all(|e| e
+            .delay_next_unlock
+            .is_some_and(|d| (30.0..=130.0).contains(&d))));
+    }
+
+    #[test]
+    fn jitter_only_swaps_similar_rarities() {
+        let list = [achievement("a", Some(50.0)), achievement("b", Some(49.0))];
+        let eligible: Vec<&AchievementDto> = list.iter().collect();
+        let mut rng = StdRng::seed_from_u64(1);
+        let firsts: std::collections::HashSet<String> = (0..50)
+            .map(|_| plan(&eligible, &settings(DelayDistribution::Uniform), &mut rng))
+            .map(|o| o.achievements[0].id.clone())
+            .collect();
+        assert_eq!(firsts.len(), 2);
+    }
+
+    #[test]
+    fn log_normal_clusters_toward_the_middle() {
+        let s = settings(DelayDistribution::LogNormal);
+        let mut rng = StdRng::seed_from_u64(42);
+        let draws: Vec<f64> = (0..2_000)
+            .map(|_| draw_delay_minutes(&s, &mut rng))
+            .collect();
+        let mut sorted = draws.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let median = sorted[sorted.len() / 2];
+        // Geometric mean of 30 and 130 is ~62.4, well below the uniform median of 80.
+        assert!((55.0..70.0).contains(&median), "median {median}");
+        assert!(draws.iter().all(|d| (30.0..=130.0).contains(d)));
+    }
+
+    #[test]
+    fn empirical_resamples_configured_delays_or_falls_back() {
+        let mut s = settings(DelayDistribution::Empirical);
+        s.empirical_delays = vec![45.0, 90.0];
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..20 {
+            let d = draw_delay_minutes(&s, &mut rng);
+            assert!(d == 45.0 || d == 90.0);
+        }
+        s.empirical_delays.clear();
+        let d = draw_delay_minutes(&s, &mut rng);
+        assert!((30.0..=130.0).contains(&d));
+    }
+}
@@ -0,0 +1,246 @@
+//! Shareable achievement-order plans - one game's `order::AchievementOrder` written to a
+//! standalone, self-describing file (its own `format` marker, [`PLAN_SCHEMA_VERSION`] and app id)
+//! so it can move between accounts or people, unlike the per-account `achievement_order/<app_id>.json`
+//! it's exported from. `path` comes from the frontend's dialog picker both ways, same provenance as
+//! `card_farming::ledger::export`'s.
+//!
+//! **Importing validates before it saves** - every id is checked against the live
+//! `get_achievement_data` schema ([`validate`]). A plan whose ids don't all exist there is reported
+//! back unsaved, so the user sees exactly which ids it carries that this game doesn't have (and
+//! which of the game's achievements it doesn't mention) rather than having them vanish; importing
+//! again with `force` saves it minus the unknown ids.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::achievements::AchievementDto;
+use crate::error::{AppError, AppResult};
+
+use super::order::{AchievementOrder, AchievementOrderEntry};
+
+/// Marks a file as one of these plans rather than any other JSON someone picked.
+pub const PLAN_FORMAT: &str = "steam-game-idler.achievement-order";
+
+/// Bumped whenever [`AchievementOrderPlan`]'s shape changes incompatibly.
+pub const PLAN_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AchievementOrderPlan {
+    pub format: String,
+    pub schema_version: u32,
+    pub app_id: u32,
+    pub exported_at_ms: i64,
+    pub achievements: Vec<AchievementOrderEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay_before_first_unlock: Option<f64>,
+}
+
+impl AchievementOrderPlan {
+    pub fn new(app_id: u32, order: AchievementOrder, exported_at_ms: i64) -> Self {
+        AchievementOrderPlan {
+            format: PLAN_FORMAT.to_string(),
+            schema_version: PLAN_SCHEMA_VERSION,
+            app_id,
+            exported_at_ms,
+            achievements: order.achievements,
+            delay_before_first_unlock: order.delay_before_first_unlock,
+        }
+    }
+}
+
+/// What importing a plan found - and, when `saved`, what was written.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanImportReport {
+    pub app_id: u32,
+    pub saved: bool,
+    /// The order as it would be (or was) saved - the plan minus `unknown_ids`/`duplicate_ids`.
+    pub order: AchievementOrder,
+    /// Ids in the plan that aren't in this game's schema.
+    pub unknown_ids: Vec<String>,
+    /// Ids listed more than once - only the first entry is kept.
+    pub duplicate_ids: Vec<String>,
+    /// Schema achievements the plan doesn't mention - not an error (the unlocker orders them after
+    /// the plan's own, by rarity), just worth knowing before relying on it.
+    pub missing_ids: Vec<String>,
+}
+
+impl PlanImportReport {
+    pub fn has_mismatches(&self) -> bool {
+        !self.unknown_ids.is_empty() || !self.duplicate_ids.is_empty()
+    }
+}
+
+pub fn write(plan: &AchievementOrderPlan, path: &Path) -> AppResult<()> {
+    let contents = serde_json::to_string_pretty(plan)
+        .map_err(|e| AppError::AchievementOrderPlanIo(e.to_string()))?;
+    std::fs::write(path, contents).map_err(|e| {
+        AppError::AchievementOrderPlanIo(format!("failed to write {}: {e}", path.display()))
+    })
+}
+
+pub fn read(path: &Path) -> AppResult<AchievementOrderPlan> {
+    let contents = std::fs::read_to_string(path).map_err(|e| {
+        AppError::AchievementOrderPlanIo(format!("failed to read {}: {e}", path.display()))
+    })?;
+    parse(&contents)
+}
+
+/// Checks the envelope (`format`, then `schema_version`) before the body, so an unrelated JSON
+/// file or one from a newer build fails with a reason rather than a field-level parse error.
+fn parse(contents: &str) -> AppResult<AchievementOrderPlan> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Envelope {
+        format: Option<String>,
+        schema_version: Option<u32>,
+    }
+    let envelope: Envelope = serde_json::from_str(contents)
+        .map_err(|e| AppError::AchievementOrderPlanInvalid(e.to_string()))?;
+    if envelope.format.as_deref() != Some(PLAN_FORMAT) {
+        return Err(AppError::AchievementOrderPlanInvalid(
+            "not an achievement order plan".to_string(),
+        ));
+    }
+    match envelope.schema_version {
+        Some(version) if version > PLAN_SCHEMA_VERSION => {
+            return Err(AppError::AchievementOrderPlanUnsupportedVersion(version))
+        }
+        Some(_) => {}
+        None => {
+            return Err(AppError::AchievementOrderPlanInvalid(
+                "missing schemaVersion".to_string(),
+            ))
+        }
+    }
+    serde_json::from_str(contents).map_err(|e| AppError::AchievementOrderPlanInvalid(e.to_string()))
+}
+
+/// Matches `plan`'s ids against `schema` - pure, so the report is tested without Steam. Entry order,
+/// skip flags and delays carry over untouched for every id that survives.
+pub fn validate(plan: &AchievementOrderPlan, schema: &[AchievementDto]) -> PlanImportReport {
+    let known: HashSet<&str> = schema.iter().map(|a| a.id.as_str()).collect();
+    let mut seen = HashSet::new();
+    let mut achievements = Vec::new();
+    let mut unknown_ids = Vec::new();
+    let mut duplicate_ids = Vec::new();
+    for entry in &plan.achievements {
+        if !known.contains(entry.id.as_str()) {
+            unknown_ids.push(entry.id.clone());
+        } else if !seen.insert(entry.id.as_str()) {
+            duplicate_ids.push(entry.id.clone());
+        } else {
+            achievements.push(entry.clone());
+        }
+    }
+    let missing_ids = schema
+        .iter()
+        .filter(|a| !seen.contains(a.id.as_str()))
+        .map(|a| a.id.clone())
+        .collect();
+    PlanImportReport {
+        app_id: plan.app_id,
+        saved: false,
+        order: AchievementOrder {
+            achievements,
+            delay_before_first_unlock: plan.delay_before_first_unlock,
+        },
+        unknown_ids,
+        duplicate_ids,
+        missing_ids,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema(ids: &[&str]) -> Vec<AchievementDto> {
+        ids.iter()
+            .map(|id| AchievementDto {
+                id: id.to_string(),
+                name: id.to_string(),
+                description: String::new(),
+                icon_normal: String::new(),
+                icon_locked: String::new(),
+                permission: 0,
+                hidden: false,
+                achieved: false,
+                percent: None,
+                protected_achievement: false,
+                flags: String::new(),
+                progress: None,
+            })
+            .collect()
+    }
+
+    fn entry(id: &str, skip: bool) -> AchievementOrderEntry {
+        AchievementOrderEntry {
+            id: id.to_string(),
+            skip,
+            delay_next_unlock: Some(1.5),
+        }
+    }
+
+    #[test]
+    fn mismatches_are_reported_and_the_rest_kept_in_order() {
+        let plan = AchievementOrderPlan::new(
+            480,
+            AchievementOrder {
+                achievements: vec![
+                    entry("b", false),
+                    entry("gone", false),
+                    entry("a", true),
+                    entry("b", true),
+                ],
+                delay_before_first_unlock: Some(3.0),
+            },
+            0,
+        );
+        let report = validate(&plan, &schema(&["a", "b", "c"]));
+        let ids: Vec<&str> = report
+            .order
+            .achievements
+            .iter()
+            .map(|e| e.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["b", "a"]);
+        assert!(report.order.achievements[1].skip);
+        assert_eq!(report.order.delay_before_first_unlock, Some(3.0));
+        assert_eq!(report.unknown_ids, vec!["gone"]);
+        assert_eq!(report.duplicate_ids, vec!["b"]);
+        assert_eq!(report.missing_ids, vec!["c"]);
+        assert!(report.has_mismatches());
+    }
+
+    #[test]
+    fn round_trips_and_rejects_foreign_or_newer_files() {
+        let plan = AchievementOrderPlan::new(
+            480,
+            AchievementOrder {
+                achievements: vec![entry("a", false)],
+                delay_before_first_unlock: None,
+            },
+            1_700_000_000_000,
+        );
+        let parsed = parse(&serde_json::to_string(&plan).unwrap()).unwrap();
+        assert_eq!(parsed.app_id, 480);
+        assert_eq!(parsed.achievements[0].id, "a");
+
+        assert!(matches!(
+            parse(r#"{"achievements": []}"#),
+            Err(AppError::AchievementOrderPlanInvalid(_))
+        ));
+        let newer = format!(
+            r#"{{"format": "{PLAN_FORMAT}", "schemaVersion": {}}}"#,
+            PLAN_SCHEMA_VERSION + 1
+        );
+        assert!(matches!(
+            parse(&newer),
+            Err(AppError::AchievementOrderPlanUnsupportedVersion(_))
+        ));
+    }
+}
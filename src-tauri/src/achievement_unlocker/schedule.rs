@@ -0,0 +1,221 @@
+//! Schedule-window rules for the unlocker - when `settings.schedule` is on, an achievement is only
+//! ever unlocked inside one of `AchievementUnlockerSettings::active_schedule`'s windows.
+//!
+//! - **Several windows** combine as a union: inside any one of them is inside the schedule.
+//! - **Each window's times are in its own zone** - a named IANA `time_zone` (its offset resolved
+//!   per check, so DST moves the window with the zone's clocks), a fixed `utc_offset_minutes`, or
+//!   the machine's local time - and compared at minute resolution, `from` inclusive, `to` exclusive.
+//! - **A window spanning midnight** (`to` earlier than `from`, e.g. 22:00 to 02:00) belongs to the
+//!   day it *opens* on: a Friday-only 22:00-02:00 window covers Friday 22:00 through Saturday 02:00,
+//!   not Saturday night.
+//! - **`from == to` never opens** - the same "empty window" the original single-pair schedule
+//!   had, rather than silently meaning "all day".
+//!
+//! [`is_within`] is what `manager`'s schedule wait polls; [`next_opening`] is what the projections
+//! (`manager::update_upcoming`, `simulate`) use to move an unlock that would land outside every
+//! window to when the wait would release it.
+
+use chrono::{
+    DateTime, Datelike, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc,
+    Weekday,
+};
+use chrono_tz::Tz;
+
+use super::settings::{ScheduleTime, ScheduleWeekday, ScheduleWindow};
+
+/// How many days ahead [`next_opening`] looks - a full week plus the day a midnight-spanning window
+/// may have opened on, so any window with at least one weekday is always found.
+const LOOKAHEAD_DAYS: i64 = 8;
+
+fn to_chrono(day: ScheduleWeekday) -> Weekday {
+    match day {
+        ScheduleWeekday::Monday => Weekday::Mon,
+        ScheduleWeekday::Tuesday => Weekday::Tue,
+        ScheduleWeekday::Wednesday => Weekday::Wed,
+        ScheduleWeekday::Thursday => Weekday::Thu,
+        ScheduleWeekday::Friday => Weekday::Fri,
+        ScheduleWeekday::Saturday => Weekday::Sat,
+        ScheduleWeekday::Sunday => Weekday::Sun,
+    }
+}
+
+fn opens_on(window: &ScheduleWindow, day: Weekday) -> bool {
+    window.weekdays.is_empty() || window.weekdays.iter().any(|d| to_chrono(*d) == day)
+}
+
+fn hm(time: ScheduleTime) -> (u32, u32) {
+    (u32::from(time.hour), u32::from(time.minute))
+}
+
+/// The zone a window's times are in - see `ScheduleWindow::time_zone`. A zone name `chrono-tz`
+/// doesn't know (only possible in a hand-edited settings file - `settings::set` refuses one) falls
+/// through to the offset, then local time.
+enum Zone {
+    Named(Tz),
+    Fixed(FixedOffset),
+    Local,
+}
+
+fn zone(window: &ScheduleWindow) -> Zone {
+    if let Some(tz) = window
+        .time_zone
+        .as_deref()
+        .and_then(|name| name.parse().ok())
+    {
+        return Zone::Named(tz);
+    }
+    match window
+        .utc_offset_minutes
+        .and_then(|m| FixedOffset::east_opt(m * 60))
+    {
+        Some(offset) => Zone::Fixed(offset),
+        None => Zone::Local,
+    }
+}
+
+/// `at` as wall-clock time in `window`'s zone.
+fn wall_clock(window: &ScheduleWindow, at: DateTime<Utc>) -> NaiveDateTime {
+    match zone(window) {
+        Zone::Named(tz) => at.with_timezone(&tz).naive_local(),
+        Zone::Fixed(offset) => at.with_timezone(&offset).naive_local(),
+        Zone::Local => at.with_timezone(&Local).naive_local(),
+    }
+}
+
+/// The instant `wall` is in `window`'s zone - `None` for a local time skipped by a DST change, the
+/// earlier one for a time a DST change repeats.
+fn instant(window: &ScheduleWindow, wall: NaiveDateTime) -> Option<DateTime<Utc>> {
+    match zone(window) {
+        Zone::Named(tz) => tz
+            .from_local_datetime(&wall)
+            .earliest()
+            .map(|t| t.with_timezone(&Utc)),
+        Zone::Fixed(offset) => offset
+            .from_local_datetime(&wall)
+            .single()
+            .map(|t| t.with_timezone(&Utc)),
+        Zone::Local => Local
+            .from_local_datetime(&wall)
+            .earliest()
+            .map(|t| t.with_timezone(&Utc)),
+    }
+}
+
+fn window_contains(window: &ScheduleWindow, at: DateTime<Utc>) -> bool {
+    let wall = wall_clock(window, at);
+    let current = (wall.hour(), wall.minute());
+    let (from, to) = (hm(window.from), hm(window.to));
+    let day = wall.weekday();
+    if from < to {
+        opens_on(window, day) && current >= from && current < to
+    } else if to < from {
+        (current >= from && opens_on(window, day)) || (current < to && opens_on(window, day.pred()))
+    } else {
+        false
+    }
+}
+
+/// Whether `at` falls inside any of `windows`.
+pub fn is_within(windows: &[ScheduleWindow], at: DateTime<Utc>) -> bool {
+    windows.iter().any(|w| window_contains(w, at))
+}
+
+/// `at` itself if it's inside a window, otherwise the earliest instant after it that is - `None`
+/// when no window ever opens (every one empty, or no windows at all).
+pub fn next_opening(windows: &[ScheduleWindow], at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if is_within(windows, at) {
+        return Some(at);
+    }
+    windows
+        .iter()
+        .filter_map(|window| {
+            let (hour, minute) = hm(window.from);
+            let today: NaiveDate = wall_clock(window, at).date();
+            (0..=LOOKAHEAD_DAYS)
+                .filter_map(|d| today.checked_add_signed(chrono::Duration::days(d)))
+                .filter_map(|date| instant(window, date.and_hms_opt(hour, minute, 0)?))
+                .find(|opens| *opens > at && window_contains(window, *opens))
+        })
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(from: (u8, u8), to: (u8, u8), weekdays: Vec<ScheduleWeekday>) -> ScheduleWindow {
+        ScheduleWindow {
+            from: ScheduleTime {
+                hour: from.0,
+                minute: from.1,
+            },
+            to: ScheduleTime {
+                hour: to.0,
+                minute: to.1,
+            },
+            weekdays,
+            time_zone: None,
+            utc_offset_minutes: Some(0),
+        }
+    }
+
+    /// 2026-03-06 is a Friday.
+    fn utc(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn midnight_spanning_window_belongs_to_the_day_it_opens() {
+        let friday_night = [window((22, 0), (2, 0), vec![ScheduleWeekday::Friday])];
+        assert!(is_within(&friday_night, utc(6, 23, 0)));
+        assert!(is_within(&friday_night, utc(7, 1, 59)));
+        assert!(!is_within(&friday_night, utc(7, 2, 0)));
+        assert!(!is_within(&friday_night, utc(7, 23, 0)));
+        assert!(!is_within(&friday_night, utc(6, 1, 0)));
+    }
+
+    #[test]
+    fn windows_union_and_respect_their_offset() {
+        let mut evening_utc_plus_2 = window((18, 0), (20, 0), Vec::new());
+        evening_utc_plus_2.utc_offset_minutes = Some(120);
+        let windows = [window((8, 0), (9, 0), Vec::new()), evening_utc_plus_2];
+        assert!(is_within(&windows, utc(6, 8, 30)));
+        // 16:30 UTC is 18:30 at UTC+2.
+        assert!(is_within(&windows, utc(6, 16, 30)));
+        assert!(!is_within(&windows, utc(6, 18, 30)));
+    }
+
+    #[test]
+    fn named_zone_follows_dst() {
+        // New York moves from UTC-5 to UTC-4 on Sunday 2026-03-08.
+        let mut morning = window((9, 0), (10, 0), Vec::new());
+        morning.time_zone = Some("America/New_York".to_string());
+        assert!(is_within(&[morning.clone()], utc(6, 14, 30)));
+        assert!(!is_within(&[morning.clone()], utc(9, 14, 30)));
+        assert!(is_within(&[morning.clone()], utc(9, 13, 30)));
+        assert_eq!(next_opening(&[morning], utc(8, 15, 0)), Some(utc(9, 13, 0)));
+    }
+
+    #[test]
+    fn next_opening_skips_to_the_soonest_allowed_window() {
+        let windows = [
+            window((9, 0), (17, 0), vec![ScheduleWeekday::Monday]),
+            window((20, 0), (22, 0), vec![ScheduleWeekday::Saturday]),
+        ];
+        // Friday noon -> Saturday 20:00, before Monday 09:00.
+        assert_eq!(next_opening(&windows, utc(6, 12, 0)), Some(utc(7, 20, 0)));
+        // Already inside: unchanged.
+        assert_eq!(next_opening(&windows, utc(7, 21, 15)), Some(utc(7, 21, 15)));
+        // Saturday after close -> Monday 09:00.
+        assert_eq!(next_opening(&windows, utc(7, 22, 0)), Some(utc(9, 9, 0)));
+    }
+
+    #[test]
+    fn empty_windows_never_open() {
+        assert_eq!(
+            next_opening(&[window((9, 0), (9, 0), Vec::new())], utc(6, 0, 0)),
+            None
+        );
+        assert_eq!(next_opening(&[], utc(6, 0, 0)), None);
+    }
+}
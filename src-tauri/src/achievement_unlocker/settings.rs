@@ -24,6 +24,10 @@ use crate::platform;
 
 const SETTINGS_FILE_NAME: &str = "achievement_unlocker_settings.json";
 
+fn default_stat_progress() -> bool {
+    true
+}
+
 static WRITE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
 
 /// A schedule boundary time - just `hour`/`minute`, since the automation loop this gates only
@@ -35,6 +39,64 @@ pub struct ScheduleTime {
     pub minute: u8,
 }
 
+/// A day a [`ScheduleWindow`] may open on - local to the window's own time zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScheduleWeekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+/// One allowed unlocking window - see `schedule`'s module doc comment for how windows combine,
+/// span midnight and pick their weekday.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleWindow {
+    pub from: ScheduleTime,
+    pub to: ScheduleTime,
+    /// Days the window opens on - empty means every day.
+    #[serde(default)]
+    pub weekdays: Vec<ScheduleWeekday>,
+    /// IANA zone the window's times are in, e.g. `"America/New_York"` - resolved on every check,
+    /// so the window follows that zone's DST changes. Takes precedence over `utc_offset_minutes`.
+    #[serde(default)]
+    pub time_zone: Option<String>,
+    /// Fixed offset from UTC the window's times are in, e.g. `-300` for UTC-5 - used only when
+    /// `time_zone` is unset, and `None` for both is the machine's local time. A fixed offset never
+    /// follows DST: `-300` stays UTC-5 all year, an hour off New York's clock every summer.
+    #[serde(default)]
+    pub utc_offset_minutes: Option<i32>,
+}
+
+/// How a game without a saved custom order gets ordered - see `natural`'s module doc comment.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UnlockOrdering {
+    /// Strict global-percentage descending, nothing saved - the original behavior.
+    #[default]
+    Percent,
+    /// Generate (and save through `order::save`) a rarity-aware plan with jittered ordering and
+    /// per-achievement delays, the first time the game is scanned.
+    Natural,
+}
+
+/// Which distribution the delays the unlocker picks itself are drawn from - see
+/// `natural::draw_delay_minutes`. Every one stays within `interval`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DelayDistribution {
+    #[default]
+    Uniform,
+    LogNormal,
+    /// Resampled from `empirical_delays`.
+    Empirical,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AchievementUnlockerSettings {
@@ -49,8 +111,14 @@ pub struct AchievementUnlockerSettings {
     /// Random delay range (minutes) between unlocking consecutive achievements: `[min, max]`.
     pub interval: [u32; 2],
     pub schedule: bool,
+    /// The original single every-day local window - still what applies while `schedule_windows`
+    /// is empty (see [`Self::active_schedule`]).
     pub schedule_from: ScheduleTime,
     pub schedule_to: ScheduleTime,
+    /// Any number of windows, each with its own weekdays and offset - replaces
+    /// `schedule_from`/`schedule_to` entirely once non-empty.
+    #[serde(default)]
+    pub schedule_windows: Vec<ScheduleWindow>,
     pub next_task_checkbox: bool,
     /// What to start once the queue empties: `"cardFarming"` or `"autoIdle"`, matching `main`'s two
     /// options - see `achievement_unlocker::manager::maybe_start_next_task`. Deliberately a loose
@@ -59,6 +127,26 @@ pub struct AchievementUnlockerSettings {
     /// task-picker UI uses, for no real safety benefit over just matching on the string once, where
     /// `maybe_start_next_task` already does.
     pub next_task: Option<String>,
+    /// `#[serde(default)]` on this and the two fields below so an existing on-disk settings file
+    /// (serialized before they existed) still deserializes, to the original behavior.
+    #[serde(default)]
+    pub ordering: UnlockOrdering,
+    #[serde(default)]
+    pub delay_distribution: DelayDistribution,
+    /// Delay samples (minutes) for `DelayDistribution::Empirical` - typically gaps between a real
+    /// player's consecutive unlocks, taken from an import-timings lookup.
+    #[serde(default)]
+    pub empirical_delays: Vec<f64>,
+    /// Resume an interrupted run (see `checkpoint`) as soon as the account is ready instead of
+    /// asking first - applied by the frontend, like `multiple_games`.
+    #[serde(default)]
+    pub auto_resume: bool,
+    /// Walk a progress-based achievement's stat up to its threshold through `update_stats` before
+    /// unlocking it - see `achievements::progress`. Works in both sign-in modes: thresholds come
+    /// from the schema SteamUtility fetched (`AchievementDto::progress`), not the local Steam
+    /// client's cache. On by default, including for a settings file saved before this existed.
+    #[serde(default = "default_stat_progress")]
+    pub stat_progress: bool,
 }
 
 impl Default for AchievementUnlockerSettings {
@@ -79,12 +167,39 @@ impl Default for AchievementUnlockerSettings {
                 hour: 23,
                 minute: 0,
             },
+            schedule_windows: Vec::new(),
             next_task_checkbox: false,
             next_task: None,
+            ordering: UnlockOrdering::default(),
+            delay_distribution: DelayDistribution::default(),
+            empirical_delays: Vec::new(),
+            auto_resume: false,
+            stat_progress: true,
         }
     }
 }
 
+impl AchievementUnlockerSettings {
+    /// The windows unlocking is limited to, or `None` when `schedule` is off - `schedule_windows`,
+    /// or the legacy `schedule_from`/`schedule_to` pair as an every-day local window while that's
+    /// empty.
+    pub fn active_schedule(&self) -> Option<Vec<ScheduleWindow>> {
+        if !self.schedule {
+            return None;
+        }
+        if !self.schedule_windows.is_empty() {
+            return Some(self.schedule_windows.clone());
+        }
+        Some(vec![ScheduleWindow {
+            from: self.schedule_from,
+            to: self.schedule_to,
+            weekdays: Vec::new(),
+            time_zone: None,
+            utc_offset_minutes: None,
+        }])
+    }
+}
+
 #[derive(Debug, Default, Serialize, Deserialize)]
 struct CachedSettings {
     #[serde(default)]
@@ -148,12 +263,22 @@ pub async fn get(app_handle: &AppHandle, steam_id: &str) -> AppResult<Achievemen
 }
 
 /// Whole-struct replace, not a dot-path merge - the frontend always has the full settings object
-/// on hand (it just fetched it via `get`), so there's no partial-update case to support.
+/// on hand (it just fetched it via `get`), so there's no partial-update case to support. A window
+/// naming a zone `chrono-tz` doesn't know is refused here rather than silently read as local time.
 pub async fn set(
     app_handle: &AppHandle,
     steam_id: &str,
     settings: AchievementUnlockerSettings,
 ) -> AppResult<AchievementUnlockerSettings> {
+    if let Some(zone) = settings.schedule_windows.iter().find_map(|w| {
+        w.time_zone
+            .as_deref()
+            .filter(|zone| zone.parse::<chrono_tz::Tz>().is_err())
+    }) {
+        return Err(AppError::AchievementUnlockerTimeZoneInvalid(
+            zone.to_string(),
+        ));
+    }
     let _guard = WRITE_LOCK.lock().await;
     let mut cached = read_unlocked(app_handle, steam_id)?;
     cached.settings = settings;
@@ -0,0 +1,438 @@
+//! Dry-run projection of an unlocker session ([`AchievementUnlockerManager::simulate`]) - the
+//! queue, per-game `order`, max-unlocks overrides, max-playtime caps, schedule windows,
+//! [`INITIAL_DELAY`]/[`INTER_GAME_DELAY`] and worker count, run through `manager`'s own scan/plan
+//! helpers against a virtual clock instead of real waits. Nothing is idled, unlocked, saved or
+//! dequeued, and nothing here talks to Steam.
+//!
+//! **Achievement data comes from the caller** (`achievement_data`, keyed by app id - whatever the
+//! frontend already has loaded), since fetching it is exactly the Steam round trip a dry run must
+//! not make. A queued game with no entry is reported as [`SimulatedOutcome::NoAchievementData`]
+//! rather than guessed at.
+//!
+//! **Assumes every unlock succeeds first try** - no retry backoff, no failed achievement carried
+//! over to a later pass - so the projection is one pass long. Random delays (no custom
+//! `delay_next_unlock`) are drawn exactly like a real run's, so two simulations of the same queue
+//! differ the same way two real runs would; a `Natural` game with no saved plan gets an in-memory
+//! plan that isn't saved, so the real run will draw a different one.
+//!
+//! [`AchievementUnlockerManager::simulate`]: super::AchievementUnlockerManager::simulate
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::achievements::AchievementDto;
+use crate::error::AppResult;
+
+use super::manager::{
+    build_scanned_game, eligible_achievements, resolve_delays, ScannedGame, INITIAL_DELAY,
+    INTER_GAME_DELAY,
+};
+use super::settings::{ScheduleWindow, UnlockOrdering};
+use super::{cache, natural, order, schedule, settings, UpcomingAchievement};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SimulatedOutcome {
+    Finished,
+    MaxUnlocksReached,
+    /// Over its max-playtime cap at scan time, or projected to cross it partway through.
+    MaxPlaytime,
+    NothingToUnlock,
+    /// Not in the caller's `achievement_data` - see this module's doc comment.
+    NoAchievementData,
+    /// The schedule is on but none of its windows ever opens (e.g. `from == to`, or no windows at
+    /// all) - a real run would wait on this game forever.
+    ScheduleNeverOpens,
+}
+
+/// One queued game's projected run. `worker`/`started_at_ms`/`finished_at_ms` are `None` for a game
+/// dropped before the unlock phase (anything but `Finished`/`MaxUnlocksReached`/a mid-run
+/// `MaxPlaytime`).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedGame {
+    pub app_id: u32,
+    pub name: String,
+    pub outcome: SimulatedOutcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worker: Option<u32>,
+    /// When the game becomes active - after its worker's [`INITIAL_DELAY`]/[`INTER_GAME_DELAY`],
+    /// before its own `delay_before_first_unlock`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at_ms: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at_ms: Option<i64>,
+    /// Every projected unlock, in order - same shape as a live session's `upcoming` projection.
+    pub unlocks: Vec<UpcomingAchievement>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnlockSimulation {
+    pub worker_count: u32,
+    pub started_at_ms: i64,
+    /// The last game's finish - `started_at_ms` when nothing would run at all.
+    pub finished_at_ms: i64,
+    /// In queue order.
+    pub games: Vec<SimulatedGame>,
+}
+
+/// A game that survived the simulated scan phase, with everything [`run_timeline`] needs
+/// pre-resolved so the timeline itself is pure.
+struct ReadyGame {
+    queue_index: usize,
+    game: ScannedGame,
+    delays: Vec<u64>,
+    max_unlocks: Option<u32>,
+    baseline_playtime_minutes: u64,
+    max_playtime_minutes: Option<u32>,
+}
+
+fn dropped(app_id: u32, name: &str, outcome: SimulatedOutcome) -> SimulatedGame {
+    SimulatedGame {
+        app_id,
+        name: name.to_string(),
+        outcome,
+        worker: None,
+        started_at_ms: None,
+        finished_at_ms: None,
+        unlocks: Vec::new(),
+    }
+}
+
+/// Phase 2 against a virtual clock - mirrors `manager::run_unlock_phase`/`unlock_game`: each game
+/// goes to whichever worker frees up first (the real shared-queue work-stealing), a worker's first
+/// game waits [`INITIAL_DELAY`], later ones [`INTER_GAME_DELAY`] only single-worker and without a
+/// pre-delay of their own, and every unlock waits for [`schedule::next_opening`] first.
+fn run_timeline(
+    ready: Vec<ReadyGame>,
+    worker_count: u32,
+    windows: Option<&[ScheduleWindow]>,
+    start: DateTime<Utc>,
+) -> Vec<(usize, SimulatedGame)> {
+    let ms = chrono::Duration::milliseconds;
+    let mut free_at = vec![(start, true); worker_count as usize];
+    let mut results = Vec::new();
+
+    for ready_game in ready {
+        let ReadyGame {
+            queue_index,
+            game,
+            delays,
+            max_unlocks,
+            baseline_playtime_minutes,
+            max_playtime_minutes,
+        } = ready_game;
+
+        if let Some(windows) = windows {
+            if schedule::next_opening(windows, start).is_none() {
+                results.push((
+                    queue_index,
+                    dropped(
+                        game.app_id,
+                        &game.name,
+                        SimulatedOutcome::ScheduleNeverOpens,
+                    ),
+                ));
+                continue;
+            }
+        }
+
+        let (worker, (mut t, is_first)) = free_at
+            .iter()
+            .copied()
+            .enumerate()
+            .min_by_key(|(index, (at, _))| (*at, *index))
+            .expect("worker_count is at least 1");
+        if is_first {
+            t += ms(INITIAL_DELAY.as_millis() as i64);
+        } else if game.delay_before_first_unlock_ms == 0 && worker_count == 1 {
+            t += ms(INTER_GAME_DELAY.as_millis() as i64);
+        }
+        let started_at = t;
+        t += ms(game.delay_before_first_unlock_ms as i64);
+
+        let total = game.achievements.len() as u32;
+        let mut processed = 0;
+        let mut unlocks = Vec::new();
+        let mut outcome = SimulatedOutcome::Finished;
+        for (index, achievement) in game.achievements.iter().enumerate() {
+            let elapsed_minutes = (t - started_at).num_minutes().max(0) as u64;
+            if max_playtime_minutes
+                .is_some_and(|cap| baseline_playtime_minutes + elapsed_minutes >= u64::from(cap))
+            {
+                outcome = SimulatedOutcome::MaxPlaytime;
+                break;
+            }
+            if let Some(opens) = windows.and_then(|w| schedule::next_opening(w, t)) {
+                t = opens;
+            }
+
+            unlocks.push(UpcomingAchievement {
+                id: achievement.id.clone(),
+                name: achievement.name.clone(),
+                icon_locked: achievement.icon_locked.clone(),
+                percent: achievement.percent,
+                unlock_at_ms: t.timestamp_millis(),
+            });
+            processed += 1;
+            if processed == total {
+                break;
+            }
+            if max_unlocks.is_some_and(|m| processed >= m) {
+                outcome = SimulatedOutcome::MaxUnlocksReached;
+                break;
+            }
+            t += ms(delays[index] as i64);
+        }
+
+        free_at[worker] = (t, false);
+        results.push((
+            queue_index,
+            SimulatedGame {
+                app_id: game.app_id,
+                name: game.name,
+                outcome,
+                worker: Some(worker as u32),
+                started_at_ms: Some(started_at.timestamp_millis()),
+                finished_at_ms: Some(t.timestamp_millis()),
+                unlocks,
+            },
+        ));
+    }
+    results
+}
+
+/// Projects a run of `steam_id`'s current queue starting now - see this module's doc comment.
+/// `worker_count` is already clamped by the caller.
+pub async fn simulate(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    achievement_data: HashMap<u32, Vec<AchievementDto>>,
+    worker_count: u32,
+) -> AppResult<UnlockSimulation> {
+    let queue = cache::read(app_handle, steam_id).await?;
+    let unlocker_settings = settings::get(app_handle, steam_id).await?;
+    let playtime_by_app_id: HashMap<u32, u64> =
+        crate::games::commands::get_owned_games_cache(app_handle.clone(), steam_id.to_string())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|g| (g.app_id, g.playtime_forever_minutes))
+            .collect();
+
+    let mut results = Vec::new();
+    let mut ready = Vec::new();
+    for (queue_index, entry) in queue.iter().enumerate() {
+        let playtime = playtime_by_app_id.get(&entry.app_id).copied().unwrap_or(0);
+        let max_playtime_minutes = crate::max_playtime::settings::effective_max_playtime(
+            app_handle,
+            steam_id,
+            entry.app_id,
+        )
+        .await
+        .unwrap_or(None);
+        let dropped_with = |outcome| (queue_index, dropped(entry.app_id, &entry.name, outcome));
+
+        if max_playtime_minutes.is_some_and(|cap| playtime >= u64::from(cap)) {
+            results.push(dropped_with(SimulatedOutcome::MaxPlaytime));
+            continue;
+        }
+        let Some(achievements) = achievement_data.get(&entry.app_id) else {
+            results.push(dropped_with(SimulatedOutcome::NoAchievementData));
+            continue;
+        };
+        let Some(eligible) = eligible_achievements(achievements, &unlocker_settings) else {
+            results.push(dropped_with(SimulatedOutcome::NothingToUnlock));
+            continue;
+        };
+
+        let mut saved_order = order::get(app_handle, steam_id, entry.app_id)
+            .await
+            .ok()
+            .flatten();
+        if saved_order.is_none()
+            && unlocker_settings.ordering == UnlockOrdering::Natural
+            && !eligible.is_empty()
+        {
+            saved_order = Some(natural::plan(
+                &eligible,
+                &unlocker_settings,
+                &mut rand::thread_rng(),
+            ));
+        }
+        let max_unlocks = settings::get_max_unlocks(app_handle, steam_id, entry.app_id)
+            .await
+            .unwrap_or(None);
+        let game = build_scanned_game(entry, eligible, saved_order.as_ref(), max_unlocks);
+        if game.achievements.is_empty() {
+            results.push(dropped_with(SimulatedOutcome::NothingToUnlock));
+            continue;
+        }
+
+        let delays = resolve_delays(&game, &unlocker_settings, &mut rand::thread_rng());
+        ready.push(ReadyGame {
+            queue_index,
+            game,
+            delays,
+            max_unlocks,
+            baseline_playtime_minutes: playtime,
+            max_playtime_minutes,
+        });
+    }
+
+    let start = Utc::now();
+    results.extend(run_timeline(
+        ready,
+        worker_count,
+        unlocker_settings.active_schedule().as_deref(),
+        start,
+    ));
+    results.sort_by_key(|(queue_index, _)| *queue_index);
+    let games: Vec<SimulatedGame> = results.into_iter().map(|(_, game)| game).collect();
+
+    let started_at_ms = start.timestamp_millis();
+    let finished_at_ms = games
+        .iter()
+        .filter_map(|g| g.finished_at_ms)
+        .max()
+        .unwrap_or(started_at_ms);
+    Ok(UnlockSimulation {
+        worker_count,
+        started_at_ms,
+        finished_at_ms,
+        games,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::super::manager::ScannedAchievement;
+    use super::super::settings::ScheduleTime;
+    use super::*;
+
+    fn game(app_id: u32, count: usize, delay_before_first_unlock_ms: u64) -> ScannedGame {
+        ScannedGame {
+            app_id,
+            name: format!("game {app_id}"),
+            achievements: (0..count)
+                .map(|i| ScannedAchievement {
+                    id: format!("a{i}"),
+                    name: format!("a{i}"),
+                    icon_locked: String::new(),
+                    percent: None,
+                    delay_next_unlock_ms: None,
+                })
+                .collect(),
+            delay_before_first_unlock_ms,
+            achievement_count: count as u32,
+            resume: None,
+            stat_progress: HashMap::new(),
+        }
+    }
+
+    fn ready(queue_index: usize, game: ScannedGame, delay_ms: u64) -> ReadyGame {
+        let delays = vec![delay_ms; game.achievements.len()];
+        ReadyGame {
+            queue_index,
+            game,
+            delays,
+            max_unlocks: None,
+            baseline_playtime_minutes: 0,
+            max_playtime_minutes: None,
+        }
+    }
+
+    fn noon() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, 12, 0, 0).unwrap()
+    }
+
+    const MINUTE: i64 = 60_000;
+
+    #[test]
+    fn single_worker_paces_initial_and_inter_game_delays() {
+        let start = noon();
+        let games = vec![
+            ready(0, game(1, 2, 0), 30 * MINUTE as u64),
+            ready(1, game(2, 1, 0), 30 * MINUTE as u64),
+        ];
+        let results = run_timeline(games, 1, None, start);
+        let t0 = start.timestamp_millis();
+
+        let first = &results[0].1;
+        let unlock_times: Vec<i64> = first.unlocks.iter().map(|u| u.unlock_at_ms - t0).collect();
+        assert_eq!(unlock_times, vec![10_000, 10_000 + 30 * MINUTE]);
+        assert_eq!(first.finished_at_ms, Some(t0 + 10_000 + 30 * MINUTE));
+
+        let second = &results[1].1;
+        assert_eq!(
+            second.started_at_ms,
+            Some(t0 + 10_000 + 30 * MINUTE + 2 * MINUTE)
+        );
+        assert_eq!(second.outcome, SimulatedOutcome::Finished);
+    }
+
+    #[test]
+    fn next_game_goes_to_the_first_free_worker() {
+        let start = noon();
+        let games = vec![
+            ready(0, game(1, 3, 0), 60 * MINUTE as u64),
+            ready(1, game(2, 1, 0), 60 * MINUTE as u64),
+            ready(2, game(3, 1, 0), 60 * MINUTE as u64),
+        ];
+        let results = run_timeline(games, 2, None, start);
+        let workers: Vec<Option<u32>> = results.iter().map(|(_, g)| g.worker).collect();
+        // Worker 1 finishes its single-achievement game right after the initial delay, so it picks
+        // up the third game - and in multi-worker mode without an inter-game delay.
+        assert_eq!(workers, vec![Some(0), Some(1), Some(1)]);
+        assert_eq!(
+            results[2].1.unlocks[0].unlock_at_ms,
+            start.timestamp_millis() + 10_000
+        );
+    }
+
+    #[test]
+    fn max_unlocks_stops_early() {
+        let mut capped = ready(0, game(1, 5, 0), MINUTE as u64);
+        capped.max_unlocks = Some(2);
+        let results = run_timeline(vec![capped], 1, None, noon());
+        assert_eq!(results[0].1.unlocks.len(), 2);
+        assert_eq!(results[0].1.outcome, SimulatedOutcome::MaxUnlocksReached);
+    }
+
+    fn window(from: (u8, u8), to: (u8, u8)) -> ScheduleWindow {
+        ScheduleWindow {
+            from: ScheduleTime {
+                hour: from.0,
+                minute: from.1,
+            },
+            to: ScheduleTime {
+                hour: to.0,
+                minute: to.1,
+            },
+            weekdays: Vec::new(),
+            time_zone: None,
+            utc_offset_minutes: Some(0),
+        }
+    }
+
+    #[test]
+    fn unlocks_wait_for_the_schedule_window() {
+        let evening = [window((18, 0), (22, 0))];
+        let results = run_timeline(vec![ready(0, game(1, 1, 0), 0)], 1, Some(&evening), noon());
+        assert_eq!(
+            results[0].1.unlocks[0].unlock_at_ms,
+            Utc.with_ymd_and_hms(2026, 3, 2, 18, 0, 0)
+                .unwrap()
+                .timestamp_millis()
+        );
+
+        let never = [window((9, 0), (9, 0))];
+        let results = run_timeline(vec![ready(0, game(1, 1, 0), 0)], 1, Some(&never), noon());
+        assert_eq!(results[0].1.outcome, SimulatedOutcome::ScheduleNeverOpens);
+    }
+}
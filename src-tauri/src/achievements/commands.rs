@@ -1,11 +1,17 @@
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
 
 use crate::error::AppResult;
-use crate::games::commands::GamesAccount;
+use crate::games::commands::{resolve_steam_id, GamesAccount};
 use crate::local_steam;
 use crate::local_steam::commands::require_steam_running;
+use crate::local_steam::user_game_stats::LocalAchievementData;
 use crate::steam_agent::AgentManager;
 
+use super::data_cache::{self, AchievementDataResponse};
+use super::library::{self, LibraryScanResult, LibraryStats};
+use super::snapshots::{
+    self, RestoreResult, SelectionSnapshotResult, SnapshotFailure, SnapshotReason, SnapshotSummary,
+};
 use super::{AchievementData, BulkAchievementResult, StatUpdate};
 
 /// Fetches achievement/stat data for `app_id`. One command for both sign-in modes, branching
@@ -16,18 +22,91 @@ use super::{AchievementData, BulkAchievementResult, StatUpdate};
 /// Steam schema language key via `steam_language::steam_language_for_locale`. Only agent mode
 /// uses it - CLI mode already gets live-localized text from the local Steam client itself, see
 /// that module's doc comment for why this app's locale can't influence CLI mode the same way.
+///
+/// Reads and writes through `data_cache` - a live result is cached with its fetch time, and a
+/// failed live fetch serves the cached copy marked stale instead, failing only when there's none.
+/// CLI mode tries the local Steam client's own stats cache ([`read_local_achievement_data`])
+/// before that, marked stale as of when the client last wrote it - it's usually the fresher of the
+/// two, and covers games this app has never fetched.
 #[tauri::command]
 pub async fn get_achievement_data(
+    app_handle: AppHandle,
     agent_manager: State<'_, AgentManager>,
     account: GamesAccount,
     app_id: u32,
     locale: String,
+) -> AppResult<AchievementDataResponse> {
+    let live = fetch_achievement_data(&agent_manager, account.clone(), app_id, &locale).await;
+    // Resolved after the fetch - a failed resolve (agent mode with no live session) only means
+    // there's no cache to write to or fall back on, not that the fetch itself failed.
+    let steam_id = resolve_steam_id(&account, &agent_manager).await.ok();
+    match live {
+        Ok(data) => {
+            let fetched_at_ms = chrono::Utc::now().timestamp_millis();
+            if let Some(steam_id) = &steam_id {
+                if let Err(e) =
+                    data_cache::write(&app_handle, steam_id, app_id, &data, fetched_at_ms).await
+                {
+                    tracing::warn!(app_id, error = %e.code(), "achievement manager: failed to cache achievement data");
+                }
+            }
+            Ok(AchievementDataResponse {
+                data,
+                fetched_at_ms,
+                stale_since_ms: None,
+                stale_reason: None,
+            })
+        }
+        Err(e) => {
+            let Some(steam_id) = steam_id else {
+                return Err(e);
+            };
+            if matches!(account, GamesAccount::Local { .. }) {
+                match read_local_achievement_data(&steam_id, app_id, &locale).await {
+                    Ok(Some(local)) => {
+                        tracing::info!(app_id, error = %e.code(), "achievement manager: live fetch failed, serving the local Steam client's cached stats");
+                        return Ok(AchievementDataResponse {
+                            data: local.data,
+                            fetched_at_ms: local.cached_at_ms,
+                            stale_since_ms: Some(local.cached_at_ms),
+                            stale_reason: Some(e.code()),
+                        });
+                    }
+                    Ok(None) => {}
+                    Err(local_error) => {
+                        tracing::warn!(app_id, error = %local_error.code(), "achievement manager: failed to read the local Steam client's cached stats");
+                    }
+                }
+            }
+            match data_cache::read_stale(&app_handle, &steam_id, app_id, e.code()).await {
+                Ok(Some(cached)) => {
+                    tracing::info!(app_id, error = %e.code(), "achievement manager: live fetch failed, serving cached achievement data");
+                    Ok(cached)
+                }
+                Ok(None) => Err(e),
+                Err(cache_error) => {
+                    tracing::warn!(app_id, error = %cache_error.code(), "achievement manager: failed to read cached achievement data");
+                    Err(e)
+                }
+            }
+        }
+    }
+}
+
+/// The live half of `get_achievement_data`, with no cache on either side - for backend callers
+/// (the achievement unlocker, snapshots, the library scan) that act on what they fetch and so must
+/// never be handed a stale copy.
+pub(crate) async fn fetch_achievement_data(
+    agent_manager: &AgentManager,
+    account: GamesAccount,
+    app_id: u32,
+    locale: &str,
 ) -> AppResult<AchievementData> {
     let mut data = match account {
         GamesAccount::Agent { username } => {
-            let steam_language = super::steam_language::steam_language_for_locale(&locale);
+            let steam_language = super::steam_language::steam_language_for_locale(locale);
             achievements_get_agent_racing_schema_check(
-                &agent_manager,
+                agent_manager,
                 &username,
                 app_id,
                 steam_language,
@@ -42,6 +121,24 @@ pub async fn get_achievement_data(
     Ok(data)
 }
 
+/// CLI mode's offline source - the local Steam client's own stats cache
+/// (`local_steam::user_game_stats`), in `locale`'s schema language and with percentages backfilled
+/// like a live agent-mode fetch. `None` when the client hasn't cached `app_id` for this account.
+pub(crate) async fn read_local_achievement_data(
+    steam_id: &str,
+    app_id: u32,
+    locale: &str,
+) -> AppResult<Option<LocalAchievementData>> {
+    let steam_language = super::steam_language::steam_language_for_locale(locale);
+    let Some(mut local) =
+        local_steam::user_game_stats::read_achievement_data(steam_id, app_id, steam_language)?
+    else {
+        return Ok(None);
+    };
+    backfill_global_percentages(&mut local.data, app_id).await;
+    Ok(Some(local))
+}
+
 /// Races the daemon's `achievements_get` round trip against `web_api::confirm_empty_schema` - only
 /// agent mode hits the slow ~7-10s daemon response for a schema-less title (CLI mode's
 /// `SteamworksSession` already resolves that case near-instantly, see its `RequestUserStats` doc
@@ -109,13 +206,14 @@ async fn backfill_global_percentages(data: &mut AchievementData, app_id: u32) {
 /// desired `unlock` flag directly (`!achieved` for a toggle-style click).
 #[tauri::command]
 pub async fn set_achievement(
+    app_handle: AppHandle,
     agent_manager: State<'_, AgentManager>,
     account: GamesAccount,
     app_id: u32,
     achievement_id: String,
     unlock: bool,
 ) -> AppResult<()> {
-    let result = match account {
+    let result = match account.clone() {
         GamesAccount::Agent { username } => {
             agent_manager
                 .set_achievement(&username, app_id, achievement_id.clone(), unlock)
@@ -134,16 +232,25 @@ pub async fn set_achievement(
             tracing::warn!(app_id, achievement_id, unlock, error = %e, "achievement manager: set achievement failed")
         }
     }
+    invalidate_cached(&app_handle, &account, app_id).await;
     result
 }
 
 #[tauri::command]
 pub async fn unlock_all_achievements(
+    app_handle: AppHandle,
     agent_manager: State<'_, AgentManager>,
     account: GamesAccount,
     app_id: u32,
 ) -> AppResult<BulkAchievementResult> {
-    let result = match account {
+    snapshot_before(
+        &app_handle,
+        &account,
+        app_id,
+        SnapshotReason::BeforeUnlockAll,
+    )
+    .await?;
+    let result = match account.clone() {
         GamesAccount::Agent { username } => {
             agent_manager
                 .bulk_set_achievements(&username, app_id, true)
@@ -164,16 +271,19 @@ pub async fn unlock_all_achievements(
             "achievement manager: bulk set"
         );
     }
+    invalidate_cached(&app_handle, &account, app_id).await;
     result
 }
 
 #[tauri::command]
 pub async fn lock_all_achievements(
+    app_handle: AppHandle,
     agent_manager: State<'_, AgentManager>,
     account: GamesAccount,
     app_id: u32,
 ) -> AppResult<BulkAchievementResult> {
-    let result = match account {
+    snapshot_before(&app_handle, &account, app_id, SnapshotReason::BeforeLockAll).await?;
+    let result = match account.clone() {
         GamesAccount::Agent { username } => {
             agent_manager
                 .bulk_set_achievements(&username, app_id, false)
@@ -194,20 +304,40 @@ pub async fn lock_all_achievements(
             "achievement manager: bulk set"
         );
     }
+    invalidate_cached(&app_handle, &account, app_id).await;
     result
 }
 
+/// No automatic snapshot, unlike the other bulk commands - a stat save is routine, and a snapshot
+/// per save would add a live fetch to every one and push real restore points out of the
+/// [`snapshots::MAX_AUTOMATIC_SNAPSHOTS_PER_GAME`] cap. The statistics tab's staged save asks for
+/// one `BeforeBulkEdit` snapshot itself first, the same way "Apply changes" does for achievements.
 #[tauri::command]
 pub async fn update_stats(
-    agent_manager: State<'_, AgentManager>,
+    app_handle: AppHandle,
+    account: GamesAccount,
+    app_id: u32,
+    stats: Vec<StatUpdate>,
+) -> AppResult<()> {
+    write_stats(&app_handle, account, app_id, stats).await
+}
+
+/// `update_stats`'s write - shared with `restore_snapshot`, which already took its own
+/// `BeforeRestore` snapshot, and the achievement unlocker's stat-progress steps
+/// (`achievement_unlocker::manager::advance_stats`).
+pub(crate) async fn write_stats(
+    app_handle: &AppHandle,
     account: GamesAccount,
     app_id: u32,
     stats: Vec<StatUpdate>,
 ) -> AppResult<()> {
     let stat_count = stats.len();
-    let result = match account {
+    let result = match account.clone() {
         GamesAccount::Agent { username } => {
-            agent_manager.update_stats(&username, app_id, stats).await
+            app_handle
+                .state::<AgentManager>()
+                .update_stats(&username, app_id, stats)
+                .await
         }
         GamesAccount::Local { .. } => {
             require_steam_running()?;
@@ -217,16 +347,25 @@ pub async fn update_stats(
     if result.is_ok() {
         tracing::info!(app_id, stat_count, "achievement manager: stats updated");
     }
+    invalidate_cached(app_handle, &account, app_id).await;
     result
 }
 
 #[tauri::command]
 pub async fn reset_all_stats(
+    app_handle: AppHandle,
     agent_manager: State<'_, AgentManager>,
     account: GamesAccount,
     app_id: u32,
 ) -> AppResult<()> {
-    let result = match account {
+    snapshot_before(
+        &app_handle,
+        &account,
+        app_id,
+        SnapshotReason::BeforeResetStats,
+    )
+    .await?;
+    let result = match account.clone() {
         GamesAccount::Agent { username } => agent_manager.reset_all_stats(&username, app_id).await,
         GamesAccount::Local { .. } => {
             require_steam_running()?;
@@ -236,5 +375,312 @@ pub async fn reset_all_stats(
     if result.is_ok() {
         tracing::info!(app_id, "achievement manager: stats reset");
     }
+    invalidate_cached(&app_handle, &account, app_id).await;
     result
 }
+
+/// Drops `app_id`'s offline copy after a write to it - see `data_cache` - and marks its library
+/// summary for the next scan (`library::mark_dirty`). Runs whatever the write's outcome, since a
+/// failed or partial write may still have changed something. Best-effort: a copy that survives is
+/// still only ever served marked stale.
+async fn invalidate_cached(app_handle: &AppHandle, account: &GamesAccount, app_id: u32) {
+    let result = match resolve_steam_id(account, &app_handle.state::<AgentManager>()).await {
+        Ok(steam_id) => {
+            let cached = data_cache::invalidate(app_handle, &steam_id, app_id).await;
+            let library = library::mark_dirty(app_handle, &steam_id, app_id).await;
+            cached.and(library)
+        }
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        tracing::warn!(app_id, error = %e.code(), "achievement manager: failed to invalidate cached achievement data");
+    }
+}
+
+/// Fetches `app_id`'s live state for a snapshot. Backend-initiated, no frontend locale to follow -
+/// the same "english" `achievement_unlocker::manager` passes, since restoring matches by id and the
+/// stored names are only ever a label.
+async fn fetch_live(
+    app_handle: &AppHandle,
+    account: &GamesAccount,
+    app_id: u32,
+) -> AppResult<AchievementData> {
+    fetch_achievement_data(
+        &app_handle.state::<AgentManager>(),
+        account.clone(),
+        app_id,
+        "english",
+    )
+    .await
+}
+
+/// The automatic snapshot every destructive bulk command takes first. A failed snapshot fails the
+/// command itself rather than going ahead unprotected - the whole point is that the operation
+/// after it can't otherwise be undone.
+async fn snapshot_before(
+    app_handle: &AppHandle,
+    account: &GamesAccount,
+    app_id: u32,
+    reason: SnapshotReason,
+) -> AppResult<()> {
+    let steam_id = resolve_steam_id(account, &app_handle.state::<AgentManager>()).await?;
+    let data = fetch_live(app_handle, account, app_id).await?;
+    let snapshot = snapshots::take(app_handle, &steam_id, app_id, data, reason, None, None).await?;
+    tracing::info!(app_id, snapshot_id = %snapshot.id, ?reason, "achievement manager: automatic snapshot taken");
+    Ok(())
+}
+
+/// Snapshots one game on demand. `reason` defaults to `Manual`; the achievement manager's staged
+/// "Apply changes" passes `BeforeBulkEdit` so its snapshot is pruned like the other automatic ones.
+#[tauri::command]
+pub async fn take_achievement_snapshot(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    account: GamesAccount,
+    app_id: u32,
+    label: Option<String>,
+    reason: Option<SnapshotReason>,
+) -> AppResult<SnapshotSummary> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    let data = fetch_live(&app_handle, &account, app_id).await?;
+    snapshots::take(
+        &app_handle,
+        &steam_id,
+        app_id,
+        data,
+        reason.unwrap_or(SnapshotReason::Manual),
+        label,
+        None,
+    )
+    .await
+}
+
+/// Snapshots every game in `app_ids` under one shared `batch_id`. Per-game failures are reported
+/// in `failed` and don't stop the rest of the selection.
+#[tauri::command]
+pub async fn take_achievement_snapshots(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    account: GamesAccount,
+    app_ids: Vec<u32>,
+    label: Option<String>,
+) -> AppResult<SelectionSnapshotResult> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    let batch_id = snapshots::new_id(chrono::Utc::now().timestamp_millis());
+    let mut result = SelectionSnapshotResult {
+        batch_id: batch_id.clone(),
+        taken: Vec::new(),
+        failed: Vec::new(),
+    };
+    for app_id in app_ids {
+        let taken = match fetch_live(&app_handle, &account, app_id).await {
+            Ok(data) => {
+                snapshots::take(
+                    &app_handle,
+                    &steam_id,
+                    app_id,
+                    data,
+                    SnapshotReason::Manual,
+                    label.clone(),
+                    Some(batch_id.clone()),
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+        match taken {
+            Ok(summary) => result.taken.push(summary),
+            Err(e) => {
+                tracing::warn!(app_id, error = %e.code(), "achievement manager: snapshot failed for selected game");
+                result.failed.push(SnapshotFailure {
+                    app_id,
+                    error: e.code(),
+                });
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Newest first; `app_id: None` lists every game's.
+#[tauri::command]
+pub async fn list_achievement_snapshots(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    account: GamesAccount,
+    app_id: Option<u32>,
+) -> AppResult<Vec<SnapshotSummary>> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    snapshots::list(&app_handle, &steam_id, app_id).await
+}
+
+#[tauri::command]
+pub async fn delete_achievement_snapshot(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    account: GamesAccount,
+    app_id: u32,
+    snapshot_id: String,
+) -> AppResult<()> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    snapshots::delete(&app_handle, &steam_id, app_id, &snapshot_id).await
+}
+
+/// Rolls one game back to a snapshot - see `snapshots`' module doc comment.
+#[tauri::command]
+pub async fn restore_achievement_snapshot(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    account: GamesAccount,
+    app_id: u32,
+    snapshot_id: String,
+) -> AppResult<RestoreResult> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    let snapshot = snapshots::load(&app_handle, &steam_id, app_id, &snapshot_id).await?;
+    restore_snapshot(&app_handle, &account, &steam_id, snapshot).await
+}
+
+/// Restores every game of one `take_achievement_snapshots` selection. A game that fails outright
+/// gets a result with `error` set instead of failing the others.
+#[tauri::command]
+pub async fn restore_achievement_snapshot_batch(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    account: GamesAccount,
+    batch_id: String,
+) -> AppResult<Vec<RestoreResult>> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    let members: Vec<SnapshotSummary> = snapshots::list(&app_handle, &steam_id, None)
+        .await?
+        .into_iter()
+        .filter(|s| s.batch_id.as_deref() == Some(batch_id.as_str()))
+        .collect();
+    let mut results = Vec::new();
+    for member in members {
+        let restored =
+            match snapshots::load(&app_handle, &steam_id, member.app_id, &member.id).await {
+                Ok(snapshot) => restore_snapshot(&app_handle, &account, &steam_id, snapshot).await,
+                Err(e) => Err(e),
+            };
+        results.push(restored.unwrap_or_else(|e| {
+            tracing::warn!(app_id = member.app_id, error = %e.code(), "achievement manager: snapshot restore failed for batch member");
+            RestoreResult {
+                app_id: member.app_id,
+                error: Some(e.code()),
+                ..RestoreResult::default()
+            }
+        }));
+    }
+    Ok(results)
+}
+
+/// Scans every owned game's achievements and returns library-wide completion stats, emitting
+/// `library::ACHIEVEMENT_LIBRARY_SCAN_EVENT` as it goes. Only games played since their last scan
+/// are refetched unless `full` is set - see `library`'s module doc comment. `locale` is passed
+/// through to `get_achievement_data`, since the rarest-achievements list shows names.
+#[tauri::command]
+pub async fn scan_achievement_library(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    account: GamesAccount,
+    locale: String,
+    full: Option<bool>,
+) -> AppResult<LibraryScanResult> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    library::scan(
+        &app_handle,
+        &account,
+        &steam_id,
+        &locale,
+        full.unwrap_or(false),
+    )
+    .await
+}
+
+/// The last scan's stats, straight from cache - empty stats if the library was never scanned.
+#[tauri::command]
+pub async fn get_achievement_library_stats(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    account: GamesAccount,
+) -> AppResult<LibraryStats> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    let games = library::read(&app_handle, &steam_id).await?;
+    Ok(library::summarize(&games))
+}
+
+/// Diffs `snapshot` against the live state and writes back only what differs, after snapshotting
+/// the state it's about to replace. Achievements go one `set_achievement` at a time (a failure is
+/// recorded and the rest carry on, like `applyStagedChanges`); stats go in one `update_stats`.
+async fn restore_snapshot(
+    app_handle: &AppHandle,
+    account: &GamesAccount,
+    steam_id: &str,
+    snapshot: snapshots::AchievementSnapshot,
+) -> AppResult<RestoreResult> {
+    let app_id = snapshot.app_id;
+    let live = fetch_live(app_handle, account, app_id).await?;
+    let plan = snapshots::plan_restore(&snapshot.data, &live);
+    let mut result = RestoreResult {
+        app_id,
+        skipped: plan.skipped.clone(),
+        ..RestoreResult::default()
+    };
+    if plan.unlock.is_empty() && plan.lock.is_empty() && plan.stats.is_empty() {
+        return Ok(result);
+    }
+
+    let undo = snapshots::take(
+        app_handle,
+        steam_id,
+        app_id,
+        live,
+        SnapshotReason::BeforeRestore,
+        None,
+        None,
+    )
+    .await?;
+    result.undo_snapshot_id = Some(undo.id);
+
+    for (ids, unlock) in [(&plan.unlock, true), (&plan.lock, false)] {
+        for id in ids {
+            let set = set_achievement(
+                app_handle.clone(),
+                app_handle.state::<AgentManager>(),
+                account.clone(),
+                app_id,
+                id.clone(),
+                unlock,
+            )
+            .await;
+            match (set, unlock) {
+                (Ok(()), true) => result.unlocked.push(id.clone()),
+                (Ok(()), false) => result.locked.push(id.clone()),
+                (Err(_), _) => result.failed.push(id.clone()),
+            }
+        }
+    }
+
+    if !plan.stats.is_empty() {
+        let ids: Vec<String> = plan.stats.iter().map(|s| s.name.clone()).collect();
+        match write_stats(app_handle, account.clone(), app_id, plan.stats).await {
+            Ok(()) => result.stats_updated = ids,
+            Err(e) => {
+                tracing::warn!(app_id, error = %e.code(), "achievement manager: stat restore failed");
+                result.failed.extend(ids);
+            }
+        }
+    }
+
+    tracing::info!(
+        app_id,
+        snapshot_id = %snapshot.id,
+        unlocked = result.unlocked.len(),
+        locked = result.locked.len(),
+        stats = result.stats_updated.len(),
+        failed = result.failed.len(),
+        skipped = result.skipped.len(),
+        "achievement manager: snapshot restored"
+    );
+    Ok(result)
+}
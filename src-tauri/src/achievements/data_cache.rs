@@ -0,0 +1,118 @@
+//! Offline copy of each game's last successful `get_achievement_data` fetch, per account and per
+//! game (`<steam_id>/achievement_data/<app_id>.json`, one file per game for the same reason
+//! `achievement_unlocker::order` uses one). `commands::get_achievement_data` writes through it on
+//! every live fetch and falls back to it when the live fetch fails - most often CLI mode with
+//! Steam closed - so a game's achievements stay browsable, marked stale (see
+//! [`AchievementDataResponse`]).
+//!
+//! **Invalidated, not patched, on writes** - `set_achievement`/`update_stats` (and the bulk/reset
+//! commands) drop the game's file rather than editing it in place, since only a fresh fetch knows
+//! what a write actually changed server-side (protected items, increment-only stats, a partial
+//! bulk result).
+//!
+//! Agent mode can only fall back while its session still resolves a SteamID64
+//! (`AgentManager::steam_id`) - with no session at all there's no account to key the lookup by.
+
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tokio::sync::Mutex;
+
+use crate::error::{AppError, AppResult};
+use crate::fs_utils::atomic_write_json;
+use crate::platform;
+
+use super::AchievementData;
+
+const CACHE_DIR_NAME: &str = "achievement_data";
+
+static WRITE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedAchievementData {
+    fetched_at_ms: i64,
+    data: AchievementData,
+}
+
+/// `get_achievement_data`'s return shape - the data itself (flattened, so it reads exactly like an
+/// `AchievementData`) plus when it was fetched. `stale_since_ms` is only set when the live fetch
+/// failed and this is the cached copy - it equals `fetched_at_ms`, and `stale_reason` is the
+/// failed fetch's `AppError::code`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AchievementDataResponse {
+    #[serde(flatten)]
+    pub data: AchievementData,
+    pub fetched_at_ms: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stale_since_ms: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stale_reason: Option<String>,
+}
+
+fn cache_file_path(app_handle: &AppHandle, steam_id: &str, app_id: u32) -> AppResult<PathBuf> {
+    Ok(platform::cache_dir(app_handle)?
+        .join(steam_id)
+        .join(CACHE_DIR_NAME)
+        .join(format!("{app_id}.json")))
+}
+
+/// The cached copy as a stale response, or `None` if the game was never cached (or was invalidated
+/// since).
+pub async fn read_stale(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    app_id: u32,
+    stale_reason: String,
+) -> AppResult<Option<AchievementDataResponse>> {
+    let path = cache_file_path(app_handle, steam_id, app_id)?;
+    let _guard = WRITE_LOCK.lock().await;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::AchievementDataCacheIo(e.to_string()))?;
+    let cached: CachedAchievementData = serde_json::from_str(&contents)
+        .map_err(|e| AppError::AchievementDataCacheIo(e.to_string()))?;
+    Ok(Some(AchievementDataResponse {
+        data: cached.data,
+        fetched_at_ms: cached.fetched_at_ms,
+        stale_since_ms: Some(cached.fetched_at_ms),
+        stale_reason: Some(stale_reason),
+    }))
+}
+
+pub async fn write(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    app_id: u32,
+    data: &AchievementData,
+    fetched_at_ms: i64,
+) -> AppResult<()> {
+    let path = cache_file_path(app_handle, steam_id, app_id)?;
+    let _guard = WRITE_LOCK.lock().await;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::AchievementDataCacheIo(e.to_string()))?;
+    }
+    atomic_write_json(
+        &path,
+        &CachedAchievementData {
+            fetched_at_ms,
+            data: data.clone(),
+        },
+    )
+    .map_err(|e| AppError::AchievementDataCacheIo(e.to_string()))
+}
+
+pub async fn invalidate(app_handle: &AppHandle, steam_id: &str, app_id: u32) -> AppResult<()> {
+    let path = cache_file_path(app_handle, steam_id, app_id)?;
+    let _guard = WRITE_LOCK.lock().await;
+    if path.exists() {
+        std::fs::remove_file(&path).map_err(|e| AppError::AchievementDataCacheIo(e.to_string()))?;
+    }
+    Ok(())
+}
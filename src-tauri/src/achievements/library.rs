@@ -0,0 +1,575 @@
+//! Library-wide achievement completion - walks every owned game (`games::commands::
+//! get_owned_games_cache`), reads each one's achievement data, and caches a per-game summary
+//! (`<steam_id>/achievement_library.json`) that [`summarize`] turns into completion statistics.
+//! Never through `get_achievement_data`'s offline copy (`data_cache`) - a game's data comes either
+//! from the local Steam client or straight from Steam (`commands::fetch_achievement_data`).
+//!
+//! - **Local first in CLI mode** - a game the local Steam client has cached stats for is read from
+//!   that cache (`commands::read_local_achievement_data`), with no SteamUtility process and no
+//!   running client; only the rest are fetched live. That copy is only as fresh as the client last
+//!   synced it, so its age is kept with the summary ([`LibraryGame::stats_cached_at_ms`]).
+//! - **Incremental** - a later scan only refetches games whose `playtime_forever_minutes` changed
+//!   since they were last scanned (or that were never scanned), since achievements are only
+//!   earned by playing - or that the achievement manager wrote to since ([`mark_dirty`]), which
+//!   are fetched live even in CLI mode since the client's cache may not have caught up with the
+//!   write yet. CLI mode without a Web API key has no playtime at all, so there every unwritten
+//!   game looks unchanged after the first pass - `full` forces a complete rescan for that case.
+//! - **Bounded** - at most [`SCAN_CONCURRENCY`] fetches in flight, below
+//!   `local_steam::achievements`' own process cap so a scan never starves the achievement manager.
+//! - **Failures are per game** - a game that can't be fetched (Game Coordinator titles in agent
+//!   mode, a crashed SteamUtility process) keeps whatever it had cached and is reported back by its
+//!   `AppError::code`, never failing the whole scan.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex as StdMutex};
+
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Mutex;
+
+use crate::error::{AppError, AppResult};
+use crate::fs_utils::atomic_write_json;
+use crate::games::commands::GamesAccount;
+use crate::games::OwnedGame;
+use crate::platform;
+use crate::steam_agent::AgentManager;
+
+use super::AchievementData;
+
+const LIBRARY_FILE_NAME: &str = "achievement_library.json";
+
+/// Concurrent `fetch_achievement_data` fetches per scan.
+pub const SCAN_CONCURRENCY: usize = 4;
+
+/// The cache is written every this many fetched games, not just at the end, so closing the app
+/// partway through a first scan of a large library doesn't throw the finished part away.
+const PERSIST_EVERY: u32 = 25;
+
+/// How many of the rarest held achievements [`summarize`] reports.
+const RAREST_LIMIT: usize = 10;
+
+/// Emitted after each fetched game, with `{ steamId, progress: LibraryScanProgress }`.
+pub const ACHIEVEMENT_LIBRARY_SCAN_EVENT: &str = "achievement-library-scan-progress";
+
+static WRITE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+/// Accounts with a scan in flight - a second scan for the same account is rejected rather than
+/// doubling every fetch.
+static RUNNING: LazyLock<StdMutex<HashSet<String>>> =
+    LazyLock::new(|| StdMutex::new(HashSet::new()));
+
+struct RunningGuard(String);
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        RUNNING.lock().unwrap().remove(&self.0);
+    }
+}
+
+/// One unlocked achievement, kept for the rarest-achievements list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeldAchievement {
+    pub id: String,
+    pub name: String,
+    pub icon_normal: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percent: Option<f32>,
+}
+
+/// One game's cached scan result. `playtime_forever_minutes` is the playtime at scan time - what
+/// the next scan compares against.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryGame {
+    pub app_id: u32,
+    pub name: Option<String>,
+    pub playtime_forever_minutes: u64,
+    pub scanned_at_ms: i64,
+    /// When the local Steam client cached the stats this summary was read from - `None` when they
+    /// were fetched live. See the module doc comment's "local first".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats_cached_at_ms: Option<i64>,
+    /// When the achievement manager last wrote to this game after it was scanned - see
+    /// [`mark_dirty`]. Cleared by the rescan it forces.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dirty_since_ms: Option<i64>,
+    pub total: u32,
+    pub unlocked: u32,
+    pub held: Vec<HeldAchievement>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CachedLibrary {
+    games: Vec<LibraryGame>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameCompletion {
+    pub app_id: u32,
+    pub name: Option<String>,
+    pub unlocked: u32,
+    pub total: u32,
+    /// `0..=100`.
+    pub completion: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RareAchievement {
+    pub app_id: u32,
+    pub game_name: Option<String>,
+    pub id: String,
+    pub name: String,
+    pub icon_normal: String,
+    pub percent: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryStats {
+    /// Every game with at least one achievement, most complete first.
+    pub games: Vec<GameCompletion>,
+    pub perfect_games: Vec<GameCompletion>,
+    /// Mean of `completion` over games with at least one achievement unlocked - Steam's own
+    /// "average game completion rate" definition, so an untouched backlog doesn't drag it down.
+    /// `None` until something is unlocked.
+    pub average_completion: Option<f64>,
+    pub achievements_unlocked: u32,
+    /// Held achievements with the lowest global unlock percentage, rarest first.
+    pub rarest: Vec<RareAchievement>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_scanned_at_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryScanFailure {
+    pub app_id: u32,
+    pub name: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryScanProgress {
+    pub checked: u32,
+    pub total: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryScanResult {
+    /// Games fetched this run.
+    pub scanned: u32,
+    /// Games whose cached result was reused because their playtime hadn't changed.
+    pub unchanged: u32,
+    pub failed: Vec<LibraryScanFailure>,
+    pub stats: LibraryStats,
+}
+
+fn library_file_path(app_handle: &AppHandle, steam_id: &str) -> AppResult<PathBuf> {
+    Ok(platform::cache_dir(app_handle)?
+        .join(steam_id)
+        .join(LIBRARY_FILE_NAME))
+}
+
+pub async fn read(app_handle: &AppHandle, steam_id: &str) -> AppResult<Vec<LibraryGame>> {
+    let path = library_file_path(app_handle, steam_id)?;
+    let _guard = WRITE_LOCK.lock().await;
+    read_unlocked(&path)
+}
+
+fn read_unlocked(path: &Path) -> AppResult<Vec<LibraryGame>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents =
+        std::fs::read_to_string(path).map_err(|e| AppError::AchievementLibraryIo(e.to_string()))?;
+    if contents.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let cached: CachedLibrary = serde_json::from_str(&contents)
+        .map_err(|e| AppError::AchievementLibraryIo(e.to_string()))?;
+    Ok(cached.games)
+}
+
+fn write_unlocked(path: &Path, mut games: Vec<LibraryGame>) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::AchievementLibraryIo(e.to_string()))?;
+    }
+    games.sort_by_key(|g| g.app_id);
+    atomic_write_json(path, &CachedLibrary { games })
+        .map_err(|e| AppError::AchievementLibraryIo(e.to_string()))
+}
+
+/// A scan's in-progress copy, with any [`mark_dirty`] that landed on disk since the scan read it
+/// carried over - see [`carry_dirty`].
+async fn write(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    games: &HashMap<u32, LibraryGame>,
+) -> AppResult<()> {
+    let path = library_file_path(app_handle, steam_id)?;
+    let _guard = WRITE_LOCK.lock().await;
+    let mut games = games.clone();
+    carry_dirty(&mut games, read_unlocked(&path).unwrap_or_default());
+    write_unlocked(&path, games.into_values().collect())
+}
+
+/// Keeps a dirty mark from `on_disk` unless the scan's own entry was fetched after it was made -
+/// a write that lands mid-scan must still force the next scan, not be overwritten by the copy the
+/// scan started from.
+fn carry_dirty(games: &mut HashMap<u32, LibraryGame>, on_disk: Vec<LibraryGame>) {
+    for disk in on_disk {
+        let Some(dirty_since_ms) = disk.dirty_since_ms else {
+            continue;
+        };
+        if let Some(game) = games.get_mut(&disk.app_id) {
+            if game.scanned_at_ms <= dirty_since_ms {
+                game.dirty_since_ms = Some(dirty_since_ms);
+            }
+        }
+    }
+}
+
+/// Flags `app_id` for the next scan after the achievement manager wrote to it - playtime alone
+/// never changes for a write, so without this the summary would keep the pre-write counts until
+/// the game is next played. A game that was never scanned needs nothing: it's scanned anyway.
+pub async fn mark_dirty(app_handle: &AppHandle, steam_id: &str, app_id: u32) -> AppResult<()> {
+    let path = library_file_path(app_handle, steam_id)?;
+    let _guard = WRITE_LOCK.lock().await;
+    let mut games = read_unlocked(&path)?;
+    let Some(game) = games.iter_mut().find(|g| g.app_id == app_id) else {
+        return Ok(());
+    };
+    game.dirty_since_ms = Some(chrono::Utc::now().timestamp_millis());
+    write_unlocked(&path, games)
+}
+
+/// Whether `game` needs fetching again - never scanned, played since, or written to since.
+fn needs_scan(game: &OwnedGame, cached: Option<&LibraryGame>) -> bool {
+    cached.is_none_or(|c| {
+        c.dirty_since_ms.is_some() || c.playtime_forever_minutes != game.playtime_forever_minutes
+    })
+}
+
+fn to_library_game(
+    game: &OwnedGame,
+    data: &AchievementData,
+    stats_cached_at_ms: Option<i64>,
+    scanned_at_ms: i64,
+) -> LibraryGame {
+    let held: Vec<HeldAchievement> = data
+        .achievements
+        .iter()
+        .filter(|a| a.achieved)
+        .map(|a| HeldAchievement {
+            id: a.id.clone(),
+            name: a.name.clone(),
+            icon_normal: a.icon_normal.clone(),
+            percent: a.percent,
+        })
+        .collect();
+    LibraryGame {
+        app_id: game.app_id,
+        name: game.name.clone(),
+        playtime_forever_minutes: game.playtime_forever_minutes,
+        scanned_at_ms,
+        stats_cached_at_ms,
+        dirty_since_ms: None,
+        total: data.achievements.len() as u32,
+        unlocked: held.len() as u32,
+        held,
+    }
+}
+
+/// Completion statistics over whatever is cached - pure, so it's tested without Steam.
+pub fn summarize(games: &[LibraryGame]) -> LibraryStats {
+    let mut completions: Vec<GameCompletion> = games
+        .iter()
+        .filter(|g| g.total > 0)
+        .map(|g| GameCompletion {
+            app_id: g.app_id,
+            name: g.name.clone(),
+            unlocked: g.unlocked,
+            total: g.total,
+            completion: f64::from(g.unlocked) * 100.0 / f64::from(g.total),
+        })
+        .collect();
+    completions.sort_by(|a, b| {
+        b.completion
+            .total_cmp(&a.completion)
+            .then(a.app_id.cmp(&b.app_id))
+    });
+
+    let started: Vec<f64> = completions
+        .iter()
+        .filter(|c| c.unlocked > 0)
+        .map(|c| c.completion)
+        .collect();
+    let average_completion =
+        (!started.is_empty()).then(|| started.iter().sum::<f64>() / started.len() as f64);
+
+    let mut rarest: Vec<RareAchievement> = games
+        .iter()
+        .flat_map(|g| {
+            g.held.iter().filter_map(|a| {
+                Some(RareAchievement {
+                    app_id: g.app_id,
+                    game_name: g.name.clone(),
+                    id: a.id.clone(),
+                    name: a.name.clone(),
+                    icon_normal: a.icon_normal.clone(),
+                    percent: a.percent?,
+                })
+            })
+        })
+        .collect();
+    rarest.sort_by(|a, b| {
+        a.percent
+            .total_cmp(&b.percent)
+            .then(a.app_id.cmp(&b.app_id))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    rarest.truncate(RAREST_LIMIT);
+
+    LibraryStats {
+        perfect_games: completions
+            .iter()
+            .filter(|c| c.unlocked == c.total)
+            .cloned()
+            .collect(),
+        games: completions,
+        average_completion,
+        achievements_unlocked: games.iter().map(|g| g.unlocked).sum(),
+        rarest,
+        last_scanned_at_ms: games.iter().map(|g| g.scanned_at_ms).max(),
+    }
+}
+
+fn emit_progress(app_handle: &AppHandle, steam_id: &str, checked: u32, total: u32) {
+    let _ = app_handle.emit(
+        ACHIEVEMENT_LIBRARY_SCAN_EVENT,
+        serde_json::json!({
+            "steamId": steam_id,
+            "progress": LibraryScanProgress { checked, total },
+        }),
+    );
+}
+
+/// One game's data for a scan, with the local client's cache time when it came from there - see
+/// the module doc comment's "local first". `live` skips the local copy (a game marked dirty).
+async fn fetch_game(
+    app_handle: &AppHandle,
+    account: &GamesAccount,
+    steam_id: &str,
+    app_id: u32,
+    locale: &str,
+    live: bool,
+) -> AppResult<(AchievementData, Option<i64>)> {
+    if !live && matches!(account, GamesAccount::Local { .. }) {
+        match super::commands::read_local_achievement_data(steam_id, app_id, locale).await {
+            Ok(Some(local)) => return Ok((local.data, Some(local.cached_at_ms))),
+            Ok(None) => {}
+            Err(e) => {
+                tracing::warn!(app_id, error = %e.code(), "achievement library: failed to read the local stats cache");
+            }
+        }
+    }
+    super::commands::fetch_achievement_data(
+        &app_handle.state::<AgentManager>(),
+        account.clone(),
+        app_id,
+        locale,
+    )
+    .await
+    .map(|data| (data, None))
+}
+
+/// Scans `steam_id`'s owned games (see the module doc comment) and returns the refreshed stats.
+/// `full` refetches every game regardless of playtime. Games no longer owned drop out of the cache.
+pub async fn scan(
+    app_handle: &AppHandle,
+    account: &GamesAccount,
+    steam_id: &str,
+    locale: &str,
+    full: bool,
+) -> AppResult<LibraryScanResult> {
+    if !RUNNING.lock().unwrap().insert(steam_id.to_string()) {
+        return Err(AppError::AchievementLibraryScanInProgress);
+    }
+    let _running = RunningGuard(steam_id.to_string());
+
+    let owned =
+        crate::games::commands::get_owned_games_cache(app_handle.clone(), steam_id.to_string())?;
+    let owned_ids: HashSet<u32> = owned.iter().map(|g| g.app_id).collect();
+    let mut library: HashMap<u32, LibraryGame> = read(app_handle, steam_id)
+        .await?
+        .into_iter()
+        .filter(|g| owned_ids.contains(&g.app_id))
+        .map(|g| (g.app_id, g))
+        .collect();
+
+    let to_scan: Vec<(OwnedGame, bool)> = owned
+        .iter()
+        .filter(|g| full || needs_scan(g, library.get(&g.app_id)))
+        .map(|g| {
+            let dirty = library
+                .get(&g.app_id)
+                .is_some_and(|c| c.dirty_since_ms.is_some());
+            (g.clone(), dirty)
+        })
+        .collect();
+    let total = to_scan.len() as u32;
+    let unchanged = owned.len() as u32 - total;
+    emit_progress(app_handle, steam_id, 0, total);
+
+    let mut results = stream::iter(to_scan)
+        .map(|(game, dirty)| async move {
+            // Stamped before the fetch, so a write landing mid-fetch still counts as after it -
+            // see `carry_dirty`.
+            let scanned_at_ms = chrono::Utc::now().timestamp_millis();
+            let result =
+                fetch_game(app_handle, account, steam_id, game.app_id, locale, dirty).await;
+            (game, scanned_at_ms, result)
+        })
+        .buffer_unordered(SCAN_CONCURRENCY);
+
+    let mut checked = 0;
+    let mut failed = Vec::new();
+    while let Some((game, scanned_at_ms, result)) = results.next().await {
+        match result {
+            Ok((data, stats_cached_at_ms)) => {
+                library.insert(
+                    game.app_id,
+                    to_library_game(&game, &data, stats_cached_at_ms, scanned_at_ms),
+                );
+            }
+            Err(e) => {
+                tracing::warn!(app_id = game.app_id, error = %e.code(), "achievement library: failed to scan game");
+                failed.push(LibraryScanFailure {
+                    app_id: game.app_id,
+                    name: game.name.clone(),
+                    reason: e.code(),
+                });
+            }
+        }
+        checked += 1;
+        emit_progress(app_handle, steam_id, checked, total);
+        if checked % PERSIST_EVERY == 0 {
+            write(app_handle, steam_id, &library).await?;
+        }
+    }
+    write(app_handle, steam_id, &library).await?;
+
+    // `buffer_unordered` finishes in any order - keep the report stable.
+    failed.sort_by_key(|f| f.app_id);
+    tracing::info!(
+        steam_id,
+        scanned = total - failed.len() as u32,
+        unchanged,
+        failed = failed.len(),
+        "achievement library: scan finished"
+    );
+    let games: Vec<LibraryGame> = library.into_values().collect();
+    Ok(LibraryScanResult {
+        scanned: total - failed.len() as u32,
+        unchanged,
+        failed,
+        stats: summarize(&games),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn held(id: &str, percent: Option<f32>) -> HeldAchievement {
+        HeldAchievement {
+            id: id.to_string(),
+            name: id.to_string(),
+            icon_normal: String::new(),
+            percent,
+        }
+    }
+
+    fn game(app_id: u32, total: u32, held: Vec<HeldAchievement>) -> LibraryGame {
+        LibraryGame {
+            app_id,
+            name: None,
+            playtime_forever_minutes: 60,
+            scanned_at_ms: i64::from(app_id),
+            stats_cached_at_ms: None,
+            dirty_since_ms: None,
+            total,
+            unlocked: held.len() as u32,
+            held,
+        }
+    }
+
+    #[test]
+    fn summarizes_completion_perfect_games_and_rarest() {
+        let games = [
+            game(10, 2, vec![held("a", Some(40.0)), held("b", Some(2.5))]),
+            game(20, 4, vec![held("c", Some(0.8)), held("d", None)]),
+            game(30, 5, vec![]),
+            game(40, 0, vec![]),
+        ];
+        let stats = summarize(&games);
+
+        let order: Vec<u32> = stats.games.iter().map(|g| g.app_id).collect();
+        assert_eq!(order, vec![10, 20, 30]);
+        assert_eq!(stats.perfect_games.len(), 1);
+        assert_eq!(stats.perfect_games[0].app_id, 10);
+        // Untouched app 30 doesn't count: (100 + 50) / 2.
+        assert_eq!(stats.average_completion, Some(75.0));
+        assert_eq!(stats.achievements_unlocked, 4);
+        let rarest: Vec<&str> = stats.rarest.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(rarest, vec!["c", "b", "a"]);
+        assert_eq!(stats.last_scanned_at_ms, Some(40));
+
+        assert_eq!(summarize(&[game(30, 5, vec![])]).average_completion, None);
+    }
+
+    #[test]
+    fn only_new_or_played_games_need_scanning() {
+        let owned = |playtime_forever_minutes| OwnedGame {
+            app_id: 10,
+            name: None,
+            playtime_forever_minutes,
+            rtime_last_played: 0,
+            last_refund_eligible_purchase_unix_seconds: None,
+            app_type: None,
+        };
+        let cached = game(10, 2, vec![]);
+        assert!(needs_scan(&owned(60), None));
+        assert!(!needs_scan(&owned(60), Some(&cached)));
+        assert!(needs_scan(&owned(75), Some(&cached)));
+
+        let written = LibraryGame {
+            dirty_since_ms: Some(50),
+            ..cached
+        };
+        assert!(needs_scan(&owned(60), Some(&written)));
+    }
+
+    #[test]
+    fn a_write_during_a_scan_stays_dirty() {
+        let dirty = |app_id, dirty_since_ms| LibraryGame {
+            dirty_since_ms: Some(dirty_since_ms),
+            ..game(app_id, 2, vec![])
+        };
+        // 10 was marked before the scan fetched it (scanned_at_ms 10), 20 after (scanned_at_ms 20).
+        let mut games: HashMap<u32, LibraryGame> =
+            [(10, game(10, 2, vec![])), (20, game(20, 2, vec![]))].into();
+        carry_dirty(&mut games, vec![dirty(10, 5), dirty(20, 25), dirty(30, 1)]);
+        assert_eq!(games[&10].dirty_since_ms, None);
+        assert_eq!(games[&20].dirty_since_ms, Some(25));
+        assert!(!games.contains_key(&30));
+    }
+}
@@ -1,9 +1,17 @@
 //! Achievement/stat data and actions for one game, shared between both sign-in modes. One command
 //! surface (`commands::*`) branches internally on `GamesAccount`, not a pair of mode-specific
 //! commands. Backs the single-game achievement-manager overlay and the achievement-unlocker
-//! automation feature, which needs the same primitives plus its own queue/timing logic.
+//! automation feature, which needs the same primitives plus its own queue/timing logic. `snapshots`
+//! keeps restorable per-game copies of that data, taken automatically before destructive bulk
+//! commands; `progress` plans the stat writes behind progress-based achievements; `library` scans
+//! every owned game for library-wide completion statistics; `data_cache` keeps each game's last
+//! fetch so it stays browsable (marked stale) when a live fetch fails.
 
 pub mod commands;
+pub mod data_cache;
+pub mod library;
+pub mod progress;
+pub mod snapshots;
 pub mod steam_language;
 pub mod web_api;
 
@@ -29,6 +37,11 @@ use serde::{Deserialize, Serialize};
 /// achievements come back without one (always agent mode, since it's a public, session-independent
 /// Web API lookup - see `web_api::fetch_global_percentages`), so this stays optional here rather
 /// than a hard mode-based guarantee.
+///
+/// `progress` is the schema's stat threshold for a progress-based achievement (see `progress`),
+/// parsed by SteamUtility's schema walker from whichever schema its backend fetched - so agent mode
+/// gets it with no local Steam client at all. Omitted on the wire when absent, and `default` so
+/// data from an older SteamUtility build or `data_cache` still deserializes.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AchievementDto {
@@ -48,6 +61,18 @@ pub struct AchievementDto {
     ))]
     pub protected_achievement: bool,
     pub flags: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress: Option<AchievementProgress>,
+}
+
+/// Mirrors `libs/SteamUtility/Core/Models/AchievementProgress.cs` - unlocked once stat `stat_id`
+/// (a `StatDto::id`) reaches `max_value`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AchievementProgress {
+    pub stat_id: String,
+    pub min_value: f64,
+    pub max_value: f64,
 }
 
 /// Mirrors `libs/SteamUtility/Core/Models/StatDto.cs`. Unlike `AchievementDto`, most of this DTO's
@@ -96,7 +121,7 @@ pub struct AchievementData {
 /// human-readable display name. Confirmed live: passing a real `StatDto.name` (e.g. "Enemies
 /// killed") failed with `stat_not_found`; passing the same stat's `id` (`stat_enemies_killed`)
 /// succeeded. The future achievement-manager frontend must build this request from `StatDto.id`.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct StatUpdate {
     pub name: String,
     pub value: f64,
@@ -0,0 +1,229 @@
+//! Stat-driven completion planning for progress-based achievements - ones the schema ties to a stat
+//! threshold (`AchievementDto::progress`, see [`links`]). Forcing such an achievement alone leaves
+//! its progress stat where it was (often 0), which no real player's profile ever shows; [`plan`]
+//! works out the stat values that would have earned each one, so the unlocker can walk the stat up
+//! through `update_stats` before the unlock itself.
+//!
+//! - **Walked in unlock order** - a stat shared by several achievements (10/50/100 wins) climbs
+//!   through each threshold in turn, and an achievement whose threshold an earlier one already
+//!   passed needs no steps of its own.
+//! - **Only ever raised**, never lowered - so increment-only stats are always safe, and a stat
+//!   already at or past the threshold is left alone.
+//! - **Skipped, not forced**, when the stat can't be written: protected, not in the live stat
+//!   list, or a type `update_stats` can't set directly (`avgrate`). Those achievements still get
+//!   the plain direct unlock.
+
+use std::collections::HashMap;
+
+use crate::local_steam::stats_schema::ProgressLink;
+
+use super::{AchievementDto, StatDto, StatUpdate};
+
+#[derive(Clone, Copy, PartialEq)]
+enum StatKind {
+    Integer,
+    Float,
+}
+
+fn stat_kind(stat: &StatDto) -> Option<StatKind> {
+    match stat.stat_type.to_ascii_lowercase().as_str() {
+        "int" | "integer" => Some(StatKind::Integer),
+        "float" => Some(StatKind::Float),
+        _ => None,
+    }
+}
+
+/// `steps` evenly spaced values from just above `from` up to exactly `to` - whole numbers for an
+/// integer stat, with duplicates (a climb of fewer units than steps) dropped.
+fn climb(from: f64, to: f64, steps: usize, kind: StatKind) -> Vec<f64> {
+    let mut values: Vec<f64> = Vec::new();
+    for k in 1..=steps {
+        let raw = from + (to - from) * k as f64 / steps as f64;
+        let value = match kind {
+            StatKind::Integer if k == steps => to.ceil(),
+            StatKind::Integer => raw.floor(),
+            StatKind::Float => raw,
+        };
+        if value > from && values.last().is_none_or(|last| value > *last) {
+            values.push(value);
+        }
+    }
+    values
+}
+
+/// The progress links `achievements` carry - every backend fills `AchievementDto::progress` from
+/// the schema it fetched, so this works the same in agent mode, with no local Steam client.
+pub fn links(achievements: &[AchievementDto]) -> Vec<ProgressLink> {
+    achievements
+        .iter()
+        .filter_map(|a| {
+            let progress = a.progress.as_ref()?;
+            Some(ProgressLink {
+                achievement_id: a.id.clone(),
+                stat_id: progress.stat_id.clone(),
+                min_value: progress.min_value,
+                max_value: progress.max_value,
+            })
+        })
+        .collect()
+}
+
+/// Per achievement id (only those with something to do), the `update_stats` writes that bring its
+/// stat up to its threshold in `steps` increments, the last one landing exactly on it. `order` is
+/// the unlock order; `stats` the live values.
+pub fn plan(
+    order: &[&str],
+    stats: &[StatDto],
+    links: &[ProgressLink],
+    steps: usize,
+) -> HashMap<String, Vec<StatUpdate>> {
+    let link_by_achievement: HashMap<&str, &ProgressLink> = links
+        .iter()
+        .map(|l| (l.achievement_id.as_str(), l))
+        .collect();
+    let stat_by_id: HashMap<&str, &StatDto> = stats.iter().map(|s| (s.id.as_str(), s)).collect();
+    let mut current: HashMap<&str, f64> = stats
+        .iter()
+        .filter_map(|s| Some((s.id.as_str(), s.value.as_f64()?)))
+        .collect();
+
+    let mut planned = HashMap::new();
+    for achievement_id in order {
+        let Some(link) = link_by_achievement.get(achievement_id) else {
+            continue;
+        };
+        let Some(stat) = stat_by_id.get(link.stat_id.as_str()) else {
+            continue;
+        };
+        let Some(kind) = stat_kind(stat).filter(|_| !stat.protected_stat) else {
+            continue;
+        };
+        let Some(from) = current.get(stat.id.as_str()).copied() else {
+            continue;
+        };
+        if from >= link.max_value {
+            continue;
+        }
+        let updates: Vec<StatUpdate> = climb(from, link.max_value, steps.max(1), kind)
+            .into_iter()
+            .map(|value| StatUpdate {
+                name: stat.id.clone(),
+                value,
+            })
+            .collect();
+        if let Some(last) = updates.last() {
+            current.insert(stat.id.as_str(), last.value);
+            planned.insert(achievement_id.to_string(), updates);
+        }
+    }
+    planned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::AchievementProgress;
+    use super::*;
+
+    fn stat(id: &str, stat_type: &str, value: f64, protected_stat: bool) -> StatDto {
+        StatDto {
+            id: id.to_string(),
+            name: id.to_string(),
+            stat_type: stat_type.to_string(),
+            permission: 0,
+            value: value.into(),
+            increment_only: true,
+            protected_stat,
+            flags: String::new(),
+        }
+    }
+
+    fn link(achievement_id: &str, stat_id: &str, max_value: f64) -> ProgressLink {
+        ProgressLink {
+            achievement_id: achievement_id.to_string(),
+            stat_id: stat_id.to_string(),
+            min_value: 0.0,
+            max_value,
+        }
+    }
+
+    fn values(updates: &[StatUpdate]) -> Vec<f64> {
+        updates.iter().map(|u| u.value).collect()
+    }
+
+    #[test]
+    fn shared_stat_climbs_through_each_threshold_in_unlock_order() {
+        let stats = [stat("wins", "int", 2.0, false)];
+        let links = [
+            link("win_10", "wins", 10.0),
+            link("win_50", "wins", 50.0),
+            link("win_5", "wins", 5.0),
+        ];
+        let plan = plan(&["win_10", "win_50", "win_5"], &stats, &links, 4);
+        assert_eq!(values(&plan["win_10"]), vec![4.0, 6.0, 8.0, 10.0]);
+        assert_eq!(values(&plan["win_50"]), vec![20.0, 30.0, 40.0, 50.0]);
+        // Already passed by the time it comes up.
+        assert!(!plan.contains_key("win_5"));
+    }
+
+    #[test]
+    fn small_integer_climbs_collapse_and_floats_stay_fractional() {
+        let stats = [
+            stat("bosses", "integer", 0.0, false),
+            stat("distance", "float", 0.0, false),
+        ];
+        let links = [
+            link("boss_2", "bosses", 2.0),
+            link("walk_1", "distance", 1.0),
+        ];
+        let plan = plan(&["boss_2", "walk_1"], &stats, &links, 4);
+        assert_eq!(values(&plan["boss_2"]), vec![1.0, 2.0]);
+        assert_eq!(values(&plan["walk_1"]), vec![0.25, 0.5, 0.75, 1.0]);
+    }
+
+    #[test]
+    fn unwritable_or_satisfied_stats_are_left_to_the_direct_unlock() {
+        let stats = [
+            stat("rate", "avgrate", 0.0, false),
+            stat("locked", "int", 0.0, true),
+            stat("done", "int", 12.0, false),
+        ];
+        let links = [
+            link("a", "rate", 1.0),
+            link("b", "locked", 1.0),
+            link("c", "done", 10.0),
+            link("d", "missing", 1.0),
+        ];
+        assert!(plan(&["a", "b", "c", "d", "unlinked"], &stats, &links, 3).is_empty());
+    }
+
+    #[test]
+    fn links_come_from_the_fetched_schema() {
+        let achievement = |id: &str, progress: Option<AchievementProgress>| AchievementDto {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            icon_normal: String::new(),
+            icon_locked: String::new(),
+            permission: 0,
+            hidden: false,
+            achieved: false,
+            percent: None,
+            protected_achievement: false,
+            flags: String::new(),
+            progress,
+        };
+        // What the agent daemon sends: the threshold rides on the DTO itself.
+        let achievements: Vec<AchievementDto> = serde_json::from_str(
+            r#"[{"id":"win_10","name":"","description":"","iconNormal":"","iconLocked":"",
+                "permission":0,"hidden":false,"achieved":false,"protected_achievement":false,
+                "flags":"","progress":{"statId":"wins","minValue":0,"maxValue":10}}]"#,
+        )
+        .unwrap();
+        let achievements = [achievements, vec![achievement("plain", None)]].concat();
+
+        assert_eq!(links(&achievements), vec![link("win_10", "wins", 10.0)]);
+        let stats = [stat("wins", "int", 0.0, false)];
+        let plan = plan(&["win_10", "plain"], &stats, &links(&achievements), 2);
+        assert_eq!(values(&plan["win_10"]), vec![5.0, 10.0]);
+    }
+}
@@ -0,0 +1,494 @@
+//! Per-game achievement/stat snapshots for one account - a copy of the full [`AchievementData`]
+//! (`<steam_id>/achievement_snapshots/<app_id>/<snapshot_id>.json`) that
+//! `commands::restore_achievement_snapshot` can roll a game back to, since
+//! `lock_all_achievements`/`reset_all_stats`/a bulk edit otherwise can't be undone.
+//!
+//! - **Taken automatically** by `commands::snapshot_before` ahead of every destructive bulk
+//!   command (see [`SnapshotReason`]), and on demand for one game or a whole selection - a
+//!   selection's snapshots share a `batch_id` so they can be restored together.
+//! - **Versioned** - every file carries [`SNAPSHOT_FORMAT_VERSION`]; a file from a newer build is
+//!   refused with `achievement_snapshot_unsupported_version` rather than half-read.
+//! - **Restoring diffs, it doesn't replay** - [`plan_restore`] compares the snapshot against a
+//!   fresh `get_achievement_data` and only the differences go back through `set_achievement`/
+//!   `update_stats`, so both sign-in modes restore through the exact same primitives a manual edit
+//!   uses. Protected achievements/stats, ones no longer in the schema, and increment-only stats
+//!   the snapshot has *lower* than now are reported as skipped instead of attempted.
+//! - **Automatic snapshots are capped** at [`MAX_AUTOMATIC_SNAPSHOTS_PER_GAME`] per game (oldest
+//!   pruned first); manual ones are kept until deleted.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tokio::sync::Mutex;
+
+use crate::error::{AppError, AppResult};
+use crate::fs_utils::atomic_write_json;
+use crate::platform;
+
+use super::{AchievementData, StatUpdate};
+
+const SNAPSHOT_DIR_NAME: &str = "achievement_snapshots";
+
+/// Bumped whenever [`AchievementSnapshot`]'s stored shape changes incompatibly.
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+pub const MAX_AUTOMATIC_SNAPSHOTS_PER_GAME: usize = 20;
+
+static WRITE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+/// Why a snapshot was taken - everything but `Manual` is automatic and subject to pruning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SnapshotReason {
+    Manual,
+    BeforeUnlockAll,
+    BeforeLockAll,
+    /// No longer taken - `commands::update_stats` stopped snapshotting every save - but kept so
+    /// snapshots an older build took still load.
+    BeforeUpdateStats,
+    BeforeResetStats,
+    /// The achievement manager's staged edits - "Apply changes" (a loop of `set_achievement` calls
+    /// with no backend command of its own) and the statistics tab's save (`update_stats`, which
+    /// takes none itself), so the frontend asks for this one explicitly.
+    BeforeBulkEdit,
+    /// Taken by the restore itself, so a restore is just as undoable as anything else.
+    BeforeRestore,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AchievementSnapshot {
+    pub version: u32,
+    pub id: String,
+    pub app_id: u32,
+    pub taken_at_ms: i64,
+    pub reason: SnapshotReason,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Shared by every snapshot of one `take_achievement_snapshots` selection.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch_id: Option<String>,
+    pub data: AchievementData,
+}
+
+/// An [`AchievementSnapshot`] without its data - what the listing commands return.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotSummary {
+    pub id: String,
+    pub app_id: u32,
+    pub taken_at_ms: i64,
+    pub reason: SnapshotReason,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub batch_id: Option<String>,
+    pub achieved_count: u32,
+    pub achievement_count: u32,
+    pub stat_count: u32,
+}
+
+impl From<&AchievementSnapshot> for SnapshotSummary {
+    fn from(s: &AchievementSnapshot) -> Self {
+        SnapshotSummary {
+            id: s.id.clone(),
+            app_id: s.app_id,
+            taken_at_ms: s.taken_at_ms,
+            reason: s.reason,
+            label: s.label.clone(),
+            batch_id: s.batch_id.clone(),
+            achieved_count: s.data.achievements.iter().filter(|a| a.achieved).count() as u32,
+            achievement_count: s.data.achievements.len() as u32,
+            stat_count: s.data.stats.len() as u32,
+        }
+    }
+}
+
+/// The writes that take a game's live state back to a snapshot - see [`plan_restore`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RestorePlan {
+    pub unlock: Vec<String>,
+    pub lock: Vec<String>,
+    pub stats: Vec<StatUpdate>,
+    /// Achievement/stat ids that differ but can't be written back.
+    pub skipped: Vec<String>,
+}
+
+/// Outcome of `restore_achievement_snapshot` for one game.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreResult {
+    pub app_id: u32,
+    /// The `BeforeRestore` snapshot of the state this restore replaced - `None` if nothing differed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub undo_snapshot_id: Option<String>,
+    pub unlocked: Vec<String>,
+    pub locked: Vec<String>,
+    pub stats_updated: Vec<String>,
+    pub skipped: Vec<String>,
+    pub failed: Vec<String>,
+    /// Set when the whole game failed (a batch restore's per-game error code) - everything else is
+    /// then empty.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotFailure {
+    pub app_id: u32,
+    pub error: String,
+}
+
+/// Outcome of `take_achievement_snapshots`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelectionSnapshotResult {
+    pub batch_id: String,
+    pub taken: Vec<SnapshotSummary>,
+    pub failed: Vec<SnapshotFailure>,
+}
+
+fn game_dir(app_handle: &AppHandle, steam_id: &str, app_id: u32) -> AppResult<PathBuf> {
+    Ok(platform::cache_dir(app_handle)?
+        .join(steam_id)
+        .join(SNAPSHOT_DIR_NAME)
+        .join(app_id.to_string()))
+}
+
+fn snapshot_path(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    app_id: u32,
+    snapshot_id: &str,
+) -> AppResult<PathBuf> {
+    // Ids are only ever minted by `new_id` - anything else (a path separator, `..`) is just a
+    // snapshot that doesn't exist.
+    if snapshot_id.is_empty()
+        || !snapshot_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        return Err(AppError::AchievementSnapshotNotFound(
+            snapshot_id.to_string(),
+        ));
+    }
+    Ok(game_dir(app_handle, steam_id, app_id)?.join(format!("{snapshot_id}.json")))
+}
+
+/// `<taken_at_ms>-<random>` - sorts chronologically as a string for any realistic timestamp, and
+/// the suffix keeps two snapshots of one game within the same millisecond apart.
+pub fn new_id(taken_at_ms: i64) -> String {
+    format!("{taken_at_ms}-{:08x}", rand::random::<u32>())
+}
+
+fn parse(contents: &str) -> AppResult<AchievementSnapshot> {
+    #[derive(Deserialize)]
+    struct Versioned {
+        version: u32,
+    }
+    let Versioned { version } = serde_json::from_str(contents)
+        .map_err(|e| AppError::AchievementSnapshotIo(e.to_string()))?;
+    if version > SNAPSHOT_FORMAT_VERSION {
+        return Err(AppError::AchievementSnapshotUnsupportedVersion(version));
+    }
+    serde_json::from_str(contents).map_err(|e| AppError::AchievementSnapshotIo(e.to_string()))
+}
+
+fn read_dir_snapshots(dir: &PathBuf) -> AppResult<Vec<AchievementSnapshot>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut snapshots = Vec::new();
+    for entry in fs::read_dir(dir).map_err(|e| AppError::AchievementSnapshotIo(e.to_string()))? {
+        let path = entry
+            .map_err(|e| AppError::AchievementSnapshotIo(e.to_string()))?
+            .path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let parsed = fs::read_to_string(&path)
+            .map_err(|e| AppError::AchievementSnapshotIo(e.to_string()))
+            .and_then(|contents| parse(&contents));
+        match parsed {
+            Ok(snapshot) => snapshots.push(snapshot),
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e.code(), "achievement snapshots: skipping unreadable snapshot");
+            }
+        }
+    }
+    snapshots.sort_by_key(|s| s.taken_at_ms);
+    Ok(snapshots)
+}
+
+/// Saves `data` as a new snapshot of `app_id`, pruning old automatic ones past the cap.
+pub async fn take(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    app_id: u32,
+    data: AchievementData,
+    reason: SnapshotReason,
+    label: Option<String>,
+    batch_id: Option<String>,
+) -> AppResult<SnapshotSummary> {
+    let _guard = WRITE_LOCK.lock().await;
+    let taken_at_ms = chrono::Utc::now().timestamp_millis();
+    let snapshot = AchievementSnapshot {
+        version: SNAPSHOT_FORMAT_VERSION,
+        id: new_id(taken_at_ms),
+        app_id,
+        taken_at_ms,
+        reason,
+        label,
+        batch_id,
+        data,
+    };
+    let path = snapshot_path(app_handle, steam_id, app_id, &snapshot.id)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::AchievementSnapshotIo(e.to_string()))?;
+    }
+    atomic_write_json(&path, &snapshot)
+        .map_err(|e| AppError::AchievementSnapshotIo(e.to_string()))?;
+
+    if reason != SnapshotReason::Manual {
+        let dir = game_dir(app_handle, steam_id, app_id)?;
+        let automatic: Vec<AchievementSnapshot> = read_dir_snapshots(&dir)?
+            .into_iter()
+            .filter(|s| s.reason != SnapshotReason::Manual)
+            .collect();
+        let excess = automatic
+            .len()
+            .saturating_sub(MAX_AUTOMATIC_SNAPSHOTS_PER_GAME);
+        for old in &automatic[..excess] {
+            if let Err(e) = fs::remove_file(dir.join(format!("{}.json", old.id))) {
+                tracing::warn!(app_id, snapshot_id = %old.id, error = %e, "achievement snapshots: failed to prune old snapshot");
+            }
+        }
+    }
+    Ok(SnapshotSummary::from(&snapshot))
+}
+
+/// Newest first. `app_id: None` lists every game's snapshots.
+pub async fn list(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    app_id: Option<u32>,
+) -> AppResult<Vec<SnapshotSummary>> {
+    let _guard = WRITE_LOCK.lock().await;
+    let root = platform::cache_dir(app_handle)?
+        .join(steam_id)
+        .join(SNAPSHOT_DIR_NAME);
+    let dirs: Vec<PathBuf> = match app_id {
+        Some(app_id) => vec![root.join(app_id.to_string())],
+        None if root.exists() => fs::read_dir(&root)
+            .map_err(|e| AppError::AchievementSnapshotIo(e.to_string()))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_dir())
+            .collect(),
+        None => Vec::new(),
+    };
+    let mut summaries = Vec::new();
+    for dir in dirs {
+        summaries.extend(read_dir_snapshots(&dir)?.iter().map(SnapshotSummary::from));
+    }
+    summaries.sort_by(|a, b| b.taken_at_ms.cmp(&a.taken_at_ms));
+    Ok(summaries)
+}
+
+pub async fn load(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    app_id: u32,
+    snapshot_id: &str,
+) -> AppResult<AchievementSnapshot> {
+    let _guard = WRITE_LOCK.lock().await;
+    let path = snapshot_path(app_handle, steam_id, app_id, snapshot_id)?;
+    if !path.exists() {
+        return Err(AppError::AchievementSnapshotNotFound(
+            snapshot_id.to_string(),
+        ));
+    }
+    let contents =
+        fs::read_to_string(&path).map_err(|e| AppError::AchievementSnapshotIo(e.to_string()))?;
+    parse(&contents)
+}
+
+/// Idempotent - a missing snapshot is already deleted.
+pub async fn delete(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    app_id: u32,
+    snapshot_id: &str,
+) -> AppResult<()> {
+    let _guard = WRITE_LOCK.lock().await;
+    let path = snapshot_path(app_handle, steam_id, app_id, snapshot_id)?;
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(AppError::AchievementSnapshotIo(e.to_string())),
+    }
+}
+
+fn stat_value(value: &serde_json::Value) -> Option<f64> {
+    value.as_f64()
+}
+
+/// What it takes to turn `live` back into `snapshot` - pure, so the diff is tested without Steam.
+/// Entries only in the snapshot (dropped from the schema since) are skipped; entries only in
+/// `live` (added since) are left alone, since the snapshot says nothing about them.
+pub fn plan_restore(snapshot: &AchievementData, live: &AchievementData) -> RestorePlan {
+    let mut plan = RestorePlan::default();
+
+    let live_achievements: HashMap<&str, _> = live
+        .achievements
+        .iter()
+        .map(|a| (a.id.as_str(), a))
+        .collect();
+    for saved in &snapshot.achievements {
+        match live_achievements.get(saved.id.as_str()) {
+            Some(current) if current.achieved == saved.achieved => {}
+            Some(current) if !current.protected_achievement => {
+                if saved.achieved {
+                    plan.unlock.push(saved.id.clone());
+                } else {
+                    plan.lock.push(saved.id.clone());
+                }
+            }
+            _ => plan.skipped.push(saved.id.clone()),
+        }
+    }
+
+    let live_stats: HashMap<&str, _> = live.stats.iter().map(|s| (s.id.as_str(), s)).collect();
+    for saved in &snapshot.stats {
+        let Some(current) = live_stats.get(saved.id.as_str()) else {
+            plan.skipped.push(saved.id.clone());
+            continue;
+        };
+        let (Some(saved_value), Some(current_value)) =
+            (stat_value(&saved.value), stat_value(&current.value))
+        else {
+            plan.skipped.push(saved.id.clone());
+            continue;
+        };
+        if saved_value == current_value {
+            continue;
+        }
+        if current.protected_stat || (current.increment_only && saved_value < current_value) {
+            plan.skipped.push(saved.id.clone());
+            continue;
+        }
+        plan.stats.push(StatUpdate {
+            name: saved.id.clone(),
+            value: saved_value,
+        });
+    }
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{AchievementDto, StatDto};
+    use super::*;
+
+    fn achievement(id: &str, achieved: bool, protected_achievement: bool) -> AchievementDto {
+        AchievementDto {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            icon_normal: String::new(),
+            icon_locked: String::new(),
+            permission: 0,
+            hidden: false,
+            achieved,
+            percent: None,
+            protected_achievement,
+            flags: String::new(),
+            progress: None,
+        }
+    }
+
+    fn stat(id: &str, value: serde_json::Value, increment_only: bool) -> StatDto {
+        StatDto {
+            id: id.to_string(),
+            name: id.to_string(),
+            stat_type: "int".to_string(),
+            permission: 0,
+            value,
+            increment_only,
+            protected_stat: false,
+            flags: String::new(),
+        }
+    }
+
+    #[test]
+    fn only_differences_are_planned() {
+        let snapshot = AchievementData {
+            achievements: vec![
+                achievement("same", true, false),
+                achievement("relock", false, false),
+                achievement("reunlock", true, false),
+                achievement("protected", true, false),
+                achievement("gone", true, false),
+            ],
+            stats: Vec::new(),
+        };
+        let live = AchievementData {
+            achievements: vec![
+                achievement("same", true, false),
+                achievement("relock", true, false),
+                achievement("reunlock", false, false),
+                achievement("protected", false, true),
+                achievement("new", true, false),
+            ],
+            stats: Vec::new(),
+        };
+        let plan = plan_restore(&snapshot, &live);
+        assert_eq!(plan.unlock, vec!["reunlock"]);
+        assert_eq!(plan.lock, vec!["relock"]);
+        assert_eq!(plan.skipped, vec!["protected", "gone"]);
+    }
+
+    #[test]
+    fn stats_restore_by_id_except_increment_only_decreases() {
+        let snapshot = AchievementData {
+            achievements: Vec::new(),
+            stats: vec![
+                stat("kills", 10.into(), false),
+                stat("wins", 3.into(), true),
+                stat("distance", 2.5.into(), false),
+                stat("deaths", 4.into(), false),
+            ],
+        };
+        let live = AchievementData {
+            achievements: Vec::new(),
+            stats: vec![
+                stat("kills", 0.into(), false),
+                stat("wins", 7.into(), true),
+                stat("distance", 2.5.into(), false),
+                stat("deaths", 4.into(), false),
+            ],
+        };
+        let plan = plan_restore(&snapshot, &live);
+        assert_eq!(
+            plan.stats,
+            vec![StatUpdate {
+                name: "kills".to_string(),
+                value: 10.0
+            }]
+        );
+        assert_eq!(plan.skipped, vec!["wins"]);
+    }
+
+    #[test]
+    fn newer_format_versions_are_refused() {
+        let newer = format!(r#"{{"version": {}}}"#, SNAPSHOT_FORMAT_VERSION + 1);
+        assert!(matches!(
+            parse(&newer),
+            Err(AppError::AchievementSnapshotUnsupportedVersion(_))
+        ));
+    }
+}
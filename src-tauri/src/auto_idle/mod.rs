@@ -12,7 +12,7 @@
 //! machine loses the localStorage half but keeps the list). Putting `enabled` on the entry itself
 //! keeps one authoritative file.
 
-mod cache;
+pub(crate) mod cache;
 pub mod commands;
 
 use serde::{Deserialize, Serialize};
@@ -0,0 +1,54 @@
+use tauri::{AppHandle, State};
+
+use crate::error::AppResult;
+use crate::games::commands::{resolve_steam_id, GamesAccount};
+use crate::steam_agent::AgentManager;
+use crate::steam_community::{session, SteamCookies};
+
+use super::settings::{self, BadgeCraftingSettings};
+use super::CraftReport;
+
+/// Crafts every complete card set `account` owns, except opted-out games - or, with `dry_run`,
+/// just reports what it would craft. `manual_cookies` behaves exactly as in
+/// `card_farming::commands::get_games_with_drops`.
+#[tauri::command]
+pub async fn craft_badges(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    account: GamesAccount,
+    manual_cookies: Option<SteamCookies>,
+    dry_run: bool,
+) -> AppResult<CraftReport> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    let cookies = session::resolve(
+        &app_handle,
+        &agent_manager,
+        &account,
+        &steam_id,
+        manual_cookies,
+    )
+    .await?;
+    super::craft_all(&app_handle, &steam_id, &cookies, dry_run).await
+}
+
+#[tauri::command]
+pub async fn get_badge_crafting_settings(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    account: GamesAccount,
+) -> AppResult<BadgeCraftingSettings> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    settings::get(&app_handle, &steam_id).await
+}
+
+/// Whole-struct replace - see `settings::set`'s doc comment.
+#[tauri::command]
+pub async fn set_badge_crafting_settings(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    account: GamesAccount,
+    settings: BadgeCraftingSettings,
+) -> AppResult<BadgeCraftingSettings> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    settings::set(&app_handle, &steam_id, settings).await
+}
@@ -0,0 +1,171 @@
+//! The craft request itself - `POST /profiles/{steam_id}/ajaxcraftbadge/`, the same form the badge
+//! page's "Craft Badge" button submits, which can take a badge up several levels in one request.
+
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::steam_community::{cookie_header, steam_client, SteamCookies};
+
+use super::{CraftResult, CraftableBadge};
+
+/// Pause between consecutive craft requests - a bulk craft after a long farming session can be
+/// dozens of badges, and Community AJAX endpoints rate-limit per session.
+const CRAFT_DELAY: Duration = Duration::from_secs(2);
+
+/// Crafts each badge in turn. Never fails as a whole - a per-badge failure (network, a Steam-side
+/// refusal, an unexpected body) is recorded on that badge's [`CraftResult`] and the rest carry on.
+pub(super) async fn craft_badges(
+    steam_id: &str,
+    cookies: &SteamCookies,
+    badges: Vec<CraftableBadge>,
+) -> Vec<CraftResult> {
+    let client = match steam_client() {
+        Ok(client) => client,
+        Err(e) => {
+            let message = format!("Failed to build HTTP client: {e}");
+            return badges
+                .into_iter()
+                .map(|badge| failed(badge, message.clone()))
+                .collect();
+        }
+    };
+    let cookie_value = cookie_header(steam_id, cookies);
+    let url = format!("https://steamcommunity.com/profiles/{steam_id}/ajaxcraftbadge/");
+
+    let mut results = Vec::with_capacity(badges.len());
+    for (index, badge) in badges.into_iter().enumerate() {
+        if index > 0 {
+            tokio::time::sleep(CRAFT_DELAY).await;
+        }
+        let form_data = [
+            ("appid", badge.app_id.to_string()),
+            ("series", "1".to_string()),
+            ("border_color", u8::from(badge.foil).to_string()),
+            ("levels", badge.craftable_levels.to_string()),
+            ("sessionid", cookies.sid.clone()),
+        ];
+        let border = if badge.foil { "?border=1" } else { "" };
+        let response = client
+            .post(&url)
+            .header("Cookie", &cookie_value)
+            .header(
+                "Referer",
+                format!(
+                    "https://steamcommunity.com/profiles/{steam_id}/gamecards/{}/{border}",
+                    badge.app_id
+                ),
+            )
+            .header("Origin", "https://steamcommunity.com")
+            .form(&form_data)
+            .send()
+            .await;
+
+        let body = match response {
+            Ok(r) => r.text().await,
+            Err(e) => Err(e),
+        };
+        let result = match body {
+            Ok(body) => parse_craft_response(badge, &body),
+            Err(e) => failed(badge, format!("Request failed: {e}")),
+        };
+        if result.success {
+            tracing::info!(
+                steam_id,
+                app_id = result.badge.app_id,
+                foil = result.badge.foil,
+                levels = result.levels_crafted,
+                "badges: crafted badge"
+            );
+        } else {
+            tracing::warn!(
+                steam_id,
+                app_id = result.badge.app_id,
+                foil = result.badge.foil,
+                message = result.message.as_deref().unwrap_or_default(),
+                "badges: craft failed"
+            );
+        }
+        results.push(result);
+    }
+    results
+}
+
+fn failed(badge: CraftableBadge, message: String) -> CraftResult {
+    CraftResult {
+        badge,
+        success: false,
+        levels_crafted: 0,
+        new_level: None,
+        message: Some(message),
+    }
+}
+
+/// `{"success": 1, "Badge": {"level": N, ...}, "rgDroppedItems": [...]}` on success; any other
+/// `success` value (Steam's `EResult`) or a non-JSON body (a login page) is a failure.
+fn parse_craft_response(badge: CraftableBadge, body: &str) -> CraftResult {
+    let Ok(data) = serde_json::from_str::<Value>(body) else {
+        let snippet: String = body.chars().take(200).collect();
+        return failed(badge, format!("Unexpected response: {snippet}"));
+    };
+    let success = data.get("success").and_then(Value::as_i64);
+    if success != Some(1) {
+        return failed(
+            badge,
+            format!("Steam refused the craft (result {})", success.unwrap_or(0)),
+        );
+    }
+    let new_level = data
+        .get("Badge")
+        .and_then(|b| b.get("level"))
+        .and_then(Value::as_u64)
+        .map(|level| level as u32);
+    let levels_crafted = new_level.map_or(badge.craftable_levels, |level| {
+        level.saturating_sub(badge.level)
+    });
+    CraftResult {
+        badge,
+        success: true,
+        levels_crafted,
+        new_level,
+        message: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn badge() -> CraftableBadge {
+        CraftableBadge {
+            app_id: 440,
+            name: "Team Fortress 2".to_string(),
+            foil: false,
+            level: 2,
+            sets: 3,
+            craftable_levels: 3,
+        }
+    }
+
+    #[test]
+    fn parses_successful_craft() {
+        let result = parse_craft_response(
+            badge(),
+            r#"{"success":1,"Badge":{"level":5,"xp":500},"rgDroppedItems":[{"title":"Emoticon"}]}"#,
+        );
+        assert!(result.success);
+        assert_eq!(result.new_level, Some(5));
+        assert_eq!(result.levels_crafted, 3);
+    }
+
+    #[test]
+    fn refused_craft_and_html_body_are_failures() {
+        let refused = parse_craft_response(badge(), r#"{"success":15}"#);
+        assert!(!refused.success);
+        assert_eq!(refused.levels_crafted, 0);
+
+        let html = parse_craft_response(badge(), "<html>Sign In</html>");
+        assert!(!html.success);
+        assert!(html.message.unwrap().contains("Sign In"));
+    }
+}
@@ -0,0 +1,121 @@
+//! Game badge crafting: finds every game whose trading-card set is complete (once per level the
+//! account can still craft) and crafts its badge through Steam Community's own `ajaxcraftbadge`
+//! endpoint - the request the "Craft Badge" button on a game's badge page makes. Shares
+//! `steam_community`'s cookie/session-acquisition mechanism with `card_farming` and `inventory`
+//! (same `GamesAccount` + `manual_cookies` command surface, see `steam_community::session::
+//! resolve`'s doc comment), against `/badges` and `/gamecards/{appid}` pages.
+//!
+//! **Two-step scrape** ([`scraper`]): the badge overview pages only say a row is craftable, not how
+//! many full sets exist or what level the badge is already at, so each craftable game's own
+//! gamecards page is fetched too. Foil badges are their own row/page (`?border=1`) with a max level
+//! of 1, crafted independently of the regular badge.
+//!
+//! **Opt-out, dry run, report** - games listed in `settings::BadgeCraftingSettings::
+//! excluded_app_ids` are never crafted (but still reported, so the user can see what they skipped);
+//! `commands::craft_badges` with `dry_run` returns exactly what it *would* craft without sending a
+//! single craft request; a real run returns a per-badge outcome. `auto_craft_after_farming` runs the
+//! real thing automatically once a card-farming cycle finishes its queue (see
+//! `card_farming::manager::run_cycle`), reporting through [`BADGE_CRAFTING_FINISHED_EVENT`] since
+//! no command is waiting on that run.
+
+pub mod commands;
+mod craft;
+mod scraper;
+pub mod settings;
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::error::AppResult;
+use crate::steam_community::SteamCookies;
+
+/// Emitted when the post-farming auto-craft finishes - payload `{"steamId": "...", "report":
+/// CraftReport}`. `commands::craft_badges` returns its report directly and doesn't emit this.
+pub const BADGE_CRAFTING_FINISHED_EVENT: &str = "badge-crafting-finished";
+
+/// Regular game badges top out at level 5; foil badges are a single level.
+const MAX_REGULAR_LEVEL: u32 = 5;
+const MAX_FOIL_LEVEL: u32 = 1;
+
+/// One badge with at least one complete card set - as found, before any crafting.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CraftableBadge {
+    pub app_id: u32,
+    pub name: String,
+    pub foil: bool,
+    /// Badge level already earned - `0` if never crafted.
+    pub level: u32,
+    /// Complete sets owned - the minimum owned count across every card in the set.
+    pub sets: u32,
+    /// How many levels one craft request can take this badge up by: `sets`, capped at whatever's
+    /// left before the badge's max level.
+    pub craftable_levels: u32,
+}
+
+/// What happened (or, on a dry run, would happen) to one badge.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CraftResult {
+    pub badge: CraftableBadge,
+    pub success: bool,
+    /// Levels actually crafted - `0` on a dry run or a failure.
+    pub levels_crafted: u32,
+    /// The badge's level afterwards, as Steam reported it.
+    pub new_level: Option<u32>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CraftReport {
+    pub dry_run: bool,
+    /// One entry per badge crafted (or that would be, on a dry run), in scrape order.
+    pub results: Vec<CraftResult>,
+    /// Craftable badges left alone because their game is opted out.
+    pub excluded: Vec<CraftableBadge>,
+}
+
+/// Finds every craftable badge and crafts the ones not opted out (or, with `dry_run`, only lists
+/// them). The shared body behind `commands::craft_badges` and the post-farming auto-craft.
+pub async fn craft_all(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    cookies: &SteamCookies,
+    dry_run: bool,
+) -> AppResult<CraftReport> {
+    let excluded_app_ids = settings::get(app_handle, steam_id).await?.excluded_app_ids;
+    let badges = scraper::get_craftable_badges(steam_id, cookies).await?;
+    let (excluded, to_craft): (Vec<_>, Vec<_>) = badges
+        .into_iter()
+        .partition(|b| excluded_app_ids.contains(&b.app_id));
+
+    let results = if dry_run {
+        to_craft
+            .into_iter()
+            .map(|badge| CraftResult {
+                levels_crafted: 0,
+                new_level: None,
+                success: true,
+                message: None,
+                badge,
+            })
+            .collect()
+    } else {
+        craft::craft_badges(steam_id, cookies, to_craft).await
+    };
+
+    tracing::info!(
+        steam_id,
+        dry_run,
+        crafted = results.iter().filter(|r| r.success).count(),
+        failed = results.iter().filter(|r| !r.success).count(),
+        excluded = excluded.len(),
+        "badges: crafting pass finished"
+    );
+    Ok(CraftReport {
+        dry_run,
+        results,
+        excluded,
+    })
+}
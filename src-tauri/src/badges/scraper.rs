@@ -0,0 +1,214 @@
+//! Badge-page scraping - the overview pages for which games are craftable, then each of those
+//! games' own gamecards page for its exact set count and current level. Same plain cookie-header
+//! `reqwest` client `card_farming::scraper` uses (see that module's doc comment). Parsing is kept
+//! separate from fetching so it can be tested against the saved pages under `fixtures/`.
+
+use regex::Regex;
+use scraper::{Html, Selector};
+
+use crate::error::{AppError, AppResult};
+use crate::steam_community::session::is_session_revoked;
+use crate::steam_community::{cookie_header, steam_client, SteamCookies};
+
+use super::{CraftableBadge, MAX_FOIL_LEVEL, MAX_REGULAR_LEVEL};
+
+/// One craftable row from the overview pages - `(app_id, foil)`, deduplicated by the caller.
+fn parse_overview(html: &str) -> Vec<(u32, bool)> {
+    let document = Html::parse_document(html);
+    let craft_button_selector = Selector::parse("a.badge_craft_button").unwrap();
+    let href_regex = Regex::new(r"/gamecards/(\d+)/?(\?border=1)?").unwrap();
+
+    document
+        .select(&craft_button_selector)
+        .filter_map(|button| {
+            let href = button.value().attr("href")?;
+            let captures = href_regex.captures(href)?;
+            let app_id = captures[1].parse::<u32>().ok()?;
+            Some((app_id, captures.get(2).is_some()))
+        })
+        .collect()
+}
+
+fn detect_max_page(html: &str) -> usize {
+    let document = Html::parse_document(html);
+    let pagelink_selector = Selector::parse(".pageLinks .pagelink").unwrap();
+    document
+        .select(&pagelink_selector)
+        .filter_map(|link| link.text().next()?.trim().parse::<usize>().ok())
+        .max()
+        .unwrap_or(1)
+}
+
+/// One game's gamecards page - `None` when the set isn't complete (or the page isn't a gamecards
+/// page at all, e.g. a login redirect), so a stale overview row can never turn into a craft
+/// request.
+fn parse_gamecards(html: &str, app_id: u32, foil: bool) -> Option<CraftableBadge> {
+    let document = Html::parse_document(html);
+    let card_selector = Selector::parse(".badge_card_set_card").unwrap();
+    let qty_selector = Selector::parse(".badge_card_set_text_qty").unwrap();
+    let title_selector = Selector::parse(".badge_title").unwrap();
+    let level_selector = Selector::parse(".badge_info_description").unwrap();
+    let qty_regex = Regex::new(r"\((\d+)\)").unwrap();
+    let level_regex = Regex::new(r"Level (\d+)").unwrap();
+
+    // Steam only renders a quantity label for more than one copy - an owned card without one is a
+    // single copy, an unowned card is zero.
+    let sets = document
+        .select(&card_selector)
+        .map(|card| {
+            if !card.value().classes().any(|c| c == "owned") {
+                return 0;
+            }
+            card.select(&qty_selector)
+                .next()
+                .and_then(|qty| {
+                    qty_regex
+                        .captures(&qty.text().collect::<String>())
+                        .and_then(|c| c[1].parse::<u32>().ok())
+                })
+                .unwrap_or(1)
+        })
+        .min()?;
+    if sets == 0 {
+        return None;
+    }
+
+    let level = document
+        .select(&level_selector)
+        .next()
+        .and_then(|e| {
+            level_regex
+                .captures(&e.text().collect::<String>())
+                .and_then(|c| c[1].parse::<u32>().ok())
+        })
+        .unwrap_or(0);
+    let max_level = if foil {
+        MAX_FOIL_LEVEL
+    } else {
+        MAX_REGULAR_LEVEL
+    };
+    let craftable_levels = sets.min(max_level.saturating_sub(level));
+    if craftable_levels == 0 {
+        return None;
+    }
+
+    let name = document
+        .select(&title_selector)
+        .next()
+        .map(|e| e.text().collect::<String>())
+        .unwrap_or_default();
+    // "<Game> Badge" on a gamecards page, unlike the overview's bare game name.
+    let name = name.trim();
+    let name = name.strip_suffix(" Badge").unwrap_or(name).to_string();
+
+    Some(CraftableBadge {
+        app_id,
+        name,
+        foil,
+        level,
+        sets,
+        craftable_levels,
+    })
+}
+
+async fn fetch(
+    client: &reqwest::Client,
+    steam_id: &str,
+    cookie_value: &str,
+    url: String,
+) -> AppResult<String> {
+    let response = client
+        .get(url)
+        .header("Cookie", cookie_value)
+        .send()
+        .await
+        .map_err(|e| AppError::BadgeScrapeFailed(e.to_string()))?;
+    if is_session_revoked(&response) {
+        return Err(AppError::SteamCommunitySessionExpired(steam_id.to_string()));
+    }
+    response
+        .text()
+        .await
+        .map_err(|e| AppError::BadgeScrapeFailed(e.to_string()))
+}
+
+/// Every badge the account can craft right now. Unlike `card_farming::scraper`'s best-effort
+/// paging, any failed page fails the whole call - a partial list would read as "nothing else to
+/// craft", and a rerun costs nothing. Gamecards pages are fetched one at a time; there are rarely
+/// more than a handful and Steam rate-limits Community pages per session.
+pub async fn get_craftable_badges(
+    steam_id: &str,
+    cookies: &SteamCookies,
+) -> AppResult<Vec<CraftableBadge>> {
+    let client = steam_client().map_err(|e| AppError::BadgeScrapeFailed(e.to_string()))?;
+    let cookie_value = cookie_header(steam_id, cookies);
+    let page_url = |page: usize| {
+        format!("https://steamcommunity.com/profiles/{steam_id}/badges/?l=english&p={page}")
+    };
+
+    let first_page = fetch(&client, steam_id, &cookie_value, page_url(1)).await?;
+    let mut rows = parse_overview(&first_page);
+    for page in 2..=detect_max_page(&first_page) {
+        let html = fetch(&client, steam_id, &cookie_value, page_url(page)).await?;
+        rows.extend(parse_overview(&html));
+    }
+    rows.sort_unstable();
+    rows.dedup();
+
+    let mut badges = Vec::with_capacity(rows.len());
+    for (app_id, foil) in rows {
+        let border = if foil { "&border=1" } else { "" };
+        let url = format!(
+            "https://steamcommunity.com/profiles/{steam_id}/gamecards/{app_id}/?l=english{border}"
+        );
+        let html = fetch(&client, steam_id, &cookie_value, url).await?;
+        match parse_gamecards(&html, app_id, foil) {
+            Some(badge) => badges.push(badge),
+            None => tracing::debug!(
+                steam_id,
+                app_id,
+                foil,
+                "badges: overview row marked craftable but gamecards page has no complete set"
+            ),
+        }
+    }
+    Ok(badges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OVERVIEW: &str = include_str!("fixtures/badges_overview.html");
+    const GAMECARDS_COMPLETE: &str = include_str!("fixtures/gamecards_complete.html");
+    const GAMECARDS_INCOMPLETE: &str = include_str!("fixtures/gamecards_incomplete.html");
+
+    #[test]
+    fn overview_finds_craftable_rows_including_foil() {
+        assert_eq!(parse_overview(OVERVIEW), vec![(440, false), (570, true)]);
+        assert_eq!(detect_max_page(OVERVIEW), 3);
+    }
+
+    #[test]
+    fn gamecards_counts_complete_sets_and_level() {
+        let badge = parse_gamecards(GAMECARDS_COMPLETE, 440, false).unwrap();
+        assert_eq!(badge.name, "Team Fortress 2");
+        assert_eq!(badge.level, 2);
+        // Quantities (3), 1 (no label), (2), (4) - the unlabeled single copy caps it at one set.
+        assert_eq!(badge.sets, 1);
+        assert_eq!(badge.craftable_levels, 1);
+    }
+
+    #[test]
+    fn gamecards_caps_levels_at_max() {
+        let badge = parse_gamecards(GAMECARDS_COMPLETE, 440, true);
+        // Foil max level is 1 and the fixture's badge is already level 2.
+        assert!(badge.is_none());
+    }
+
+    #[test]
+    fn gamecards_with_missing_card_is_not_craftable() {
+        assert!(parse_gamecards(GAMECARDS_INCOMPLETE, 570, false).is_none());
+        assert!(parse_gamecards("<html><body>Sign In</body></html>", 570, false).is_none());
+    }
+}
@@ -0,0 +1,91 @@
+//! Per-account badge-crafting settings - own file in the per-SteamID64 directory, whole-struct
+//! get/set, same pattern as `card_farming::settings`.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tokio::sync::Mutex;
+
+use crate::error::{AppError, AppResult};
+use crate::fs_utils::atomic_write_json;
+use crate::platform;
+
+const SETTINGS_FILE_NAME: &str = "badge_crafting_settings.json";
+
+static WRITE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BadgeCraftingSettings {
+    /// Craft every complete set automatically once a card-farming cycle runs out of games to farm
+    /// (see `card_farming::manager::run_cycle`). Off by default - crafting consumes the cards, which
+    /// some users would rather sell.
+    pub auto_craft_after_farming: bool,
+    /// Games never crafted, manually or automatically - covers both the regular and foil badge.
+    pub excluded_app_ids: Vec<u32>,
+}
+
+fn settings_file_path(app_handle: &AppHandle, steam_id: &str) -> AppResult<PathBuf> {
+    Ok(platform::cache_dir(app_handle)?
+        .join(steam_id)
+        .join(SETTINGS_FILE_NAME))
+}
+
+/// Same self-heal-to-defaults-on-unparseable-file behavior as every other per-account settings
+/// module's `read_unlocked` (see `card_farming::settings::read_unlocked`'s comment).
+fn read_unlocked(app_handle: &AppHandle, steam_id: &str) -> AppResult<BadgeCraftingSettings> {
+    let path = settings_file_path(app_handle, steam_id)?;
+    if !path.exists() {
+        return Ok(BadgeCraftingSettings::default());
+    }
+
+    let contents =
+        fs::read_to_string(&path).map_err(|e| AppError::BadgeCraftingSettingsIo(e.to_string()))?;
+    if contents.trim().is_empty() {
+        return Ok(BadgeCraftingSettings::default());
+    }
+
+    match serde_json::from_str::<BadgeCraftingSettings>(&contents) {
+        Ok(settings) => Ok(settings),
+        Err(_) => {
+            tracing::warn!(
+                steam_id,
+                "badges: badge_crafting_settings.json failed to parse, resetting to defaults"
+            );
+            let defaults = BadgeCraftingSettings::default();
+            write_unlocked(app_handle, steam_id, &defaults)?;
+            Ok(defaults)
+        }
+    }
+}
+
+fn write_unlocked(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    settings: &BadgeCraftingSettings,
+) -> AppResult<()> {
+    let path = settings_file_path(app_handle, steam_id)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::BadgeCraftingSettingsIo(e.to_string()))?;
+    }
+    atomic_write_json(&path, settings).map_err(|e| AppError::BadgeCraftingSettingsIo(e.to_string()))
+}
+
+pub async fn get(app_handle: &AppHandle, steam_id: &str) -> AppResult<BadgeCraftingSettings> {
+    let _guard = WRITE_LOCK.lock().await;
+    read_unlocked(app_handle, steam_id)
+}
+
+/// Whole-struct replace - see `achievement_unlocker::settings::set`'s doc comment for why.
+pub async fn set(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    settings: BadgeCraftingSettings,
+) -> AppResult<BadgeCraftingSettings> {
+    let _guard = WRITE_LOCK.lock().await;
+    write_unlocked(app_handle, steam_id, &settings)?;
+    Ok(settings)
+}
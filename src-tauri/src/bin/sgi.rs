@@ -0,0 +1,5 @@
+//! Headless command-line entry point - see `steam_game_idler_lib::run_cli`.
+
+fn main() -> std::process::ExitCode {
+    steam_game_idler_lib::run_cli()
+}
@@ -7,18 +7,21 @@ use crate::games::commands::{resolve_steam_id, GamesAccount};
 use crate::local_steam::commands::require_steam_running;
 use crate::steam_agent::AgentManager;
 
+use super::ledger::{CardDropLedgerExportFormat, CardDropLedgerQuery, CardDropRecord};
 use super::{
-    blacklist, scraper, session, settings, whitelist, CardFarmingBlacklistEntry,
-    CardFarmingManager, CardFarmingWhitelistEntry, FarmingState, GameWithDrops, SteamCookies,
+    blacklist, ledger, market_value, scraper, session, settings, whitelist,
+    CardFarmingBlacklistEntry, CardFarmingManager, CardFarmingWhitelistEntry, FarmingState,
+    GameWithDrops, SteamCookies,
 };
-use settings::CardFarmingSettings;
+use settings::{CardFarmingSettings, FarmingPriority};
 
 /// Every owned game with at least one card drop remaining, for `account`'s Steam Community
 /// session - excludes any game the account has blacklisted (see [`blacklist`]'s doc comment), so a
 /// blacklisted game never reappears in the browse list to begin with. `manual_cookies` lets the
 /// caller skip automatic hidden-webview session acquisition entirely - the casual/free-tier
 /// fallback (see `mod.rs`'s doc comment); omit it to use the automatic path, gated behind
-/// `hasGamerAccess` on the frontend.
+/// `hasGamerAccess` on the frontend. Each game carries its `expected_value` once its cards have
+/// been priced (see `market_value`'s module doc comment).
 #[tauri::command]
 pub async fn get_games_with_drops(
     app_handle: AppHandle,
@@ -40,11 +43,24 @@ pub async fn get_games_with_drops(
         .into_iter()
         .map(|entry| entry.app_id)
         .collect();
-    let games: Vec<GameWithDrops> = scraper::get_games_with_drops(&steam_id, &cookies)
-        .await?
-        .into_iter()
-        .filter(|g| !blacklisted.contains(&g.app_id))
-        .collect();
+    let mut games: Vec<GameWithDrops> =
+        scraper::get_games_with_drops(&app_handle, &steam_id, &cookies)
+            .await?
+            .into_iter()
+            .filter(|g| !blacklisted.contains(&g.app_id))
+            .collect();
+    // Cached values only, unless market-value priority is on - see `market_value::annotate`.
+    let farming_settings = settings::get(&app_handle, &steam_id).await?;
+    market_value::annotate(
+        &app_handle,
+        &steam_id,
+        &cookies,
+        &mut games,
+        &HashSet::new(),
+        farming_settings.priority == FarmingPriority::MarketValue,
+        farming_settings.price_cache_hours,
+    )
+    .await;
     tracing::info!(
         steam_id,
         count = games.len(),
@@ -221,3 +237,39 @@ pub async fn clear_card_farming_blacklist(
     let steam_id = resolve_steam_id(&account, &agent_manager).await?;
     blacklist::clear(&app_handle, &steam_id).await
 }
+
+/// This account's recorded card drops matching `query` (omit it for the whole ledger), oldest
+/// first. See [`ledger`]'s doc comment.
+#[tauri::command]
+pub async fn get_card_drop_ledger(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    account: GamesAccount,
+    query: Option<CardDropLedgerQuery>,
+) -> AppResult<Vec<CardDropRecord>> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    ledger::query(&app_handle, &steam_id, &query.unwrap_or_default()).await
+}
+
+/// Writes the records matching `query` to `path` as CSV or JSON and returns how many were written -
+/// `path` comes from the frontend's save dialog (see `ledger::export`).
+#[tauri::command]
+pub async fn export_card_drop_ledger(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    account: GamesAccount,
+    query: Option<CardDropLedgerQuery>,
+    format: CardDropLedgerExportFormat,
+    path: String,
+) -> AppResult<usize> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    let records = ledger::query(&app_handle, &steam_id, &query.unwrap_or_default()).await?;
+    ledger::export(&records, format, std::path::Path::new(&path))?;
+    tracing::info!(
+        steam_id,
+        count = records.len(),
+        ?format,
+        "card farming: exported drop ledger"
+    );
+    Ok(records.len())
+}
@@ -0,0 +1,431 @@
+//! Completion-time estimates for a running farming cycle - per game and for the whole remaining
+//! library, republished on [`super::FarmingState::eta`] once per outer-loop iteration. Pure: every
+//! input is handed in by `manager::run_cycle`, so the whole model is unit-testable.
+//!
+//! **Drop rate** is learned from [`super::ledger`]: a game's own recorded drops (total `idle_ms` over
+//! total cards) when it has any, else the account-wide rate across every game, else
+//! [`DEFAULT_MS_PER_DROP`] for an account with no history at all. Only `ReadyFarm` records count -
+//! a bulk-idle drop's `idle_ms` measures accumulation time, not drop time. `idle_ms` is wall-clock
+//! time in the active set, so the restart-cycle's own stop/pause/sweep overhead is already baked
+//! into a learned rate.
+//!
+//! **Schedule** mirrors `manager::select_targets` under the selected strategy's
+//! [`StrategyPlan`] rather than assuming everything farms at once:
+//! - Ready games go first, in `manager::select_targets`' `FarmingPriority` order (fewest
+//!   remaining, or highest per-drop market value first) - one at a time, or up to
+//!   `MAX_CONCURRENT_FARMING` side by side with `allow_multi_game_farming` on and a non-solo
+//!   strategy (a finished game's slot going to the next in line).
+//! - Drops are only ever observed once per iteration, so every game's farming time is rounded up
+//!   to whole [`CycleTimings::cycle`]s.
+//! - Without a bulk-idle phase every game counts as ready. With one, accumulating games only
+//!   bulk-idle once nothing is ready, accruing [`CycleTimings::idle_per_cycle`] of playtime per
+//!   iteration. They're estimated as if the whole batch
+//!   reaches `hours_until_farmable` first and is farmed afterwards - the cycle only ever runs one
+//!   phase at a time, so the library total comes out the same either way, but an individual
+//!   accumulating game's estimate is the coarsest number here.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use serde::Serialize;
+
+use super::ledger::CardDropRecord;
+use super::manager::{priority_order, MAX_CONCURRENT_FARMING};
+use super::settings::FarmingPriority;
+use super::strategy::{CycleTimings, StrategyPlan};
+use super::{FarmingProgress, GameWithDrops, Phase};
+
+/// Fallback rate for an account with no recorded drops yet - roughly what the restart cycle
+/// achieves on a typical ready game.
+pub const DEFAULT_MS_PER_DROP: i64 = 30 * 60 * 1000;
+
+/// Where a game's `ms_per_drop` came from - lets the frontend mark an estimate as a guess.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DropRateSource {
+    Game,
+    Account,
+    Default,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameEta {
+    pub app_id: u32,
+    pub remaining: u32,
+    pub ms_per_drop: i64,
+    pub rate_source: DropRateSource,
+    /// Unix millis this game is expected to run out of drops.
+    pub completes_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FarmingEta {
+    /// Every active and queued game, in expected completion order.
+    pub games: Vec<GameEta>,
+    /// Unix millis the last game is expected to finish - `None` when there's nothing left.
+    pub library_completes_at: Option<i64>,
+}
+
+/// Learned per-game and account-wide rates - see this module's doc comment.
+#[derive(Debug, Default)]
+pub struct DropRates {
+    per_game: HashMap<u32, i64>,
+    account: Option<i64>,
+}
+
+impl DropRates {
+    pub fn learn(records: &[CardDropRecord]) -> Self {
+        let mut per_game: HashMap<u32, (i64, i64)> = HashMap::new();
+        let mut total = (0i64, 0i64);
+        for r in records
+            .iter()
+            .filter(|r| r.phase == Phase::ReadyFarm && r.idle_ms > 0 && r.count > 0)
+        {
+            let entry = per_game.entry(r.app_id).or_default();
+            entry.0 += r.idle_ms;
+            entry.1 += r.count as i64;
+            total.0 += r.idle_ms;
+            total.1 += r.count as i64;
+        }
+        DropRates {
+            per_game: per_game
+                .into_iter()
+                .map(|(app_id, (ms, cards))| (app_id, ms / cards))
+                .collect(),
+            account: (total.1 > 0).then(|| total.0 / total.1),
+        }
+    }
+
+    fn rate(&self, app_id: u32) -> (i64, DropRateSource) {
+        if let Some(&ms) = self.per_game.get(&app_id) {
+            (ms, DropRateSource::Game)
+        } else if let Some(ms) = self.account {
+            (ms, DropRateSource::Account)
+        } else {
+            (DEFAULT_MS_PER_DROP, DropRateSource::Default)
+        }
+    }
+}
+
+fn cycle_ms(timings: &CycleTimings, targets: usize) -> i64 {
+    timings.cycle(targets).as_millis() as i64
+}
+
+/// `work_ms` rounded up to whole cycles - at least one, since even a game one drop away needs a
+/// cycle to finish before the re-scrape that notices it.
+fn whole_cycles(work_ms: i64, cycle: i64) -> i64 {
+    let cycles = (work_ms.max(1) + cycle - 1) / cycle;
+    cycles * cycle
+}
+
+/// Inputs for [`estimate`] - a snapshot of one outer-loop iteration.
+pub struct EtaInput<'a> {
+    pub phase: Phase,
+    pub active: &'a [FarmingProgress],
+    pub queue: &'a [GameWithDrops],
+    pub plan: &'a StrategyPlan,
+    pub allow_multi_game_farming: bool,
+    pub hours_until_farmable: u32,
+    pub priority: FarmingPriority,
+    /// Each priced game's `GameWithDrops::expected_value` - `FarmingProgress` doesn't carry it,
+    /// and `MarketValue` priority orders by it.
+    pub expected_values: &'a HashMap<u32, f64>,
+    pub rates: &'a DropRates,
+    /// Each game's most recent ledger drop - with `active_since`, how much of the next drop's
+    /// interval an active game has already served.
+    pub last_drop_at: &'a HashMap<u32, i64>,
+    pub now_ms: i64,
+}
+
+pub fn estimate(input: &EtaInput) -> FarmingEta {
+    let threshold = input.hours_until_farmable as f32;
+    let mut ready: Vec<(u32, u32, i64)> = Vec::new();
+    let mut accumulating: Vec<(u32, u32, f32)> = Vec::new();
+
+    for p in input.active {
+        if input.phase == Phase::ReadyFarm {
+            let served = input
+                .last_drop_at
+                .get(&p.app_id)
+                .map_or(p.active_since, |&t| t.max(p.active_since));
+            ready.push((p.app_id, p.remaining, input.now_ms - served));
+        } else {
+            accumulating.push((p.app_id, p.remaining, p.playtime_hours));
+        }
+    }
+    for g in input.queue {
+        if !input.plan.bulk_idle || g.playtime_hours >= threshold {
+            ready.push((g.app_id, g.remaining, 0));
+        } else {
+            accumulating.push((g.app_id, g.remaining, g.playtime_hours));
+        }
+    }
+
+    let mut games = Vec::new();
+    let ready_done = schedule_farming(input, &ready, input.now_ms, &mut games);
+
+    // Highest playtime first, same priority `select_phase` gives the limited bulk-idle slots.
+    accumulating.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+    let batch = accumulating.len().min(MAX_CONCURRENT_FARMING);
+    let timings = &input.plan.timings;
+    let accrual_per_cycle = timings.idle_per_cycle().as_millis() as i64;
+    let needed_cycles: Vec<i64> = accumulating
+        .iter()
+        .map(|&(_, _, playtime)| {
+            let needed_ms = (((threshold - playtime).max(0.0) as f64) * 3_600_000.0) as i64;
+            (needed_ms + accrual_per_cycle - 1) / accrual_per_cycle
+        })
+        .collect();
+    let accumulated_at = run_slots(&needed_cycles, batch, cycle_ms(timings, batch), ready_done);
+    let accumulation_done = accumulated_at.iter().copied().max().unwrap_or(ready_done);
+    let now_ready: Vec<(u32, u32, i64)> = accumulating
+        .iter()
+        .map(|&(app_id, remaining, _)| (app_id, remaining, 0))
+        .collect();
+    schedule_farming(input, &now_ready, accumulation_done, &mut games);
+
+    games.sort_by_key(|g| g.completes_at);
+    let library_completes_at = games.last().map(|g| g.completes_at);
+    FarmingEta {
+        games,
+        library_completes_at,
+    }
+}
+
+/// Farms `games` (`(app_id, remaining, already_served_ms)`) from `start` in `input.priority`
+/// order, pushing each one's estimate and returning when the last finishes.
+fn schedule_farming(
+    input: &EtaInput,
+    games: &[(u32, u32, i64)],
+    start: i64,
+    out: &mut Vec<GameEta>,
+) -> i64 {
+    let mut ordered = games.to_vec();
+    let key = |&(app_id, remaining, _): &(u32, u32, i64)| {
+        (remaining, input.expected_values.get(&app_id).copied())
+    };
+    ordered.sort_by(|a, b| priority_order(input.priority, key(a), key(b)));
+    let slots = if input.allow_multi_game_farming && !input.plan.solo {
+        ordered.len().min(MAX_CONCURRENT_FARMING)
+    } else {
+        1
+    };
+    let cycle = cycle_ms(&input.plan.timings, slots.max(1));
+    let cycles: Vec<i64> = ordered
+        .iter()
+        .map(|&(app_id, remaining, served)| {
+            let (ms_per_drop, _) = input.rates.rate(app_id);
+            whole_cycles(remaining as i64 * ms_per_drop - served, cycle) / cycle
+        })
+        .collect();
+    let finished = run_slots(&cycles, slots, cycle, start);
+    for (&(app_id, remaining, _), &completes_at) in ordered.iter().zip(&finished) {
+        let (ms_per_drop, rate_source) = input.rates.rate(app_id);
+        out.push(GameEta {
+            app_id,
+            remaining,
+            ms_per_drop,
+            rate_source,
+            completes_at,
+        });
+    }
+    finished.iter().copied().max().unwrap_or(start)
+}
+
+/// Runs jobs of `cycles[i]` cycles each, in order, across `slots` parallel slots starting at
+/// `start` - each job takes the earliest-free slot. Returns each job's finish time.
+fn run_slots(cycles: &[i64], slots: usize, cycle: i64, start: i64) -> Vec<i64> {
+    let mut free: BinaryHeap<Reverse<i64>> = (0..slots.max(1)).map(|_| Reverse(start)).collect();
+    cycles
+        .iter()
+        .map(|&n| {
+            let Reverse(at) = free.pop().unwrap_or(Reverse(start));
+            let done = at + n * cycle;
+            free.push(Reverse(done));
+            done
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::LazyLock;
+
+    use super::super::strategy::{CustomStrategy, FarmingStrategy};
+    use super::*;
+
+    static TWO_PHASE: LazyLock<StrategyPlan> =
+        LazyLock::new(|| FarmingStrategy::TwoPhase.plan(&CustomStrategy::default()));
+    static NO_VALUES: LazyLock<HashMap<u32, f64>> = LazyLock::new(HashMap::new);
+
+    fn progress(app_id: u32, remaining: u32, active_since: i64) -> FarmingProgress {
+        FarmingProgress {
+            app_id,
+            name: String::new(),
+            initial_remaining: remaining,
+            remaining,
+            playtime_hours: 10.0,
+            active_since,
+        }
+    }
+
+    fn queued(app_id: u32, remaining: u32, playtime_hours: f32) -> GameWithDrops {
+        GameWithDrops {
+            app_id,
+            name: String::new(),
+            remaining,
+            playtime_hours,
+            expected_value: None,
+        }
+    }
+
+    fn drop_record(app_id: u32, idle_ms: i64) -> CardDropRecord {
+        CardDropRecord {
+            app_id,
+            name: String::new(),
+            dropped_at: 0,
+            count: 1,
+            remaining: 0,
+            phase: Phase::ReadyFarm,
+            strategy: FarmingStrategy::TwoPhase,
+            idle_ms,
+        }
+    }
+
+    fn input<'a>(
+        active: &'a [FarmingProgress],
+        queue: &'a [GameWithDrops],
+        multi: bool,
+        rates: &'a DropRates,
+        last_drop_at: &'a HashMap<u32, i64>,
+    ) -> EtaInput<'a> {
+        EtaInput {
+            phase: Phase::ReadyFarm,
+            active,
+            queue,
+            plan: &TWO_PHASE,
+            allow_multi_game_farming: multi,
+            hours_until_farmable: 2,
+            priority: FarmingPriority::FewestDrops,
+            expected_values: &NO_VALUES,
+            rates,
+            last_drop_at,
+            now_ms: 0,
+        }
+    }
+
+    #[test]
+    fn learns_per_game_then_account_then_default() {
+        let rates = DropRates::learn(&[
+            drop_record(1, 1_000),
+            drop_record(1, 3_000),
+            drop_record(2, 8_000),
+        ]);
+        assert_eq!(rates.rate(1), (2_000, DropRateSource::Game));
+        assert_eq!(rates.rate(3), (4_000, DropRateSource::Account));
+        assert_eq!(
+            DropRates::default().rate(3),
+            (DEFAULT_MS_PER_DROP, DropRateSource::Default)
+        );
+    }
+
+    #[test]
+    fn solo_farms_sequentially_in_whole_cycles() {
+        let rates = DropRates::default();
+        let none = HashMap::new();
+        let active = [progress(1, 1, 0)];
+        let queue = [queued(2, 2, 5.0)];
+        let eta = estimate(&input(&active, &queue, false, &rates, &none));
+        let cycle = cycle_ms(&TWO_PHASE.timings, 1);
+        let one = whole_cycles(DEFAULT_MS_PER_DROP, cycle);
+        let two = whole_cycles(2 * DEFAULT_MS_PER_DROP, cycle);
+        assert_eq!(eta.games[0].completes_at, one);
+        assert_eq!(eta.games[1].completes_at, one + two);
+        assert_eq!(eta.library_completes_at, Some(one + two));
+    }
+
+    #[test]
+    fn multi_farms_side_by_side() {
+        let rates = DropRates::default();
+        let none = HashMap::new();
+        let active = [progress(1, 1, 0), progress(2, 2, 0)];
+        let eta = estimate(&input(&active, &[], true, &rates, &none));
+        let cycle = cycle_ms(&TWO_PHASE.timings, 2);
+        assert_eq!(
+            eta.library_completes_at,
+            Some(whole_cycles(2 * DEFAULT_MS_PER_DROP, cycle))
+        );
+    }
+
+    #[test]
+    fn credits_time_already_served_since_last_drop() {
+        let rates = DropRates::default();
+        let last_drop_at = HashMap::from([(1, -DEFAULT_MS_PER_DROP)]);
+        let active = [progress(1, 1, -2 * DEFAULT_MS_PER_DROP)];
+        let eta = estimate(&input(&active, &[], false, &rates, &last_drop_at));
+        assert_eq!(
+            eta.library_completes_at,
+            Some(cycle_ms(&TWO_PHASE.timings, 1))
+        );
+    }
+
+    #[test]
+    fn accumulating_games_finish_after_ready_ones() {
+        let rates = DropRates::default();
+        let none = HashMap::new();
+        let active = [progress(1, 1, 0)];
+        let queue = [queued(2, 1, 1.0)];
+        let eta = estimate(&input(&active, &queue, false, &rates, &none));
+        assert_eq!(eta.games[0].app_id, 1);
+        assert_eq!(eta.games[1].app_id, 2);
+        // One farmable hour short, accrued at (main window + sweep slot) per cycle.
+        assert!(eta.games[1].completes_at - eta.games[0].completes_at > 3_600_000);
+    }
+
+    #[test]
+    fn simple_strategy_farms_everything_solo_without_accumulating() {
+        let plan = FarmingStrategy::Simple.plan(&CustomStrategy::default());
+        let rates = DropRates::default();
+        let none = HashMap::new();
+        let active = [progress(1, 1, 0)];
+        let queue = [queued(2, 1, 0.0)];
+        let eta = estimate(&EtaInput {
+            plan: &plan,
+            ..input(&active, &queue, true, &rates, &none)
+        });
+        let one = whole_cycles(DEFAULT_MS_PER_DROP, cycle_ms(&plan.timings, 1));
+        assert_eq!(eta.library_completes_at, Some(2 * one));
+    }
+
+    #[test]
+    fn empty_library_has_no_completion_time() {
+        let rates = DropRates::default();
+        let none = HashMap::new();
+        let eta = estimate(&input(&[], &[], false, &rates, &none));
+        assert!(eta.games.is_empty());
+        assert_eq!(eta.library_completes_at, None);
+    }
+
+    #[test]
+    fn market_value_priority_farms_the_most_valuable_drops_first() {
+        let rates = DropRates::default();
+        let none = HashMap::new();
+        // Per drop: 1 is worth 0.10, 2 is worth 0.50, 3 isn't priced.
+        let values = HashMap::from([(1, 0.30), (2, 1.00)]);
+        let queue = [queued(1, 3, 5.0), queued(2, 2, 5.0), queued(3, 1, 5.0)];
+        let base = input(&[], &queue, false, &rates, &none);
+        let eta = estimate(&EtaInput {
+            priority: FarmingPriority::MarketValue,
+            expected_values: &values,
+            ..base
+        });
+        let order: Vec<u32> = eta.games.iter().map(|g| g.app_id).collect();
+        assert_eq!(order, vec![2, 1, 3]);
+
+        let eta = estimate(&input(&[], &queue, false, &rates, &none));
+        let order: Vec<u32> = eta.games.iter().map(|g| g.app_id).collect();
+        assert_eq!(order, vec![3, 2, 1]);
+    }
+}
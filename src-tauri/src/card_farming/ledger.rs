@@ -0,0 +1,289 @@
+//! Persists one account's card-drop ledger - every drop `manager::run_cycle` detects (a game's
+//! scraped `remaining` falling between two polls), keyed by resolved SteamID64, in its own file
+//! alongside [`super::blacklist`]/[`super::whitelist`] and with the same one-lock-per-read-modify-
+//! write pattern. Unlike [`super::FarmingState::completed`] this outlives the session that produced
+//! it, which is the point: it's the only record of what farming actually produced over time.
+//!
+//! Append-only from the cycle's side - nothing here ever prunes or rewrites an existing record.
+//! A drop is rare enough (a handful per game, ever) that the whole-file rewrite per append every
+//! other per-account file already uses stays cheap even after years of farming.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tokio::sync::Mutex;
+
+use crate::error::{AppError, AppResult};
+use crate::fs_utils::atomic_write_json;
+use crate::platform;
+
+use super::strategy::FarmingStrategy;
+use super::Phase;
+
+const CACHE_FILE_NAME: &str = "card_drop_ledger.json";
+
+static WRITE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+/// One detected drop event. `count` is usually 1 - more only when several cards dropped within a
+/// single poll interval, which the scrape can't tell apart, so they're recorded as one event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CardDropRecord {
+    pub app_id: u32,
+    pub name: String,
+    /// Unix millis of the poll that observed the drop - not the drop itself, which happened at some
+    /// point since the previous poll.
+    pub dropped_at: i64,
+    pub count: u32,
+    /// Drops still remaining for this game right after this one.
+    pub remaining: u32,
+    /// Which phase the cycle was running when the drop was observed - almost always `ReadyFarm`,
+    /// but a bulk-idled game can still drop if Steam's own playtime threshold is lower than
+    /// `hours_until_farmable`, and that's worth seeing.
+    pub phase: Phase,
+    /// The `CardFarmingSettings::strategy` in effect - what lets strategies be compared by their
+    /// actual output. Records from before strategies existed read as `TwoPhase`, which is what
+    /// produced them.
+    #[serde(default)]
+    pub strategy: FarmingStrategy,
+    /// Millis this game spent in the cycle's `active` set since its previous recorded drop this
+    /// session, or since it last entered `active` for the session's first drop - i.e. how much
+    /// idling this drop cost.
+    pub idle_ms: i64,
+}
+
+/// `get_card_drop_ledger`/`export_card_drop_ledger`'s filter. Every field is optional - an empty
+/// query matches the whole ledger. `from`/`to` are unix millis, `from` inclusive, `to` exclusive,
+/// so consecutive weekly ranges never count a drop twice.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CardDropLedgerQuery {
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub app_id: Option<u32>,
+    pub strategy: Option<FarmingStrategy>,
+}
+
+impl CardDropLedgerQuery {
+    fn matches(&self, record: &CardDropRecord) -> bool {
+        self.from.is_none_or(|from| record.dropped_at >= from)
+            && self.to.is_none_or(|to| record.dropped_at < to)
+            && self.app_id.is_none_or(|app_id| record.app_id == app_id)
+            && self
+                .strategy
+                .is_none_or(|strategy| record.strategy == strategy)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CardDropLedgerExportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedLedger {
+    drops: Vec<CardDropRecord>,
+}
+
+fn cache_file_path(app_handle: &AppHandle, steam_id: &str) -> AppResult<PathBuf> {
+    Ok(platform::cache_dir(app_handle)?
+        .join(steam_id)
+        .join(CACHE_FILE_NAME))
+}
+
+/// Deliberately no self-heal-to-empty on a parse failure (unlike the settings modules) - silently
+/// replacing an unreadable ledger with an empty one would throw away exactly the history this file
+/// exists to keep. The error surfaces instead, and the file is left for the user to inspect.
+fn read_unlocked(app_handle: &AppHandle, steam_id: &str) -> AppResult<Vec<CardDropRecord>> {
+    let path = cache_file_path(app_handle, steam_id)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let contents =
+        fs::read_to_string(&path).map_err(|e| AppError::CardDropLedgerIo(e.to_string()))?;
+    if contents.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let cached: CachedLedger =
+        serde_json::from_str(&contents).map_err(|e| AppError::CardDropLedgerIo(e.to_string()))?;
+    Ok(cached.drops)
+}
+
+fn write_unlocked(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    drops: &[CardDropRecord],
+) -> AppResult<()> {
+    let path = cache_file_path(app_handle, steam_id)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::CardDropLedgerIo(e.to_string()))?;
+    }
+    atomic_write_json(
+        &path,
+        &CachedLedger {
+            drops: drops.to_vec(),
+        },
+    )
+    .map_err(|e| AppError::CardDropLedgerIo(e.to_string()))
+}
+
+pub async fn append(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    record: CardDropRecord,
+) -> AppResult<()> {
+    let _guard = WRITE_LOCK.lock().await;
+    let mut drops = read_unlocked(app_handle, steam_id)?;
+    drops.push(record);
+    write_unlocked(app_handle, steam_id, &drops)
+}
+
+/// Every record matching `query`, oldest first.
+pub async fn query(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    query: &CardDropLedgerQuery,
+) -> AppResult<Vec<CardDropRecord>> {
+    let _guard = WRITE_LOCK.lock().await;
+    let mut drops: Vec<CardDropRecord> = read_unlocked(app_handle, steam_id)?
+        .into_iter()
+        .filter(|r| query.matches(r))
+        .collect();
+    drops.sort_by_key(|r| r.dropped_at);
+    Ok(drops)
+}
+
+/// Writes `records` to `path` - a path the frontend obtained from `@tauri-apps/plugin-dialog`'s
+/// save picker, same provenance as `customization::set_background`'s `source_path`.
+pub fn export(
+    records: &[CardDropRecord],
+    format: CardDropLedgerExportFormat,
+    path: &Path,
+) -> AppResult<()> {
+    let contents = match format {
+        CardDropLedgerExportFormat::Csv => to_csv(records),
+        CardDropLedgerExportFormat::Json => serde_json::to_string_pretty(records)
+            .map_err(|e| AppError::CardDropLedgerIo(e.to_string()))?,
+    };
+    fs::write(path, contents)
+        .map_err(|e| AppError::CardDropLedgerIo(format!("failed to write {}: {e}", path.display())))
+}
+
+/// RFC 4180 CSV with a header row. `droppedAt` is written as RFC 3339 UTC rather than raw millis
+/// since a spreadsheet is the main consumer; `idleMinutes` likewise, rounded down.
+fn to_csv(records: &[CardDropRecord]) -> String {
+    let mut out =
+        String::from("appId,name,droppedAt,count,remaining,phase,strategy,idleMinutes\r\n");
+    for r in records {
+        let dropped_at = chrono::DateTime::from_timestamp_millis(r.dropped_at)
+            .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+            .unwrap_or_default();
+        let phase = match r.phase {
+            Phase::ReadyFarm => "readyFarm",
+            Phase::BulkIdle => "bulkIdle",
+        };
+        let strategy = match r.strategy {
+            FarmingStrategy::TwoPhase => "twoPhase",
+            FarmingStrategy::Simple => "simple",
+            FarmingStrategy::FastRestart => "fastRestart",
+            FarmingStrategy::Custom => "custom",
+        };
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{},{}\r\n",
+            r.app_id,
+            csv_field(&r.name),
+            dropped_at,
+            r.count,
+            r.remaining,
+            phase,
+            strategy,
+            r.idle_ms / 60_000
+        ));
+    }
+    out
+}
+
+/// Quotes a field only when it needs it (a comma, quote, or line break), doubling inner quotes.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(app_id: u32, name: &str, dropped_at: i64) -> CardDropRecord {
+        CardDropRecord {
+            app_id,
+            name: name.to_string(),
+            dropped_at,
+            count: 1,
+            remaining: 2,
+            phase: Phase::ReadyFarm,
+            strategy: FarmingStrategy::TwoPhase,
+            idle_ms: 125 * 60_000,
+        }
+    }
+
+    #[test]
+    fn query_filters_by_range_and_game() {
+        let r = record(440, "TF2", 1_000);
+        let week = CardDropLedgerQuery {
+            from: Some(1_000),
+            to: Some(2_000),
+            app_id: None,
+            strategy: None,
+        };
+        assert!(week.matches(&r));
+        assert!(!CardDropLedgerQuery {
+            from: Some(0),
+            to: Some(1_000),
+            app_id: None,
+            strategy: None,
+        }
+        .matches(&r));
+        assert!(!CardDropLedgerQuery {
+            app_id: Some(730),
+            ..Default::default()
+        }
+        .matches(&r));
+        assert!(!CardDropLedgerQuery {
+            strategy: Some(FarmingStrategy::Simple),
+            ..Default::default()
+        }
+        .matches(&r));
+        assert!(CardDropLedgerQuery::default().matches(&r));
+    }
+
+    #[test]
+    fn csv_escapes_names_and_formats_times() {
+        let csv = to_csv(&[
+            record(440, "Team Fortress 2", 0),
+            record(10, "Counter-Strike, \"Classic\"", 0),
+        ]);
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+        assert_eq!(
+            lines[0],
+            "appId,name,droppedAt,count,remaining,phase,strategy,idleMinutes"
+        );
+        assert_eq!(
+            lines[1],
+            "440,Team Fortress 2,1970-01-01T00:00:00Z,1,2,readyFarm,twoPhase,125"
+        );
+        assert_eq!(
+            lines[2],
+            "10,\"Counter-Strike, \"\"Classic\"\"\",1970-01-01T00:00:00Z,1,2,readyFarm,twoPhase,125"
+        );
+    }
+}
@@ -25,9 +25,14 @@ use crate::games::{self, commands::GamesAccount};
 use crate::idling::{self, IdleTarget, IdlingManager};
 use crate::steam_agent::AgentManager;
 use crate::steam_community::credentials;
+use crate::webhooks::{self, WebhookEvent};
 
+use super::eta;
+use super::ledger::{self, CardDropRecord};
+use super::market_value;
 use super::refund_window;
-use super::settings;
+use super::settings::{self, FarmingPriority};
+use super::strategy::{CycleTimings, FarmingStrategy, StrategyPlan};
 use super::whitelist;
 use super::{
     blacklist, scraper, CompletedFarm, CompletedFarmReason, FarmingProgress, FarmingState,
@@ -39,14 +44,7 @@ use super::FARMING_STATE_EVENT;
 /// comment on `MAX_CONCURRENT_GAMES`), defined again here (rather than reaching into `idling`'s
 /// private internals) so this module's own active-set bookkeeping stays consistent with what the
 /// idling claims registry will actually accept.
-const MAX_CONCURRENT_FARMING: usize = 32;
-
-/// How long the restart-cycle idles its target(s) before the first stop of each iteration.
-const FULL_IDLE_DURATION: Duration = Duration::from_secs(5 * 60);
-/// How long the restart-cycle pauses between the main idle window and the individual sweep.
-const MICRO_PAUSE: Duration = Duration::from_secs(5);
-/// How long each target gets idled, alone, during the restart-cycle's individual sweep.
-const MICRO_IDLE_DURATION: Duration = Duration::from_secs(5);
+pub(super) const MAX_CONCURRENT_FARMING: usize = 32;
 
 /// One owned-games-cache entry's worth of data this module's checks care about - bundles real
 /// playtime (skip-settings, refund-window playtime threshold) and purchase recency (refund-window
@@ -138,10 +136,7 @@ async fn resolve_candidates(
     excluded_app_ids: &HashSet<u32>,
     completed: &mut Vec<CompletedFarm>,
 ) -> AppResult<ResolvedCandidates> {
-    let games = scraper::get_games_with_drops(steam_id, cookies).await?;
-    let games_by_app_id: HashMap<u32, GameWithDrops> =
-        games.into_iter().map(|g| (g.app_id, g)).collect();
-
+    let mut games = scraper::get_games_with_drops(app_handle, steam_id, cookies).await?;
     let blacklisted: HashSet<u32> = blacklist::read(app_handle, steam_id)
         .await?
         .into_iter()
@@ -151,6 +146,38 @@ async fn resolve_candidates(
     let game_cache = owned_game_cache_lookup(app_handle, steam_id);
     let now = chrono::Utc::now().timestamp();
 
+    // Everything the classification below is bound to drop - not worth a market request.
+    let unpriced: HashSet<u32> = games
+        .iter()
+        .map(|g| g.app_id)
+        .filter(|app_id| {
+            excluded_app_ids.contains(app_id)
+                || blacklisted.contains(app_id)
+                || (!whitelist_entries.is_empty()
+                    && !whitelist_entries
+                        .iter()
+                        .any(|entry| entry.app_id == *app_id))
+                || filter_reason(
+                    game_cache.get(app_id).copied().unwrap_or_default(),
+                    farming_settings,
+                    now,
+                )
+                .is_some()
+        })
+        .collect();
+    market_value::annotate(
+        app_handle,
+        steam_id,
+        cookies,
+        &mut games,
+        &unpriced,
+        farming_settings.priority == FarmingPriority::MarketValue,
+        farming_settings.price_cache_hours,
+    )
+    .await;
+    let games_by_app_id: HashMap<u32, GameWithDrops> =
+        games.into_iter().map(|g| (g.app_id, g)).collect();
+
     let mut ready = Vec::new();
     let mut accumulating = Vec::new();
     let classify = |game: GameWithDrops, ready: &mut Vec<GameWithDrops>, accumulating: &mut Vec<GameWithDrops>| {
@@ -283,30 +310,144 @@ async fn prune_whitelist_member(
     });
 }
 
+/// The ledger record for `count` drops of `prog`'s game observed at `now_ms`, leaving
+/// `remaining`. `idle_ms` runs from the game's previous drop this session, clamped to its
+/// `active_since` so queue time never counts as idle time, and `now_ms` becomes its new last drop.
+fn record_drop(
+    prog: &FarmingProgress,
+    count: u32,
+    remaining: u32,
+    last_drop_at: &mut HashMap<u32, i64>,
+    now_ms: i64,
+    phase: Phase,
+    strategy: FarmingStrategy,
+) -> CardDropRecord {
+    let since = last_drop_at
+        .get(&prog.app_id)
+        .map_or(prog.active_since, |&t| t.max(prog.active_since));
+    last_drop_at.insert(prog.app_id, now_ms);
+    CardDropRecord {
+        app_id: prog.app_id,
+        name: prog.name.clone(),
+        dropped_at: now_ms,
+        count,
+        remaining,
+        phase,
+        strategy,
+        idle_ms: now_ms - since,
+    }
+}
+
+/// The drops that emptied each `DropsExhausted` game in `completed` - a finished game leaves the
+/// scrape instead of showing up with fewer drops, so the per-target comparison in `run_cycle`
+/// never sees its last drop(s). Counted from the last `remaining` this session observed.
+fn exhausted_drops(
+    completed: &[CompletedFarm],
+    progress: &HashMap<u32, FarmingProgress>,
+    last_drop_at: &mut HashMap<u32, i64>,
+    now_ms: i64,
+    phase: Phase,
+    strategy: FarmingStrategy,
+) -> Vec<CardDropRecord> {
+    completed
+        .iter()
+        .filter(|farm| matches!(farm.reason, CompletedFarmReason::DropsExhausted))
+        .filter_map(|farm| progress.get(&farm.app_id))
+        .filter(|prog| prog.remaining > 0)
+        .map(|prog| {
+            record_drop(
+                prog,
+                prog.remaining,
+                0,
+                last_drop_at,
+                now_ms,
+                phase,
+                strategy,
+            )
+        })
+        .collect()
+}
+
+/// Appends `record` to the ledger and sends its `CardDropped` webhook.
+async fn report_drop(app_handle: &AppHandle, steam_id: &str, record: CardDropRecord) {
+    let app_id = record.app_id;
+    let event = WebhookEvent::CardDropped {
+        app_id,
+        name: record.name.clone(),
+        dropped: record.count,
+        remaining: record.remaining,
+    };
+    if let Err(e) = ledger::append(app_handle, steam_id, record).await {
+        tracing::warn!(steam_id, app_id, error = %e, "card farming: failed to record drop in ledger");
+    }
+    webhooks::notify(app_handle, steam_id, event);
+}
+
+/// The ready-farm phase's candidate order for `priority` (see `settings::FarmingPriority`). Market
+/// value compares per-drop value, not `expected_value` itself - a game with many cheap drops left
+/// shouldn't outrank one expensive drop. Unpriced games sort after every priced one.
+fn ready_order(
+    priority: FarmingPriority,
+    a: &GameWithDrops,
+    b: &GameWithDrops,
+) -> std::cmp::Ordering {
+    priority_order(
+        priority,
+        (a.remaining, a.expected_value),
+        (b.remaining, b.expected_value),
+    )
+}
+
+/// [`ready_order`] on bare `(remaining, expected_value)` pairs - also what `eta` orders its ready
+/// games by, so the estimate farms them in the order this module actually will.
+pub(super) fn priority_order(
+    priority: FarmingPriority,
+    a: (u32, Option<f64>),
+    b: (u32, Option<f64>),
+) -> std::cmp::Ordering {
+    let fewest_drops = a.0.cmp(&b.0);
+    match priority {
+        FarmingPriority::FewestDrops => fewest_drops,
+        FarmingPriority::MarketValue => {
+            let drop_value = |(remaining, expected_value): (u32, Option<f64>)| {
+                expected_value.map(|v| v / f64::from(remaining.max(1)))
+            };
+            match (drop_value(a), drop_value(b)) {
+                (Some(a), Some(b)) => b.partial_cmp(&a).unwrap_or(std::cmp::Ordering::Equal),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            }
+            .then(fewest_drops)
+        }
+    }
+}
+
 /// Picks exactly one phase from `candidates` and its target(s) - pure and unit-testable, no
 /// network/lock access. `None` means nothing is eligible at all (the cycle should end).
 fn select_phase(
     candidates: &ResolvedCandidates,
     allow_multi_game_farming: bool,
+    priority: FarmingPriority,
 ) -> Option<(Phase, Vec<GameWithDrops>)> {
     if !candidates.ready.is_empty() {
         if allow_multi_game_farming {
             let mut ready = candidates.ready.clone();
-            // Fewest remaining drops first - same tie-break the solo case uses below, now
-            // deciding who gets priority for the limited concurrent-farm slots when there are
+            // `priority` order - same order the solo case picks from below, now deciding who
+            // gets priority for the limited concurrent-farm slots when there are
             // more than MAX_CONCURRENT_FARMING games ready at once. Capping here (not just
             // relying on `idling::claims`'s own downstream cap) matters because this list also
             // drives `state.active`/`progress` - without it, games beyond the cap would show as
             // "farming" while actually only getting a few seconds of real idle time per cycle
             // (during the restart-cycle's individual sweep), never the full main window.
-            ready.sort_by_key(|g| g.remaining);
+            ready.sort_by(|a, b| ready_order(priority, a, b));
             ready.truncate(MAX_CONCURRENT_FARMING);
             Some((Phase::ReadyFarm, ready))
         } else {
             let target = candidates
                 .ready
                 .iter()
-                .min_by_key(|g| g.remaining)?
+                .min_by(|a, b| ready_order(priority, a, b))?
                 .clone();
             Some((Phase::ReadyFarm, vec![target]))
         }
@@ -362,21 +503,49 @@ async fn announce_idle_set(
         .map(|_| ())
 }
 
+/// [`select_phase`] as the selected strategy shapes it (see `strategy`'s module doc comment): with
+/// no bulk-idle phase every candidate counts as ready, and a solo strategy never multi-farms.
+fn select_targets(
+    candidates: &ResolvedCandidates,
+    plan: &StrategyPlan,
+    allow_multi_game_farming: bool,
+    priority: FarmingPriority,
+) -> Option<(Phase, Vec<GameWithDrops>)> {
+    let allow_multi = allow_multi_game_farming && !plan.solo;
+    if plan.bulk_idle {
+        return select_phase(candidates, allow_multi, priority);
+    }
+    let merged = ResolvedCandidates {
+        ready: candidates
+            .ready
+            .iter()
+            .chain(&candidates.accumulating)
+            .cloned()
+            .collect(),
+        accumulating: Vec::new(),
+    };
+    select_phase(&merged, allow_multi, priority)
+}
+
 /// Runs one full stop/pause/restart micro-cycle against `targets` - the same sequence whether
 /// `targets.len()` is 1 (a solo ready-farm pick) or more (a bulk-idle batch, or a multi-game
 /// ready-farm batch when `allow_multi_game_farming` is on): idle everything together for
-/// [`FULL_IDLE_DURATION`], stop, pause [`MICRO_PAUSE`], then sweep every target individually
-/// ([`MICRO_IDLE_DURATION`] each, stopped between each - exactly one target physically idling at any
-/// instant during this step) before returning control to the caller for a fresh
+/// `timings.main_window`, stop, pause `timings.pause`, then sweep every target individually
+/// (`timings.sweep_per_game` each, stopped between each - exactly one target physically idling at
+/// any instant during this step) before returning control to the caller for a fresh
 /// re-scrape/reclassify. Every stop and start here is a real `IdleClaimsRegistry::
 /// replace_owner_claim` call - cheap for agent mode (a single local IPC round trip to the daemon,
 /// no Steam-side rate limit in this codebase) and for CLI mode (`IdlingManager::set_games` diffs
 /// kill/spawn per process, fast even for a full batch) alike; the real wall-clock cost of the
 /// individual sweep is the deliberate per-target dwell time itself, identical for both modes.
+///
+/// With `timings.restart` off there's no stop at all - the targets are left idling and the next
+/// iteration's identical claim is a no-op, so a game that stays selected idles continuously.
 async fn run_restart_cycle(
     app_handle: &AppHandle,
     account: &GamesAccount,
     targets: &[IdleTarget],
+    timings: &CycleTimings,
     stopped: &AtomicBool,
     wake: &Notify,
 ) -> CycleOutcome {
@@ -387,13 +556,18 @@ async fn run_restart_cycle(
     if let Err(e) = announce_idle_set(app_handle, account.clone(), targets).await {
         tracing::warn!(error = %e.code(), "card farming: failed to start idling this cycle's targets");
     }
-    if let Some(outcome) = race_wait(FULL_IDLE_DURATION, stopped, wake).await {
+    if !timings.restart {
+        return race_wait(timings.main_window, stopped, wake)
+            .await
+            .unwrap_or(CycleOutcome::Completed);
+    }
+    if let Some(outcome) = race_wait(timings.main_window, stopped, wake).await {
         let _ = announce_idle_set(app_handle, account.clone(), &[]).await;
         return outcome;
     }
     let _ = announce_idle_set(app_handle, account.clone(), &[]).await;
 
-    if let Some(outcome) = race_wait(MICRO_PAUSE, stopped, wake).await {
+    if let Some(outcome) = race_wait(timings.pause, stopped, wake).await {
         return outcome;
     }
 
@@ -407,7 +581,7 @@ async fn run_restart_cycle(
         {
             tracing::warn!(app_id = target.app_id, error = %e.code(), "card farming: failed to start idling during the individual sweep");
         }
-        if let Some(outcome) = race_wait(MICRO_IDLE_DURATION, stopped, wake).await {
+        if let Some(outcome) = race_wait(timings.sweep_per_game, stopped, wake).await {
             let _ = announce_idle_set(app_handle, account.clone(), &[]).await;
             return outcome;
         }
@@ -687,6 +861,14 @@ async fn run_cycle(
     // doc comment for why entries persist only while a game stays continuously selected as a
     // target, and `resolve_candidates`'s doc comment for how a dropped-out entry gets reconciled.
     let mut progress: HashMap<u32, FarmingProgress> = HashMap::new();
+    // Unix millis of each game's most recent detected drop this session - the start point for the
+    // next drop's `ledger::CardDropRecord::idle_ms`. Never pruned alongside `progress`: a game that
+    // drops back to `queue` and later returns still has a meaningful "last drop" time, it's only
+    // clamped to its new `active_since` below so queue time never counts as idle time.
+    let mut last_drop_at: HashMap<u32, i64> = HashMap::new();
+    // The phase `progress`'s games were last farmed in - what an exhausted game's final drop is
+    // recorded under, since it's only noticed on the next iteration.
+    let mut farmed_phase: Option<Phase> = None;
     // Only set at the one break site below where nothing is eligible at all - stays `false` for
     // every other way this loop can end (a manual stop, mid-wait `stopped` firing, or a resolve
     // error), so `maybe_start_next_task` only ever chains on a genuine "nothing left" finish.
@@ -697,7 +879,7 @@ async fn run_cycle(
         let excluded_snapshot = excluded_app_ids.lock().await.clone();
 
         let mut new_completed = Vec::new();
-        let candidates = match resolve_candidates(
+        let resolved = resolve_candidates(
             &app_handle,
             &steam_id,
             &cookies,
@@ -706,8 +888,28 @@ async fn run_cycle(
             &excluded_snapshot,
             &mut new_completed,
         )
-        .await
-        {
+        .await;
+        if let Some(phase) = farmed_phase {
+            let now_ms = chrono::Utc::now().timestamp_millis();
+            for record in exhausted_drops(
+                &new_completed,
+                &progress,
+                &mut last_drop_at,
+                now_ms,
+                phase,
+                farming_settings.strategy,
+            ) {
+                report_drop(&app_handle, &steam_id, record).await;
+            }
+        }
+        for farm in &new_completed {
+            webhooks::notify(
+                &app_handle,
+                &steam_id,
+                WebhookEvent::FarmCompleted(farm.clone()),
+            );
+        }
+        let candidates = match resolved {
             Ok(c) => c,
             Err(AppError::SteamCommunitySessionExpired(id)) => {
                 let _ = credentials::clear(&id);
@@ -739,13 +941,22 @@ async fn run_cycle(
             emit_state(&app_handle, &steam_id, &state).await;
         }
 
-        let Some((phase, targets)) =
-            select_phase(&candidates, farming_settings.allow_multi_game_farming)
-        else {
+        // Re-derived every iteration, like everything else read from `farming_settings`, so a
+        // strategy change takes effect on the next iteration without restarting the cycle.
+        let plan = farming_settings
+            .strategy
+            .plan(&farming_settings.custom_strategy);
+        let Some((phase, targets)) = select_targets(
+            &candidates,
+            &plan,
+            farming_settings.allow_multi_game_farming,
+            farming_settings.priority,
+        ) else {
             queue_genuinely_empty = true;
             break;
         };
 
+        farmed_phase = Some(phase);
         let target_ids: HashSet<u32> = targets.iter().map(|g| g.app_id).collect();
         let queue_list: Vec<GameWithDrops> = candidates
             .ready
@@ -769,16 +980,71 @@ async fn run_cycle(
                 playtime_hours: game.playtime_hours,
                 active_since: now_ms,
             });
+            if game.remaining < entry.remaining {
+                let record = record_drop(
+                    entry,
+                    entry.remaining - game.remaining,
+                    game.remaining,
+                    &mut last_drop_at,
+                    now_ms,
+                    phase,
+                    farming_settings.strategy,
+                );
+                report_drop(&app_handle, &steam_id, record).await;
+            }
             entry.remaining = game.remaining;
             entry.playtime_hours = game.playtime_hours;
             active_progress.push(entry.clone());
         }
 
+        // Re-read every iteration (not cached for the session) so a drop recorded just above
+        // already feeds this iteration's rates. A read failure only costs the estimate.
+        let drop_history = ledger::query(&app_handle, &steam_id, &Default::default())
+            .await
+            .unwrap_or_default();
+        let mut last_ledger_drop: HashMap<u32, i64> = HashMap::new();
+        for record in &drop_history {
+            let at = last_ledger_drop.entry(record.app_id).or_default();
+            *at = (*at).max(record.dropped_at);
+        }
+        // Strategies drop at different rates (that's the point of having several), so only the
+        // current one's history is learned from - unless it has none yet, where any history still
+        // beats the flat default.
+        let same_strategy: Vec<CardDropRecord> = drop_history
+            .iter()
+            .filter(|r| r.strategy == farming_settings.strategy)
+            .cloned()
+            .collect();
+        let rates = if same_strategy.is_empty() {
+            eta::DropRates::learn(&drop_history)
+        } else {
+            eta::DropRates::learn(&same_strategy)
+        };
+        let expected_values: HashMap<u32, f64> = targets
+            .iter()
+            .chain(&queue_list)
+            .filter_map(|g| Some((g.app_id, g.expected_value?)))
+            .collect();
+        let eta = eta::estimate(&eta::EtaInput {
+            phase,
+            active: &active_progress,
+            queue: &queue_list,
+            plan: &plan,
+            allow_multi_game_farming: farming_settings.allow_multi_game_farming,
+            hours_until_farmable: farming_settings.hours_until_farmable,
+            priority: farming_settings.priority,
+            expected_values: &expected_values,
+            rates: &rates,
+            last_drop_at: &last_ledger_drop,
+            now_ms,
+        });
+
         {
             let mut s = state.lock().await;
             s.phase = Some(phase);
             s.active = active_progress;
             s.queue = queue_list;
+            s.eta = Some(eta);
         }
         emit_state(&app_handle, &steam_id, &state).await;
 
@@ -790,7 +1056,16 @@ async fn run_cycle(
             })
             .collect();
 
-        match run_restart_cycle(&app_handle, &account, &idle_targets, &stopped, &wake).await {
+        match run_restart_cycle(
+            &app_handle,
+            &account,
+            &idle_targets,
+            &plan.timings,
+            &stopped,
+            &wake,
+        )
+        .await
+        {
             CycleOutcome::Stopped => break,
             CycleOutcome::Completed | CycleOutcome::Woken => continue,
         }
@@ -803,18 +1078,40 @@ async fn run_cycle(
         s.phase = None;
         s.active.clear();
         s.queue.clear();
+        s.eta = None;
     }
     emit_state(&app_handle, &steam_id, &state).await;
 
-    if queue_genuinely_empty {
-        maybe_start_next_task(&app_handle, &account, &steam_id).await;
-    }
-
     app_handle
         .state::<CardFarmingManager>()
         .remove(&steam_id)
         .await;
     tracing::info!(steam_id, "card farming: cycle ended");
+
+    if queue_genuinely_empty {
+        // After the session entry is gone - crafting is many requests to Steam Community and isn't
+        // part of the farm, so it shouldn't keep farming "running" - but before chaining into the
+        // next task, since the finished queue is exactly when new full sets exist. A crafting
+        // failure never blocks the chain (see `badges::craft_all`).
+        let auto_craft = crate::badges::settings::get(&app_handle, &steam_id)
+            .await
+            .map(|s| s.auto_craft_after_farming)
+            .unwrap_or(false);
+        if auto_craft {
+            match crate::badges::craft_all(&app_handle, &steam_id, &cookies, false).await {
+                Ok(report) => {
+                    let _ = app_handle.emit(
+                        crate::badges::BADGE_CRAFTING_FINISHED_EVENT,
+                        serde_json::json!({ "steamId": steam_id, "report": report }),
+                    );
+                }
+                Err(e) => {
+                    tracing::warn!(steam_id, error = %e.code(), "card farming: post-farming badge crafting failed");
+                }
+            }
+        }
+        maybe_start_next_task(&app_handle, &account, &steam_id).await;
+    }
 }
 
 #[cfg(test)]
@@ -827,6 +1124,7 @@ mod tests {
             name: format!("Game {app_id}"),
             remaining,
             playtime_hours,
+            expected_value: None,
         }
     }
 
@@ -836,7 +1134,8 @@ mod tests {
             ready: vec![game(1, 3, 5.0)],
             accumulating: vec![game(2, 5, 1.0)],
         };
-        let (phase, targets) = select_phase(&candidates, false).unwrap();
+        let (phase, targets) =
+            select_phase(&candidates, false, FarmingPriority::FewestDrops).unwrap();
         assert_eq!(phase, Phase::ReadyFarm);
         assert_eq!(targets.iter().map(|g| g.app_id).collect::<Vec<_>>(), vec![1]);
     }
@@ -847,7 +1146,8 @@ mod tests {
             ready: vec![game(1, 5, 5.0), game(2, 2, 5.0), game(3, 8, 5.0)],
             accumulating: vec![],
         };
-        let (phase, targets) = select_phase(&candidates, false).unwrap();
+        let (phase, targets) =
+            select_phase(&candidates, false, FarmingPriority::FewestDrops).unwrap();
         assert_eq!(phase, Phase::ReadyFarm);
         assert_eq!(targets.len(), 1);
         assert_eq!(targets[0].app_id, 2);
@@ -859,7 +1159,8 @@ mod tests {
             ready: vec![game(1, 5, 5.0), game(2, 2, 5.0)],
             accumulating: vec![],
         };
-        let (phase, targets) = select_phase(&candidates, true).unwrap();
+        let (phase, targets) =
+            select_phase(&candidates, true, FarmingPriority::FewestDrops).unwrap();
         assert_eq!(phase, Phase::ReadyFarm);
         assert_eq!(targets.len(), 2);
     }
@@ -873,7 +1174,8 @@ mod tests {
             ready,
             accumulating: vec![],
         };
-        let (phase, targets) = select_phase(&candidates, true).unwrap();
+        let (phase, targets) =
+            select_phase(&candidates, true, FarmingPriority::FewestDrops).unwrap();
         assert_eq!(phase, Phase::ReadyFarm);
         assert_eq!(targets.len(), MAX_CONCURRENT_FARMING);
         assert!(targets.iter().any(|g| g.app_id == 0));
@@ -888,7 +1190,8 @@ mod tests {
             ready: vec![],
             accumulating: vec![game(1, 5, 1.0), game(2, 5, 2.5)],
         };
-        let (phase, targets) = select_phase(&candidates, false).unwrap();
+        let (phase, targets) =
+            select_phase(&candidates, false, FarmingPriority::FewestDrops).unwrap();
         assert_eq!(phase, Phase::BulkIdle);
         assert_eq!(targets.len(), 2);
     }
@@ -901,7 +1204,8 @@ mod tests {
             ready: vec![],
             accumulating,
         };
-        let (phase, targets) = select_phase(&candidates, false).unwrap();
+        let (phase, targets) =
+            select_phase(&candidates, false, FarmingPriority::FewestDrops).unwrap();
         assert_eq!(phase, Phase::BulkIdle);
         assert_eq!(targets.len(), MAX_CONCURRENT_FARMING);
         // Highest playtime_hours (closest to threshold) must be included - id 39 has the highest,
@@ -916,7 +1220,52 @@ mod tests {
             ready: vec![],
             accumulating: vec![],
         };
-        assert!(select_phase(&candidates, false).is_none());
+        assert!(select_phase(&candidates, false, FarmingPriority::FewestDrops).is_none());
+    }
+
+    #[test]
+    fn select_targets_simple_strategy_farms_accumulating_games_solo() {
+        use super::super::strategy::{CustomStrategy, FarmingStrategy};
+        let candidates = ResolvedCandidates {
+            ready: vec![game(1, 5, 5.0)],
+            accumulating: vec![game(2, 2, 0.5)],
+        };
+        let plan = FarmingStrategy::Simple.plan(&CustomStrategy::default());
+        let (phase, targets) =
+            select_targets(&candidates, &plan, true, FarmingPriority::FewestDrops).unwrap();
+        assert_eq!(phase, Phase::ReadyFarm);
+        assert_eq!(
+            targets.iter().map(|g| g.app_id).collect::<Vec<_>>(),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn select_phase_market_value_prefers_highest_value_per_drop() {
+        let priced = |app_id, remaining, expected_value| GameWithDrops {
+            expected_value,
+            ..game(app_id, remaining, 5.0)
+        };
+        let candidates = ResolvedCandidates {
+            // Per-drop values: 0.10, 0.50, unpriced, 0.50 - ties broken by fewest remaining.
+            ready: vec![
+                priced(1, 1, Some(0.10)),
+                priced(2, 4, Some(2.00)),
+                priced(3, 1, None),
+                priced(4, 2, Some(1.00)),
+            ],
+            accumulating: vec![],
+        };
+        let (_, targets) = select_phase(&candidates, true, FarmingPriority::MarketValue).unwrap();
+        assert_eq!(
+            targets.iter().map(|g| g.app_id).collect::<Vec<_>>(),
+            vec![4, 2, 1, 3]
+        );
+        let (_, targets) = select_phase(&candidates, false, FarmingPriority::FewestDrops).unwrap();
+        assert_eq!(
+            targets.iter().map(|g| g.app_id).collect::<Vec<_>>(),
+            vec![1]
+        );
     }
 
     fn cached(playtime_minutes: u64, purchase: Option<i64>) -> CachedGameInfo {
@@ -979,4 +1328,71 @@ mod tests {
         let now = 1_000_000;
         assert!(filter_reason(cached(0, Some(now)), &s, now).is_none());
     }
+
+    #[test]
+    fn exhausted_drops_records_the_final_drop_of_a_game_that_left_the_scrape() {
+        let progress = HashMap::from([(
+            440,
+            FarmingProgress {
+                app_id: 440,
+                name: "Game 440".to_string(),
+                initial_remaining: 3,
+                remaining: 1,
+                playtime_hours: 5.0,
+                active_since: 1_000,
+            },
+        )]);
+        let completed = |app_id, reason| CompletedFarm {
+            app_id,
+            name: format!("Game {app_id}"),
+            remaining: 0,
+            reason,
+            farmable_at: None,
+        };
+        // 440 went from 1 remaining to missing from the scrape; 570 was never farmed this session.
+        let completed = vec![
+            completed(440, CompletedFarmReason::DropsExhausted),
+            completed(570, CompletedFarmReason::NoDropsRemaining),
+        ];
+        let mut last_drop_at = HashMap::from([(440, 4_000)]);
+
+        let records = exhausted_drops(
+            &completed,
+            &progress,
+            &mut last_drop_at,
+            10_000,
+            Phase::ReadyFarm,
+            FarmingStrategy::TwoPhase,
+        );
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].app_id, 440);
+        assert_eq!((records[0].count, records[0].remaining), (1, 0));
+        assert_eq!(records[0].idle_ms, 6_000);
+        assert_eq!(last_drop_at[&440], 10_000);
+    }
+
+    #[test]
+    fn record_drop_times_a_first_drop_from_active_since() {
+        let prog = FarmingProgress {
+            app_id: 440,
+            name: "Game 440".to_string(),
+            initial_remaining: 2,
+            remaining: 2,
+            playtime_hours: 5.0,
+            active_since: 7_000,
+        };
+        // A drop from before the game last re-entered `active` doesn't stretch the idle time.
+        let mut last_drop_at = HashMap::from([(440, 1_000)]);
+        let record = record_drop(
+            &prog,
+            2,
+            0,
+            &mut last_drop_at,
+            10_000,
+            Phase::ReadyFarm,
+            FarmingStrategy::TwoPhase,
+        );
+        assert_eq!(record.idle_ms, 3_000);
+    }
 }
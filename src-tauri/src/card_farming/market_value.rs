@@ -0,0 +1,316 @@
+//! Card market value per game - what one card drop is worth on average, for
+//! `settings::FarmingPriority::MarketValue` and `GameWithDrops::expected_value`. A drop is a
+//! uniformly random card from the game's set, so a drop's value is the mean market price of the
+//! set's cards: card names come from the account's own gamecards page for the game (same
+//! cookie-authenticated client as [`super::scraper`]), each card's price from
+//! `inventory::market::get_item_price` (`{appid}-{card name}` is a trading card's
+//! `market_hash_name`), priced on whichever side of the order book `inventory::settings::
+//! InventorySettings::price_preference` already prefers, in its `currency`.
+//!
+//! **Cached per app, refreshed a few apps at a time.** Pricing one game is one page fetch plus one
+//! orderbook request per card (5-15 of them), and the orderbook endpoint rate-limits hard
+//! (`AppError::MarketPriceRateLimited`) - so values are cached per app in
+//! `card_price_cache.json` for `CardFarmingSettings::price_cache_hours`, and one [`annotate`] call
+//! prices at most [`MAX_APPS_PER_REFRESH`] uncached games, paced by [`PRICE_REQUEST_DELAY`]. A
+//! farming cycle re-annotates every outer-loop iteration, so a large library fills in over its
+//! first few iterations instead of stalling the first one; a game not priced yet just sorts after
+//! every priced one.
+//!
+//! Never fails the caller - every error (cache IO, a failed page, a rate limit) is logged and the
+//! affected games are left without a value.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use scraper::{Html, Node, Selector};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tokio::sync::Mutex;
+
+use crate::error::{AppError, AppResult};
+use crate::fs_utils::atomic_write_json;
+use crate::inventory::market::get_item_price;
+use crate::inventory::settings::{self as inventory_settings, PricePreference};
+use crate::platform;
+use crate::steam_community::session::is_session_revoked;
+use crate::steam_community::{cookie_header, steam_client};
+
+use super::{GameWithDrops, SteamCookies};
+
+const PRICE_CACHE_FILE_NAME: &str = "card_price_cache.json";
+
+/// Uncached games priced per [`annotate`] call - see this module's doc comment.
+const MAX_APPS_PER_REFRESH: usize = 3;
+
+/// Pause before every orderbook request after the first - on top of `get_item_price`'s own 429
+/// backoff, which only kicks in once Steam has already started refusing.
+const PRICE_REQUEST_DELAY: Duration = Duration::from_millis(1_500);
+
+static WRITE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedAppPrice {
+    /// Steam currency ID the value was priced in - a currency change makes every entry stale.
+    currency: String,
+    /// Mean card price - `None` when none of the set's cards had a price (cached anyway, so an
+    /// unmarketable set isn't re-fetched every iteration).
+    drop_value: Option<f64>,
+    /// Unix seconds.
+    fetched_at: i64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PriceCache {
+    apps: HashMap<u32, CachedAppPrice>,
+}
+
+fn cache_file_path(app_handle: &AppHandle, steam_id: &str) -> AppResult<PathBuf> {
+    Ok(platform::cache_dir(app_handle)?
+        .join(steam_id)
+        .join(PRICE_CACHE_FILE_NAME))
+}
+
+/// Purely a cache - an unreadable file just starts over empty rather than erroring.
+fn read_unlocked(app_handle: &AppHandle, steam_id: &str) -> AppResult<PriceCache> {
+    let path = cache_file_path(app_handle, steam_id)?;
+    if !path.exists() {
+        return Ok(PriceCache::default());
+    }
+    let contents =
+        fs::read_to_string(&path).map_err(|e| AppError::CardPriceCacheIo(e.to_string()))?;
+    Ok(serde_json::from_str(&contents).unwrap_or_default())
+}
+
+fn write_unlocked(app_handle: &AppHandle, steam_id: &str, cache: &PriceCache) -> AppResult<()> {
+    let path = cache_file_path(app_handle, steam_id)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::CardPriceCacheIo(e.to_string()))?;
+    }
+    atomic_write_json(&path, cache).map_err(|e| AppError::CardPriceCacheIo(e.to_string()))
+}
+
+async fn read_cache(app_handle: &AppHandle, steam_id: &str) -> PriceCache {
+    let _guard = WRITE_LOCK.lock().await;
+    read_unlocked(app_handle, steam_id).unwrap_or_else(|e| {
+        tracing::warn!(steam_id, error = %e.code(), "card farming: failed to read card price cache");
+        PriceCache::default()
+    })
+}
+
+/// Re-reads the cache under the lock so entries another [`annotate`] wrote while this one was
+/// pricing survive, then adds `priced` on top. Returns the merged cache even if the write fails.
+async fn merge_and_write(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    priced: HashMap<u32, CachedAppPrice>,
+) -> PriceCache {
+    let _guard = WRITE_LOCK.lock().await;
+    let mut cache = read_unlocked(app_handle, steam_id).unwrap_or_default();
+    cache.apps.extend(priced);
+    if let Err(e) = write_unlocked(app_handle, steam_id, &cache) {
+        tracing::warn!(steam_id, error = %e.code(), "card farming: failed to write card price cache");
+    }
+    cache
+}
+
+fn is_fresh(entry: &CachedAppPrice, currency: &str, now: i64, ttl_secs: i64) -> bool {
+    entry.currency == currency && now - entry.fetched_at < ttl_secs
+}
+
+/// Each card's name on a gamecards page - the `.badge_card_set_text` title line's own text,
+/// without the `(N)` owned-quantity label nested inside it.
+fn parse_card_names(html: &str) -> Vec<String> {
+    let document = Html::parse_document(html);
+    let card_selector = Selector::parse(".badge_card_set_card").unwrap();
+    let title_selector = Selector::parse(".badge_card_set_text").unwrap();
+    document
+        .select(&card_selector)
+        .filter_map(|card| {
+            let title = card.select(&title_selector).next()?;
+            let name = title
+                .children()
+                .filter_map(|child| match child.value() {
+                    Node::Text(text) => Some(&**text),
+                    _ => None,
+                })
+                .collect::<String>()
+                .trim()
+                .to_string();
+            (!name.is_empty()).then_some(name)
+        })
+        .collect()
+}
+
+fn mean(prices: &[f64]) -> Option<f64> {
+    (!prices.is_empty()).then(|| prices.iter().sum::<f64>() / prices.len() as f64)
+}
+
+/// Prices one game's card set. `Err` when the gamecards page itself can't be fetched or the market
+/// starts rate-limiting - either way further requests this round would likely fail too, so
+/// [`annotate`] stops refreshing until its next call. A single card's failed lookup isn't an error.
+async fn price_app(
+    steam_id: &str,
+    cookies: &SteamCookies,
+    app_id: u32,
+    currency: &str,
+    preference: PricePreference,
+) -> AppResult<Option<f64>> {
+    let client = steam_client().map_err(|e| AppError::CardFarmingScrapeFailed(e.to_string()))?;
+    let response = client
+        .get(format!(
+            "https://steamcommunity.com/profiles/{steam_id}/gamecards/{app_id}/?l=english"
+        ))
+        .header("Cookie", cookie_header(steam_id, cookies))
+        .send()
+        .await
+        .map_err(|e| AppError::CardFarmingScrapeFailed(e.to_string()))?;
+    if is_session_revoked(&response) {
+        return Err(AppError::SteamCommunitySessionExpired(steam_id.to_string()));
+    }
+    let html = response
+        .text()
+        .await
+        .map_err(|e| AppError::CardFarmingScrapeFailed(e.to_string()))?;
+
+    let mut prices = Vec::new();
+    for (index, name) in parse_card_names(&html).iter().enumerate() {
+        if index > 0 {
+            tokio::time::sleep(PRICE_REQUEST_DELAY).await;
+        }
+        let market_hash_name = format!("{app_id}-{name}");
+        match get_item_price(&market_hash_name, Some(currency.to_string())).await {
+            Ok(price) => {
+                let value = match preference {
+                    PricePreference::HighestBuyOrder => {
+                        price.highest_buy_order.or(price.lowest_sell_order)
+                    }
+                    PricePreference::LowestSellOrder => {
+                        price.lowest_sell_order.or(price.highest_buy_order)
+                    }
+                };
+                prices.extend(value);
+            }
+            Err(AppError::MarketPriceRateLimited) => return Err(AppError::MarketPriceRateLimited),
+            // Usually a card whose market name isn't plain `{appid}-{name}` (a duplicate-named
+            // card gets a suffix) - the rest of the set still prices the game.
+            Err(e) => {
+                tracing::debug!(app_id, market_hash_name, error = %e.code(), "card farming: card price lookup failed");
+            }
+        }
+    }
+    Ok(mean(&prices))
+}
+
+/// Fills in `expected_value` (drop value x remaining drops) on every game with a cached value,
+/// pricing up to [`MAX_APPS_PER_REFRESH`] uncached ones first when `refresh` is set. Browsing
+/// (`commands::get_games_with_drops`) passes `refresh` only while `MarketValue` priority is on, so
+/// the default mode never sends a market request. Games in `unpriced` still get a cached value but
+/// are never priced - the farming cycle passes every game it won't farm, so blacklisted/excluded/
+/// skipped games don't spend the round's few refreshes.
+///
+/// The cache lock is only held to read and to merge-and-write, never across the pricing requests
+/// themselves - a round takes several seconds per game, and a browse-tab refresh shouldn't queue
+/// behind a farming cycle's round (or the reverse).
+pub async fn annotate(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    cookies: &SteamCookies,
+    games: &mut [GameWithDrops],
+    unpriced: &HashSet<u32>,
+    refresh: bool,
+    cache_hours: u32,
+) {
+    let (currency, preference) = match inventory_settings::get(app_handle, steam_id).await {
+        Ok(settings) => (settings.currency, settings.price_preference),
+        Err(e) => {
+            tracing::warn!(steam_id, error = %e.code(), "card farming: failed to read inventory settings for card pricing");
+            return;
+        }
+    };
+    let now = chrono::Utc::now().timestamp();
+    let ttl_secs = i64::from(cache_hours) * 3_600;
+
+    let mut cache = read_cache(app_handle, steam_id).await;
+
+    if refresh {
+        let stale: Vec<u32> = games
+            .iter()
+            .map(|g| g.app_id)
+            .filter(|app_id| {
+                !unpriced.contains(app_id)
+                    && !cache
+                        .apps
+                        .get(app_id)
+                        .is_some_and(|entry| is_fresh(entry, &currency, now, ttl_secs))
+            })
+            .take(MAX_APPS_PER_REFRESH)
+            .collect();
+        let mut priced = HashMap::new();
+        for app_id in stale {
+            match price_app(steam_id, cookies, app_id, &currency, preference).await {
+                Ok(drop_value) => {
+                    priced.insert(
+                        app_id,
+                        CachedAppPrice {
+                            currency: currency.clone(),
+                            drop_value,
+                            fetched_at: now,
+                        },
+                    );
+                }
+                Err(e) => {
+                    tracing::warn!(steam_id, app_id, error = %e.code(), "card farming: card pricing stopped for this round");
+                    break;
+                }
+            }
+        }
+        if !priced.is_empty() {
+            cache = merge_and_write(app_handle, steam_id, priced).await;
+        }
+    }
+
+    for game in games.iter_mut() {
+        game.expected_value = cache
+            .apps
+            .get(&game.app_id)
+            .filter(|entry| is_fresh(entry, &currency, now, ttl_secs))
+            .and_then(|entry| entry.drop_value)
+            .map(|value| value * f64::from(game.remaining));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn card_names_exclude_quantity_label() {
+        let html = include_str!("../badges/fixtures/gamecards_complete.html");
+        assert_eq!(
+            parse_card_names(html),
+            vec!["Heavy", "Scout", "Soldier", "Pyro"]
+        );
+    }
+
+    #[test]
+    fn cache_entry_expires_and_is_currency_scoped() {
+        let entry = CachedAppPrice {
+            currency: "1".to_string(),
+            drop_value: Some(0.12),
+            fetched_at: 1_000,
+        };
+        assert!(is_fresh(&entry, "1", 1_000 + 3_599, 3_600));
+        assert!(!is_fresh(&entry, "1", 1_000 + 3_600, 3_600));
+        assert!(!is_fresh(&entry, "3", 1_000, 3_600));
+    }
+
+    #[test]
+    fn drop_value_is_mean_card_price() {
+        assert!((mean(&[0.1, 0.2, 0.3]).unwrap() - 0.2).abs() < 1e-9);
+        assert_eq!(mean(&[]), None);
+    }
+}
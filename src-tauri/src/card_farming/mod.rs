@@ -7,7 +7,8 @@
 //! together, prioritizing whichever are closest to the threshold when there are more than
 //! `manager::MAX_CONCURRENT_FARMING` of them. Both phases run through the same restart-cycle helper
 //! (`manager::run_restart_cycle`): idle the target(s), stop, a short pause, an individual sweep of
-//! every target, stop, then the outer loop reclassifies. Calls
+//! every target, stop, then the outer loop reclassifies. That's the default `TwoPhase` strategy -
+//! [`strategy`] has the others, which change the cycle's timings and phase shape but not this flow. Calls
 //! `idling::claims::IdleClaimsRegistry::replace_owner_claim` directly rather than duplicating
 //! process-management logic - this module owns no process/spawn logic of its own.
 //!
@@ -39,13 +40,17 @@
 
 pub mod blacklist;
 pub mod commands;
+pub mod eta;
+pub mod ledger;
 pub mod manager;
+mod market_value;
 mod refund_window;
 mod scraper;
 pub mod settings;
+pub mod strategy;
 pub mod whitelist;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 pub use crate::steam_community::{session, SteamCookies};
 pub use blacklist::CardFarmingBlacklistEntry;
@@ -66,10 +71,14 @@ pub struct GameWithDrops {
     pub name: String,
     pub remaining: u32,
     pub playtime_hours: f32,
+    /// Market value of every remaining drop combined, in the account's inventory currency -
+    /// `None` until the game's cards have been priced (see `market_value`'s module doc comment).
+    pub expected_value: Option<f64>,
 }
 
 /// Which phase the cycle is currently running - see this module's doc comment. Never both at once.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+/// `Deserialize` only so [`ledger`] can read its own records back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum Phase {
     /// Solo- (or, with `allow_multi_game_farming` on, multi-) farming one or more games that have
@@ -148,6 +157,9 @@ pub struct FarmingState {
     /// (see `AppError::SteamCommunitySessionExpired`) - a hard stop, since every active game shares
     /// the same cookies: one expiring means all of them are dead.
     pub session_expired: bool,
+    /// Per-game and whole-library completion estimates, recomputed once per outer-loop iteration
+    /// alongside `active`/`queue` - `None` while nothing is running. See [`eta`]'s doc comment.
+    pub eta: Option<eta::FarmingEta>,
 }
 
 /// One game in [`FarmingState::active`], with a live `remaining` count updated as the cycle polls.
@@ -1,18 +1,31 @@
 //! Card-drop scraping via cookie-authenticated requests to `steamcommunity.com` - a plain
 //! `reqwest` client, not the session-acquisition webview's own cookie jar (see
 //! `steam_community::session`'s doc comment on why the literal cookie values matter here).
+//!
+//! Every page's parse also reports what it skipped and why ([`ParseDiagnostics`]), so a Steam markup
+//! change fails loudly with a redacted copy of the page under the logs directory instead of quietly
+//! reading as "nothing left to farm". The parser is tested against saved pages under `fixtures/`.
+
+use std::fs;
+use std::path::PathBuf;
 
 use futures::future::join_all;
 use regex::Regex;
 use scraper::selectable::Selectable;
 use scraper::{Html, Selector};
+use tauri::AppHandle;
 
 use crate::error::{AppError, AppResult};
+use crate::platform;
 use crate::steam_community::session::is_session_revoked;
 use crate::steam_community::{cookie_header, steam_client};
 
 use super::{GameWithDrops, SteamCookies};
 
+/// Subdirectory of `platform::logs_dir` drifted pages are saved into (see [`save_page_dump`]).
+const PAGE_DUMP_DIR: &str = "scraper_dumps";
+const MAX_PAGE_DUMPS: usize = 10;
+
 /// Extracts "N.N hrs on record" from a `.badge_title_stats_playtime` element - generic over
 /// `Html`/`ElementRef` via `scraper::Selectable` since this runs scoped to a single `.badge_row`
 /// (one row per game on the account's badge overview pages).
@@ -53,24 +66,128 @@ fn detect_max_page(html: &str) -> usize {
         .unwrap_or(1)
 }
 
-fn parse_games_with_drops(html: &str) -> Vec<GameWithDrops> {
+/// Why a `.badge_row` produced no [`GameWithDrops`]. The first two are ordinary page content; the
+/// rest mean a row that *looked* like a game with drops couldn't be read - the signal
+/// [`ParseDiagnostics::drift`] is built on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SkipReason {
+    /// A community/event badge (Years of Service, a sale badge), not a game's - identified by its
+    /// row linking to `/badges/{id}` rather than a game's `/gamecards/{appId}`, never merely by
+    /// what's missing from it, so a renamed progress element can't pass every row off as one.
+    NotAGameBadge,
+    /// "No card drops remaining" - a game that's already dropped every card.
+    NoDropsRemaining,
+    /// No `.progress_info_bold`, on a row that isn't a recognizable community badge either.
+    MissingProgressInfo,
+    /// Progress text that matches neither the drops-remaining nor the no-drops wording.
+    UnrecognizedProgressText(String),
+    /// Drops remaining, but no parseable `steam://run/{appId}` link to take the app id from.
+    MissingAppLink,
+}
+
+impl SkipReason {
+    fn is_expected(&self) -> bool {
+        matches!(
+            self,
+            SkipReason::NotAGameBadge | SkipReason::NoDropsRemaining
+        )
+    }
+}
+
+/// What one badge overview page's parse saw - returned alongside the games so a page that parsed
+/// to "nothing to farm" can be told apart from a page whose markup no longer matches.
+#[derive(Debug, Default)]
+struct ParseDiagnostics {
+    /// Whether the page has the `.badges_sheet` container at all.
+    has_badge_sheet: bool,
+    rows_seen: usize,
+    rows_parsed: usize,
+    /// `(row index on the page, reason)` for every row that yielded no game.
+    skipped: Vec<(usize, SkipReason)>,
+}
+
+impl ParseDiagnostics {
+    fn unexpected_skips(&self) -> impl Iterator<Item = &(usize, SkipReason)> {
+        self.skipped
+            .iter()
+            .filter(|(_, reason)| !reason.is_expected())
+    }
+
+    /// `Some(description)` once the page looks like a layout change rather than an account with
+    /// nothing to farm: no badge list at all, a badge list with no `.badge_row`s in it (far more
+    /// likely a renamed row class than an account without a single badge - which has nothing to
+    /// farm anyway, so failing loudly there costs nothing), or more than half of the rows that
+    /// looked like games failed to parse. A lone odd row among many good ones stays a logged
+    /// warning - Steam has always had the occasional malformed row, and failing the whole scrape on
+    /// one would stop farming for everything else.
+    fn drift(&self) -> Option<String> {
+        if self.rows_seen == 0 {
+            return Some(if self.has_badge_sheet {
+                "badge list found but no rows in it".to_string()
+            } else {
+                "no badge list found on the page".to_string()
+            });
+        }
+        let unexpected: Vec<_> = self.unexpected_skips().collect();
+        let candidates = self.rows_parsed + unexpected.len();
+        if unexpected.is_empty() || unexpected.len() * 2 <= candidates {
+            return None;
+        }
+        let reasons = unexpected
+            .iter()
+            .map(|(index, reason)| format!("row {index}: {reason:?}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Some(format!(
+            "{} of {candidates} game rows could not be parsed ({reasons})",
+            unexpected.len()
+        ))
+    }
+}
+
+fn parse_games_with_drops(html: &str) -> (Vec<GameWithDrops>, ParseDiagnostics) {
     let document = Html::parse_document(html);
+    let badge_sheet_selector = Selector::parse(".badges_sheet").unwrap();
     let badge_row_selector = Selector::parse(".badge_row").unwrap();
     let progress_info_bold_selector = Selector::parse(".progress_info_bold").unwrap();
     let badge_title_selector = Selector::parse(".badge_title").unwrap();
     let app_link_selector = Selector::parse(".btn_green_white_innerfade").unwrap();
+    let row_link_selector = Selector::parse(".badge_row_overlay").unwrap();
+    let community_badge_link = Regex::new(r"/badges/\d+/?$").unwrap();
     let regex = Regex::new(r"(\d+)\s+card\s+drop(?:s)?\s+remaining").unwrap();
 
+    let mut diagnostics = ParseDiagnostics {
+        has_badge_sheet: document.select(&badge_sheet_selector).next().is_some(),
+        ..Default::default()
+    };
     let mut games = Vec::new();
-    for badge_row in document.select(&badge_row_selector) {
+    for (index, badge_row) in document.select(&badge_row_selector).enumerate() {
+        diagnostics.rows_seen += 1;
         let Some(progress_info) = badge_row.select(&progress_info_bold_selector).next() else {
+            let is_community_badge = badge_row
+                .select(&row_link_selector)
+                .next()
+                .and_then(|e| e.value().attr("href"))
+                .is_some_and(|href| community_badge_link.is_match(href));
+            let reason = if is_community_badge {
+                SkipReason::NotAGameBadge
+            } else {
+                SkipReason::MissingProgressInfo
+            };
+            diagnostics.skipped.push((index, reason));
             continue;
         };
-        let progress_text = progress_info.text().collect::<String>();
-        let Some(captures) = regex.captures(&progress_text) else {
-            continue;
-        };
-        let Some(remaining) = captures[1].parse::<u32>().ok() else {
+        let progress_text = progress_info.text().collect::<String>().trim().to_string();
+        let Some(remaining) = regex
+            .captures(&progress_text)
+            .and_then(|captures| captures[1].parse::<u32>().ok())
+        else {
+            let reason = if progress_text.eq_ignore_ascii_case("No card drops remaining") {
+                SkipReason::NoDropsRemaining
+            } else {
+                SkipReason::UnrecognizedProgressText(progress_text)
+            };
+            diagnostics.skipped.push((index, reason));
             continue;
         };
 
@@ -83,6 +200,9 @@ fn parse_games_with_drops(html: &str) -> Vec<GameWithDrops> {
             .and_then(|href| href.strip_prefix("steam://run/"))
             .and_then(|id| id.trim_end_matches('/').parse::<u32>().ok())
         else {
+            diagnostics
+                .skipped
+                .push((index, SkipReason::MissingAppLink));
             continue;
         };
 
@@ -95,14 +215,116 @@ fn parse_games_with_drops(html: &str) -> Vec<GameWithDrops> {
 
         let playtime_hours = extract_playtime_hours(badge_row);
 
+        diagnostics.rows_parsed += 1;
         games.push(GameWithDrops {
             app_id,
             name,
             remaining,
             playtime_hours,
+            expected_value: None,
         });
     }
-    games
+    (games, diagnostics)
+}
+
+/// Replaces every value on the page that could authenticate as the account - the cookie values
+/// themselves (raw and URL-decoded, in case a script echoes them), the page's embedded
+/// `g_sessionID`, and any `webapi_token` attribute - so a saved page is safe to attach to a public
+/// bug report.
+fn redact_page(html: &str, cookies: &SteamCookies) -> String {
+    let mut redacted = html.to_string();
+    let secrets = [Some(&cookies.sid), Some(&cookies.sls), cookies.sma.as_ref()];
+    for secret in secrets.into_iter().flatten().filter(|s| !s.is_empty()) {
+        redacted = redacted.replace(secret.as_str(), "[REDACTED]");
+        if let Ok(decoded) = urlencoding::decode(secret) {
+            if decoded != secret.as_str() {
+                redacted = redacted.replace(decoded.as_ref(), "[REDACTED]");
+            }
+        }
+    }
+    let session_id = Regex::new(r#"g_sessionID\s*=\s*"[^"]*""#).unwrap();
+    let redacted = session_id.replace_all(&redacted, r#"g_sessionID = "[REDACTED]""#);
+    let webapi_token = Regex::new(r#"(webapi_token="?)[^"\s>]*"#).unwrap();
+    webapi_token
+        .replace_all(&redacted, "${1}[REDACTED]")
+        .into_owned()
+}
+
+/// Saves a drifted page under `platform::logs_dir`'s `scraper_dumps/` (redacted, see
+/// [`redact_page`]), next to the log file a bug report already attaches, keeping only the newest
+/// [`MAX_PAGE_DUMPS`]. Best-effort - a failed save is logged and the drift error still surfaces,
+/// just without a path.
+fn save_page_dump(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    cookies: &SteamCookies,
+    page: usize,
+    html: &str,
+) -> Option<PathBuf> {
+    let result = (|| -> std::io::Result<PathBuf> {
+        let dir = platform::logs_dir(app_handle)
+            .map_err(|e| std::io::Error::other(e.to_string()))?
+            .join(PAGE_DUMP_DIR);
+        fs::create_dir_all(&dir)?;
+        let timestamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ");
+        let path = dir.join(format!("badges_{timestamp}_p{page}.html"));
+        fs::write(&path, redact_page(html, cookies))?;
+
+        // Timestamp-first names sort chronologically, so the oldest are simply the first ones.
+        let mut dumps: Vec<PathBuf> = fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|ext| ext == "html"))
+            .collect();
+        dumps.sort();
+        for old in dumps
+            .iter()
+            .take(dumps.len().saturating_sub(MAX_PAGE_DUMPS))
+        {
+            let _ = fs::remove_file(old);
+        }
+        Ok(path)
+    })();
+    match result {
+        Ok(path) => Some(path),
+        Err(e) => {
+            tracing::warn!(steam_id, page, error = %e, "card farming: failed to save drifted badge page");
+            None
+        }
+    }
+}
+
+/// Turns one page's diagnostics into the scrape's outcome: a drifted page saves a dump and fails the
+/// whole scrape with `AppError::CardFarmingScraperDrift`; a page with only a few unexpected skips is
+/// logged and farming carries on with what did parse.
+fn check_page(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    cookies: &SteamCookies,
+    page: usize,
+    html: &str,
+    diagnostics: &ParseDiagnostics,
+) -> AppResult<()> {
+    if let Some(drift) = diagnostics.drift() {
+        let dump = save_page_dump(app_handle, steam_id, cookies, page, html);
+        tracing::error!(
+            steam_id,
+            page,
+            rows_seen = diagnostics.rows_seen,
+            rows_parsed = diagnostics.rows_parsed,
+            dump = dump.as_ref().map(|p| p.display().to_string()),
+            "card farming: badge page layout looks changed - {drift}"
+        );
+        let saved = dump
+            .map(|p| format!(" - page saved to {}", p.display()))
+            .unwrap_or_default();
+        return Err(AppError::CardFarmingScraperDrift(format!(
+            "page {page}: {drift}{saved}"
+        )));
+    }
+    for (index, reason) in diagnostics.unexpected_skips() {
+        tracing::warn!(steam_id, page, row = index, reason = ?reason, "card farming: skipped unparseable badge row");
+    }
+    Ok(())
 }
 
 /// Every owned game with at least one card drop remaining, scraped from the account's badge
@@ -112,8 +334,11 @@ fn parse_games_with_drops(html: &str) -> Vec<GameWithDrops> {
 /// except page 1 specifically, which also errors the whole call with
 /// `AppError::SteamCommunitySessionExpired` if the session turns out to be dead (see
 /// `is_session_revoked`), rather than silently returning an empty/partial games list that would
-/// look like "nothing left to farm".
+/// look like "nothing left to farm". Any page that *was* fetched but looks like a markup change
+/// fails the call too, with `AppError::CardFarmingScraperDrift` (see [`check_page`]) - the same
+/// "don't let a broken scrape read as nothing to farm" rule.
 pub async fn get_games_with_drops(
+    app_handle: &AppHandle,
     steam_id: &str,
     cookies: &SteamCookies,
 ) -> AppResult<Vec<GameWithDrops>> {
@@ -135,7 +360,7 @@ pub async fn get_games_with_drops(
 
     // Checked only on page 1: pagination viability is decided from this page, and if the session
     // is dead it's dead account-wide, so there's no need to repeat this per page. Pages 2+ keep
-    // their existing best-effort `Vec::new()` degradation on any failure, untouched.
+    // their existing best-effort skip on any request failure, untouched.
     if is_session_revoked(&first_page_response) {
         return Err(AppError::SteamCommunitySessionExpired(steam_id.to_string()));
     }
@@ -146,14 +371,24 @@ pub async fn get_games_with_drops(
     })?;
 
     let max_page = detect_max_page(&first_page_html);
-    let mut games = parse_games_with_drops(&first_page_html);
+    let (mut games, diagnostics) = parse_games_with_drops(&first_page_html);
     tracing::debug!(
         steam_id,
         max_page,
         page_1_matches = games.len(),
+        rows_seen = diagnostics.rows_seen,
+        skipped = diagnostics.skipped.len(),
         html_len = first_page_html.len(),
         "card farming: scraped badge overview page 1"
     );
+    check_page(
+        app_handle,
+        steam_id,
+        cookies,
+        1,
+        &first_page_html,
+        &diagnostics,
+    )?;
 
     if max_page > 1 {
         let remaining_pages = (2..=max_page).map(|page| {
@@ -165,23 +400,142 @@ pub async fn get_games_with_drops(
                     Ok(r) => r,
                     Err(e) => {
                         tracing::warn!(steam_id, page, error = %e, "card farming: badge overview page request failed, skipping page");
-                        return Vec::new();
+                        return (page, None);
                     }
                 };
                 match response.text().await {
-                    Ok(html) => parse_games_with_drops(&html),
+                    Ok(html) => (page, Some(html)),
                     Err(e) => {
                         tracing::warn!(steam_id, page, error = %e, "card farming: badge overview page response failed to read, skipping page");
-                        Vec::new()
+                        (page, None)
                     }
                 }
             }
         });
 
-        for page_games in join_all(remaining_pages).await {
+        for (page, html) in join_all(remaining_pages).await {
+            let Some(html) = html else {
+                continue;
+            };
+            let (page_games, diagnostics) = parse_games_with_drops(&html);
+            check_page(app_handle, steam_id, cookies, page, &html, &diagnostics)?;
             games.extend(page_games);
         }
     }
 
     Ok(games)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = include_str!("fixtures/badges_page.html");
+    const PAGE_DRIFTED: &str = include_str!("fixtures/badges_page_drifted.html");
+    const PAGE_REDESIGNED: &str = include_str!("fixtures/badges_page_redesigned.html");
+    const PAGE_NO_PROGRESS: &str = include_str!("fixtures/badges_page_no_progress.html");
+    const PAGE_NO_ROWS: &str = include_str!("fixtures/badges_page_no_rows.html");
+
+    #[test]
+    fn parses_games_and_expected_skips() {
+        let (games, diagnostics) = parse_games_with_drops(PAGE);
+        let parsed: Vec<_> = games
+            .iter()
+            .map(|g| (g.app_id, g.name.as_str(), g.remaining))
+            .collect();
+        assert_eq!(
+            parsed,
+            vec![(440, "Team Fortress 2", 3), (570, "Dota 2", 1)]
+        );
+        assert_eq!(games[0].playtime_hours, 12.4);
+        assert_eq!(diagnostics.rows_seen, 4);
+        assert_eq!(
+            diagnostics.skipped,
+            vec![
+                (2, SkipReason::NoDropsRemaining),
+                (3, SkipReason::NotAGameBadge)
+            ]
+        );
+        assert_eq!(diagnostics.drift(), None);
+        assert_eq!(detect_max_page(PAGE), 2);
+    }
+
+    #[test]
+    fn changed_progress_wording_is_drift() {
+        let (games, diagnostics) = parse_games_with_drops(PAGE_DRIFTED);
+        assert!(games.is_empty());
+        assert_eq!(
+            diagnostics.skipped[0],
+            (
+                0,
+                SkipReason::UnrecognizedProgressText("3 cards left to earn".to_string())
+            )
+        );
+        assert!(diagnostics.drift().unwrap().starts_with("3 of 3 game rows"));
+    }
+
+    #[test]
+    fn missing_badge_list_is_drift() {
+        let (games, diagnostics) = parse_games_with_drops(PAGE_REDESIGNED);
+        assert!(games.is_empty());
+        assert_eq!(diagnostics.rows_seen, 0);
+        assert!(diagnostics.drift().is_some());
+    }
+
+    #[test]
+    fn renamed_progress_element_is_drift() {
+        let (games, diagnostics) = parse_games_with_drops(PAGE_NO_PROGRESS);
+        assert!(games.is_empty());
+        assert_eq!(
+            diagnostics.skipped,
+            vec![
+                (0, SkipReason::MissingProgressInfo),
+                (1, SkipReason::MissingProgressInfo),
+                (2, SkipReason::MissingProgressInfo),
+                (3, SkipReason::NotAGameBadge)
+            ]
+        );
+        assert!(diagnostics.drift().unwrap().starts_with("3 of 3 game rows"));
+    }
+
+    #[test]
+    fn renamed_badge_rows_are_drift() {
+        let (games, diagnostics) = parse_games_with_drops(PAGE_NO_ROWS);
+        assert!(games.is_empty());
+        assert!(diagnostics.has_badge_sheet);
+        assert_eq!(diagnostics.rows_seen, 0);
+        assert_eq!(
+            diagnostics.drift().as_deref(),
+            Some("badge list found but no rows in it")
+        );
+    }
+
+    #[test]
+    fn lone_unparseable_row_is_tolerated() {
+        let page = PAGE.replace(
+            "steam://run/570/",
+            "https://store.steampowered.com/app/570/",
+        );
+        let (games, diagnostics) = parse_games_with_drops(&page);
+        assert_eq!(games.len(), 1);
+        assert_eq!(diagnostics.unexpected_skips().count(), 1);
+        assert_eq!(diagnostics.drift(), None);
+    }
+
+    #[test]
+    fn redacts_session_values() {
+        let cookies = SteamCookies {
+            sid: "a1b2c3d4e5f6a7b8c9d0e1f2".to_string(),
+            sls: "76561198000000000%7C%7CeyJ0eXAi".to_string(),
+            sma: None,
+        };
+        let page = format!("{PAGE}<script>var t = \"76561198000000000||eyJ0eXAi\";</script>");
+        let redacted = redact_page(&page, &cookies);
+        assert!(!redacted.contains("a1b2c3d4e5f6a7b8c9d0e1f2"));
+        assert!(!redacted.contains("eyJ0eXAi"));
+        assert!(!redacted.contains("eyJhbGciOiJFZERTQSJ9"));
+        assert!(redacted.contains("g_sessionID = \"[REDACTED]\""));
+        // Everything else is left intact so the dump still shows the markup that broke.
+        assert!(redacted.contains("3 card drops remaining"));
+    }
+}
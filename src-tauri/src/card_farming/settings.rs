@@ -28,6 +28,8 @@ use crate::error::{AppError, AppResult};
 use crate::fs_utils::atomic_write_json;
 use crate::platform;
 
+use super::strategy::{CustomStrategy, FarmingStrategy};
+
 const SETTINGS_FILE_NAME: &str = "card_farming_settings.json";
 
 static WRITE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
@@ -36,6 +38,24 @@ fn default_hours_until_farmable() -> u32 {
     3
 }
 
+fn default_price_cache_hours() -> u32 {
+    24
+}
+
+/// How the ready-farm phase orders its candidates - which game gets farmed next (or, with
+/// `allow_multi_game_farming`, which get the limited concurrent slots). Never affects the
+/// bulk-idle phase, which is ordered by closeness to `hours_until_farmable` regardless.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FarmingPriority {
+    /// Fewest drops remaining first - finishes games off soonest.
+    #[default]
+    FewestDrops,
+    /// Highest market value per drop first (see `market_value`'s module doc comment), fewest drops
+    /// remaining breaking ties and ordering games not priced yet.
+    MarketValue,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CardFarmingSettings {
@@ -97,6 +117,23 @@ pub struct CardFarmingSettings {
     /// file predating this field).
     #[serde(default = "default_hours_until_farmable")]
     pub hours_until_farmable: u32,
+    /// Which farming strategy the cycle runs - see `strategy`'s module doc comment. Re-read every
+    /// outer-loop iteration, like `allow_multi_game_farming`. `#[serde(default)]` (`TwoPhase`, the
+    /// only behavior before this existed) so an existing on-disk settings file still deserializes.
+    #[serde(default)]
+    pub strategy: FarmingStrategy,
+    /// Only used while `strategy` is `Custom`, but kept regardless. `#[serde(default)]` for the same
+    /// reason as `strategy`.
+    #[serde(default)]
+    pub custom_strategy: CustomStrategy,
+    /// `#[serde(default)]` (`FewestDrops`, the only ordering before this existed) so an existing
+    /// on-disk settings file still deserializes.
+    #[serde(default)]
+    pub priority: FarmingPriority,
+    /// How long a game's card price stays cached before `market_value` re-prices it. Defaults to
+    /// `24` via a named default function, same reason as `hours_until_farmable`.
+    #[serde(default = "default_price_cache_hours")]
+    pub price_cache_hours: u32,
 }
 
 impl Default for CardFarmingSettings {
@@ -111,6 +148,10 @@ impl Default for CardFarmingSettings {
             skip_refundable_games: false,
             allow_multi_game_farming: false,
             hours_until_farmable: default_hours_until_farmable(),
+            strategy: FarmingStrategy::default(),
+            custom_strategy: CustomStrategy::default(),
+            priority: FarmingPriority::default(),
+            price_cache_hours: default_price_cache_hours(),
         }
     }
 }
@@ -0,0 +1,198 @@
+//! Selectable farming strategies - what `manager::run_cycle` does with each outer-loop iteration's
+//! resolved candidates. Every strategy shares the same scrape/resolve/whitelist/blacklist pipeline
+//! and the same `manager::select_phase` tie-breaks; they differ only in the three knobs a
+//! [`StrategyPlan`] carries:
+//! - **Timings** - how long the targets idle together each iteration, and the stop/pause/sweep
+//!   choreography after it (`manager::run_restart_cycle`).
+//! - **Bulk idle** - whether games under `hours_until_farmable` get their own accumulating phase,
+//!   or are farmed right away alongside the ready ones.
+//! - **Solo** - whether `allow_multi_game_farming` is honored at all.
+//!
+//! Chosen per account in `CardFarmingSettings::strategy`, and stamped onto every
+//! `ledger::CardDropRecord` so strategies can be compared by what they actually produced.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// `TwoPhase` is the long-standing behavior (see `mod.rs`'s module doc comment) and the default,
+/// so an existing settings file - and every ledger record written before this existed - reads as
+/// that.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FarmingStrategy {
+    /// Ready games farmed with the full restart cycle, accumulating games bulk-idled toward
+    /// `hours_until_farmable` whenever nothing is ready.
+    #[default]
+    TwoPhase,
+    /// One game at a time, fewest remaining first, idled continuously until its drops run out -
+    /// no restarts, no playtime threshold, `allow_multi_game_farming` ignored.
+    Simple,
+    /// `TwoPhase`'s shape with a much shorter main window, restarting every couple of minutes.
+    FastRestart,
+    /// `TwoPhase`'s shape with the user's own [`CustomStrategy`] values.
+    Custom,
+}
+
+/// The user-tunable values behind [`FarmingStrategy::Custom`] - ignored by every other strategy.
+/// Stored even while another strategy is selected, so switching away and back keeps them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CustomStrategy {
+    /// Seconds the targets idle together each iteration - also the re-scrape interval, so it's
+    /// floored at [`MIN_MAIN_WINDOW`] to keep badge-page scraping at a sane rate.
+    pub main_window_secs: u32,
+    /// Whether to stop, pause and sweep each target after the main window (see
+    /// `manager::run_restart_cycle`). Off means targets just keep idling between re-scrapes.
+    pub restart: bool,
+    pub pause_secs: u32,
+    pub sweep_secs_per_game: u32,
+    pub bulk_idle: bool,
+}
+
+impl Default for CustomStrategy {
+    fn default() -> Self {
+        let two_phase = CycleTimings::TWO_PHASE;
+        Self {
+            main_window_secs: two_phase.main_window.as_secs() as u32,
+            restart: true,
+            pause_secs: two_phase.pause.as_secs() as u32,
+            sweep_secs_per_game: two_phase.sweep_per_game.as_secs() as u32,
+            bulk_idle: true,
+        }
+    }
+}
+
+/// Floor for a custom main window - see [`CustomStrategy::main_window_secs`].
+const MIN_MAIN_WINDOW: Duration = Duration::from_secs(60);
+/// Ceiling for a custom pause/per-game sweep - anything longer just stops the targets for no gain.
+const MAX_RESTART_STEP: Duration = Duration::from_secs(60);
+/// How often `Simple` re-scrapes - it has no restart cycle, so this is purely a poll interval.
+const SIMPLE_POLL_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// One iteration's idle/stop choreography - see `manager::run_restart_cycle`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CycleTimings {
+    pub main_window: Duration,
+    /// `false` skips the stop/pause/sweep entirely, leaving the targets idling into the next
+    /// iteration (`pause`/`sweep_per_game` are then unused).
+    pub restart: bool,
+    pub pause: Duration,
+    pub sweep_per_game: Duration,
+}
+
+impl CycleTimings {
+    const TWO_PHASE: CycleTimings = CycleTimings {
+        main_window: Duration::from_secs(5 * 60),
+        restart: true,
+        pause: Duration::from_secs(5),
+        sweep_per_game: Duration::from_secs(5),
+    };
+
+    /// One full iteration's wall-clock length with `targets` games selected.
+    pub fn cycle(&self, targets: usize) -> Duration {
+        if self.restart {
+            self.main_window + self.pause + self.sweep_per_game * targets as u32
+        } else {
+            self.main_window
+        }
+    }
+
+    /// How much of one iteration each target actually spends idling - the main window plus its own
+    /// sweep slot.
+    pub fn idle_per_cycle(&self) -> Duration {
+        if self.restart {
+            self.main_window + self.sweep_per_game
+        } else {
+            self.main_window
+        }
+    }
+}
+
+/// Everything `manager::run_cycle` and `eta::estimate` need from a strategy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StrategyPlan {
+    pub timings: CycleTimings,
+    pub bulk_idle: bool,
+    pub solo: bool,
+}
+
+impl FarmingStrategy {
+    pub fn plan(self, custom: &CustomStrategy) -> StrategyPlan {
+        match self {
+            FarmingStrategy::TwoPhase => StrategyPlan {
+                timings: CycleTimings::TWO_PHASE,
+                bulk_idle: true,
+                solo: false,
+            },
+            FarmingStrategy::Simple => StrategyPlan {
+                timings: CycleTimings {
+                    main_window: SIMPLE_POLL_INTERVAL,
+                    restart: false,
+                    pause: Duration::ZERO,
+                    sweep_per_game: Duration::ZERO,
+                },
+                bulk_idle: false,
+                solo: true,
+            },
+            FarmingStrategy::FastRestart => StrategyPlan {
+                timings: CycleTimings {
+                    main_window: Duration::from_secs(2 * 60),
+                    restart: true,
+                    pause: Duration::from_secs(2),
+                    sweep_per_game: Duration::from_secs(3),
+                },
+                bulk_idle: true,
+                solo: false,
+            },
+            FarmingStrategy::Custom => StrategyPlan {
+                timings: CycleTimings {
+                    main_window: Duration::from_secs(custom.main_window_secs.into())
+                        .max(MIN_MAIN_WINDOW),
+                    restart: custom.restart,
+                    pause: Duration::from_secs(custom.pause_secs.into()).min(MAX_RESTART_STEP),
+                    sweep_per_game: Duration::from_secs(custom.sweep_secs_per_game.into())
+                        .min(MAX_RESTART_STEP),
+                },
+                bulk_idle: custom.bulk_idle,
+                solo: false,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_custom_matches_two_phase() {
+        assert_eq!(
+            FarmingStrategy::Custom.plan(&CustomStrategy::default()),
+            FarmingStrategy::TwoPhase.plan(&CustomStrategy::default())
+        );
+    }
+
+    #[test]
+    fn custom_timings_are_clamped() {
+        let plan = FarmingStrategy::Custom.plan(&CustomStrategy {
+            main_window_secs: 1,
+            restart: true,
+            pause_secs: 3600,
+            sweep_secs_per_game: 3600,
+            bulk_idle: false,
+        });
+        assert_eq!(plan.timings.main_window, MIN_MAIN_WINDOW);
+        assert_eq!(plan.timings.pause, MAX_RESTART_STEP);
+        assert_eq!(plan.timings.sweep_per_game, MAX_RESTART_STEP);
+        assert!(!plan.bulk_idle);
+    }
+
+    #[test]
+    fn cycle_length_without_restart_is_just_the_main_window() {
+        let plan = FarmingStrategy::Simple.plan(&CustomStrategy::default());
+        assert_eq!(plan.timings.cycle(1), SIMPLE_POLL_INTERVAL);
+        let two_phase = CycleTimings::TWO_PHASE;
+        assert_eq!(two_phase.cycle(3), Duration::from_secs(5 * 60 + 5 + 15));
+    }
+}
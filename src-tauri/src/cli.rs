@@ -0,0 +1,682 @@
+//! The headless `sgi` binary (`src/bin/sgi.rs`) - drives the same managers the desktop app's
+//! Tauri commands do (`IdleClaimsRegistry`, `CardFarmingManager`, `AchievementUnlockerManager`,
+//! `AgentManager`) from subcommands instead, for machines with no desktop session to show the
+//! webview on.
+//!
+//! **Same app, no windows** - rather than a parallel non-Tauri code path, this builds a real Tauri
+//! `App` from the same `tauri.conf.json` context (so the same bundle identifier, and therefore the
+//! same `platform::cache_dir`/`platform::logs_dir`) with its configured windows stripped out, and
+//! manages the exact same state `lib.rs`'s `run()` does via `crate::manage_state`. Every manager
+//! already only ever needs an `AppHandle` (for `cache_dir`, `state::<T>()` lookups, and `emit`),
+//! never a window, so the desktop app and `sgi` read and write the same per-SteamID64 queues,
+//! whitelists and settings files with no translation layer in between. On Linux this still links
+//! WebKitGTK and initializes GTK when the `App` is built - a truly display-less box needs a virtual
+//! one (`xvfb-run sgi ...`), the same constraint every Tauri binary has there.
+//!
+//! **Live state is per-process** - farming/unlocker/idle sessions live in the managers' in-memory
+//! maps, exactly like the desktop app, so `sgi farm start`/`sgi unlocker start`/`sgi idle` run in
+//! the foreground until the session ends or Ctrl-C, streaming every state event to stdout as it
+//! happens. `sgi status` has no running session of its own to report on - it reports the account's
+//! persisted state (queue, whitelist/blacklist, settings) instead. Running `sgi` and the desktop app
+//! against the same account at the same time is not guarded against: neither process knows about
+//! the other's sessions, same as two desktop instances would be if the single-instance plugin
+//! weren't registered (it deliberately isn't here, since `sgi` must be able to run alongside an
+//! open desktop window for read-only `status` use).
+//!
+//! **Account selection** - `--steam-id <id64>` picks CLI mode (the real local Steam client must be
+//! running and signed in to that account, same as the desktop app's CLI mode); `--agent <username>`
+//! resumes that account's saved agent-mode session via `AgentManager::login_with_token`, so the
+//! account has to have been signed in through the desktop app once first (the refresh token lives
+//! in the OS credential store, never on the command line).
+
+use std::process::ExitCode;
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Listener, Manager};
+
+use crate::achievement_unlocker::{
+    self, settings::AchievementUnlockerSettings, AchievementUnlockerEntry,
+    AchievementUnlockerManager, ACHIEVEMENT_UNLOCKER_STATE_EVENT,
+};
+use crate::card_farming::{
+    self, settings::CardFarmingSettings, CardFarmingBlacklistEntry, CardFarmingManager,
+    CardFarmingWhitelistEntry, FARMING_STATE_EVENT,
+};
+use crate::error::{AppError, AppResult};
+use crate::games::commands::{get_owned_games_cache, resolve_steam_id, GamesAccount};
+use crate::idling::claims::{IdleClaimsRegistry, OWNER_MANUAL};
+use crate::idling::{IdleTarget, IdlingManager, IDLE_STATE_EVENT};
+use crate::local_steam::commands::require_steam_running;
+use crate::max_playtime;
+use crate::steam_agent::AgentManager;
+use crate::steam_community::{credentials, session};
+
+const USAGE: &str = "\
+usage: sgi (--steam-id <steamid64> | --agent <username>) [--json] <command>
+
+commands:
+  idle <app_id>...                    idle the given games until Ctrl-C
+  farm start                          run card farming until it finishes or Ctrl-C
+  unlocker start [--max-concurrent N] run the achievement unlocker until it finishes or Ctrl-C
+  unlocker resume [--max-concurrent N]
+                                      same, picking up an interrupted run's saved checkpoint
+  status                              print the account's persisted queues and settings
+
+--json prints state events/status as JSON (one object per line) instead of a summary.";
+
+/// How long to wait for an agent-mode session's SteamID64 after a successful token resume -
+/// `status_changed` (which carries it) arrives asynchronously after the `login_with_token`
+/// response itself, see `AgentManager::steam_id`'s doc comment.
+const AGENT_STEAM_ID_TIMEOUT: Duration = Duration::from_secs(30);
+const AGENT_STEAM_ID_POLL: Duration = Duration::from_millis(250);
+
+/// How often the foreground loop re-checks whether a farming/unlocker session ended on its own -
+/// the managers don't expose a completion future, only their current state.
+const SESSION_POLL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum AccountArg {
+    SteamId(String),
+    Agent(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Command {
+    Idle { app_ids: Vec<u32> },
+    FarmStart,
+    UnlockerStart { max_concurrent_games: u32 },
+    UnlockerResume { max_concurrent_games: u32 },
+    Status,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Invocation {
+    account: AccountArg,
+    json: bool,
+    command: Command,
+}
+
+/// Hand-rolled rather than pulling in an argument-parsing crate for four subcommands - flags may
+/// appear anywhere in the argument list, before or after the subcommand words.
+fn parse_args(args: &[String]) -> Result<Invocation, String> {
+    let mut account = None;
+    let mut json = false;
+    let mut max_concurrent_games = None;
+    let mut positional = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--steam-id" | "--agent" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| format!("{arg} requires a value"))?
+                    .clone();
+                if account.is_some() {
+                    return Err("only one of --steam-id/--agent may be given".to_string());
+                }
+                account = Some(if arg == "--steam-id" {
+                    AccountArg::SteamId(value)
+                } else {
+                    AccountArg::Agent(value)
+                });
+            }
+            "--json" => json = true,
+            "--max-concurrent" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| "--max-concurrent requires a value".to_string())?;
+                max_concurrent_games = Some(
+                    value
+                        .parse::<u32>()
+                        .map_err(|_| format!("invalid --max-concurrent value: {value}"))?,
+                );
+            }
+            other if other.starts_with("--") => return Err(format!("unknown option: {other}")),
+            other => positional.push(other),
+        }
+    }
+
+    let account = account.ok_or_else(|| "one of --steam-id/--agent is required".to_string())?;
+
+    let command = match positional.as_slice() {
+        ["idle", ids @ ..] if !ids.is_empty() => Command::Idle {
+            app_ids: ids
+                .iter()
+                .map(|id| {
+                    id.parse::<u32>()
+                        .map_err(|_| format!("invalid app id: {id}"))
+                })
+                .collect::<Result<_, _>>()?,
+        },
+        ["idle"] => return Err("idle requires at least one app id".to_string()),
+        ["farm", "start"] => Command::FarmStart,
+        ["unlocker", "start"] => Command::UnlockerStart {
+            max_concurrent_games: max_concurrent_games.unwrap_or(1),
+        },
+        ["unlocker", "resume"] => Command::UnlockerResume {
+            max_concurrent_games: max_concurrent_games.unwrap_or(1),
+        },
+        ["status"] => Command::Status,
+        [] => return Err("missing command".to_string()),
+        other => return Err(format!("unknown command: {}", other.join(" "))),
+    };
+
+    if max_concurrent_games.is_some()
+        && !matches!(
+            command,
+            Command::UnlockerStart { .. } | Command::UnlockerResume { .. }
+        )
+    {
+        return Err(
+            "--max-concurrent only applies to `unlocker start`/`unlocker resume`".to_string(),
+        );
+    }
+
+    Ok(Invocation {
+        account,
+        json,
+        command,
+    })
+}
+
+/// `sgi`'s whole lifecycle: parse, build the windowless app, run one subcommand to completion,
+/// then tear down every tracked `SteamUtility.exe` child. Exit code `2` for a usage error, `1` for
+/// a runtime `AppError` (its stable `code()` is printed, same string the frontend would see).
+pub fn run() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|a| a == "-h" || a == "--help") {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+    let invocation = match parse_args(&args) {
+        Ok(invocation) => invocation,
+        Err(message) => {
+            eprintln!("sgi: {message}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut context = crate::context();
+    context.config_mut().app.windows.clear();
+    let app = match crate::manage_state(tauri::Builder::default()).build(context) {
+        Ok(app) => app,
+        Err(e) => {
+            eprintln!("sgi: failed to initialize: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Kept alive for the whole run - dropping it flushes and closes the log file writer, see
+    // `logging::init`'s doc comment.
+    let _log_guard = match crate::logging::init(app.handle()) {
+        Ok(guard) => Some(guard),
+        Err(e) => {
+            eprintln!("sgi: logging disabled: {e}");
+            None
+        }
+    };
+
+    let app_handle = app.handle().clone();
+    let result = tauri::async_runtime::block_on(async {
+        let result = execute(&app_handle, invocation).await;
+        shutdown(&app_handle).await;
+        result
+    });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            tracing::warn!(error = %e, "sgi: command failed");
+            eprintln!("sgi: {} ({e})", e.code());
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Kills only the children this process itself spawned - deliberately not
+/// `updater::kill_all_steam_utility_processes`, whose by-process-name sweep would also take down an
+/// open desktop app's own idlers/agent sessions. A detached agent daemon `sgi` reattached to rather
+/// than spawned is only disconnected from (`AgentManager::release_all`), never shut down.
+async fn shutdown(app_handle: &AppHandle) {
+    app_handle.state::<AgentManager>().release_all().await;
+    app_handle.state::<IdlingManager>().kill_all().await;
+    app_handle.state::<IdleClaimsRegistry>().clear().await;
+}
+
+async fn execute(app_handle: &AppHandle, invocation: Invocation) -> AppResult<()> {
+    let account = resolve_account(app_handle, &invocation.account).await?;
+    let agent_manager = app_handle.state::<AgentManager>();
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    tracing::info!(steam_id = %steam_id, command = ?invocation.command, "sgi: running command");
+
+    match invocation.command {
+        Command::Idle { app_ids } => {
+            idle(app_handle, account, &steam_id, app_ids, invocation.json).await
+        }
+        Command::FarmStart => farm(app_handle, account, &steam_id, invocation.json).await,
+        Command::UnlockerStart {
+            max_concurrent_games,
+        } => {
+            unlock(
+                app_handle,
+                account,
+                &steam_id,
+                max_concurrent_games,
+                false,
+                invocation.json,
+            )
+            .await
+        }
+        Command::UnlockerResume {
+            max_concurrent_games,
+        } => {
+            unlock(
+                app_handle,
+                account,
+                &steam_id,
+                max_concurrent_games,
+                true,
+                invocation.json,
+            )
+            .await
+        }
+        Command::Status => status(app_handle, &steam_id, invocation.json).await,
+    }
+}
+
+/// Agent mode resumes the saved session and then waits for its SteamID64 to arrive - every
+/// downstream call keys off it via `resolve_steam_id`, which fails outright rather than waiting.
+async fn resolve_account(app_handle: &AppHandle, account: &AccountArg) -> AppResult<GamesAccount> {
+    match account {
+        AccountArg::SteamId(steam_id) => Ok(GamesAccount::Local {
+            steam_id: steam_id.clone(),
+        }),
+        AccountArg::Agent(username) => {
+            let agent_manager = app_handle.state::<AgentManager>();
+            if !agent_manager
+                .login_with_token(app_handle, username.clone())
+                .await?
+            {
+                return Err(AppError::Agent("login_with_token_failed".to_string()));
+            }
+
+            let deadline = tokio::time::Instant::now() + AGENT_STEAM_ID_TIMEOUT;
+            loop {
+                match agent_manager.steam_id(username).await {
+                    Ok(_) => break,
+                    Err(AppError::AgentSteamIdUnknown)
+                        if tokio::time::Instant::now() < deadline =>
+                    {
+                        tokio::time::sleep(AGENT_STEAM_ID_POLL).await;
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            Ok(GamesAccount::Agent {
+                username: username.clone(),
+            })
+        }
+    }
+}
+
+/// Same claim path as `idling::commands::toggle_manual_idle`'s "start" branch, for every requested
+/// game at once under the `"manual"` owner - including the max-playtime cap check, which skips (and
+/// reports) a capped game instead of failing the whole set. Game names come from the owned-games
+/// cache when present; CLI mode's idle window title falls back to the bare app id otherwise.
+async fn idle(
+    app_handle: &AppHandle,
+    account: GamesAccount,
+    steam_id: &str,
+    app_ids: Vec<u32>,
+    json: bool,
+) -> AppResult<()> {
+    if matches!(account, GamesAccount::Local { .. }) {
+        require_steam_running()?;
+    }
+
+    let owned = get_owned_games_cache(app_handle.clone(), steam_id.to_string()).unwrap_or_default();
+    let mut targets = Vec::new();
+    for app_id in app_ids {
+        let game = owned.iter().find(|g| g.app_id == app_id);
+        let playtime = game.map(|g| g.playtime_forever_minutes).unwrap_or(0);
+        if max_playtime::settings::is_over_cap(app_handle, steam_id, app_id, playtime).await? {
+            eprintln!("sgi: skipping {app_id}: max playtime cap reached");
+            continue;
+        }
+        let name = game
+            .and_then(|g| g.name.clone())
+            .unwrap_or_else(|| app_id.to_string());
+        targets.push(IdleTarget { app_id, name });
+    }
+
+    let _listener = EventPrinter::listen(app_handle, IDLE_STATE_EVENT, json);
+    let result = app_handle
+        .state::<IdleClaimsRegistry>()
+        .replace_owner_claim(
+            app_handle,
+            app_handle.state(),
+            app_handle.state(),
+            account,
+            OWNER_MANUAL,
+            targets,
+        )
+        .await?;
+    for failure in &result.failures {
+        eprintln!("sgi: {} failed to start: {}", failure.app_id, failure.error);
+    }
+    if result.app_ids.is_empty() {
+        return Ok(());
+    }
+
+    println!("idling {:?} - press Ctrl-C to stop", result.app_ids);
+    wait_for_ctrl_c().await;
+    Ok(())
+}
+
+/// Mirrors `card_farming::commands::start_farming`, minus the webview: a CLI-mode account can't
+/// acquire cookies automatically here (that path opens a hidden login window), so it uses the
+/// cookies saved through the desktop app's Steam Credentials tab instead. Agent mode derives them
+/// from the live session as usual.
+async fn farm(
+    app_handle: &AppHandle,
+    account: GamesAccount,
+    steam_id: &str,
+    json: bool,
+) -> AppResult<()> {
+    let manual_cookies = match account {
+        GamesAccount::Local { .. } => {
+            require_steam_running()?;
+            Some(credentials::get(steam_id)?.ok_or_else(|| {
+                AppError::SteamCommunitySessionFailed(
+                    "no saved Steam Community credentials for this account - save them from the \
+                     desktop app's Settings first"
+                        .to_string(),
+                )
+            })?)
+        }
+        GamesAccount::Agent { .. } => None,
+    };
+
+    let agent_manager = app_handle.state::<AgentManager>();
+    let cookies = session::resolve(
+        app_handle,
+        &agent_manager,
+        &account,
+        steam_id,
+        manual_cookies,
+    )
+    .await?;
+
+    let _listener = EventPrinter::listen(app_handle, FARMING_STATE_EVENT, json);
+    let manager = app_handle.state::<CardFarmingManager>().inner();
+    manager
+        .start(app_handle, steam_id.to_string(), account, cookies)
+        .await?;
+
+    run_until_finished(
+        move || async move { manager.state(steam_id).await.is_farming },
+        || manager.stop(steam_id),
+    )
+    .await
+}
+
+/// Mirrors `achievement_unlocker::commands::start_achievement_unlocker` (or, with `resume`,
+/// `resume_achievement_unlocker` - a Ctrl-C stop keeps the checkpoint, so `sgi unlocker resume`
+/// also picks up where an earlier `sgi` run left off). `max_concurrent_games` is clamped by the
+/// manager itself, same as it is for the frontend's value - this doesn't re-check any tier gate
+/// either.
+async fn unlock(
+    app_handle: &AppHandle,
+    account: GamesAccount,
+    steam_id: &str,
+    max_concurrent_games: u32,
+    resume: bool,
+    json: bool,
+) -> AppResult<()> {
+    if matches!(account, GamesAccount::Local { .. }) {
+        require_steam_running()?;
+    }
+
+    let _listener = EventPrinter::listen(app_handle, ACHIEVEMENT_UNLOCKER_STATE_EVENT, json);
+    let manager = app_handle.state::<AchievementUnlockerManager>().inner();
+    if resume {
+        manager
+            .resume(
+                app_handle,
+                steam_id.to_string(),
+                account,
+                max_concurrent_games,
+            )
+            .await?;
+    } else {
+        manager
+            .start(
+                app_handle,
+                steam_id.to_string(),
+                account,
+                max_concurrent_games,
+            )
+            .await?;
+    }
+
+    run_until_finished(
+        move || async move { manager.state(steam_id).await.is_running },
+        || manager.stop(steam_id),
+    )
+    .await
+}
+
+/// Blocks until `is_running` reports the session ended on its own, or Ctrl-C - in which case it
+/// stops the session first so its own cleanup (claim release, final state emit) runs before
+/// `shutdown` kills the children underneath it.
+async fn run_until_finished<R, RFut, S, SFut>(is_running: R, stop: S) -> AppResult<()>
+where
+    R: Fn() -> RFut,
+    RFut: std::future::Future<Output = bool>,
+    S: FnOnce() -> SFut,
+    SFut: std::future::Future<Output = AppResult<()>>,
+{
+    let poll = async {
+        while is_running().await {
+            tokio::time::sleep(SESSION_POLL).await;
+        }
+    };
+    tokio::select! {
+        _ = poll => Ok(()),
+        _ = wait_for_ctrl_c() => {
+            eprintln!("sgi: stopping...");
+            stop().await
+        }
+    }
+}
+
+async fn wait_for_ctrl_c() {
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        tracing::warn!(error = %e, "sgi: failed to listen for Ctrl-C, exiting immediately");
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StatusReport {
+    steam_id: String,
+    achievement_unlocker_queue: Vec<AchievementUnlockerEntry>,
+    achievement_unlocker_settings: AchievementUnlockerSettings,
+    card_farming_whitelist: Vec<CardFarmingWhitelistEntry>,
+    card_farming_blacklist: Vec<CardFarmingBlacklistEntry>,
+    card_farming_settings: CardFarmingSettings,
+}
+
+async fn status(app_handle: &AppHandle, steam_id: &str, json: bool) -> AppResult<()> {
+    let report = StatusReport {
+        steam_id: steam_id.to_string(),
+        achievement_unlocker_queue: achievement_unlocker::cache::read(app_handle, steam_id).await?,
+        achievement_unlocker_settings: achievement_unlocker::settings::get(app_handle, steam_id)
+            .await?,
+        card_farming_whitelist: card_farming::whitelist::read(app_handle, steam_id).await?,
+        card_farming_blacklist: card_farming::blacklist::read(app_handle, steam_id).await?,
+        card_farming_settings: card_farming::settings::get(app_handle, steam_id).await?,
+    };
+
+    if json {
+        println!("{}", serde_json::to_string(&report)?);
+        return Ok(());
+    }
+
+    println!("account {}", report.steam_id);
+    println!(
+        "achievement unlocker queue: {} game(s)",
+        report.achievement_unlocker_queue.len()
+    );
+    for entry in &report.achievement_unlocker_queue {
+        println!("  {} {}", entry.app_id, entry.name);
+    }
+    println!(
+        "card farming whitelist: {} game(s), blacklist: {} game(s)",
+        report.card_farming_whitelist.len(),
+        report.card_farming_blacklist.len()
+    );
+    Ok(())
+}
+
+/// Prints every emission of one state event for as long as it's alive - `--json` prints each raw
+/// payload on its own line (the same payload the frontend's `listen()` receives), otherwise a
+/// one-line summary. Unlistens on drop.
+struct EventPrinter {
+    app_handle: AppHandle,
+    id: tauri::EventId,
+}
+
+impl EventPrinter {
+    fn listen(app_handle: &AppHandle, event: &'static str, json: bool) -> Self {
+        let id = app_handle.listen_any(event, move |e| {
+            if json {
+                println!("{}", e.payload());
+                return;
+            }
+            match serde_json::from_str::<Value>(e.payload()) {
+                Ok(payload) => println!("{}", summarize(event, &payload)),
+                Err(_) => println!("{event}: {}", e.payload()),
+            }
+        });
+        Self {
+            app_handle: app_handle.clone(),
+            id,
+        }
+    }
+}
+
+impl Drop for EventPrinter {
+    fn drop(&mut self) {
+        self.app_handle.unlisten(self.id);
+    }
+}
+
+/// Human-readable one-liner for a state event payload - deliberately shallow (counts and app ids,
+/// not every field), `--json` is the lossless option.
+fn summarize(event: &str, payload: &Value) -> String {
+    let count = |state: &Value, key: &str| state[key].as_array().map_or(0, Vec::len);
+    let app_ids = |state: &Value, key: &str| -> Vec<u64> {
+        state[key]
+            .as_array()
+            .map(|items| items.iter().filter_map(|i| i["appId"].as_u64()).collect())
+            .unwrap_or_default()
+    };
+
+    match event {
+        FARMING_STATE_EVENT => {
+            let state = &payload["state"];
+            format!(
+                "farming: active {:?}, {} queued, {} completed",
+                app_ids(state, "active"),
+                count(state, "queue"),
+                count(state, "completed"),
+            )
+        }
+        ACHIEVEMENT_UNLOCKER_STATE_EVENT => {
+            let state = &payload["state"];
+            format!(
+                "unlocker: active {:?}, {} completed",
+                app_ids(state, "active"),
+                count(state, "completed"),
+            )
+        }
+        _ => format!("idling: {}", payload["appIds"]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn parses_idle_with_multiple_app_ids() {
+        let invocation = parse_args(&args("--steam-id 76561198000000000 idle 730 440")).unwrap();
+        assert_eq!(
+            invocation,
+            Invocation {
+                account: AccountArg::SteamId("76561198000000000".to_string()),
+                json: false,
+                command: Command::Idle {
+                    app_ids: vec![730, 440]
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn global_flags_may_follow_the_subcommand() {
+        let invocation = parse_args(&args("status --json --agent alice")).unwrap();
+        assert_eq!(invocation.account, AccountArg::Agent("alice".to_string()));
+        assert!(invocation.json);
+        assert_eq!(invocation.command, Command::Status);
+    }
+
+    #[test]
+    fn unlocker_start_defaults_to_one_game() {
+        let invocation = parse_args(&args("--agent alice unlocker start")).unwrap();
+        assert_eq!(
+            invocation.command,
+            Command::UnlockerStart {
+                max_concurrent_games: 1
+            }
+        );
+        let invocation =
+            parse_args(&args("--agent alice unlocker start --max-concurrent 4")).unwrap();
+        assert_eq!(
+            invocation.command,
+            Command::UnlockerStart {
+                max_concurrent_games: 4
+            }
+        );
+    }
+
+    #[test]
+    fn parses_unlocker_resume() {
+        let invocation =
+            parse_args(&args("--steam-id 1 unlocker resume --max-concurrent 2")).unwrap();
+        assert_eq!(
+            invocation.command,
+            Command::UnlockerResume {
+                max_concurrent_games: 2
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_missing_account_and_bad_input() {
+        assert!(parse_args(&args("farm start")).is_err());
+        assert!(parse_args(&args("--agent a --steam-id 1 status")).is_err());
+        assert!(parse_args(&args("--agent a idle")).is_err());
+        assert!(parse_args(&args("--agent a idle abc")).is_err());
+        assert!(parse_args(&args("--agent a farm stop")).is_err());
+        assert!(parse_args(&args("--agent a status --max-concurrent 2")).is_err());
+    }
+}
@@ -46,6 +46,18 @@ pub enum AppError {
     #[error("failed to parse loginusers.vdf: {0}")]
     LoginVdfParse(String),
 
+    #[error("failed to read/parse a binary VDF file: {0}")]
+    BinaryVdfParse(String),
+
+    #[error("failed to read/parse localconfig.vdf: {0}")]
+    LocalConfigVdf(String),
+
+    #[error("failed to read/parse the Steam client's collections: {0}")]
+    SteamCollections(String),
+
+    #[error("Steam collection {0} was not found")]
+    SteamCollectionNotFound(String),
+
     #[error("Steam ID {0} was not found in loginusers.vdf")]
     SteamIdNotFound(String),
 
@@ -94,9 +106,24 @@ pub enum AppError {
     #[error("failed to read/write the card-farming blacklist cache: {0}")]
     CardFarmingBlacklistCacheIo(String),
 
+    #[error("failed to read/write the card-drop ledger: {0}")]
+    CardDropLedgerIo(String),
+
+    #[error("failed to read/write the card price cache: {0}")]
+    CardPriceCacheIo(String),
+
+    #[error("failed to scrape Steam Community badge pages: {0}")]
+    BadgeScrapeFailed(String),
+
+    #[error("failed to read/write the badge-crafting settings cache: {0}")]
+    BadgeCraftingSettingsIo(String),
+
     #[error("failed to read/write the achievement-unlocker settings cache: {0}")]
     AchievementUnlockerSettingsIo(String),
 
+    #[error("unknown schedule time zone: {0}")]
+    AchievementUnlockerTimeZoneInvalid(String),
+
     #[error("failed to read/write the presence settings cache: {0}")]
     PresenceSettingsIo(String),
 
@@ -106,6 +133,39 @@ pub enum AppError {
     #[error("failed to read/write a custom achievement order file: {0}")]
     AchievementOrderIo(String),
 
+    #[error("no custom achievement order is saved for app {0}")]
+    AchievementOrderNotSaved(u32),
+
+    #[error("failed to read/write an achievement order plan file: {0}")]
+    AchievementOrderPlanIo(String),
+
+    #[error("invalid achievement order plan: {0}")]
+    AchievementOrderPlanInvalid(String),
+
+    #[error("achievement order plan was exported by a newer version (schema {0})")]
+    AchievementOrderPlanUnsupportedVersion(u32),
+
+    #[error("failed to read/write the achievement-unlocker checkpoint: {0}")]
+    AchievementUnlockerCheckpointIo(String),
+
+    #[error("failed to read/write cached achievement data: {0}")]
+    AchievementDataCacheIo(String),
+
+    #[error("failed to read/write the achievement library cache: {0}")]
+    AchievementLibraryIo(String),
+
+    #[error("an achievement library scan is already running for this account")]
+    AchievementLibraryScanInProgress,
+
+    #[error("failed to read/write an achievement snapshot: {0}")]
+    AchievementSnapshotIo(String),
+
+    #[error("achievement snapshot not found: {0}")]
+    AchievementSnapshotNotFound(String),
+
+    #[error("achievement snapshot was saved by a newer version (format {0})")]
+    AchievementSnapshotUnsupportedVersion(u32),
+
     #[error("the requested Steam profile is private or has no public achievement data")]
     PlayerProfilePrivate,
 
@@ -117,6 +177,9 @@ pub enum AppError {
     #[error("could not resolve \"{0}\" to a Steam profile")]
     PlayerProfileNotFound(String),
 
+    #[error("the signed-in account's Steam friend list is private or unavailable: {0}")]
+    FriendListUnavailable(String),
+
     #[error("failed to fetch/parse the free games list: {0}")]
     FreeGamesScrapeFailed(String),
 
@@ -156,6 +219,11 @@ pub enum AppError {
     #[error("failed to fetch/parse card-drop data: {0}")]
     CardFarmingScrapeFailed(String),
 
+    /// The badge overview pages no longer parse the way `card_farming::scraper` expects - see
+    /// `ParseDiagnostics::drift`. Carries the description plus the redacted page dump's path.
+    #[error("Steam's badge page layout looks changed: {0}")]
+    CardFarmingScraperDrift(String),
+
     /// A domain error code from a CLI-mode one-shot `SteamUtility.exe` command's JSON envelope
     /// (e.g. `achievement_protected`, `unsupported_game_coordinator`), forwarded verbatim - same
     /// passthrough behavior as `Agent`, kept as a separate variant so log lines can still tell
@@ -213,6 +281,27 @@ pub enum AppError {
 
     #[error("failed to read/write the custom background image: {0}")]
     CustomBackgroundIo(String),
+
+    #[error("failed to connect to the detached agent daemon: {0}")]
+    AgentDetachedConnect(String),
+
+    #[error("failed to read/write the detached agent session record: {0}")]
+    AgentDetachedSessionIo(String),
+
+    #[error("failed to read/write the remote API token file: {0}")]
+    RemoteApiIo(String),
+
+    #[error("failed to start the remote API server: {0}")]
+    RemoteApiBind(String),
+
+    #[error("failed to read/write webhook settings: {0}")]
+    WebhookSettingsIo(String),
+
+    #[error("webhook delivery failed: {0}")]
+    WebhookDeliveryFailed(String),
+
+    #[error("webhook template does not render to valid JSON: {0}")]
+    WebhookTemplateInvalid(String),
 }
 
 impl AppError {
@@ -233,6 +322,10 @@ impl AppError {
             AppError::SteamNotFound(_) => "steam_not_found".to_string(),
             AppError::LoginVdfIo(_) => "login_vdf_io_failed".to_string(),
             AppError::LoginVdfParse(_) => "login_vdf_parse_failed".to_string(),
+            AppError::BinaryVdfParse(_) => "binary_vdf_parse_failed".to_string(),
+            AppError::LocalConfigVdf(_) => "local_config_vdf_failed".to_string(),
+            AppError::SteamCollections(_) => "steam_collections_read_failed".to_string(),
+            AppError::SteamCollectionNotFound(_) => "steam_collection_not_found".to_string(),
             AppError::SteamIdNotFound(_) => "steam_id_not_found".to_string(),
             AppError::RegistryUpdate(_) => "registry_update_failed".to_string(),
             AppError::LocalProcessSpawn(_) => "local_process_spawn_failed".to_string(),
@@ -255,15 +348,46 @@ impl AppError {
             AppError::CardFarmingBlacklistCacheIo(_) => {
                 "card_farming_blacklist_cache_io_failed".to_string()
             }
+            AppError::CardDropLedgerIo(_) => "card_drop_ledger_io_failed".to_string(),
+            AppError::CardPriceCacheIo(_) => "card_price_cache_io_failed".to_string(),
+            AppError::BadgeScrapeFailed(_) => "badge_scrape_failed".to_string(),
+            AppError::BadgeCraftingSettingsIo(_) => "badge_crafting_settings_io_failed".to_string(),
             AppError::AchievementUnlockerSettingsIo(_) => {
                 "achievement_unlocker_settings_io_failed".to_string()
             }
+            AppError::AchievementUnlockerTimeZoneInvalid(_) => {
+                "achievement_unlocker_time_zone_invalid".to_string()
+            }
             AppError::PresenceSettingsIo(_) => "presence_settings_io_failed".to_string(),
             AppError::OwnershipSettingsIo(_) => "ownership_settings_io_failed".to_string(),
             AppError::AchievementOrderIo(_) => "achievement_order_io_failed".to_string(),
+            AppError::AchievementOrderNotSaved(_) => "achievement_order_not_saved".to_string(),
+            AppError::AchievementOrderPlanIo(_) => "achievement_order_plan_io_failed".to_string(),
+            AppError::AchievementOrderPlanInvalid(_) => {
+                "achievement_order_plan_invalid".to_string()
+            }
+            AppError::AchievementOrderPlanUnsupportedVersion(_) => {
+                "achievement_order_plan_unsupported_version".to_string()
+            }
+            AppError::AchievementUnlockerCheckpointIo(_) => {
+                "achievement_unlocker_checkpoint_io_failed".to_string()
+            }
+            AppError::AchievementDataCacheIo(_) => "achievement_data_cache_io_failed".to_string(),
+            AppError::AchievementLibraryIo(_) => "achievement_library_io_failed".to_string(),
+            AppError::AchievementLibraryScanInProgress => {
+                "achievement_library_scan_in_progress".to_string()
+            }
+            AppError::AchievementSnapshotIo(_) => "achievement_snapshot_io_failed".to_string(),
+            AppError::AchievementSnapshotNotFound(_) => {
+                "achievement_snapshot_not_found".to_string()
+            }
+            AppError::AchievementSnapshotUnsupportedVersion(_) => {
+                "achievement_snapshot_unsupported_version".to_string()
+            }
             AppError::PlayerProfilePrivate => "player_profile_private".to_string(),
             AppError::PlayerNoTimestamps => "player_no_timestamps".to_string(),
             AppError::PlayerProfileNotFound(_) => "player_profile_not_found".to_string(),
+            AppError::FriendListUnavailable(_) => "friend_list_unavailable".to_string(),
             AppError::FreeGamesScrapeFailed(_) => "free_games_scrape_failed".to_string(),
             AppError::StoreLoginFailed(_) => "store_login_failed".to_string(),
             AppError::StoreClaimFailed(_) => "store_claim_failed".to_string(),
@@ -278,6 +402,7 @@ impl AppError {
                 "steam_community_session_expired".to_string()
             }
             AppError::CardFarmingScrapeFailed(_) => "card_farming_scrape_failed".to_string(),
+            AppError::CardFarmingScraperDrift(_) => "card_farming_scraper_drift".to_string(),
             AppError::SteamUtility(code) => code.clone(),
             AppError::InventoryFetchFailed(_) => "inventory_fetch_failed".to_string(),
             AppError::InventoryCacheIo(_) => "inventory_cache_io_failed".to_string(),
@@ -296,6 +421,13 @@ impl AppError {
             AppError::ZoomFactorFailed(_) => "zoom_factor_failed".to_string(),
             AppError::CustomBackgroundInvalid(_) => "custom_background_invalid".to_string(),
             AppError::CustomBackgroundIo(_) => "custom_background_io_failed".to_string(),
+            AppError::AgentDetachedConnect(_) => "agent_detached_connect_failed".to_string(),
+            AppError::AgentDetachedSessionIo(_) => "agent_detached_session_io_failed".to_string(),
+            AppError::RemoteApiIo(_) => "remote_api_io_failed".to_string(),
+            AppError::RemoteApiBind(_) => "remote_api_bind_failed".to_string(),
+            AppError::WebhookSettingsIo(_) => "webhook_settings_io_failed".to_string(),
+            AppError::WebhookDeliveryFailed(_) => "webhook_delivery_failed".to_string(),
+            AppError::WebhookTemplateInvalid(_) => "webhook_template_invalid".to_string(),
         }
     }
 }
@@ -7,7 +7,7 @@
 //! component shape this codebase avoids. If a later list-type feature turns out to need genuinely
 //! identical logic, extract a shared helper then.
 
-mod cache;
+pub(crate) mod cache;
 pub mod commands;
 
 use serde::{Deserialize, Serialize};
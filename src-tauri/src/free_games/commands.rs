@@ -4,6 +4,7 @@ use crate::error::AppResult;
 use crate::games::commands::{resolve_steam_id, GamesAccount};
 use crate::local_steam;
 use crate::steam_agent::AgentManager;
+use crate::webhooks::{self, WebhookEvent};
 
 use super::settings::FreeGamesSettings;
 use super::{discovery, FreeGameClaimOutcome, FreeGameEntry};
@@ -27,8 +28,8 @@ pub async fn claim_free_game(
     account: GamesAccount,
     app_id: u32,
 ) -> AppResult<FreeGameClaimOutcome> {
-    // Cloned before the match consumes `account` by value - kept only so the log lines below can
-    // identify which account a claim outcome belongs to (there was previously no account
+    // Cloned before the match consumes `account` by value - kept so the log lines below can
+    // identify which account a claim outcome belongs to (and for the webhook's SteamID64 lookup) (there was previously no account
     // identifier in this log line at all, which made a background account's claim indistinguishable
     // from the currently-active one when diagnosing an auto-redeem issue).
     let account_for_log = account.clone();
@@ -58,6 +59,17 @@ pub async fn claim_free_game(
             tracing::warn!(account = ?account_for_log, app_id, code = %e.code(), error = %e, "free games: claim errored")
         }
     }
+    if matches!(outcome, Ok(FreeGameClaimOutcome::Granted)) {
+        // Resolved only on success (an agent account's SteamID64 comes from its live session) -
+        // a failed lookup just means no webhook, never a failed claim.
+        if let Ok(steam_id) = resolve_steam_id(&account_for_log, &agent_manager).await {
+            webhooks::notify(
+                &app_handle,
+                &steam_id,
+                WebhookEvent::FreeGameClaimed { app_id },
+            );
+        }
+    }
     outcome
 }
 
@@ -1,8 +1,10 @@
 //! Small filesystem helpers shared across features - currently just the atomic-write pattern used
 //! anywhere JSON state is persisted to disk (`settings.json`, `user_summaries.json`), so a crash or
-//! power loss mid-write can never leave a truncated/corrupt file behind.
+//! power loss mid-write can never leave a truncated/corrupt file behind, plus its owner-only
+//! variant for the few secrets kept as plain files.
 
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
 
 use serde::Serialize;
@@ -10,15 +12,43 @@ use serde::Serialize;
 static ATOMIC_WRITE_COUNTER: AtomicU64 = AtomicU64::new(0);
 
 /// Writes JSON to `path` via a temp-file-then-rename so a crash or power loss mid-write can never
-/// leave a truncated/corrupt file behind - `rename` on the same filesystem is atomic. The temp
-/// filename combines the process id with a monotonic counter, not just the pid: two writes from
-/// the same process share one pid, so the counter is what actually prevents them from colliding on
-/// the same temp path.
+/// leave a truncated/corrupt file behind - `rename` on the same filesystem is atomic.
 pub fn atomic_write_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> std::io::Result<()> {
     let json = serde_json::to_string_pretty(value).map_err(std::io::Error::other)?;
-    let unique = ATOMIC_WRITE_COUNTER.fetch_add(1, Ordering::Relaxed);
-    let tmp_path = path.with_extension(format!("{}-{}.tmp", std::process::id(), unique));
+    let tmp_path = tmp_path(path);
     std::fs::write(&tmp_path, &json)?;
     std::fs::rename(&tmp_path, path)?;
     Ok(())
 }
+
+/// [`atomic_write_json`] for a secret (`remote_api::auth`'s bearer token): the temp file is
+/// created owner-only (`0600`) on Unix before anything is written to it, so neither it nor the
+/// renamed result is ever readable by other local users, even briefly - a plain `fs::write` would
+/// create it with the umask's usual world-readable `0644`. On Windows the file inherits the
+/// per-user app data directory's ACL, which already keeps other users out.
+pub fn atomic_write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let tmp_path = tmp_path(path);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let written = options
+        .open(&tmp_path)
+        .and_then(|mut file| file.write_all(contents))
+        .and_then(|()| std::fs::rename(&tmp_path, path));
+    if written.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+    written
+}
+
+/// The temp filename combines the process id with a monotonic counter, not just the pid: two
+/// writes from the same process share one pid, so the counter is what actually prevents them from
+/// colliding on the same temp path.
+fn tmp_path(path: &Path) -> PathBuf {
+    let unique = ATOMIC_WRITE_COUNTER.fetch_add(1, Ordering::Relaxed);
+    path.with_extension(format!("{}-{}.tmp", std::process::id(), unique))
+}
@@ -1,8 +1,12 @@
+use std::collections::{HashMap, HashSet};
+
 use serde::Deserialize;
 use tauri::{AppHandle, State};
 
 use crate::error::AppResult;
 use crate::local_steam;
+use crate::local_steam::appinfo::AppMetadata;
+use crate::local_steam::localconfig::LocalPlaytime;
 use crate::steam_agent::AgentManager;
 
 use super::{cache, merge, web_api, OwnedGame, OwnedGamesResult};
@@ -103,8 +107,34 @@ pub async fn get_owned_games(
         (merge::from_agent(raw_games), false)
     } else {
         let api_key = crate::credential_store::load_web_api_key()?;
-        let fetch = web_api::fetch_owned_games(&steam_id, api_key).await?;
-        (merge::merge(raw_games, fetch.games), fetch.possibly_private)
+        let local_playtime = read_local_playtime(steam_id.clone()).await;
+        let fetch = match web_api::fetch_owned_games(&steam_id, api_key).await {
+            Ok(fetch) => fetch,
+            // `localconfig.vdf` still has playtime for the games it knows - a list with that beats
+            // no list at all. With nothing local to fall back on, the Web API's error stands.
+            Err(e) if !local_playtime.is_empty() => {
+                tracing::warn!(
+                    steam_id,
+                    error = %e,
+                    "Steam Web API owned-games fetch failed, using localconfig.vdf playtime only"
+                );
+                web_api::OwnedGamesFetch {
+                    games: Vec::new(),
+                    possibly_private: false,
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        let app_ids = raw_games
+            .iter()
+            .map(|g| g.app_id)
+            .chain(fetch.games.iter().map(|g| g.appid))
+            .collect();
+        let app_info = read_local_app_info(app_ids).await;
+        (
+            merge::merge(raw_games, fetch.games, &app_info, &local_playtime),
+            fetch.possibly_private,
+        )
     };
 
     cache::write(&app_handle, &steam_id, &merged)?;
@@ -121,6 +151,39 @@ pub async fn get_owned_games(
     })
 }
 
+/// `local_steam::appinfo::read` off the async runtime, best-effort - a missing, locked or
+/// unparseable `appinfo.vdf` just means no local names/types, never a failed games list. Cheap on
+/// a repeat refresh: `read` reuses its last parse until the file's mtime or size moves.
+async fn read_local_app_info(app_ids: HashSet<u32>) -> HashMap<u32, AppMetadata> {
+    match tokio::task::spawn_blocking(move || local_steam::appinfo::read(&app_ids)).await {
+        Ok(Ok(apps)) => apps,
+        Ok(Err(e)) => {
+            tracing::warn!(error = %e, "failed to read the local appinfo.vdf");
+            HashMap::new()
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "local appinfo.vdf read panicked");
+            HashMap::new()
+        }
+    }
+}
+
+/// `local_steam::localconfig::read` off the async runtime, best-effort like
+/// [`read_local_app_info`].
+async fn read_local_playtime(steam_id: String) -> HashMap<u32, LocalPlaytime> {
+    match tokio::task::spawn_blocking(move || local_steam::localconfig::read(&steam_id)).await {
+        Ok(Ok(playtime)) => playtime,
+        Ok(Err(e)) => {
+            tracing::warn!(error = %e, "failed to read the local localconfig.vdf");
+            HashMap::new()
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "local localconfig.vdf read panicked");
+            HashMap::new()
+        }
+    }
+}
+
 /// Reads back the last cached owned-games list for `steam_id` without hitting the network - an
 /// empty list if nothing has been cached yet (not an error, same convention `get_user_summary_cache`
 /// already uses).
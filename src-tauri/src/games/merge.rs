@@ -1,24 +1,50 @@
 //! Merges each backend's authoritative ownership list (real owned app IDs, name only when the
 //! backend happens to know it - see `RawOwnedGame`) with playtime/name data from the Steam Web API
 //! (`web_api::fetch_owned_games`) into the one shape the frontend renders.
+//!
+//! CLI mode also passes whatever the local Steam client's `appinfo.vdf` knows about those games
+//! (`local_steam::appinfo`) - every game's type, and a name for any game neither the backend nor
+//! the Web API named - and its `localconfig.vdf` playtime (`local_steam::localconfig`), which
+//! fills in when the Web API has none (no key, private profile, offline). Where both have a number
+//! the larger wins: either can lag the other.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+
+use crate::local_steam::appinfo::AppMetadata;
+use crate::local_steam::localconfig::LocalPlaytime;
 
 use super::web_api::WebApiGame;
 use super::{OwnedGame, RawOwnedGame};
 
-pub fn merge(raw_games: Vec<RawOwnedGame>, web_games: Vec<WebApiGame>) -> Vec<OwnedGame> {
+pub fn merge(
+    raw_games: Vec<RawOwnedGame>,
+    web_games: Vec<WebApiGame>,
+    app_info: &HashMap<u32, AppMetadata>,
+    local_playtime: &HashMap<u32, LocalPlaytime>,
+) -> Vec<OwnedGame> {
     let mut merged = Vec::with_capacity(raw_games.len());
     let mut seen: HashSet<u32> = HashSet::with_capacity(raw_games.len());
 
     for game in &raw_games {
         let web_match = web_games.iter().find(|w| w.appid == game.app_id);
+        let local = app_info.get(&game.app_id);
         let name = game
             .name
             .clone()
-            .or_else(|| web_match.and_then(|w| w.name.clone()));
-        let playtime_forever_minutes = web_match.map(|w| w.playtime_forever).unwrap_or(0);
-        let rtime_last_played = web_match.map(|w| w.rtime_last_played).unwrap_or(0);
+            .or_else(|| web_match.and_then(|w| w.name.clone()))
+            .or_else(|| local.and_then(|a| a.name.clone()));
+        let local_played = local_playtime
+            .get(&game.app_id)
+            .copied()
+            .unwrap_or_default();
+        let playtime_forever_minutes = web_match
+            .map(|w| w.playtime_forever)
+            .unwrap_or(0)
+            .max(local_played.playtime_forever_minutes);
+        let rtime_last_played = web_match
+            .map(|w| w.rtime_last_played)
+            .unwrap_or(0)
+            .max(local_played.rtime_last_played);
 
         merged.push(OwnedGame {
             app_id: game.app_id,
@@ -28,6 +54,7 @@ pub fn merge(raw_games: Vec<RawOwnedGame>, web_games: Vec<WebApiGame>) -> Vec<Ow
             // The Steam Web API has no purchase-date data at all - only agent mode's daemon
             // (via `merge::from_agent`) can ever populate this.
             last_refund_eligible_purchase_unix_seconds: None,
+            app_type: local.and_then(|a| a.app_type.clone()),
         });
         seen.insert(game.app_id);
     }
@@ -37,12 +64,22 @@ pub fn merge(raw_games: Vec<RawOwnedGame>, web_games: Vec<WebApiGame>) -> Vec<Ow
     // up to) - `main`'s `get_games_list` folds these in too rather than silently dropping them.
     for web_game in web_games {
         if seen.insert(web_game.appid) {
+            let local = app_info.get(&web_game.appid);
+            let local_played = local_playtime
+                .get(&web_game.appid)
+                .copied()
+                .unwrap_or_default();
             merged.push(OwnedGame {
                 app_id: web_game.appid,
-                name: web_game.name,
-                playtime_forever_minutes: web_game.playtime_forever,
-                rtime_last_played: web_game.rtime_last_played,
+                name: web_game.name.or_else(|| local.and_then(|a| a.name.clone())),
+                playtime_forever_minutes: web_game
+                    .playtime_forever
+                    .max(local_played.playtime_forever_minutes),
+                rtime_last_played: web_game
+                    .rtime_last_played
+                    .max(local_played.rtime_last_played),
                 last_refund_eligible_purchase_unix_seconds: None,
+                app_type: local.and_then(|a| a.app_type.clone()),
             });
         }
     }
@@ -66,6 +103,63 @@ pub fn from_agent(raw_games: Vec<RawOwnedGame>) -> Vec<OwnedGame> {
             playtime_forever_minutes: game.playtime_forever_minutes.unwrap_or(0),
             rtime_last_played: game.rtime_last_played.unwrap_or(0),
             last_refund_eligible_purchase_unix_seconds: game.last_refund_eligible_purchase_unix_seconds,
+            app_type: None,
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(app_id: u32) -> RawOwnedGame {
+        RawOwnedGame {
+            app_id,
+            name: None,
+            playtime_forever_minutes: None,
+            rtime_last_played: None,
+            last_refund_eligible_purchase_unix_seconds: None,
+        }
+    }
+
+    #[test]
+    fn local_playtime_fills_in_and_the_larger_source_wins() {
+        let web_games = vec![WebApiGame {
+            appid: 440,
+            name: Some("Team Fortress 2".to_string()),
+            playtime_forever: 100,
+            rtime_last_played: 1_700_000_000,
+        }];
+        let local_playtime = HashMap::from([
+            (
+                440,
+                LocalPlaytime {
+                    playtime_forever_minutes: 90,
+                    rtime_last_played: 1_700_000_500,
+                },
+            ),
+            (
+                570,
+                LocalPlaytime {
+                    playtime_forever_minutes: 42,
+                    rtime_last_played: 0,
+                },
+            ),
+        ]);
+
+        let merged = merge(
+            vec![raw(440), raw(570), raw(730)],
+            web_games,
+            &HashMap::new(),
+            &local_playtime,
+        );
+        let played: Vec<(u32, u64, u64)> = merged
+            .iter()
+            .map(|g| (g.app_id, g.playtime_forever_minutes, g.rtime_last_played))
+            .collect();
+        assert_eq!(
+            played,
+            vec![(440, 100, 1_700_000_500), (570, 42, 0), (730, 0, 0)]
+        );
+    }
+}
@@ -1,7 +1,9 @@
 //! Owned-games retrieval, shared between both sign-in modes - one `get_owned_games` command that
 //! branches on sign-in mode internally, not a pair of mode-specific commands with frontend-side
 //! branching. CLI mode's ownership check (`local_steam::ownership::check_ownership`) has no playtime
-//! of its own, so `web_api::fetch_owned_games`/`merge::merge` enrich it from the Steam Web API.
+//! of its own, so `web_api::fetch_owned_games`/`merge::merge` enrich it from the Steam Web API -
+//! and from the local client's `localconfig.vdf` (`local_steam::localconfig`), which keeps it
+//! working without an API key or a public profile.
 //! Agent mode's ownership check (`steam_agent::AgentManager::get_owned_apps`) comes back already
 //! enriched with playtime (`Daemon/Bot/OwnershipManager.cs` does its own SteamKit2-side
 //! enrichment), so it takes the `merge::from_agent` path instead and never touches the Web API -
@@ -66,6 +68,11 @@ pub struct OwnedGame {
     /// erroring, same resilience `settings::Settings` applies to its own fields.
     #[serde(default)]
     pub last_refund_eligible_purchase_unix_seconds: Option<i64>,
+    /// `"game"`, `"dlc"`, `"application"`, ... from the local Steam client's `appinfo.vdf`
+    /// (`local_steam::appinfo`) - so CLI mode only, and only for apps that client has cached;
+    /// `None` otherwise. `#[serde(default)]` for cache files written before this field existed.
+    #[serde(default)]
+    pub app_type: Option<String>,
 }
 
 /// `commands::get_owned_games`'s return shape - carries `possibly_private` alongside the games
@@ -250,6 +250,32 @@ impl IdleClaimsRegistry {
         self.claims.lock().await.clear();
     }
 
+    /// Records `owner`'s claim for an already-resolved `steam_id` *without* announcing anything -
+    /// only for `steam_agent::detached::restore_after_reattach`, where the reattached daemon is
+    /// already idling `targets` and a re-announce of this claim alone would briefly drop every
+    /// other owner's games before they're restored too.
+    pub async fn restore_owner_claim(
+        &self,
+        steam_id: &str,
+        account: &GamesAccount,
+        owner: &'static str,
+        targets: Vec<IdleTarget>,
+    ) {
+        self.accounts
+            .lock()
+            .await
+            .insert(steam_id.to_string(), account.clone());
+        self.claims
+            .lock()
+            .await
+            .entry(steam_id.to_string())
+            .or_default()
+            .insert(
+                owner,
+                targets.into_iter().map(|t| (t.app_id, t.name)).collect(),
+            );
+    }
+
     /// The current full `{app_id: name}` membership of one owner's claim *for `account`* - used by
     /// `toggle_manual_idle` to preserve the names of every other game already in the `"manual"`
     /// claim when adding one more.
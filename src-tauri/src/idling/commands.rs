@@ -274,6 +274,11 @@ pub async fn stop_all_idling(
     if let Err(e) = unlocker_manager.stop(&steam_id).await {
         tracing::warn!(steam_id, error = %e.code(), "stop_all_idling: failed to stop achievement unlocker session");
     }
+    // A deliberate stop, not an interruption - same as `achievement_unlocker::commands::
+    // stop_achievement_unlocker`'s `discard_checkpoint`.
+    if let Err(e) = crate::achievement_unlocker::checkpoint::clear(&app_handle, &steam_id).await {
+        tracing::warn!(steam_id, error = %e.code(), "stop_all_idling: failed to discard achievement unlocker checkpoint");
+    }
     claims
         .clear_all(&app_handle, agent_manager, idling_manager, account)
         .await
@@ -23,7 +23,7 @@
 
 pub mod cache;
 pub mod commands;
-mod market;
+pub mod market;
 mod scraper;
 pub mod settings;
 
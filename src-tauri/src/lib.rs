@@ -5,7 +5,9 @@ mod achievement_unlocker;
 mod achievements;
 mod async_utils;
 mod auto_idle;
+mod badges;
 mod card_farming;
+mod cli;
 mod credential_store;
 mod customization;
 mod debug;
@@ -22,19 +24,22 @@ mod local_steam;
 mod logging;
 mod max_playtime;
 mod platform;
+mod remote_api;
 mod settings;
 mod steam_agent;
+mod steam_collections;
 mod steam_community;
 mod steam_utility_exe;
 mod steam_web_api;
 mod subscription;
 mod tray;
 mod updater;
+mod webhooks;
 mod zoom;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    let builder = tauri::Builder::default()
         // Must be registered first so it can intercept startup before any other plugin/setup
         // runs on a second launch attempt (Tauri's own recommendation for this plugin).
         .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
@@ -76,14 +81,8 @@ pub fn run() {
             None,
         ))
         .plugin(tauri_plugin_notification::init())
-        .plugin(tauri_plugin_clipboard_manager::init())
-        .manage(steam_agent::AgentManager::new())
-        .manage(idling::IdlingManager::new())
-        .manage(idling::claims::IdleClaimsRegistry::new())
-        .manage(idling::auto_stop::IdleAutoStopRegistry::new())
-        .manage(card_farming::CardFarmingManager::new())
-        .manage(achievement_unlocker::AchievementUnlockerManager::new())
-        .manage(local_steam::commands::SteamStatusMonitor::new())
+        .plugin(tauri_plugin_clipboard_manager::init());
+    manage_state(builder)
         .setup(|app| {
             let log_guard = logging::init(app.handle())?;
             app.manage(log_guard);
@@ -95,6 +94,12 @@ pub fn run() {
             setup_window(app.handle())?;
             tray::setup(app.handle())?;
             tauri::async_runtime::spawn(max_playtime::enforcement::run(app.handle().clone()));
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(err) = remote_api::sync_with_settings(&handle).await {
+                    tracing::warn!(?err, "remote api: failed to start");
+                }
+            });
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -123,6 +128,7 @@ pub fn run() {
             local_steam::commands::launch_steam,
             local_steam::commands::prepare_steam_account_switch,
             local_steam::commands::switch_steam_account,
+            local_steam::commands::get_local_app_info,
             games::commands::get_owned_games,
             games::commands::get_owned_games_cache,
             games::commands::delete_owned_games_cache,
@@ -152,6 +158,8 @@ pub fn run() {
             auto_idle::commands::set_auto_idle_list_order,
             auto_idle::commands::set_auto_idle_enabled,
             auto_idle::commands::start_auto_idle_games,
+            steam_collections::commands::get_steam_collections,
+            steam_collections::commands::import_steam_collection,
             free_games::commands::get_free_games,
             free_games::commands::claim_free_game,
             free_games::commands::get_free_games_settings,
@@ -164,6 +172,13 @@ pub fn run() {
             settings::commands::set_anti_away,
             settings::commands::set_start_minimized,
             settings::commands::set_close_to_tray,
+            settings::commands::set_keep_agent_running,
+            remote_api::commands::get_remote_api_status,
+            remote_api::commands::set_remote_api,
+            remote_api::commands::regenerate_remote_api_token,
+            webhooks::commands::get_webhook_settings,
+            webhooks::commands::set_webhook_settings,
+            webhooks::commands::send_test_webhook,
             settings::commands::set_auto_update_games_list,
             settings::commands::set_free_game_notifications,
             settings::commands::set_theme,
@@ -180,6 +195,14 @@ pub fn run() {
             achievements::commands::lock_all_achievements,
             achievements::commands::update_stats,
             achievements::commands::reset_all_stats,
+            achievements::commands::take_achievement_snapshot,
+            achievements::commands::take_achievement_snapshots,
+            achievements::commands::list_achievement_snapshots,
+            achievements::commands::delete_achievement_snapshot,
+            achievements::commands::restore_achievement_snapshot,
+            achievements::commands::restore_achievement_snapshot_batch,
+            achievements::commands::scan_achievement_library,
+            achievements::commands::get_achievement_library_stats,
             subscription::get_device_fingerprint,
             subscription::quit_app,
             card_farming::commands::get_games_with_drops,
@@ -196,6 +219,11 @@ pub fn run() {
             card_farming::commands::add_to_card_farming_blacklist,
             card_farming::commands::remove_from_card_farming_blacklist,
             card_farming::commands::clear_card_farming_blacklist,
+            card_farming::commands::get_card_drop_ledger,
+            card_farming::commands::export_card_drop_ledger,
+            badges::commands::craft_badges,
+            badges::commands::get_badge_crafting_settings,
+            badges::commands::set_badge_crafting_settings,
             achievement_unlocker::commands::get_achievement_unlocker_queue,
             achievement_unlocker::commands::add_to_achievement_unlocker_queue,
             achievement_unlocker::commands::remove_from_achievement_unlocker_queue,
@@ -207,8 +235,16 @@ pub fn run() {
             achievement_unlocker::commands::get_achievement_unlocker_customized_app_ids,
             achievement_unlocker::commands::get_achievement_order,
             achievement_unlocker::commands::save_achievement_order,
+            achievement_unlocker::commands::export_achievement_order,
+            achievement_unlocker::commands::import_achievement_order,
+            achievement_unlocker::commands::generate_natural_achievement_order,
             achievement_unlocker::commands::import_achievement_timings,
+            achievement_unlocker::commands::aggregate_achievement_timings,
             achievement_unlocker::commands::start_achievement_unlocker,
+            achievement_unlocker::commands::simulate_achievement_unlocker,
+            achievement_unlocker::commands::resume_achievement_unlocker,
+            achievement_unlocker::commands::get_achievement_unlocker_checkpoint,
+            achievement_unlocker::commands::discard_achievement_unlocker_checkpoint,
             achievement_unlocker::commands::update_achievement_unlocker_concurrency,
             achievement_unlocker::commands::stop_achievement_unlocker,
             achievement_unlocker::commands::get_achievement_unlocker_state,
@@ -238,7 +274,7 @@ pub fn run() {
             zoom::set_zoom,
             tray::update_tray_menu,
         ])
-        .build(tauri::generate_context!())
+        .build(context())
         .expect("error while building tauri application")
         .run(|app_handle, event| {
             // Without this, quitting the app (tray Quit, titlebar close with close-to-tray off, or
@@ -248,9 +284,15 @@ pub fn run() {
             // `Child`'s `kill_on_drop` never fires. Reuses the same cleanup already wired into the
             // pre-update-install and sign-out paths (`updater::kill_all_steam_utility_processes`)
             // rather than a separate exit-only kill list, and blocks the exit on it completing -
-            // mirrors `main`'s `RunEvent::Exit => kill_tracked_processes_blocking()`.
+            // mirrors `main`'s `RunEvent::Exit => kill_tracked_processes_blocking()`. The one
+            // exception is opt-in detached mode, whose daemons are meant to outlive us - see
+            // `steam_agent::detached::detach_on_exit`.
             if let tauri::RunEvent::Exit = event {
                 tauri::async_runtime::block_on(async {
+                    if steam_agent::detached::enabled(app_handle) {
+                        steam_agent::detached::detach_on_exit(app_handle).await;
+                        return;
+                    }
                     if let Err(err) = updater::kill_all_steam_utility_processes(
                         app_handle.state(),
                         app_handle.state(),
@@ -265,6 +307,31 @@ pub fn run() {
         });
 }
 
+/// Entry point for the headless `sgi` binary (`src/bin/sgi.rs`) - see `cli.rs`'s doc comment.
+pub fn run_cli() -> std::process::ExitCode {
+    cli::run()
+}
+
+/// Every piece of managed state the managers look each other up through - shared with `cli::run`
+/// so the headless binary can never drift out of sync with what the desktop app registers.
+fn manage_state<R: tauri::Runtime>(builder: tauri::Builder<R>) -> tauri::Builder<R> {
+    builder
+        .manage(steam_agent::AgentManager::new())
+        .manage(idling::IdlingManager::new())
+        .manage(idling::claims::IdleClaimsRegistry::new())
+        .manage(idling::auto_stop::IdleAutoStopRegistry::new())
+        .manage(card_farming::CardFarmingManager::new())
+        .manage(achievement_unlocker::AchievementUnlockerManager::new())
+        .manage(local_steam::commands::SteamStatusMonitor::new())
+        .manage(remote_api::RemoteApiServer::new())
+}
+
+/// One `generate_context!` expansion for both entry points - each expansion embeds the whole
+/// frontend bundle, so a second call site would double it in the binary.
+fn context() -> tauri::Context<tauri::Wry> {
+    tauri::generate_context!()
+}
+
 /// The window starts hidden (`tauri.conf.json`'s `visible`/`focus` are both `false`) so the
 /// frontend's blank-then-painted webview never flashes as a visible window. On Windows it's shown
 /// only once the frontend emits `ready` after its first real paint - unless `Settings::
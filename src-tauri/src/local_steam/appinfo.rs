@@ -0,0 +1,327 @@
+//! Native reader for the local Steam client's app metadata cache (`<steam>/appcache/appinfo.vdf`) -
+//! each app's name, type, trading-card availability and DLC list without spawning SteamUtility
+//! (whose `Core/AppInfoParsing/AppInfoReader.cs` reads the same file for localized names). See
+//! `packageinfo` for its package-side sibling.
+//!
+//! Layout (after SteamDatabase/SteamAppInfo): a `u32` header - magic `0x075644` in the upper 24
+//! bits, format version in the low byte - then a `u32` universe, then entries until an app id of
+//! `0`. Each entry is `app_id`, `size` (bytes left in the entry), `info_state`, `last_updated`, a
+//! `u64` PICS token, a 20-byte SHA-1, `change_number`, a second 20-byte SHA-1 of the binary data
+//! (version 40+), then a binary KeyValues tree (`binary_kv`) rooted at `appinfo`. Version 41 adds
+//! an `i64` offset to a string table (`u32` count, then NUL-terminated strings) right after the
+//! universe, and the trees' keys become indices into it.
+//!
+//! Every version Steam has shipped in this layout is read - 38 and 39 are identical, 40 adds the
+//! binary hash, 41 the string table. Anything else is rejected with its header rather than
+//! misparsed.
+//!
+//! Version numbers here are the header's low byte **in decimal**, the way SteamDatabase quotes
+//! appinfo versions - `v41` (and `fixtures/appinfo_v41.vdf`) is header `0x07564429`. `packageinfo`
+//! quotes its versions in hex instead. `size` lets entries that weren't asked for be skipped
+//! without decoding their tree, which matters: the file caches every app the client has ever seen
+//! and runs to hundreds of MB - so [`read`] also keeps what it parsed until the file changes.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::SystemTime;
+
+use serde::Serialize;
+
+use crate::error::{AppError, AppResult};
+
+use super::binary_kv::{KvValue, Reader};
+
+const MAGIC: u32 = 0x07_5644;
+const OLDEST_VERSION: u32 = 38;
+const BINARY_HASH_VERSION: u32 = 40;
+const STRING_TABLE_VERSION: u32 = 41;
+const NEWEST_VERSION: u32 = 41;
+
+/// `common.category`'s key for Steam Trading Cards.
+const TRADING_CARDS_CATEGORY: &str = "category_29";
+
+/// The last [`read`]'s result, reused while `appinfo.vdf`'s mtime and size are unchanged - Steam
+/// rewrites the whole file whenever it updates an entry, so either moving means a fresh read.
+static CACHE: LazyLock<Mutex<Option<Cached>>> = LazyLock::new(|| Mutex::new(None));
+
+struct Cached {
+    path: PathBuf,
+    modified: SystemTime,
+    len: u64,
+    /// Every id a read against this file version asked for - found or not, so an id the file
+    /// doesn't have doesn't force a re-read on every call either.
+    asked: HashSet<u32>,
+    apps: HashMap<u32, AppMetadata>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppMetadata {
+    pub app_id: u32,
+    /// `common.name` - the English name, never a localized one.
+    pub name: Option<String>,
+    /// `common.type`, lowercased (`"game"`, `"dlc"`, `"application"`, `"tool"`, `"demo"`, ...) -
+    /// Steam has shipped both `Game` and `game` for the same field.
+    pub app_type: Option<String>,
+    /// Whether `common.category` lists Steam Trading Cards - the same flag the store page's
+    /// "Steam Trading Cards" feature reads.
+    pub has_trading_cards: bool,
+    /// `extended.listofdlc` - a comma-separated id list in the file.
+    pub dlc: Vec<u32>,
+    /// Packages that could grant this app - empty until [`add_package_ids`].
+    pub package_ids: Vec<u32>,
+}
+
+fn appinfo_path() -> AppResult<PathBuf> {
+    Ok(super::locate_steam()?
+        .path()
+        .join("appcache")
+        .join("appinfo.vdf"))
+}
+
+/// Metadata for every id in `app_ids` the local cache knows - ids it doesn't are simply absent, and
+/// no Steam install (or no cache file yet) is an empty map, not an error.
+pub fn read(app_ids: &HashSet<u32>) -> AppResult<HashMap<u32, AppMetadata>> {
+    let Ok(path) = appinfo_path() else {
+        return Ok(HashMap::new());
+    };
+    if app_ids.is_empty() || !path.exists() {
+        return Ok(HashMap::new());
+    }
+    read_cached(&path, app_ids)
+}
+
+/// [`read`] against `path`, through [`CACHE`] - a miss re-parses for `app_ids` plus every id the
+/// cached read already covered (when the file hasn't changed), so alternating callers asking for
+/// different ids settle on one cache entry instead of evicting each other.
+fn read_cached(path: &Path, app_ids: &HashSet<u32>) -> AppResult<HashMap<u32, AppMetadata>> {
+    let read_error = |e: std::io::Error| AppError::BinaryVdfParse(e.to_string());
+    let metadata = std::fs::metadata(path).map_err(read_error)?;
+    let modified = metadata.modified().map_err(read_error)?;
+    let len = metadata.len();
+    let pick = |apps: &HashMap<u32, AppMetadata>| {
+        app_ids
+            .iter()
+            .filter_map(|id| apps.get(id).map(|app| (*id, app.clone())))
+            .collect()
+    };
+
+    // Held across the read, so concurrent callers wait for one parse rather than each doing their
+    // own - `read` only ever runs on a blocking thread.
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let mut asked = app_ids.clone();
+    if let Some(cached) = cache
+        .as_ref()
+        .filter(|c| c.path == path && c.modified == modified && c.len == len)
+    {
+        if app_ids.is_subset(&cached.asked) {
+            return Ok(pick(&cached.apps));
+        }
+        asked.extend(&cached.asked);
+    }
+
+    let bytes = std::fs::read(path).map_err(read_error)?;
+    let apps: HashMap<u32, AppMetadata> = parse(&bytes, |app_id| asked.contains(&app_id))?
+        .into_iter()
+        .map(|app| (app.app_id, app))
+        .collect();
+    let picked = pick(&apps);
+    *cache = Some(Cached {
+        path: path.to_path_buf(),
+        modified,
+        len,
+        asked,
+        apps,
+    });
+    Ok(picked)
+}
+
+/// Fills in each app's `package_ids` from `packageinfo` - a separate pass since only
+/// `commands::get_local_app_info` wants them, and reading `packageinfo.vdf` means decoding all of it.
+pub fn add_package_ids(apps: &mut HashMap<u32, AppMetadata>) -> AppResult<()> {
+    for package in super::packageinfo::read()? {
+        for app_id in package.app_ids {
+            if let Some(app) = apps.get_mut(&app_id) {
+                app.package_ids.push(package.package_id);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Every entry `wanted` accepts, in file order - the rest are skipped undecoded.
+pub(crate) fn parse(bytes: &[u8], wanted: impl Fn(u32) -> bool) -> AppResult<Vec<AppMetadata>> {
+    let mut reader = Reader::new(bytes);
+    let header = reader.u32()?;
+    let version = header & 0xFF;
+    if header >> 8 != MAGIC || !(OLDEST_VERSION..=NEWEST_VERSION).contains(&version) {
+        return Err(AppError::BinaryVdfParse(format!(
+            "unsupported appinfo.vdf header {header:#010x}"
+        )));
+    }
+    reader.u32()?; // universe
+
+    let key_table = if version >= STRING_TABLE_VERSION {
+        let offset = usize::try_from(reader.i64()?)
+            .map_err(|_| AppError::BinaryVdfParse("negative string table offset".to_string()))?;
+        Some(read_string_table(bytes, offset)?)
+    } else {
+        None
+    };
+
+    let mut apps = Vec::new();
+    loop {
+        let app_id = reader.u32()?;
+        if app_id == 0 {
+            return Ok(apps);
+        }
+        let size = reader.u32()? as usize;
+        let entry_end = reader.position() + size;
+        if !wanted(app_id) {
+            reader.seek(entry_end)?;
+            continue;
+        }
+
+        // info_state, last_updated, PICS token, SHA-1, change_number.
+        reader.skip(4 + 4 + 8 + 20 + 4)?;
+        if version >= BINARY_HASH_VERSION {
+            reader.skip(20)?;
+        }
+        let tree_bytes = bytes.get(reader.position()..entry_end).ok_or_else(|| {
+            AppError::BinaryVdfParse(format!(
+                "app {app_id}'s entry runs past the end of the file"
+            ))
+        })?;
+        let mut tree_reader = Reader::new(tree_bytes);
+        if let Some(table) = &key_table {
+            tree_reader = tree_reader.with_key_table(table);
+        }
+        let tree = tree_reader.document()?;
+        if tree_reader.position() != tree_bytes.len() {
+            return Err(AppError::BinaryVdfParse(format!(
+                "app {app_id}'s entry did not parse to its declared size"
+            )));
+        }
+        apps.push(metadata(app_id, &tree));
+        reader.seek(entry_end)?;
+    }
+}
+
+fn read_string_table(bytes: &[u8], offset: usize) -> AppResult<Vec<String>> {
+    let mut reader = Reader::new(bytes);
+    reader.seek(offset)?;
+    let count = reader.u32()?;
+    (0..count).map(|_| reader.c_string()).collect()
+}
+
+fn metadata(app_id: u32, tree: &KvValue) -> AppMetadata {
+    let app = tree.get("appinfo");
+    let common = app.and_then(|a| a.get("common"));
+    let text = |key: &str| {
+        common
+            .and_then(|c| c.get(key))
+            .and_then(KvValue::as_str)
+            .filter(|s| !s.is_empty())
+    };
+    let has_trading_cards = common
+        .and_then(|c| c.get("category"))
+        .and_then(|c| c.get(TRADING_CARDS_CATEGORY))
+        .and_then(KvValue::as_f64)
+        .is_some_and(|v| v != 0.0);
+    let dlc = app
+        .and_then(|a| a.get("extended"))
+        .and_then(|e| e.get("listofdlc"))
+        .and_then(KvValue::as_str)
+        .map(|list| {
+            list.split(',')
+                .filter_map(|id| id.trim().parse().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    AppMetadata {
+        app_id,
+        name: text("name").map(str::to_string),
+        app_type: text("type").map(str::to_ascii_lowercase),
+        has_trading_cards,
+        dlc,
+        package_ids: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V38: &[u8] = include_bytes!("fixtures/appinfo_v38.vdf");
+    const V39: &[u8] = include_bytes!("fixtures/appinfo_v39.vdf");
+    const V40: &[u8] = include_bytes!("fixtures/appinfo_v40.vdf");
+    const V41: &[u8] = include_bytes!("fixtures/appinfo_v41.vdf");
+
+    #[test]
+    fn reads_every_shipped_version_to_the_same_metadata() {
+        for fixture in [V38, V39, V40, V41] {
+            let apps = parse(fixture, |_| true).unwrap();
+            assert_eq!(apps.len(), 3);
+            assert_eq!(
+                apps[0],
+                AppMetadata {
+                    app_id: 440,
+                    name: Some("Team Fortress 2".to_string()),
+                    app_type: Some("game".to_string()),
+                    has_trading_cards: true,
+                    dlc: vec![629330, 1010170],
+                    package_ids: Vec::new(),
+                }
+            );
+            assert_eq!(apps[1].app_type.as_deref(), Some("dlc"));
+            assert!(!apps[1].has_trading_cards);
+            assert!(apps[1].dlc.is_empty());
+            // An entry with no `common` block at all (unreleased/unknown apps) still comes back.
+            assert_eq!(apps[2].name, None);
+        }
+    }
+
+    #[test]
+    fn skips_unwanted_entries_and_rejects_unknown_headers() {
+        let apps = parse(V41, |app_id| app_id == 629330).unwrap();
+        assert_eq!(apps.len(), 1);
+        assert_eq!(
+            apps[0].name.as_deref(),
+            Some("Team Fortress 2 - Soundtrack")
+        );
+
+        let mut newer = V41.to_vec();
+        newer[0] = 42;
+        assert!(parse(&newer, |_| true).is_err());
+        assert!(parse(&V40[..V40.len() - 10], |_| true).is_err());
+    }
+
+    #[test]
+    fn cached_read_only_rereads_once_the_file_changes() {
+        let dir = std::env::temp_dir().join(format!("sgi-appinfo-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("appinfo.vdf");
+        std::fs::write(&path, V41).unwrap();
+
+        let first = read_cached(&path, &HashSet::from([440])).unwrap();
+        assert_eq!(first[&440].name.as_deref(), Some("Team Fortress 2"));
+        let both = read_cached(&path, &HashSet::from([440, 629330])).unwrap();
+        assert_eq!(both.len(), 2);
+        // Served from the cache: the first two reads' ids together, narrowed to what was asked.
+        assert_eq!(
+            CACHE.lock().unwrap().as_ref().unwrap().asked,
+            HashSet::from([440, 629330])
+        );
+        assert_eq!(
+            read_cached(&path, &HashSet::from([629330])).unwrap().len(),
+            1
+        );
+
+        // A different size is a different file - the old parse isn't served from it.
+        std::fs::write(&path, &V41[..V41.len() - 10]).unwrap();
+        assert!(read_cached(&path, &HashSet::from([440])).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,349 @@
+//! Reader for Valve's *binary* KeyValues format - what the local Steam client caches its per-game
+//! stats schemas in (`appcache/stats/UserGameStatsSchema_<app_id>.bin`, see `stats_schema`), as
+//! opposed to the text `.vdf` files `vdf` handles.
+//!
+//! Each entry is a one-byte type tag, a NUL-terminated key, then a type-specific value; an object
+//! (`TYPE_OBJECT`) is a run of entries closed by `TYPE_END`. Keys are matched case-insensitively by
+//! [`KvValue::get`], since Steam itself treats them that way and has shipped both cases of the same
+//! key across schema versions.
+//!
+//! `appcache/appinfo.vdf` (version 41+) stores keys as a 4-byte index into a string table at the
+//! end of the file instead of inline - [`Reader::with_key_table`]. Values are inline in every
+//! version. `appinfo`/`packageinfo` also drive [`Reader`] directly for their own fixed-width
+//! per-entry headers, which wrap one of these trees each.
+
+use crate::error::{AppError, AppResult};
+
+const TYPE_OBJECT: u8 = 0x00;
+const TYPE_STRING: u8 = 0x01;
+const TYPE_INT32: u8 = 0x02;
+const TYPE_FLOAT32: u8 = 0x03;
+const TYPE_POINTER: u8 = 0x04;
+const TYPE_WIDE_STRING: u8 = 0x05;
+const TYPE_COLOR: u8 = 0x06;
+const TYPE_UINT64: u8 = 0x07;
+const TYPE_END: u8 = 0x08;
+const TYPE_INT64: u8 = 0x0A;
+/// Some writers close objects with this instead of `TYPE_END`.
+const TYPE_END_ALT: u8 = 0x0B;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum KvValue {
+    /// Entries in file order - keys aren't guaranteed unique.
+    Object(Vec<(String, KvValue)>),
+    String(String),
+    Int32(i32),
+    Float32(f32),
+    UInt64(u64),
+    Int64(i64),
+}
+
+impl KvValue {
+    /// The first entry named `key` (case-insensitive), if this is an object.
+    pub fn get(&self, key: &str) -> Option<&KvValue> {
+        self.entries()
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    }
+
+    /// An object's entries - empty for anything else.
+    pub fn entries(&self) -> &[(String, KvValue)] {
+        match self {
+            KvValue::Object(entries) => entries,
+            _ => &[],
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            KvValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Any numeric value, or a string that parses as one - schemas store the same field as either
+    /// depending on which tool wrote them (e.g. a progress `max_val` of `"10"` vs `10`).
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            KvValue::Int32(v) => Some(f64::from(*v)),
+            KvValue::Float32(v) => Some(f64::from(*v)),
+            KvValue::UInt64(v) => Some(*v as f64),
+            KvValue::Int64(v) => Some(*v as f64),
+            KvValue::String(s) => s.trim().parse().ok(),
+            KvValue::Object(_) => None,
+        }
+    }
+}
+
+pub(super) struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    key_table: Option<&'a [String]>,
+}
+
+fn truncated() -> AppError {
+    AppError::BinaryVdfParse("unexpected end of data".to_string())
+}
+
+impl<'a> Reader<'a> {
+    pub(super) fn new(bytes: &'a [u8]) -> Self {
+        Reader {
+            bytes,
+            pos: 0,
+            key_table: None,
+        }
+    }
+
+    /// Reads keys as `u32` indices into `table` rather than inline strings.
+    pub(super) fn with_key_table(mut self, table: &'a [String]) -> Self {
+        self.key_table = Some(table);
+        self
+    }
+
+    pub(super) fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub(super) fn seek(&mut self, pos: usize) -> AppResult<()> {
+        if pos > self.bytes.len() {
+            return Err(truncated());
+        }
+        self.pos = pos;
+        Ok(())
+    }
+
+    pub(super) fn skip(&mut self, n: usize) -> AppResult<()> {
+        self.take(n).map(|_| ())
+    }
+
+    pub(super) fn u32(&mut self) -> AppResult<u32> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    pub(super) fn i64(&mut self) -> AppResult<i64> {
+        Ok(i64::from_le_bytes(self.array()?))
+    }
+
+    /// One tree, up to its closing `TYPE_END` or the end of the data - whichever comes first.
+    pub(super) fn document(&mut self) -> AppResult<KvValue> {
+        self.object(true).map(KvValue::Object)
+    }
+
+    fn take(&mut self, n: usize) -> AppResult<&'a [u8]> {
+        let end = self.pos.checked_add(n).ok_or_else(truncated)?;
+        let slice = self.bytes.get(self.pos..end).ok_or_else(truncated)?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> AppResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn array<const N: usize>(&mut self) -> AppResult<[u8; N]> {
+        Ok(self
+            .take(N)?
+            .try_into()
+            .expect("take returns exactly N bytes"))
+    }
+
+    pub(super) fn c_string(&mut self) -> AppResult<String> {
+        let rest = self.bytes.get(self.pos..).ok_or_else(truncated)?;
+        let len = rest.iter().position(|b| *b == 0).ok_or_else(truncated)?;
+        let s = String::from_utf8_lossy(&rest[..len]).into_owned();
+        self.pos += len + 1;
+        Ok(s)
+    }
+
+    fn key(&mut self) -> AppResult<String> {
+        let Some(table) = self.key_table else {
+            return self.c_string();
+        };
+        let index = self.u32()?;
+        table.get(index as usize).cloned().ok_or_else(|| {
+            AppError::BinaryVdfParse(format!(
+                "key index {index} outside a {}-entry string table",
+                table.len()
+            ))
+        })
+    }
+
+    fn wide_string(&mut self) -> AppResult<String> {
+        let mut units = Vec::new();
+        loop {
+            let unit = u16::from_le_bytes(self.array()?);
+            if unit == 0 {
+                break;
+            }
+            units.push(unit);
+        }
+        Ok(String::from_utf16_lossy(&units))
+    }
+
+    /// Entries up to the closing `TYPE_END` - or, at the top level, up to the end of the data.
+    fn object(&mut self, top_level: bool) -> AppResult<Vec<(String, KvValue)>> {
+        let mut entries = Vec::new();
+        loop {
+            if top_level && self.pos == self.bytes.len() {
+                return Ok(entries);
+            }
+            let tag = self.u8()?;
+            if tag == TYPE_END || tag == TYPE_END_ALT {
+                return Ok(entries);
+            }
+            let key = self.key()?;
+            let value = match tag {
+                TYPE_OBJECT => KvValue::Object(self.object(false)?),
+                TYPE_STRING => KvValue::String(self.c_string()?),
+                TYPE_INT32 | TYPE_POINTER | TYPE_COLOR => {
+                    KvValue::Int32(i32::from_le_bytes(self.array()?))
+                }
+                TYPE_FLOAT32 => KvValue::Float32(f32::from_le_bytes(self.array()?)),
+                TYPE_WIDE_STRING => KvValue::String(self.wide_string()?),
+                TYPE_UINT64 => KvValue::UInt64(u64::from_le_bytes(self.array()?)),
+                TYPE_INT64 => KvValue::Int64(i64::from_le_bytes(self.array()?)),
+                other => {
+                    return Err(AppError::BinaryVdfParse(format!(
+                        "unknown type tag {other:#04x} at offset {}",
+                        self.pos
+                    )))
+                }
+            };
+            entries.push((key, value));
+        }
+    }
+}
+
+/// Parses a whole binary KeyValues document into its root object.
+pub fn parse(bytes: &[u8]) -> AppResult<KvValue> {
+    Reader::new(bytes).document()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Builds binary KeyValues bytes for tests - here and in `stats_schema`/`appinfo`/`packageinfo`'s.
+    /// The second field collects keys into a string table instead of writing them inline.
+    #[derive(Default)]
+    pub(crate) struct Writer(pub(crate) Vec<u8>, pub(crate) Option<Vec<String>>);
+
+    impl Writer {
+        pub(crate) fn with_key_table() -> Self {
+            Writer(Vec::new(), Some(Vec::new()))
+        }
+
+        fn key(&mut self, tag: u8, key: &str) -> &mut Self {
+            self.0.push(tag);
+            match &mut self.1 {
+                Some(table) => {
+                    let index = table.iter().position(|k| k == key).unwrap_or_else(|| {
+                        table.push(key.to_string());
+                        table.len() - 1
+                    });
+                    self.0.extend_from_slice(&(index as u32).to_le_bytes());
+                }
+                None => {
+                    self.0.extend_from_slice(key.as_bytes());
+                    self.0.push(0);
+                }
+            }
+            self
+        }
+
+        pub(crate) fn begin(&mut self, key: &str) -> &mut Self {
+            self.key(TYPE_OBJECT, key)
+        }
+
+        pub(crate) fn end(&mut self) -> &mut Self {
+            self.0.push(TYPE_END);
+            self
+        }
+
+        pub(crate) fn string(&mut self, key: &str, value: &str) -> &mut Self {
+            self.key(TYPE_STRING, key);
+            self.0.extend_from_slice(value.as_bytes());
+            self.0.push(0);
+            self
+        }
+
+        pub(crate) fn int(&mut self, key: &str, value: i32) -> &mut Self {
+            self.key(TYPE_INT32, key);
+            self.0.extend_from_slice(&value.to_le_bytes());
+            self
+        }
+    }
+
+    #[test]
+    fn parses_nested_objects_and_scalars() {
+        let mut w = Writer::default();
+        w.begin("Root")
+            .string("name", "value")
+            .int("count", -3)
+            .begin("child")
+            .int("x", 7)
+            .end()
+            .end();
+        w.key(TYPE_FLOAT32, "ratio")
+            .0
+            .extend_from_slice(&1.5f32.to_le_bytes());
+        w.key(TYPE_UINT64, "big")
+            .0
+            .extend_from_slice(&u64::MAX.to_le_bytes());
+
+        let root = parse(&w.0).unwrap();
+        let inner = root.get("root").unwrap();
+        assert_eq!(inner.get("NAME").and_then(KvValue::as_str), Some("value"));
+        assert_eq!(inner.get("count").and_then(KvValue::as_f64), Some(-3.0));
+        assert_eq!(
+            inner
+                .get("child")
+                .and_then(|c| c.get("x"))
+                .and_then(KvValue::as_f64),
+            Some(7.0)
+        );
+        assert_eq!(root.get("ratio"), Some(&KvValue::Float32(1.5)));
+        assert_eq!(root.get("big"), Some(&KvValue::UInt64(u64::MAX)));
+    }
+
+    #[test]
+    fn reads_keys_from_a_string_table() {
+        let mut w = Writer::with_key_table();
+        w.begin("appinfo")
+            .int("appid", 440)
+            .begin("common")
+            .string("name", "appid")
+            .end()
+            .end()
+            .end();
+        let table = w.1.clone().unwrap();
+        assert_eq!(table, vec!["appinfo", "appid", "common", "name"]);
+
+        let mut reader = Reader::new(&w.0).with_key_table(&table);
+        let root = reader.document().unwrap();
+        assert_eq!(reader.position(), w.0.len());
+        let app = root.get("appinfo").unwrap();
+        assert_eq!(app.get("appid").and_then(KvValue::as_f64), Some(440.0));
+        assert_eq!(
+            app.get("common")
+                .and_then(|c| c.get("name"))
+                .and_then(KvValue::as_str),
+            Some("appid")
+        );
+
+        assert!(Reader::new(&w.0)
+            .with_key_table(&table[..1])
+            .document()
+            .is_err());
+    }
+
+    #[test]
+    fn truncated_and_unknown_data_fail_cleanly() {
+        let mut w = Writer::default();
+        w.begin("root").string("name", "value");
+        let cut = w.0.len() - 3;
+        assert!(parse(&w.0[..cut]).is_err());
+        assert!(parse(&[0x42, b'k', 0]).is_err());
+    }
+}
@@ -0,0 +1,210 @@
+//! The local Steam client's library collections, read from the account's cloud-synced storage
+//! namespace (`<steam>/userdata/<account_id>/config/cloudstorage/cloud-storage-namespace-1.json`) -
+//! the same store the library's "Favorites", "Hidden" and user-made shelves sync through.
+//!
+//! The file is a JSON array of `[key, entry]` pairs. Collections are the `user-collections.<id>`
+//! keys, whose entry's `value` is itself a JSON *string* holding `{ id, name, added, removed,
+//! filterSpec? }`; a deleted collection keeps its key with `is_deleted: true` and no `value` until
+//! the next sync compacts it away. Everything else in the namespace (showcases, per-app tags, ...)
+//! is ignored.
+//!
+//! **Dynamic collections are only partly readable offline.** A collection with a `filterSpec` is
+//! evaluated by the client against its store-tag/feature/play-state data at display time - none of
+//! that is reproducible here. What *is* stored is the user's manual overrides (`added` on top of
+//! the filter, `removed` excluded from it), so a dynamic collection reports those and is flagged
+//! `dynamic` so callers can say its filter matches weren't included.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AppError, AppResult};
+
+const COLLECTION_KEY_PREFIX: &str = "user-collections.";
+
+/// The built-in "Favorites" collection's id - its stored `name` is empty, the client localizes it.
+const FAVORITES_COLLECTION_ID: &str = "favorite";
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamCollection {
+    /// `"favorite"`/`"hidden"` for the built-ins, `"uc-<random>"` for user-made ones.
+    pub id: String,
+    /// Empty for the built-ins - the client supplies a localized name for those.
+    pub name: String,
+    /// `added` minus `removed`, deduplicated, in stored order. For a `dynamic` collection, only
+    /// the games added to it by hand.
+    pub app_ids: Vec<u32>,
+    pub dynamic: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct NamespaceEntry {
+    #[serde(default)]
+    is_deleted: bool,
+    value: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredCollection {
+    id: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    added: Vec<u32>,
+    #[serde(default)]
+    removed: Vec<u32>,
+    filter_spec: Option<serde_json::Value>,
+}
+
+fn namespace_path(steam_id: &str) -> AppResult<PathBuf> {
+    let account_id = super::account_id(steam_id)
+        .ok_or_else(|| AppError::SteamCollections(format!("{steam_id} is not a SteamID64")))?;
+    Ok(super::locate_steam()?
+        .path()
+        .join("userdata")
+        .join(account_id.to_string())
+        .join("config")
+        .join("cloudstorage")
+        .join("cloud-storage-namespace-1.json"))
+}
+
+/// `steam_id`'s collections, Favorites first and the rest by name - empty when Steam isn't
+/// installed or that account never opened its library on this machine.
+pub fn read(steam_id: &str) -> AppResult<Vec<SteamCollection>> {
+    let path = match namespace_path(steam_id) {
+        Ok(path) => path,
+        Err(AppError::SteamNotFound(_)) => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let text = std::fs::read_to_string(&path).map_err(|e| {
+        AppError::SteamCollections(format!("failed to read {}: {e}", path.display()))
+    })?;
+    parse(&text)
+}
+
+pub(crate) fn parse(text: &str) -> AppResult<Vec<SteamCollection>> {
+    let entries: Vec<(String, serde_json::Value)> =
+        serde_json::from_str(text).map_err(|e| AppError::SteamCollections(e.to_string()))?;
+    let mut collections: Vec<SteamCollection> = entries
+        .into_iter()
+        .filter(|(key, _)| key.starts_with(COLLECTION_KEY_PREFIX))
+        .filter_map(|(key, entry)| {
+            // One malformed collection shouldn't hide the rest - the client itself tolerates them.
+            let collection = serde_json::from_value::<NamespaceEntry>(entry)
+                .ok()
+                .filter(|entry| !entry.is_deleted)?
+                .value
+                .and_then(|value| serde_json::from_str::<StoredCollection>(&value).ok());
+            if collection.is_none() {
+                tracing::debug!(
+                    key,
+                    "steam collections: skipping deleted or unreadable entry"
+                );
+            }
+            collection
+        })
+        .map(|stored| {
+            let mut app_ids = Vec::new();
+            for app_id in stored.added {
+                if !stored.removed.contains(&app_id) && !app_ids.contains(&app_id) {
+                    app_ids.push(app_id);
+                }
+            }
+            SteamCollection {
+                id: stored.id,
+                name: stored.name,
+                app_ids,
+                dynamic: stored.filter_spec.is_some(),
+            }
+        })
+        .collect();
+    collections.sort_by_cached_key(|c| (c.id != FAVORITES_COLLECTION_ID, c.name.to_lowercase()));
+    Ok(collections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAMESPACE: &str = r#"[
+        ["user-collections.uc-zK2wYQ", {
+            "key": "user-collections.uc-zK2wYQ",
+            "timestamp": 1700000300,
+            "value": "{\"id\":\"uc-zK2wYQ\",\"name\":\"Roguelikes\",\"added\":[646570,250900,646570],\"removed\":[]}",
+            "conflictResolutionMethod": "custom",
+            "strMethodId": "union-collections",
+            "version": "14"
+        }],
+        ["user-collections.favorite", {
+            "key": "user-collections.favorite",
+            "timestamp": 1700000100,
+            "value": "{\"id\":\"favorite\",\"name\":\"\",\"added\":[440,570],\"removed\":[]}",
+            "version": "12"
+        }],
+        ["user-collections.uc-Pq81Lm", {
+            "key": "user-collections.uc-Pq81Lm",
+            "timestamp": 1700000400,
+            "value": "{\"id\":\"uc-Pq81Lm\",\"name\":\"co-op\",\"added\":[620,550],\"removed\":[550],\"filterSpec\":{\"nFormatVersion\":2,\"strSearchText\":\"\",\"filterGroups\":[{\"rgOptions\":[9],\"bAcceptUnion\":false}],\"setSuggestions\":{}}}",
+            "version": "15"
+        }],
+        ["user-collections.uc-gone", {
+            "key": "user-collections.uc-gone",
+            "timestamp": 1700000500,
+            "is_deleted": true,
+            "version": "16"
+        }],
+        ["user-collections.uc-broken", {
+            "key": "user-collections.uc-broken",
+            "timestamp": 1700000600,
+            "value": "not json",
+            "version": "17"
+        }],
+        ["showcases.1", {
+            "key": "showcases.1",
+            "timestamp": 1700000000,
+            "value": "{\"nShowcaseId\":1}",
+            "version": "3"
+        }]
+    ]"#;
+
+    #[test]
+    fn reads_live_collections_favorites_first() {
+        assert_eq!(
+            parse(NAMESPACE).unwrap(),
+            vec![
+                SteamCollection {
+                    id: "favorite".to_string(),
+                    name: String::new(),
+                    app_ids: vec![440, 570],
+                    dynamic: false,
+                },
+                SteamCollection {
+                    id: "uc-Pq81Lm".to_string(),
+                    name: "co-op".to_string(),
+                    app_ids: vec![620],
+                    dynamic: true,
+                },
+                SteamCollection {
+                    id: "uc-zK2wYQ".to_string(),
+                    name: "Roguelikes".to_string(),
+                    app_ids: vec![646570, 250900],
+                    dynamic: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_a_file_that_is_not_a_namespace() {
+        assert!(parse("[]").unwrap().is_empty());
+        assert!(matches!(
+            parse("{\"user-collections.favorite\": {}}"),
+            Err(AppError::SteamCollections(_))
+        ));
+    }
+}
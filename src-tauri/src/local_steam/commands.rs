@@ -18,6 +18,7 @@ use tauri::{AppHandle, Emitter, State};
 
 use crate::error::{AppError, AppResult};
 
+use super::appinfo::{self, AppMetadata};
 use super::vdf::{self, LocalSteamUser};
 use super::{locate_steam, login_users_vdf_path, steam_web_api};
 
@@ -284,6 +285,21 @@ fn spawn_detached(exe: &Path) -> AppResult<()> {
         .map_err(|e| AppError::LocalProcessSpawn(e.to_string()))
 }
 
+/// Name, type, trading-card availability, DLC list and granting packages for each of `app_ids`,
+/// read straight from the local Steam client's `appcache` (`appinfo`/`packageinfo`) - no
+/// SteamUtility process, and Steam needn't be running. Ids the cache doesn't know are left out;
+/// no Steam install at all is an empty list.
+#[tauri::command]
+pub async fn get_local_app_info(app_ids: Vec<u32>) -> AppResult<Vec<AppMetadata>> {
+    tokio::task::spawn_blocking(move || {
+        let mut apps = appinfo::read(&app_ids.iter().copied().collect())?;
+        appinfo::add_package_ids(&mut apps)?;
+        Ok(app_ids.iter().filter_map(|id| apps.remove(id)).collect())
+    })
+    .await
+    .map_err(|e| AppError::BinaryVdfParse(e.to_string()))?
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
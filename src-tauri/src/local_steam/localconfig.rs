@@ -0,0 +1,155 @@
+//! Per-game playtime from the local Steam client's own per-account settings file
+//! (`<steam>/userdata/<account_id>/config/localconfig.vdf`, text KeyValues - see `text_kv`), under
+//! `UserLocalConfigStore > Software > Valve > Steam > apps > <app_id>`'s `Playtime` (minutes) and
+//! `LastPlayed` (unix seconds).
+//!
+//! CLI mode's playtime otherwise only comes from the Steam Web API (`games::web_api`), which needs
+//! an API key and a public profile - `games::merge` folds this in so max-playtime caps, the
+//! card-farming skip settings and refund-window checks still have real numbers without either.
+//! It only knows what this machine's Steam client has recorded or synced, so `merge` takes the
+//! larger of the two sources rather than trusting either outright.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::error::{AppError, AppResult};
+
+use super::text_kv::{self, KvValue};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LocalPlaytime {
+    pub playtime_forever_minutes: u64,
+    pub rtime_last_played: u64,
+}
+
+fn localconfig_path(steam_id: &str) -> AppResult<PathBuf> {
+    let account_id = super::account_id(steam_id)
+        .ok_or_else(|| AppError::LocalConfigVdf(format!("{steam_id} is not a SteamID64")))?;
+    Ok(super::locate_steam()?
+        .path()
+        .join("userdata")
+        .join(account_id.to_string())
+        .join("config")
+        .join("localconfig.vdf"))
+}
+
+/// Every app `steam_id`'s `localconfig.vdf` has a playtime or last-played time for - empty when
+/// Steam isn't installed or that account never signed in on this machine.
+pub fn read(steam_id: &str) -> AppResult<HashMap<u32, LocalPlaytime>> {
+    let path = match localconfig_path(steam_id) {
+        Ok(path) => path,
+        Err(AppError::SteamNotFound(_)) => return Ok(HashMap::new()),
+        Err(e) => return Err(e),
+    };
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::LocalConfigVdf(format!("failed to read {}: {e}", path.display())))?;
+    parse(&text)
+}
+
+pub(crate) fn parse(text: &str) -> AppResult<HashMap<u32, LocalPlaytime>> {
+    let root = text_kv::parse(text).map_err(|e| AppError::LocalConfigVdf(e.to_string()))?;
+    let apps = ["UserLocalConfigStore", "Software", "Valve", "Steam", "apps"]
+        .iter()
+        .try_fold(&root, |node, key| node.get(key));
+    let Some(apps) = apps else {
+        return Ok(HashMap::new());
+    };
+    let number = |app: &KvValue, key: &str| {
+        app.get(key)
+            .and_then(KvValue::as_str)
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .unwrap_or(0)
+    };
+    Ok(apps
+        .entries()
+        .iter()
+        .filter_map(|entry| {
+            let app_id = entry.key.parse::<u32>().ok()?;
+            let playtime = LocalPlaytime {
+                playtime_forever_minutes: number(&entry.value, "Playtime"),
+                rtime_last_played: number(&entry.value, "LastPlayed"),
+            };
+            (playtime != LocalPlaytime::default()).then_some((app_id, playtime))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCALCONFIG: &str = r#""UserLocalConfigStore"
+{
+	"Software"
+	{
+		"valve"
+		{
+			"Steam"
+			{
+				"apps"
+				{
+					"440"
+					{
+						"LastPlayed"		"1700000000"
+						"Playtime2wks"		"30"
+						"Playtime"		"5025"
+						"cloud"
+						{
+							"last_sync_state"		"synchronized"
+						}
+					}
+					"570"
+					{
+						"LastPlayed"		"1690000000"
+					}
+					"730"
+					{
+						"cloud"
+						{
+							"last_sync_state"		"synchronized"
+						}
+					}
+					"not-an-app"
+					{
+						"Playtime"		"1"
+					}
+				}
+			}
+		}
+	}
+}
+"#;
+
+    #[test]
+    fn reads_playtime_and_last_played_per_app() {
+        let playtime = parse(LOCALCONFIG).unwrap();
+        assert_eq!(playtime.len(), 2);
+        assert_eq!(
+            playtime[&440],
+            LocalPlaytime {
+                playtime_forever_minutes: 5025,
+                rtime_last_played: 1_700_000_000,
+            }
+        );
+        assert_eq!(playtime[&570].playtime_forever_minutes, 0);
+        assert_eq!(playtime[&570].rtime_last_played, 1_690_000_000);
+
+        assert!(parse("\"UserLocalConfigStore\" { }").unwrap().is_empty());
+        assert!(parse("\"UserLocalConfigStore\" {").is_err());
+    }
+
+    #[test]
+    fn only_steam_id64s_map_to_a_userdata_folder() {
+        assert!(matches!(
+            localconfig_path("123"),
+            Err(AppError::LocalConfigVdf(_))
+        ));
+        assert!(matches!(
+            localconfig_path("not-a-number"),
+            Err(AppError::LocalConfigVdf(_))
+        ));
+    }
+}
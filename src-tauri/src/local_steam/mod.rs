@@ -6,10 +6,18 @@
 //! of our own the way `steam_agent::AgentProcess` does.
 
 pub(crate) mod achievements;
+pub(crate) mod appinfo;
+mod binary_kv;
+pub(crate) mod collections;
 pub mod commands;
 pub mod free_game_claim;
+pub(crate) mod localconfig;
 pub(crate) mod ownership;
+mod packageinfo;
+pub(crate) mod stats_schema;
 mod steam_web_api;
+mod text_kv;
+pub(crate) mod user_game_stats;
 mod vdf;
 
 use std::path::PathBuf;
@@ -26,6 +34,18 @@ fn login_users_vdf_path() -> AppResult<PathBuf> {
     Ok(locate_steam()?.path().join("config").join("loginusers.vdf"))
 }
 
+/// SteamID64 of account id 0.
+const STEAM_ID64_BASE: u64 = 76561197960265728;
+
+/// The 32-bit account id the local client keys per-account files by (`userdata/<account_id>/`,
+/// `UserGameStats_<account_id>_<app_id>.bin`) - `None` if `steam_id` isn't a SteamID64.
+fn account_id(steam_id: &str) -> Option<u64> {
+    steam_id
+        .parse::<u64>()
+        .ok()
+        .and_then(|id| id.checked_sub(STEAM_ID64_BASE))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
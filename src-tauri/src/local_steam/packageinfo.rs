@@ -0,0 +1,142 @@
+//! Native reader for the local Steam client's package cache (`<steam>/appcache/packageinfo.vdf`) -
+//! which apps each package (a store purchase, key or free license) grants. `appinfo`'s sibling.
+//!
+//! Layout (after SteamDatabase/SteamAppInfo): a `u32` header - magic `0x065655` in the upper 24
+//! bits, format version in the low byte - then a `u32` universe, then entries until a package id of
+//! `0xFFFFFFFF`. Each entry is `package_id`, a 20-byte SHA-1, `change_number`, a `u64` PICS token
+//! (version 0x28), then a binary KeyValues tree (`binary_kv`) keyed by the package id, whose
+//! `appids` object lists the granted apps as values.
+//!
+//! Unlike `appinfo.vdf`, entries carry no size, so every tree is decoded on the way through - the
+//! file is a fraction of `appinfo.vdf`'s size, so that's cheap. Versions 0x27 and 0x28 (headers
+//! `0x06565527`/`0x06565528`) are read; anything else is rejected with its header rather than
+//! misparsed.
+//!
+//! Version numbers here are the header's low byte **in hex**, the way packageinfo versions are
+//! usually quoted - `v28` (and `fixtures/packageinfo_v28.vdf`) is `0x28`, i.e. 40 in decimal.
+//! `appinfo` quotes its versions in decimal instead.
+//!
+//! Nothing here says which packages an account actually holds - that's the client's encrypted
+//! license cache - so this maps apps to the packages that *could* grant them, nothing more.
+
+use std::path::PathBuf;
+
+use crate::error::{AppError, AppResult};
+
+use super::binary_kv::Reader;
+
+const MAGIC: u32 = 0x06_5655;
+const OLDEST_VERSION: u32 = 0x27;
+const PICS_TOKEN_VERSION: u32 = 0x28;
+const NEWEST_VERSION: u32 = 0x28;
+const END_OF_ENTRIES: u32 = u32::MAX;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackageMetadata {
+    pub package_id: u32,
+    pub app_ids: Vec<u32>,
+}
+
+fn packageinfo_path() -> AppResult<PathBuf> {
+    Ok(super::locate_steam()?
+        .path()
+        .join("appcache")
+        .join("packageinfo.vdf"))
+}
+
+/// Every package the local cache knows - empty with no Steam install or no cache file yet.
+pub fn read() -> AppResult<Vec<PackageMetadata>> {
+    let Ok(path) = packageinfo_path() else {
+        return Ok(Vec::new());
+    };
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let bytes = std::fs::read(&path).map_err(|e| AppError::BinaryVdfParse(e.to_string()))?;
+    parse(&bytes)
+}
+
+pub(crate) fn parse(bytes: &[u8]) -> AppResult<Vec<PackageMetadata>> {
+    let mut reader = Reader::new(bytes);
+    let header = reader.u32()?;
+    let version = header & 0xFF;
+    if header >> 8 != MAGIC || !(OLDEST_VERSION..=NEWEST_VERSION).contains(&version) {
+        return Err(AppError::BinaryVdfParse(format!(
+            "unsupported packageinfo.vdf header {header:#010x}"
+        )));
+    }
+    reader.u32()?; // universe
+
+    let mut packages = Vec::new();
+    loop {
+        let package_id = reader.u32()?;
+        if package_id == END_OF_ENTRIES {
+            return Ok(packages);
+        }
+        // SHA-1, change_number.
+        reader.skip(20 + 4)?;
+        if version >= PICS_TOKEN_VERSION {
+            reader.skip(8)?;
+        }
+        let tree = reader.document()?;
+        let app_ids = tree
+            .entries()
+            .first()
+            .and_then(|(_, package)| package.get("appids"))
+            .map(|ids| {
+                ids.entries()
+                    .iter()
+                    .filter_map(|(_, id)| id.as_f64())
+                    .filter(|id| *id > 0.0)
+                    .map(|id| id as u32)
+                    .collect()
+            })
+            .unwrap_or_default();
+        packages.push(PackageMetadata {
+            package_id,
+            app_ids,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V27: &[u8] = include_bytes!("fixtures/packageinfo_v27.vdf");
+    const V28: &[u8] = include_bytes!("fixtures/packageinfo_v28.vdf");
+
+    #[test]
+    fn reads_both_shipped_versions_to_the_same_packages() {
+        for fixture in [V27, V28] {
+            assert_eq!(
+                parse(fixture).unwrap(),
+                vec![
+                    PackageMetadata {
+                        package_id: 0,
+                        app_ids: vec![7, 8],
+                    },
+                    PackageMetadata {
+                        package_id: 197845,
+                        app_ids: vec![440, 629330],
+                    },
+                    PackageMetadata {
+                        package_id: 469,
+                        app_ids: Vec::new(),
+                    },
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_headers_and_truncated_files() {
+        let mut newer = V28.to_vec();
+        newer[0] = 0x29;
+        assert!(parse(&newer).is_err());
+        let mut older = V27.to_vec();
+        older[0] = 0x26;
+        assert!(parse(&older).is_err());
+        assert!(parse(&V28[..V28.len() - 4]).is_err());
+    }
+}
@@ -0,0 +1,361 @@
+//! Achievement-to-stat progress links from the local Steam client's cached stats schema
+//! (`<steam>/appcache/stats/UserGameStatsSchema_<app_id>.bin`, binary KeyValues - see
+//! `binary_kv`). Both backends' `AchievementDto::progress` carries the same link, parsed by
+//! SteamUtility's schema walker from the schema it fetched, and that's what the unlocker plans
+//! from - [`progress_links`] is only its fallback for achievement data from a SteamUtility build
+//! that predates the field.
+//!
+//! Only readable where a local Steam client has cached the game's schema (it does so the first time
+//! the game's stats are requested on that machine - any CLI-mode `get_achievement_data` call), so
+//! a missing file just means "no links", never an error - callers fall back to unlocking directly.
+//!
+//! Only the `statvalue` progress operation is understood - the only one Steamworks' partner site
+//! offers for achievement progress. Anything else is ignored.
+//!
+//! [`definitions`] walks the same file for every achievement and stat it defines - a port of
+//! SteamUtility's `Core/SchemaParsing/SchemaWalker.cs`, so `user_game_stats` can build an
+//! `AchievementData` with no SteamUtility process at all.
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::error::{AppError, AppResult};
+
+use super::binary_kv::{self, KvValue};
+
+/// One progress-based achievement: unlocked once `stat_id` (a `StatDto::id`) reaches `max_value`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressLink {
+    pub achievement_id: String,
+    pub stat_id: String,
+    pub min_value: f64,
+    pub max_value: f64,
+}
+
+fn schema_path(app_id: u32) -> AppResult<PathBuf> {
+    Ok(super::locate_steam()?
+        .path()
+        .join("appcache")
+        .join("stats")
+        .join(format!("UserGameStatsSchema_{app_id}.bin")))
+}
+
+/// Every progress link in `app_id`'s cached schema - empty when Steam or the file isn't there.
+pub fn progress_links(app_id: u32) -> AppResult<Vec<ProgressLink>> {
+    match read_cached(app_id)? {
+        Some(bytes) => parse_progress_links(&bytes),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// `app_id`'s cached schema file - `None` when Steam or the file isn't there.
+fn read_cached(app_id: u32) -> AppResult<Option<Vec<u8>>> {
+    let Ok(path) = schema_path(app_id) else {
+        return Ok(None);
+    };
+    if !path.exists() {
+        return Ok(None);
+    }
+    std::fs::read(&path)
+        .map(Some)
+        .map_err(|e| AppError::BinaryVdfParse(e.to_string()))
+}
+
+/// Walks `<app_id> > stats > <stat> > bits > <bit> > progress` - achievements live as bits of the
+/// schema's achievement-type stats, each bit optionally carrying a `progress` block.
+pub(crate) fn parse_progress_links(bytes: &[u8]) -> AppResult<Vec<ProgressLink>> {
+    let root = binary_kv::parse(bytes)?;
+    let mut links = Vec::new();
+    for (_, game) in root.entries() {
+        let Some(stats) = game.get("stats") else {
+            continue;
+        };
+        for (_, stat) in stats.entries() {
+            let Some(bits) = stat.get("bits") else {
+                continue;
+            };
+            links.extend(
+                bits.entries()
+                    .iter()
+                    .filter_map(|(_, bit)| progress_link(bit)),
+            );
+        }
+    }
+    Ok(links)
+}
+
+fn progress_link(bit: &KvValue) -> Option<ProgressLink> {
+    let progress = bit.get("progress")?;
+    let value = progress.get("value")?;
+    if !value
+        .get("operation")?
+        .as_str()?
+        .eq_ignore_ascii_case("statvalue")
+    {
+        return None;
+    }
+    Some(ProgressLink {
+        achievement_id: bit.get("name")?.as_str()?.to_string(),
+        stat_id: value.get("operand1")?.as_str()?.to_string(),
+        min_value: progress
+            .get("min_val")
+            .and_then(KvValue::as_f64)
+            .unwrap_or(0.0),
+        max_value: progress.get("max_val")?.as_f64()?,
+    })
+}
+
+/// One achievement as the schema defines it - unlocked when bit `bit` of stat `stat_id`'s value is
+/// set. Mirrors `SchemaWalker.cs`'s `AchievementDefinition`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AchievementDefinition {
+    pub id: String,
+    pub stat_id: u32,
+    pub bit: u32,
+    pub permission: i32,
+    pub name: String,
+    pub description: String,
+    pub icon_normal: String,
+    pub icon_locked: String,
+    pub hidden: bool,
+    pub progress: Option<ProgressLink>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatKind {
+    Integer,
+    Float,
+    AvgRate,
+}
+
+impl StatKind {
+    /// `StatDto::stat_type`'s value for this kind.
+    pub fn wire_name(self) -> &'static str {
+        match self {
+            StatKind::Integer => "integer",
+            StatKind::Float => "float",
+            StatKind::AvgRate => "avgrate",
+        }
+    }
+}
+
+/// One plain numeric stat. Mirrors `SchemaWalker.cs`'s `StatDefinition`, minus the bounds nothing
+/// here reads.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatDefinition {
+    pub id: String,
+    pub stat_id: u32,
+    pub display_name: String,
+    pub kind: StatKind,
+    pub permission: i32,
+    pub increment_only: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchemaDefinitions {
+    pub achievements: Vec<AchievementDefinition>,
+    pub stats: Vec<StatDefinition>,
+}
+
+/// Every achievement and stat in `app_id`'s cached schema, display text in `language` (a Steam
+/// schema language key - see `achievements::steam_language`) - `None` when Steam or the file isn't
+/// there.
+pub fn definitions(app_id: u32, language: &str) -> AppResult<Option<SchemaDefinitions>> {
+    read_cached(app_id)?
+        .map(|bytes| parse_definitions(&bytes, language))
+        .transpose()
+}
+
+/// `<app_id> > stats > <stat_id>` - achievement-type stats contribute their `bits`, the rest one
+/// stat each.
+pub(crate) fn parse_definitions(bytes: &[u8], language: &str) -> AppResult<SchemaDefinitions> {
+    let root = binary_kv::parse(bytes)?;
+    let mut definitions = SchemaDefinitions::default();
+    for (_, game) in root.entries() {
+        let Some(stats) = game.get("stats") else {
+            continue;
+        };
+        for (key, stat) in stats.entries() {
+            let Ok(stat_id) = key.parse::<u32>() else {
+                continue;
+            };
+            let kind = match stat_type(stat) {
+                1 => StatKind::Integer,
+                2 => StatKind::Float,
+                3 => StatKind::AvgRate,
+                4 | 5 => {
+                    definitions
+                        .achievements
+                        .extend(achievement_definitions(stat_id, stat, language));
+                    continue;
+                }
+                _ => continue,
+            };
+            let id = text(stat.get("name"));
+            let display_name = localized(stat.get("display").and_then(|d| d.get("name")), language);
+            definitions.stats.push(StatDefinition {
+                display_name: if display_name.is_empty() {
+                    id.clone()
+                } else {
+                    display_name
+                },
+                id,
+                stat_id,
+                kind,
+                permission: integer(stat.get("permission")),
+                increment_only: integer(stat.get("incrementonly")) != 0,
+            });
+        }
+    }
+    Ok(definitions)
+}
+
+fn achievement_definitions<'a>(
+    stat_id: u32,
+    stat: &'a KvValue,
+    language: &'a str,
+) -> impl Iterator<Item = AchievementDefinition> + 'a {
+    stat.entries()
+        .iter()
+        .filter(|(key, _)| key.eq_ignore_ascii_case("bits"))
+        .flat_map(|(_, bits)| bits.entries())
+        .filter_map(move |(key, bit)| {
+            let display = bit.get("display");
+            let field = |key: &str| display.and_then(|d| d.get(key));
+            Some(AchievementDefinition {
+                id: text(bit.get("name")),
+                stat_id,
+                bit: key.parse().ok()?,
+                permission: integer(bit.get("permission")),
+                name: localized(field("name"), language),
+                description: localized(field("desc"), language),
+                icon_normal: text(field("icon")),
+                icon_locked: text(field("icon_gray")),
+                hidden: integer(field("hidden")) != 0,
+                progress: progress_link(bit),
+            })
+        })
+}
+
+/// `SchemaWalker.DetermineStatType` - `1` integer, `2` float, `3` avgrate, `4`/`5` (group)
+/// achievements, `0` unknown. Newer schemas dropped `type_int` for a `type` name, and some carry
+/// neither, leaving only the stat's shape to go on.
+fn stat_type(stat: &KvValue) -> i32 {
+    if let Some(type_int) = stat.get("type_int") {
+        return integer(Some(type_int));
+    }
+    if let Some(stat_type) = stat.get("type") {
+        return match stat_type.as_str().map(str::to_ascii_uppercase).as_deref() {
+            Some("INT") => 1,
+            Some("FLOAT") => 2,
+            Some("AVGRATE") => 3,
+            Some("ACHIEVEMENT" | "ACHIEVEMENTS") => 4,
+            _ => integer(Some(stat_type)),
+        };
+    }
+    if stat.get("bits").is_some() {
+        4
+    } else if stat.get("window").is_some() {
+        3
+    } else if ["min", "max", "default"]
+        .iter()
+        .any(|key| matches!(stat.get(key), Some(KvValue::Float32(_))))
+    {
+        2
+    } else {
+        1
+    }
+}
+
+/// `SchemaWalker.ResolveLocalizedString` - `node` is either one flat string or a per-language
+/// object; `language`, then English, then whichever language comes first.
+fn localized(node: Option<&KvValue>, language: &str) -> String {
+    let Some(node) = node else {
+        return String::new();
+    };
+    if let Some(flat) = node.as_str() {
+        return flat.to_string();
+    }
+    node.get(language)
+        .or_else(|| node.get("english"))
+        .or_else(|| {
+            node.entries()
+                .iter()
+                .map(|(_, v)| v)
+                .find(|v| v.as_str().is_some())
+        })
+        .and_then(KvValue::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn text(node: Option<&KvValue>) -> String {
+    node.and_then(KvValue::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn integer(node: Option<&KvValue>) -> i32 {
+    node.and_then(KvValue::as_f64).map_or(0, |v| v as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::binary_kv::tests::Writer;
+    use super::*;
+
+    #[test]
+    fn reads_statvalue_progress_blocks_only() {
+        let mut w = Writer::default();
+        w.begin("480").begin("stats");
+        w.begin("1")
+            .string("name", "NumWins")
+            .string("type", "1")
+            .end();
+        w.begin("2").string("type", "4").begin("bits");
+        w.begin("0")
+            .string("name", "ACH_WIN_10")
+            .begin("progress")
+            .string("min_val", "0")
+            .string("max_val", "10")
+            .begin("value")
+            .string("operation", "statvalue")
+            .string("operand1", "NumWins")
+            .end()
+            .end()
+            .end();
+        w.begin("1").string("name", "ACH_PLAIN").end();
+        w.begin("2")
+            .string("name", "ACH_OTHER_OP")
+            .begin("progress")
+            .int("max_val", 5)
+            .begin("value")
+            .string("operation", "somethingelse")
+            .string("operand1", "NumWins")
+            .end()
+            .end()
+            .end();
+        w.end().end(); // bits, stat 2
+        w.end().end(); // stats, game
+
+        assert_eq!(
+            parse_progress_links(&w.0).unwrap(),
+            vec![ProgressLink {
+                achievement_id: "ACH_WIN_10".to_string(),
+                stat_id: "NumWins".to_string(),
+                min_value: 0.0,
+                max_value: 10.0,
+            }]
+        );
+        // `definitions` carries the same link on the achievement it belongs to.
+        let progress: Vec<Option<f64>> = parse_definitions(&w.0, "english")
+            .unwrap()
+            .achievements
+            .iter()
+            .map(|a| a.progress.as_ref().map(|link| link.max_value))
+            .collect();
+        assert_eq!(progress, vec![Some(10.0), None, None]);
+    }
+}
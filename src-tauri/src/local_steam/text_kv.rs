@@ -0,0 +1,574 @@
+//! Reader and writer for Valve's *text* KeyValues format - the `.vdf` files the local Steam client
+//! keeps its own state in (`config/loginusers.vdf`, `config/config.vdf`,
+//! `steamapps/libraryfolders.vdf`, `userdata/<account_id>/config/localconfig.vdf`), as opposed to
+//! the binary caches `binary_kv` handles.
+//!
+//! A document is a run of `"key" "value"` and `"key" { ... }` entries. Quotes are optional around
+//! tokens without whitespace or braces; `//` starts a comment; quoted strings take `\\`, `\"`,
+//! `\n` and `\t` escapes (anything else after a backslash is kept as-is, which is how Steam itself
+//! reads unescaped Windows paths). An entry may carry a platform conditional (`[$WIN32]`), kept on
+//! [`KvEntry::condition`] rather than evaluated, so a rewrite never drops one.
+//!
+//! [`write`] emits Steam's own layout (tab indents, `"key"\t\t"value"`), and `parse(write(doc))`
+//! always gives `doc` back - duplicate keys, entry order and conditionals included. Comments and
+//! original whitespace are not kept: nothing in Steam's own files has any.
+
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KvEntry {
+    pub key: String,
+    pub value: KvValue,
+    /// A `[$WIN32]`-style conditional's contents (`$WIN32`), if the entry had one.
+    pub condition: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum KvValue {
+    /// Entries in file order - keys aren't guaranteed unique.
+    Object(Vec<KvEntry>),
+    String(String),
+}
+
+impl KvValue {
+    /// The first entry named `key` (case-insensitive, as Steam matches them), if this is an object.
+    pub fn get(&self, key: &str) -> Option<&KvValue> {
+        self.entries()
+            .iter()
+            .find(|e| e.key.eq_ignore_ascii_case(key))
+            .map(|e| &e.value)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut KvValue> {
+        self.entries_mut()
+            .iter_mut()
+            .find(|e| e.key.eq_ignore_ascii_case(key))
+            .map(|e| &mut e.value)
+    }
+
+    /// An object's entries - empty for a string.
+    pub fn entries(&self) -> &[KvEntry] {
+        match self {
+            KvValue::Object(entries) => entries,
+            KvValue::String(_) => &[],
+        }
+    }
+
+    pub fn entries_mut(&mut self) -> &mut [KvEntry] {
+        match self {
+            KvValue::Object(entries) => entries,
+            KvValue::String(_) => &mut [],
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            KvValue::String(s) => Some(s),
+            KvValue::Object(_) => None,
+        }
+    }
+}
+
+/// Where and why a document failed to parse - callers wrap it in their own file's `AppError`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    String(String),
+    Condition(String),
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::String(s) => write!(f, "\"{s}\""),
+            Token::Condition(c) => write!(f, "[{c}]"),
+            Token::Open => f.write_str("'{'"),
+            Token::Close => f.write_str("'}'"),
+        }
+    }
+}
+
+struct Lexer<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+    peeked: Option<(Token, usize, usize)>,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(text: &'a str) -> Self {
+        Lexer {
+            chars: text
+                .strip_prefix('\u{feff}')
+                .unwrap_or(text)
+                .chars()
+                .peekable(),
+            line: 1,
+            column: 1,
+            peeked: None,
+        }
+    }
+
+    fn error_at(line: usize, column: usize, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    /// Skips whitespace and `//` comments. A lone `/` is left for the unquoted-token path.
+    fn skip_trivia(&mut self) {
+        loop {
+            match self.chars.peek() {
+                Some(c) if c.is_whitespace() => {
+                    self.bump();
+                }
+                Some('/') => {
+                    let mut ahead = self.chars.clone();
+                    ahead.next();
+                    if ahead.peek() != Some(&'/') {
+                        return;
+                    }
+                    while self.chars.peek().is_some_and(|c| *c != '\n') {
+                        self.bump();
+                    }
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn quoted(&mut self, line: usize, column: usize) -> Result<String, ParseError> {
+        let mut s = String::new();
+        loop {
+            match self.bump() {
+                None => return Err(Self::error_at(line, column, "unterminated string")),
+                Some('"') => return Ok(s),
+                Some('\\') => match self.chars.peek() {
+                    Some('\\') | Some('"') => s.push(self.bump().unwrap()),
+                    Some('n') => {
+                        self.bump();
+                        s.push('\n');
+                    }
+                    Some('t') => {
+                        self.bump();
+                        s.push('\t');
+                    }
+                    _ => s.push('\\'),
+                },
+                Some(c) => s.push(c),
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Option<(Token, usize, usize)>, ParseError> {
+        if let Some(peeked) = self.peeked.take() {
+            return Ok(Some(peeked));
+        }
+        self.skip_trivia();
+        let (line, column) = (self.line, self.column);
+        let Some(c) = self.bump() else {
+            return Ok(None);
+        };
+        let token = match c {
+            '{' => Token::Open,
+            '}' => Token::Close,
+            '"' => Token::String(self.quoted(line, column)?),
+            '[' => {
+                let mut condition = String::new();
+                loop {
+                    match self.bump() {
+                        Some(']') => break,
+                        Some('\n') | None => {
+                            return Err(Self::error_at(line, column, "unterminated conditional"))
+                        }
+                        Some(c) => condition.push(c),
+                    }
+                }
+                Token::Condition(condition)
+            }
+            c => {
+                let mut s = String::from(c);
+                while let Some(&next) = self.chars.peek() {
+                    if next.is_whitespace() || matches!(next, '{' | '}' | '"') {
+                        break;
+                    }
+                    s.push(next);
+                    self.bump();
+                }
+                Token::String(s)
+            }
+        };
+        Ok(Some((token, line, column)))
+    }
+
+    fn next_is_condition(&mut self) -> Result<bool, ParseError> {
+        if self.peeked.is_none() {
+            self.peeked = self.next_token()?;
+        }
+        Ok(matches!(self.peeked, Some((Token::Condition(_), _, _))))
+    }
+}
+
+fn parse_entries(lexer: &mut Lexer, top_level: bool) -> Result<Vec<KvEntry>, ParseError> {
+    let mut entries = Vec::new();
+    loop {
+        let key = match lexer.next_token()? {
+            None if top_level => return Ok(entries),
+            None => {
+                return Err(Lexer::error_at(
+                    lexer.line,
+                    lexer.column,
+                    "unexpected end of input, expected '}'",
+                ))
+            }
+            Some((Token::Close, _, _)) if !top_level => return Ok(entries),
+            Some((Token::String(key), _, _)) => key,
+            Some((token, line, column)) => {
+                return Err(Lexer::error_at(
+                    line,
+                    column,
+                    format!("expected a key, found {token}"),
+                ))
+            }
+        };
+
+        let mut condition = None;
+        let mut next = lexer.next_token()?;
+        if let Some((Token::Condition(c), _, _)) = next {
+            condition = Some(c);
+            next = lexer.next_token()?;
+        }
+        let value = match next {
+            Some((Token::Open, _, _)) => KvValue::Object(parse_entries(lexer, false)?),
+            Some((Token::String(value), _, _)) => KvValue::String(value),
+            Some((token, line, column)) => {
+                return Err(Lexer::error_at(
+                    line,
+                    column,
+                    format!("expected a value for \"{key}\", found {token}"),
+                ))
+            }
+            None => {
+                return Err(Lexer::error_at(
+                    lexer.line,
+                    lexer.column,
+                    format!("unexpected end of input, expected a value for \"{key}\""),
+                ))
+            }
+        };
+        if condition.is_none() && lexer.next_is_condition()? {
+            if let Some((Token::Condition(c), _, _)) = lexer.next_token()? {
+                condition = Some(c);
+            }
+        }
+        entries.push(KvEntry {
+            key,
+            value,
+            condition,
+        });
+    }
+}
+
+/// Parses a whole text KeyValues document into its root object.
+pub fn parse(text: &str) -> Result<KvValue, ParseError> {
+    parse_entries(&mut Lexer::new(text), true).map(KvValue::Object)
+}
+
+fn push_quoted(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn write_entries(out: &mut String, entries: &[KvEntry], depth: usize) {
+    let indent = "\t".repeat(depth);
+    for entry in entries {
+        out.push_str(&indent);
+        push_quoted(out, &entry.key);
+        let condition = entry
+            .condition
+            .as_ref()
+            .map(|c| format!(" [{c}]"))
+            .unwrap_or_default();
+        match &entry.value {
+            KvValue::String(value) => {
+                out.push_str("\t\t");
+                push_quoted(out, value);
+                out.push_str(&condition);
+                out.push('\n');
+            }
+            KvValue::Object(children) => {
+                out.push_str(&condition);
+                out.push('\n');
+                out.push_str(&indent);
+                out.push_str("{\n");
+                write_entries(out, children, depth + 1);
+                out.push_str(&indent);
+                out.push_str("}\n");
+            }
+        }
+    }
+}
+
+/// Serializes `root`'s entries in Steam's own layout. A string root has no entries to write.
+pub fn write(root: &KvValue) -> String {
+    let mut out = String::new();
+    write_entries(&mut out, root.entries(), 0);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn string(key: &str, value: &str) -> KvEntry {
+        KvEntry {
+            key: key.to_string(),
+            value: KvValue::String(value.to_string()),
+            condition: None,
+        }
+    }
+
+    #[test]
+    fn handles_comments_escapes_unquoted_tokens_and_conditionals() {
+        let doc = parse(
+            "\u{feff}// header comment\n\
+             \"root\"\n{\n\
+             \t\"path\"\t\t\"C:\\\\Program Files (x86)\\\\Steam\"\n\
+             \t\"quote\"\t\t\"say \\\"hi\\\" {not a block}\" // trailing\n\
+             \tunquoted value\n\
+             \t\"raw\"\t\t\"C:\\Games\"\n\
+             \t\"platform\"\t\t\"win\" [$WIN32]\n\
+             \t\"nested\" [$OSX] { \"a\" \"1\" }\n\
+             }\n",
+        )
+        .unwrap();
+        let root = doc.get("ROOT").unwrap();
+        assert_eq!(
+            root.get("path").and_then(KvValue::as_str),
+            Some("C:\\Program Files (x86)\\Steam")
+        );
+        assert_eq!(
+            root.get("quote").and_then(KvValue::as_str),
+            Some("say \"hi\" {not a block}")
+        );
+        assert_eq!(
+            root.get("unquoted").and_then(KvValue::as_str),
+            Some("value")
+        );
+        assert_eq!(root.get("raw").and_then(KvValue::as_str), Some("C:\\Games"));
+        assert_eq!(root.entries()[4].condition.as_deref(), Some("$WIN32"));
+        assert_eq!(root.entries()[5].condition.as_deref(), Some("$OSX"));
+        assert_eq!(
+            root.get("nested")
+                .and_then(|n| n.get("a"))
+                .and_then(KvValue::as_str),
+            Some("1")
+        );
+        assert_eq!(parse(&write(&doc)).unwrap(), doc);
+    }
+
+    #[test]
+    fn reports_where_a_malformed_document_breaks() {
+        let err = parse("\"a\"\n{\n\t\"b\"\t\t\"c\"\n").unwrap_err();
+        assert!(err.message.contains("expected '}'"), "{err}");
+        let err = parse("\"a\" {\n\t\"b\"\n}").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 1));
+        assert!(parse("\"a\" \"unterminated").is_err());
+        assert!(parse("}").is_err());
+    }
+
+    /// Shapes of the other Steam files this reader is meant for, trimmed from real installs.
+    #[test]
+    fn reads_libraryfolders_localconfig_and_config_shapes() {
+        let libraries = parse(
+            r#""libraryfolders"
+{
+	"0"
+	{
+		"path"		"C:\\Program Files (x86)\\Steam"
+		"label"		""
+		"contentid"		"1234567890123456789"
+		"totalsize"		"0"
+		"apps"
+		{
+			"228980"		"331621418"
+			"480"		"10245"
+		}
+	}
+	"1"
+	{
+		"path"		"D:\\SteamLibrary"
+		"apps"
+		{
+		}
+	}
+}
+"#,
+        )
+        .unwrap();
+        let folders = libraries.get("libraryfolders").unwrap();
+        assert_eq!(folders.entries().len(), 2);
+        assert_eq!(
+            folders
+                .get("1")
+                .and_then(|f| f.get("path"))
+                .and_then(KvValue::as_str),
+            Some("D:\\SteamLibrary")
+        );
+
+        let local_config = parse(
+            r#""UserLocalConfigStore"
+{
+	"Software"
+	{
+		"Valve"
+		{
+			"Steam"
+			{
+				"apps"
+				{
+					"480"
+					{
+						"LastPlayed"		"1700000000"
+						"Playtime"		"125"
+						"Playtime2wks"		"5"
+					}
+				}
+			}
+		}
+	}
+}
+"#,
+        )
+        .unwrap();
+        let playtime = [
+            "UserLocalConfigStore",
+            "Software",
+            "Valve",
+            "Steam",
+            "apps",
+            "480",
+        ]
+        .iter()
+        .try_fold(&local_config, |node, key| node.get(key))
+        .and_then(|app| app.get("Playtime"))
+        .and_then(KvValue::as_str);
+        assert_eq!(playtime, Some("125"));
+
+        let config = parse(
+            r#""InstallConfigStore"
+{
+	"Software"
+	{
+		"Valve"
+		{
+			"Steam"
+			{
+				"CompatToolMapping"
+				{
+				}
+				"Accounts"
+				{
+					"someone"
+					{
+						"SteamID"		"76561197960287930"
+					}
+				}
+			}
+		}
+	}
+}
+"#,
+        )
+        .unwrap();
+        assert_eq!(parse(&write(&config)).unwrap(), config);
+    }
+
+    const ALPHABET: &[char] = &[
+        'a', 'Z', '0', ' ', '\t', '\n', '"', '\\', '{', '}', '[', ']', '/', '$', 'é', '日', '\'',
+    ];
+
+    fn random_string(rng: &mut StdRng) -> String {
+        (0..rng.gen_range(0..8))
+            .map(|_| ALPHABET[rng.gen_range(0..ALPHABET.len())])
+            .collect()
+    }
+
+    fn random_entries(rng: &mut StdRng, depth: usize) -> Vec<KvEntry> {
+        (0..rng.gen_range(0..5))
+            .map(|_| KvEntry {
+                key: random_string(rng),
+                value: if depth < 3 && rng.gen_bool(0.3) {
+                    KvValue::Object(random_entries(rng, depth + 1))
+                } else {
+                    KvValue::String(random_string(rng))
+                },
+                condition: rng
+                    .gen_bool(0.1)
+                    .then(|| ["$WIN32", "!$OSX", "$X360||$PS3"][rng.gen_range(0..3)].to_string()),
+            })
+            .collect()
+    }
+
+    /// Randomized property: parse(write(doc)) == doc, and writing is a fixed point, for arbitrary
+    /// documents whose keys/values are built from every character the format treats specially.
+    /// Seeded so a failure reproduces.
+    #[test]
+    fn parse_write_parse_is_lossless() {
+        let mut rng = StdRng::seed_from_u64(0x5EED_0F4B);
+        for _ in 0..500 {
+            let mut entries = random_entries(&mut rng, 0);
+            entries.push(string("duplicate", "1"));
+            entries.push(string("DUPLICATE", "2"));
+            let doc = KvValue::Object(entries);
+            let written = write(&doc);
+            let reparsed = parse(&written).unwrap_or_else(|e| panic!("{e}\n{written}"));
+            assert_eq!(reparsed, doc, "{written}");
+            assert_eq!(write(&reparsed), written);
+        }
+    }
+}
@@ -0,0 +1,239 @@
+//! Offline achievements: the local Steam client's own cache of an account's stat values
+//! (`<steam>/appcache/stats/UserGameStats_<account_id>_<app_id>.bin`, binary KeyValues - see
+//! `binary_kv`) joined with the game's cached schema (`stats_schema::definitions`) into the same
+//! `AchievementData` a live fetch returns - no SteamUtility process, no running client.
+//!
+//! The client rewrites that file every time it fetches or stores the game's stats, so it's as
+//! fresh as the last time anything on this machine - the game itself, or a live
+//! `get_achievement_data` - touched them. Nothing here is written back.
+//!
+//! The file is a `cache` object of `<stat_id> { data, AchievementTimes }` entries. `data` is the
+//! stat's raw 32-bit value, read the way `Daemon/Bot/AchievementHandler.cs` reads the identical
+//! values off the wire: an achievement-type stat's bits are its achievements' unlocked flags, an
+//! integer stat is the value itself, and a float/avgrate stat is the value's IEEE-754 bits.
+//! `percent` is left unset, as in agent mode - callers backfill it.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::achievements::{AchievementData, AchievementDto, AchievementProgress, StatDto};
+use crate::error::{AppError, AppResult};
+
+use super::binary_kv::{self, KvValue};
+use super::stats_schema::{self, SchemaDefinitions, StatKind};
+
+/// A locally read `AchievementData`, with when the client last wrote its stat values.
+#[derive(Debug, Clone)]
+pub struct LocalAchievementData {
+    pub data: AchievementData,
+    pub cached_at_ms: i64,
+}
+
+fn stats_path(account_id: u64, app_id: u32) -> AppResult<PathBuf> {
+    Ok(super::locate_steam()?
+        .path()
+        .join("appcache")
+        .join("stats")
+        .join(format!("UserGameStats_{account_id}_{app_id}.bin")))
+}
+
+/// `steam_id`'s achievements and stats for `app_id` as the local client last cached them, display
+/// text in `language` (a Steam schema language key) - `None` when Steam, the schema or the
+/// account's stats file isn't there.
+pub fn read_achievement_data(
+    steam_id: &str,
+    app_id: u32,
+    language: &str,
+) -> AppResult<Option<LocalAchievementData>> {
+    let Some(account_id) = super::account_id(steam_id) else {
+        return Ok(None);
+    };
+    let Ok(path) = stats_path(account_id, app_id) else {
+        return Ok(None);
+    };
+    if !path.exists() {
+        return Ok(None);
+    }
+    let Some(definitions) = stats_schema::definitions(app_id, language)? else {
+        return Ok(None);
+    };
+    let read_error = |e: std::io::Error| AppError::BinaryVdfParse(e.to_string());
+    let bytes = std::fs::read(&path).map_err(read_error)?;
+    let cached_at_ms = std::fs::metadata(&path)
+        .and_then(|m| m.modified())
+        .map_err(read_error)?
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as i64);
+    Ok(Some(LocalAchievementData {
+        data: build(&definitions, &parse_stat_values(&bytes)?),
+        cached_at_ms,
+    }))
+}
+
+/// `stat_id -> raw value` for every stat the file has a value for.
+pub(crate) fn parse_stat_values(bytes: &[u8]) -> AppResult<HashMap<u32, u32>> {
+    let root = binary_kv::parse(bytes)?;
+    let cache = root.get("cache").unwrap_or(&root);
+    Ok(cache
+        .entries()
+        .iter()
+        .filter_map(|(key, stat)| {
+            let stat_id = key.parse::<u32>().ok()?;
+            let value = match stat.get("data")? {
+                KvValue::Int32(v) => *v as u32,
+                KvValue::UInt64(v) => *v as u32,
+                KvValue::Int64(v) => *v as u32,
+                KvValue::Float32(v) => v.to_bits(),
+                KvValue::String(s) => s.trim().parse().ok()?,
+                KvValue::Object(_) => return None,
+            };
+            Some((stat_id, value))
+        })
+        .collect())
+}
+
+/// Joins schema definitions with raw stat values - a stat missing from `values` reads as `0`, the
+/// same default the wire-side join uses.
+pub(crate) fn build(
+    definitions: &SchemaDefinitions,
+    values: &HashMap<u32, u32>,
+) -> AchievementData {
+    let value = |stat_id: u32| values.get(&stat_id).copied().unwrap_or(0);
+    let achievements = definitions
+        .achievements
+        .iter()
+        .map(|def| {
+            let (protected, flags) = stat_flags(def.permission, false, true);
+            AchievementDto {
+                id: def.id.clone(),
+                name: def.name.clone(),
+                description: def.description.clone(),
+                icon_normal: def.icon_normal.clone(),
+                icon_locked: def.icon_locked.clone(),
+                permission: def.permission,
+                hidden: def.hidden,
+                achieved: def.bit < 32 && (value(def.stat_id) >> def.bit) & 1 != 0,
+                percent: None,
+                protected_achievement: protected,
+                flags,
+                progress: def.progress.as_ref().map(|link| AchievementProgress {
+                    stat_id: link.stat_id.clone(),
+                    min_value: link.min_value,
+                    max_value: link.max_value,
+                }),
+            }
+        })
+        .collect();
+    let stats = definitions
+        .stats
+        .iter()
+        .map(|def| {
+            let raw = value(def.stat_id);
+            let (protected, flags) = stat_flags(def.permission, def.increment_only, false);
+            StatDto {
+                id: def.id.clone(),
+                name: def.display_name.clone(),
+                stat_type: def.kind.wire_name().to_string(),
+                permission: def.permission,
+                value: match def.kind {
+                    StatKind::Integer => serde_json::json!(raw as i32),
+                    StatKind::Float | StatKind::AvgRate => serde_json::json!(f32::from_bits(raw)),
+                },
+                increment_only: def.increment_only,
+                protected_stat: protected,
+                flags,
+            }
+        })
+        .collect();
+    AchievementData {
+        achievements,
+        stats,
+    }
+}
+
+/// `Core/StatFlags.cs`'s `StatFlagHelper.GetFlags`, down to the `[Flags]` enum's `ToString`
+/// rendering the DTOs carry - returns whether the item is protected, and that string.
+fn stat_flags(permission: i32, increment_only: bool, is_achievement: bool) -> (bool, String) {
+    let protected_mask = if is_achievement { 3 } else { 2 };
+    let protected = permission & protected_mask != 0;
+    let names: Vec<&str> = [
+        (!is_achievement && increment_only, "IncrementOnly"),
+        (protected, "Protected"),
+        (permission & !protected_mask != 0, "UnknownPermission"),
+    ]
+    .into_iter()
+    .filter_map(|(set, name)| set.then_some(name))
+    .collect();
+    let flags = if names.is_empty() {
+        "None".to_string()
+    } else {
+        names.join(", ")
+    };
+    (protected, flags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &[u8] = include_bytes!("fixtures/UserGameStatsSchema_480.bin");
+    const STATS: &[u8] = include_bytes!("fixtures/UserGameStats_22202_480.bin");
+
+    #[test]
+    fn joins_the_cached_schema_and_stat_values() {
+        let definitions = stats_schema::parse_definitions(SCHEMA, "german").unwrap();
+        let data = build(&definitions, &parse_stat_values(STATS).unwrap());
+
+        let achieved: Vec<(&str, bool)> = data
+            .achievements
+            .iter()
+            .map(|a| (a.id.as_str(), a.achieved))
+            .collect();
+        assert_eq!(
+            achieved,
+            vec![
+                ("ACH_WIN_ONE_GAME", true),
+                ("ACH_WIN_100_GAMES", false),
+                ("ACH_TRAVEL_FAR_ACCUM", true),
+                ("ACH_SERVER_ONLY", false),
+            ]
+        );
+        let first = &data.achievements[0];
+        assert_eq!(first.name, "Gewinner");
+        assert_eq!(first.description, "Win one game.");
+        assert_eq!(first.icon_normal, "win.jpg");
+        assert_eq!(first.flags, "None");
+        assert!(data.achievements[2].hidden);
+        assert!(data.achievements[3].protected_achievement);
+        assert_eq!(data.achievements[3].flags, "Protected");
+
+        let stats: Vec<(&str, &str, serde_json::Value)> = data
+            .stats
+            .iter()
+            .map(|s| (s.id.as_str(), s.stat_type.as_str(), s.value.clone()))
+            .collect();
+        assert_eq!(
+            stats,
+            vec![
+                ("NumGames", "integer", serde_json::json!(12)),
+                ("FeetTraveled", "float", serde_json::json!(5280.5f32)),
+                ("AverageSpeed", "avgrate", serde_json::json!(0.0f32)),
+            ]
+        );
+        assert_eq!(data.stats[0].name, "Games played");
+        assert_eq!(data.stats[1].flags, "IncrementOnly, Protected");
+        assert!(data.stats[1].protected_stat);
+    }
+
+    #[test]
+    fn flags_render_like_the_csharp_enum() {
+        assert_eq!(stat_flags(0, true, true), (false, "None".to_string()));
+        assert_eq!(
+            stat_flags(6, true, false),
+            (
+                true,
+                "IncrementOnly, Protected, UnknownPermission".to_string()
+            )
+        );
+    }
+}
@@ -1,14 +1,17 @@
 //! Parsing/rewriting `<steam>/config/loginusers.vdf` - the local Steam client's own record of
 //! every account that has ever signed into it on this machine. This is CLI mode's only source of
 //! "who can sign in" - there is no network call involved, unlike agent mode's SteamKit2 login.
+//! Both directions go through `text_kv`, so nested blocks, escaped quotes and persona names with
+//! braces in them read and rewrite like any other value.
 
 use std::path::Path;
 
-use regex::Regex;
 use serde::Serialize;
 
 use crate::error::{AppError, AppResult};
 
+use super::text_kv::{self, KvValue};
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LocalSteamUser {
@@ -17,13 +20,27 @@ pub struct LocalSteamUser {
     pub most_recent: bool,
 }
 
-/// Parses every account block out of `loginusers.vdf`. Ported from `main`'s `parse_login_users` -
-/// the same single-pass regex walk over `"SteamID64" { ... "PersonaName" "..." ... }` blocks,
-/// rather than a full VDF (Valve Data Format) parser, since `main`'s approach is already proven
-/// against real-world `loginusers.vdf`
-/// quirks - notably the case-insensitive key matching for `PersonaName`, since Steam has shipped
-/// both cases in the wild. Returns accounts in the order they appear in the file (deterministic),
-/// unlike `main`'s `HashMap`-backed version.
+fn parse_document(content: &str) -> AppResult<KvValue> {
+    text_kv::parse(content).map_err(|e| AppError::LoginVdfParse(e.to_string()))
+}
+
+fn is_steam_id64(key: &str) -> bool {
+    key.len() == 17 && key.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn flag_set(account: &KvValue, key: &str) -> bool {
+    account
+        .get(key)
+        .and_then(KvValue::as_str)
+        .and_then(|v| v.trim().parse::<u32>().ok())
+        .is_some_and(|v| v != 0)
+}
+
+/// Parses every account block out of `loginusers.vdf`'s `"users"` object - only blocks keyed by a
+/// SteamID64 count, matching what `main`'s `parse_login_users` accepted. Keys match
+/// case-insensitively (`PersonaName` vs `personaname`), since Steam has shipped both cases in the
+/// wild. Returns accounts in the order they appear in the file (deterministic), unlike `main`'s
+/// `HashMap`-backed version.
 ///
 /// `most_recent` is true if *either* a legacy `MostRecent` key or a newer `AutoLogin` key is `1` -
 /// a real Steam client update was found (live-tested by the user against their actual installed
@@ -33,149 +50,65 @@ pub struct LocalSteamUser {
 /// guarantee every install has migrated to the newer one.
 pub fn parse_login_users(path: &Path) -> AppResult<Vec<LocalSteamUser>> {
     let content = std::fs::read_to_string(path).map_err(|e| AppError::LoginVdfIo(e.to_string()))?;
-
-    let user_regex = Regex::new(
-        r#""(\d{17})"\s*\{[^}]*"(?i)PersonaName"\s*"([^"]*)"|"(?i)MostRecent"\s*"(\d+)"|"(?i)AutoLogin"\s*"(\d+)""#,
-    )
-    .map_err(|e| AppError::LoginVdfParse(e.to_string()))?;
-
-    let mut users = Vec::new();
-    let mut current: Option<(String, String)> = None;
-    let mut most_recent_flag = 0u32;
-    let mut auto_login_flag = 0u32;
-
-    for cap in user_regex.captures_iter(&content) {
-        if let Some(steam_id) = cap.get(1) {
-            if let Some((steam_id, persona_name)) = current.take() {
-                users.push(LocalSteamUser {
-                    steam_id,
-                    persona_name,
-                    most_recent: most_recent_flag != 0 || auto_login_flag != 0,
-                });
-            }
-            current = Some((
-                steam_id.as_str().to_string(),
-                cap.get(2)
-                    .map(|m| m.as_str().to_string())
-                    .unwrap_or_default(),
-            ));
-            most_recent_flag = 0;
-            auto_login_flag = 0;
-        } else if let Some(value) = cap.get(3) {
-            most_recent_flag = value.as_str().parse().unwrap_or(0);
-        } else if let Some(value) = cap.get(4) {
-            auto_login_flag = value.as_str().parse().unwrap_or(0);
-        }
-    }
-
-    if let Some((steam_id, persona_name)) = current.take() {
-        users.push(LocalSteamUser {
-            steam_id,
-            persona_name,
-            most_recent: most_recent_flag != 0 || auto_login_flag != 0,
-        });
-    }
-
-    Ok(users)
+    let document = parse_document(&content)?;
+    let Some(users) = document.get("users") else {
+        return Ok(Vec::new());
+    };
+
+    Ok(users
+        .entries()
+        .iter()
+        .filter(|entry| is_steam_id64(&entry.key) && matches!(entry.value, KvValue::Object(_)))
+        .map(|entry| LocalSteamUser {
+            steam_id: entry.key.clone(),
+            persona_name: entry
+                .value
+                .get("PersonaName")
+                .and_then(KvValue::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            most_recent: flag_set(&entry.value, "MostRecent")
+                || flag_set(&entry.value, "AutoLogin"),
+        })
+        .collect())
 }
 
 /// Rewrites `AllowAutoLogin`/`MostRecent`/`AutoLogin`/`Timestamp` for `target_steam_id`'s block
 /// (set) and every other account's block (cleared), so the local Steam client auto-logs into that
-/// account on next launch. A line-by-line rewriter that tracks brace depth manually and only
-/// rewrites known keys at depth 2 (the per-account block level), leaving every other line
-/// byte-for-byte unchanged. A full VDF parser would handle nested braces more generally, but this
-/// only ever needs to touch one well-known block shape. Only rewrites whichever
-/// of these keys are actually present in a given block (some real-world files only have `AutoLogin`
-/// - see `parse_login_users`'s doc comment for why both schemas are handled), so this never inserts
-/// a key that wasn't already there. Returns the target account's `AccountName` (not persona name) -
-/// the caller needs it for the `HKCU\Software\Valve\Steam\AutoLoginUser` registry value.
+/// account on next launch. Parses the whole file, edits the per-account entries in place and
+/// writes it back in Steam's own layout - every other entry, including ones this never reads,
+/// survives untouched. Only rewrites whichever of these keys are actually present in a given block
+/// (some real-world files only have `AutoLogin` - see `parse_login_users`'s doc comment for why
+/// both schemas are handled), so this never inserts a key that wasn't already there. Returns the
+/// target account's `AccountName` (not persona name) - the caller needs it for the
+/// `HKCU\Software\Valve\Steam\AutoLoginUser` registry value.
 pub fn update_login_users_vdf(
     content: &str,
     target_steam_id: &str,
     timestamp: u64,
 ) -> AppResult<(String, String)> {
-    let mut output = String::new();
-    let mut depth: i32 = 0;
-    let mut current_user_id = String::new();
-    let mut pending_block_key = String::new();
+    let mut document = parse_document(content)?;
     let mut target_account_name = String::new();
 
-    let block_key_re =
-        Regex::new(r#"^\s*"([^"]*)"\s*$"#).map_err(|e| AppError::LoginVdfParse(e.to_string()))?;
-    let kv_re = Regex::new(r#"^\s*"([^"]*)"\s+"([^"]*)"\s*$"#)
-        .map_err(|e| AppError::LoginVdfParse(e.to_string()))?;
-
-    for line in content.lines() {
-        let trimmed = line.trim();
-
-        if trimmed == "{" {
-            depth += 1;
-            if depth == 2 && !pending_block_key.is_empty() {
-                current_user_id = pending_block_key.clone();
-            }
-            pending_block_key.clear();
-            output.push_str(line);
-            output.push('\n');
-        } else if trimmed == "}" {
-            if depth == 2 {
-                current_user_id.clear();
-            }
-            depth -= 1;
-            output.push_str(line);
-            output.push('\n');
-        } else if let Some(kv_cap) = kv_re.captures(line) {
-            let key = &kv_cap[1];
-            let value = &kv_cap[2];
-            let is_target = current_user_id == target_steam_id;
-            let leading = &line[..line.len() - line.trim_start().len()];
-
-            if depth == 2 {
-                match key {
-                    "AccountName" => {
-                        if is_target {
-                            target_account_name = value.to_string();
-                        }
-                        output.push_str(line);
-                        output.push('\n');
-                    }
-                    "AllowAutoLogin" => {
-                        let v = if is_target { "1" } else { "0" };
-                        output.push_str(&format!("{leading}\"AllowAutoLogin\"\t\t\"{v}\"\n"));
-                    }
-                    "MostRecent" => {
-                        let v = if is_target { "1" } else { "0" };
-                        output.push_str(&format!("{leading}\"MostRecent\"\t\t\"{v}\"\n"));
-                    }
-                    "AutoLogin" => {
-                        let v = if is_target { "1" } else { "0" };
-                        output.push_str(&format!("{leading}\"AutoLogin\"\t\t\"{v}\"\n"));
+    if let Some(users) = document.get_mut("users") {
+        for account in users.entries_mut() {
+            let is_target = account.key == target_steam_id;
+            for entry in account.value.entries_mut() {
+                let flag = if is_target { "1" } else { "0" };
+                let key = entry.key.to_ascii_lowercase();
+                match key.as_str() {
+                    "accountname" if is_target => {
+                        target_account_name = entry.value.as_str().unwrap_or_default().to_string();
                     }
-                    "Timestamp" => {
-                        let v = if is_target {
-                            timestamp.to_string()
-                        } else {
-                            value.to_string()
-                        };
-                        output.push_str(&format!("{leading}\"Timestamp\"\t\t\"{v}\"\n"));
+                    "allowautologin" | "mostrecent" | "autologin" => {
+                        entry.value = KvValue::String(flag.to_string());
                     }
-                    _ => {
-                        output.push_str(line);
-                        output.push('\n');
+                    "timestamp" if is_target => {
+                        entry.value = KvValue::String(timestamp.to_string());
                     }
+                    _ => {}
                 }
-            } else {
-                output.push_str(line);
-                output.push('\n');
             }
-        } else if let Some(bk_cap) = block_key_re.captures(line) {
-            if depth == 1 {
-                pending_block_key = bk_cap[1].to_string();
-            }
-            output.push_str(line);
-            output.push('\n');
-        } else {
-            output.push_str(line);
-            output.push('\n');
         }
     }
 
@@ -183,7 +116,7 @@ pub fn update_login_users_vdf(
         return Err(AppError::SteamIdNotFound(target_steam_id.to_string()));
     }
 
-    Ok((output, target_account_name))
+    Ok((text_kv::write(&document), target_account_name))
 }
 
 #[cfg(test)]
@@ -213,14 +146,8 @@ mod tests {
 }
 "#;
 
-    /// Separate from `SAMPLE_VDF`: `update_login_users_vdf` matches `AccountName`/
-    /// `AllowAutoLogin`/`MostRecent`/`Timestamp` by exact key case (ported as-is from `main`'s
-    /// `update_login_users_vdf`), unlike `parse_login_users`'s deliberately case-insensitive regex.
-    /// That's fine in practice
-    /// since Steam itself always writes these specific keys in canonical PascalCase - only
-    /// `SAMPLE_VDF`'s mixed-case second account (used to prove the parser tolerates it) would be
-    /// an unrealistic input for the *writer* path, so the update tests use this canonical fixture
-    /// instead.
+    /// Separate from `SAMPLE_VDF`: the canonical PascalCase keys Steam itself always writes, so the
+    /// update tests' substring checks on the rewritten output match what a real file looks like.
     const UPDATE_SAMPLE_VDF: &str = r#""users"
 {
 	"76561197960287930"
@@ -385,6 +312,66 @@ mod tests {
         assert!(updated.contains("\"Timestamp\"\t\t\"1234567890\""));
     }
 
+    /// The shapes the old single-regex walk got wrong: a persona name containing `}` and an
+    /// escaped quote (which ended its `[^}]*` block match early), and a nested block inside an
+    /// account (which threw off its line-based brace counting).
+    const TRICKY_VDF: &str = r#""users"
+{
+	"76561197960287930"
+	{
+		"AccountName"		"firstuser"
+		"PersonaName"		"{sc} \"quoted\" }"
+		"Extra"
+		{
+			"MostRecent"		"1"
+		}
+		"AutoLogin"		"0"
+		"Timestamp"		"1000000000"
+	}
+	"76561197960287931"
+	{
+		"AccountName"		"seconduser"
+		"PersonaName"		"Second"
+		"AutoLogin"		"1"
+		"Timestamp"		"1000000100"
+	}
+}
+"#;
+
+    #[test]
+    fn nested_blocks_escapes_and_braces_in_names_survive_parse_and_update() {
+        let dir = std::env::temp_dir().join(format!("sgi-vdf-tricky-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("loginusers.vdf");
+        std::fs::write(&path, TRICKY_VDF).unwrap();
+        let users = parse_login_users(&path).unwrap();
+
+        assert_eq!(users.len(), 2);
+        assert_eq!(users[0].persona_name, "{sc} \"quoted\" }");
+        assert!(!users[0].most_recent);
+        assert!(users[1].most_recent);
+
+        let (updated, account_name) =
+            update_login_users_vdf(TRICKY_VDF, "76561197960287930", 42).unwrap();
+        assert_eq!(account_name, "firstuser");
+        std::fs::write(&path, &updated).unwrap();
+        let users = parse_login_users(&path).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(users[0].persona_name, "{sc} \"quoted\" }");
+        assert!(users[0].most_recent);
+        assert!(!users[1].most_recent);
+        // Nested blocks aren't account-level keys - left as they were.
+        let document = text_kv::parse(&updated).unwrap();
+        let extra = document
+            .get("users")
+            .and_then(|u| u.get("76561197960287930"))
+            .and_then(|a| a.get("Extra"))
+            .and_then(|e| e.get("MostRecent"))
+            .and_then(KvValue::as_str);
+        assert_eq!(extra, Some("1"));
+    }
+
     #[test]
     fn update_errors_when_steam_id_is_unknown() {
         let err = update_login_users_vdf(SAMPLE_VDF, "00000000000000000", 1).unwrap_err();
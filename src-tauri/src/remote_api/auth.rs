@@ -0,0 +1,181 @@
+//! The remote API's single bearer token - generated on first start, stored as plain text in
+//! `remote_api_token` right next to `settings.json` (`platform::cache_dir`) so a headless box can
+//! be provisioned by just reading or replacing that one file, and never in `settings.json` itself:
+//! like the refresh tokens `settings`'s doc comment already keeps out of that file, it's a bearer
+//! credential, not a preference. Not in the OS credential store either, unlike those - the whole
+//! point is that another machine's tooling can be handed it, and a LAN client presenting it gets
+//! exactly the same control the local UI has, nothing more. Written owner-only (`0600` on Unix,
+//! see `fs_utils::atomic_write_private`), since any local user who can read it controls the app.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use rand::RngCore;
+use tauri::AppHandle;
+
+use crate::error::{AppError, AppResult};
+use crate::fs_utils::atomic_write_private;
+use crate::platform;
+
+const TOKEN_FILE_NAME: &str = "remote_api_token";
+
+fn token_path(app_handle: &AppHandle) -> AppResult<PathBuf> {
+    Ok(platform::cache_dir(app_handle)?.join(TOKEN_FILE_NAME))
+}
+
+/// 32 random bytes, lowercase hex - same shape `steam_community::session::generate_session_id`
+/// uses for its own opaque tokens, just longer since this one guards the whole app.
+fn generate() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn write(app_handle: &AppHandle, token: &str) -> AppResult<()> {
+    write_to(&token_path(app_handle)?, token)
+}
+
+fn write_to(path: &Path, token: &str) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::RemoteApiIo(e.to_string()))?;
+    }
+    atomic_write_private(path, token.as_bytes()).map_err(|e| AppError::RemoteApiIo(e.to_string()))
+}
+
+/// The token saved at `path`, if there is a non-blank one. A file other local users can read -
+/// one saved by a build that wrote it with default permissions - is rewritten owner-only first.
+fn read_from(path: &Path) -> AppResult<Option<String>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(path).map_err(|e| AppError::RemoteApiIo(e.to_string()))?;
+    let token = contents.trim();
+    if token.is_empty() {
+        return Ok(None);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(path)
+            .map_err(|e| AppError::RemoteApiIo(e.to_string()))?
+            .permissions()
+            .mode();
+        if mode & 0o077 != 0 {
+            write_to(path, token)?;
+        }
+    }
+    Ok(Some(token.to_string()))
+}
+
+/// The saved token, generating and saving one first if the file is missing or blank. Surrounding
+/// whitespace is ignored, so a hand-edited file with a trailing newline still works.
+pub fn load_or_create(app_handle: &AppHandle) -> AppResult<String> {
+    let path = token_path(app_handle)?;
+    if let Some(token) = read_from(&path)? {
+        return Ok(token);
+    }
+    let token = generate();
+    write_to(&path, &token)?;
+    Ok(token)
+}
+
+/// Replaces the saved token with a fresh one - every client holding the old one is locked out
+/// from its next request on (see `RemoteApiServer::set_token`).
+pub fn regenerate(app_handle: &AppHandle) -> AppResult<String> {
+    let token = generate();
+    write(app_handle, &token)?;
+    Ok(token)
+}
+
+/// The in-memory copy of the token every request is checked against - shared between
+/// `RemoteApiServer` (which replaces it on regeneration) and the router's auth middleware, so a
+/// regenerated token takes effect from the very next request without restarting the server.
+#[derive(Debug, Clone, Default)]
+pub struct SharedToken(Arc<Mutex<Option<String>>>);
+
+impl SharedToken {
+    pub fn get(&self) -> Option<String> {
+        self.0.lock().unwrap().clone()
+    }
+
+    pub fn set(&self, token: String) {
+        *self.0.lock().unwrap() = Some(token);
+    }
+}
+
+/// The token from an `Authorization: Bearer <token>` header value - scheme matched
+/// case-insensitively, per RFC 7235.
+pub fn bearer_token(header_value: &str) -> Option<&str> {
+    let (scheme, token) = header_value.trim().split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case("bearer") {
+        return None;
+    }
+    let token = token.trim();
+    (!token.is_empty()).then_some(token)
+}
+
+/// Compares every byte regardless of where the first mismatch is, so response timing doesn't leak
+/// how much of a guessed token was right.
+pub fn tokens_match(presented: &str, expected: &str) -> bool {
+    let (a, b) = (presented.as_bytes(), expected.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bearer_header() {
+        assert_eq!(bearer_token("Bearer abc123"), Some("abc123"));
+        assert_eq!(bearer_token("bearer  abc123 "), Some("abc123"));
+        assert_eq!(bearer_token("Basic abc123"), None);
+        assert_eq!(bearer_token("Bearer "), None);
+        assert_eq!(bearer_token("abc123"), None);
+    }
+
+    #[test]
+    fn matches_only_identical_tokens() {
+        assert!(tokens_match("deadbeef", "deadbeef"));
+        assert!(!tokens_match("deadbeee", "deadbeef"));
+        assert!(!tokens_match("deadbee", "deadbeef"));
+        assert!(!tokens_match("", "deadbeef"));
+    }
+
+    #[test]
+    fn generates_hex_tokens() {
+        let token = generate();
+        assert_eq!(token.len(), 64);
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(token, generate());
+    }
+
+    #[test]
+    fn saves_the_token_owner_only() {
+        let dir = std::env::temp_dir().join(format!("sgi-remote-api-token-{}", std::process::id()));
+        let path = dir.join(TOKEN_FILE_NAME);
+
+        write_to(&path, "deadbeef").unwrap();
+        assert_eq!(read_from(&path).unwrap().as_deref(), Some("deadbeef"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode(&path), 0o600);
+
+            // A token an older build left world-readable is tightened on its next read.
+            fs::write(&path, "cafebabe\n").unwrap();
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+            assert_eq!(read_from(&path).unwrap().as_deref(), Some("cafebabe"));
+            assert_eq!(mode(&path), 0o600);
+        }
+
+        fs::write(&path, "  \n").unwrap();
+        assert_eq!(read_from(&path).unwrap(), None);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
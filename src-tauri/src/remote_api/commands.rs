@@ -0,0 +1,41 @@
+use tauri::{AppHandle, State};
+
+use crate::error::{AppError, AppResult};
+use crate::settings::commands::{build_response, SettingsResponse};
+
+use super::{auth, RemoteApiServer, RemoteApiStatus};
+
+/// Whether the remote API is currently listening, on which port, and the token a client needs -
+/// shown in Settings so it can be copied to another machine.
+#[tauri::command]
+pub async fn get_remote_api_status(
+    app_handle: AppHandle,
+    server: State<'_, RemoteApiServer>,
+) -> AppResult<RemoteApiStatus> {
+    server.status(&app_handle).await
+}
+
+/// Saves the remote API toggle/port, then starts, restarts or stops the server to match. The
+/// settings are saved even if the port then fails to bind - the error tells the user to pick a
+/// different one, and the next launch retries whatever was saved.
+#[tauri::command]
+pub async fn set_remote_api(
+    app_handle: AppHandle,
+    enabled: bool,
+    port: u16,
+) -> AppResult<SettingsResponse> {
+    let settings = crate::settings::set_remote_api(&app_handle, enabled, port)
+        .map_err(AppError::SettingsIo)?;
+    super::sync_with_settings(&app_handle).await?;
+    build_response(settings)
+}
+
+/// Replaces the token - every remote client has to be given the new one.
+#[tauri::command]
+pub async fn regenerate_remote_api_token(
+    app_handle: AppHandle,
+    server: State<'_, RemoteApiServer>,
+) -> AppResult<RemoteApiStatus> {
+    server.set_token(auth::regenerate(&app_handle)?);
+    server.status(&app_handle).await
+}
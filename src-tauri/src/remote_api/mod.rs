@@ -0,0 +1,187 @@
+//! Opt-in LAN control API ([`crate::settings::Settings::remote_api_enabled`]) for managing several
+//! idling machines from one place: an embedded HTTP server exposing the idle/farming/unlocker
+//! commands (`routes::dispatch`) plus a WebSocket stream forwarding [`FORWARDED_EVENTS`] exactly
+//! as the frontend receives them, guarded by one bearer token (`auth`).
+//!
+//! **Same commands, not a second implementation** - every route calls the `#[tauri::command]`
+//! function of the same name with state looked up from the `AppHandle`, so a remote
+//! `toggle_manual_idle` goes through the same claims registry, max-playtime check and auto-stop
+//! bookkeeping as a click in the UI, and its effects show up in the UI live (and vice versa -
+//! remote clients see UI-driven changes on the event stream).
+//!
+//! Binds every interface (`0.0.0.0`) on [`crate::settings::Settings::remote_api_port`] - the point
+//! is reaching it from other machines - and speaks plain HTTP: the token keeps other LAN clients
+//! out, but anything on the path can read it, so this is for a trusted network (or behind a TLS
+//! reverse proxy), not the open internet.
+
+pub mod auth;
+pub mod commands;
+mod routes;
+
+use std::net::{Ipv4Addr, SocketAddr};
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, EventId, Listener, Manager};
+use tokio::sync::{broadcast, oneshot};
+
+use crate::achievement_unlocker::ACHIEVEMENT_UNLOCKER_STATE_EVENT;
+use crate::card_farming::FARMING_STATE_EVENT;
+use crate::error::{AppError, AppResult};
+use crate::idling::IDLE_STATE_EVENT;
+
+/// The state events the WebSocket stream forwards, each as `{"event": <name>, "payload": <the
+/// same payload the frontend's listen() gets>}`.
+pub const FORWARDED_EVENTS: [&str; 3] = [
+    IDLE_STATE_EVENT,
+    FARMING_STATE_EVENT,
+    ACHIEVEMENT_UNLOCKER_STATE_EVENT,
+];
+/// Per-client backlog before a slow WebSocket client starts skipping events - state events are
+/// full snapshots, so a skipped one is superseded by the next anyway.
+const EVENT_BUFFER: usize = 64;
+
+struct Running {
+    port: u16,
+    shutdown: oneshot::Sender<()>,
+    listeners: Vec<EventId>,
+}
+
+/// Managed state tracking the one running server, if any.
+#[derive(Default)]
+pub struct RemoteApiServer {
+    running: tokio::sync::Mutex<Option<Running>>,
+    /// In memory so every request doesn't re-read the token file - kept in sync by
+    /// [`Self::set_token`] when it's regenerated.
+    token: auth::SharedToken,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteApiStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    pub token: String,
+}
+
+impl RemoteApiServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn token(&self) -> Option<String> {
+        self.token.get()
+    }
+
+    pub(crate) fn set_token(&self, token: String) {
+        self.token.set(token);
+    }
+
+    fn shared_token(&self) -> auth::SharedToken {
+        self.token.clone()
+    }
+
+    pub async fn port(&self) -> Option<u16> {
+        self.running.lock().await.as_ref().map(|r| r.port)
+    }
+
+    /// Starts listening on `port`, first stopping a server already running on a different one -
+    /// idempotent if it's already running on `port`. Fails (leaving nothing running) if the port
+    /// can't be bound.
+    pub async fn start(&self, app_handle: &AppHandle, port: u16) -> AppResult<()> {
+        let mut running = self.running.lock().await;
+        if running.as_ref().is_some_and(|r| r.port == port) {
+            return Ok(());
+        }
+        if let Some(previous) = running.take() {
+            stop_running(app_handle, previous);
+        }
+
+        self.set_token(auth::load_or_create(app_handle)?);
+        let addr = SocketAddr::from((Ipv4Addr::UNSPECIFIED, port));
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .map_err(|e| AppError::RemoteApiBind(format!("{addr}: {e}")))?;
+
+        let (events, _) = broadcast::channel(EVENT_BUFFER);
+        let listeners = FORWARDED_EVENTS
+            .iter()
+            .map(|&event| {
+                let events = events.clone();
+                app_handle.listen_any(event, move |e| {
+                    let payload = serde_json::from_str::<Value>(e.payload()).unwrap_or(Value::Null);
+                    let envelope = serde_json::json!({ "event": event, "payload": payload });
+                    // No receivers (no client connected) is the normal case, not an error.
+                    let _ = events.send(envelope.to_string());
+                })
+            })
+            .collect();
+
+        let router = routes::router(routes::ApiContext {
+            app_handle: app_handle.clone(),
+            events,
+        });
+        let (shutdown, shutdown_rx) = oneshot::channel();
+        tauri::async_runtime::spawn(async move {
+            let served = axum::serve(listener, router)
+                .with_graceful_shutdown(async {
+                    let _ = shutdown_rx.await;
+                })
+                .await;
+            if let Err(err) = served {
+                tracing::warn!(%err, "remote api: server stopped with an error");
+            }
+        });
+
+        tracing::info!(port, "remote api: listening");
+        *running = Some(Running {
+            port,
+            shutdown,
+            listeners,
+        });
+        Ok(())
+    }
+
+    /// Stops the server if one is running - idempotent. Open WebSocket connections are closed
+    /// along with it.
+    pub async fn stop(&self, app_handle: &AppHandle) {
+        if let Some(running) = self.running.lock().await.take() {
+            stop_running(app_handle, running);
+            tracing::info!("remote api: stopped");
+        }
+    }
+
+    pub async fn status(&self, app_handle: &AppHandle) -> AppResult<RemoteApiStatus> {
+        let port = self.port().await;
+        let token = match self.token() {
+            Some(token) => token,
+            None => auth::load_or_create(app_handle)?,
+        };
+        Ok(RemoteApiStatus {
+            running: port.is_some(),
+            port,
+            token,
+        })
+    }
+}
+
+fn stop_running(app_handle: &AppHandle, running: Running) {
+    for id in running.listeners {
+        app_handle.unlisten(id);
+    }
+    let _ = running.shutdown.send(());
+}
+
+/// Starts (or stops) the server to match the saved settings - called once from `lib.rs`'s `setup`
+/// and again by `commands::set_remote_api` after every change. A bind failure at startup is only
+/// logged: the rest of the app shouldn't fail to launch over an occupied port.
+pub async fn sync_with_settings(app_handle: &AppHandle) -> AppResult<()> {
+    let settings = crate::settings::load(app_handle).map_err(AppError::SettingsIo)?;
+    let server = app_handle.state::<RemoteApiServer>();
+    if settings.remote_api_enabled {
+        server.start(app_handle, settings.remote_api_port).await
+    } else {
+        server.stop(app_handle).await;
+        Ok(())
+    }
+}
@@ -0,0 +1,445 @@
+//! The remote API's HTTP surface: `POST /api/{command}` with the same camelCase JSON arguments the
+//! frontend passes to `invoke(command, args)`, and `GET /api/events` for the state-event stream.
+//! Every request needs the bearer token in an `Authorization` header - only the WebSocket route
+//! also accepts it as `?token=`, since browsers can't set headers on a WebSocket handshake. The
+//! REST routes refuse a query-string token outright rather than ignoring it: a URL ends up in
+//! proxy and access logs, so a client sending one should find out on its first request.
+
+use axum::body::Bytes;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast;
+
+use crate::achievement_unlocker;
+use crate::card_farming::{self, SteamCookies};
+use crate::error::{AppError, AppResult};
+use crate::games::commands::GamesAccount;
+use crate::idling;
+
+use super::auth::{self, SharedToken};
+use super::RemoteApiServer;
+
+const EVENTS_PATH: &str = "/api/events";
+const COMMAND_PATH: &str = "/api/{command}";
+
+#[derive(Clone)]
+pub(super) struct ApiContext {
+    pub app_handle: AppHandle,
+    pub events: broadcast::Sender<String>,
+}
+
+pub(super) fn router(context: ApiContext) -> Router {
+    let token = context.app_handle.state::<RemoteApiServer>().shared_token();
+    guarded(
+        Router::new()
+            .route(EVENTS_PATH, get(events))
+            .with_state(context.clone()),
+        Router::new()
+            .route(COMMAND_PATH, post(invoke))
+            .with_state(context),
+        token,
+    )
+}
+
+/// Puts the event-stream routes and the REST routes behind their respective token checks - split
+/// out of [`router`] so the auth rules can be tested against stand-in handlers, without an
+/// `AppHandle`.
+fn guarded(events: Router, commands: Router, token: SharedToken) -> Router {
+    events
+        .route_layer(middleware::from_fn_with_state(
+            token.clone(),
+            require_token_or_query,
+        ))
+        .merge(commands.route_layer(middleware::from_fn_with_state(token, require_token)))
+}
+
+#[derive(Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+fn header_token(request: &Request) -> Option<String> {
+    request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(auth::bearer_token)
+        .map(str::to_string)
+}
+
+async fn require_token(
+    State(token): State<SharedToken>,
+    Query(query): Query<TokenQuery>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if query.token.is_some() {
+        return error_response(
+            StatusCode::UNAUTHORIZED,
+            "remote_api_query_token_rejected",
+            Some("send the token in an Authorization: Bearer header".to_string()),
+        );
+    }
+    let presented = header_token(&request);
+    authorize(&token, presented, request, next).await
+}
+
+async fn require_token_or_query(
+    State(token): State<SharedToken>,
+    Query(query): Query<TokenQuery>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let presented = header_token(&request).or(query.token);
+    authorize(&token, presented, request, next).await
+}
+
+async fn authorize(
+    token: &SharedToken,
+    presented: Option<String>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match (presented, token.get()) {
+        (Some(presented), Some(expected)) if auth::tokens_match(&presented, &expected) => {
+            next.run(request).await
+        }
+        _ => error_response(StatusCode::UNAUTHORIZED, "remote_api_unauthorized", None),
+    }
+}
+
+fn error_response(status: StatusCode, code: &str, message: Option<String>) -> Response {
+    (status, Json(json!({ "code": code, "message": message }))).into_response()
+}
+
+/// Why a `POST /api/{command}` didn't produce a result - kept apart from `AppError` because two of
+/// these are about the HTTP request itself, not anything a command did.
+enum InvokeError {
+    UnknownCommand,
+    InvalidArgs(String),
+    App(AppError),
+}
+
+async fn invoke(
+    State(context): State<ApiContext>,
+    Path(command): Path<String>,
+    body: Bytes,
+) -> Response {
+    let args = if body.is_empty() {
+        Ok(json!({}))
+    } else {
+        serde_json::from_slice::<Value>(&body).map_err(|e| InvokeError::InvalidArgs(e.to_string()))
+    };
+    let result = match args {
+        Ok(args) => dispatch(&context.app_handle, &command, args).await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(value) => Json(value).into_response(),
+        Err(InvokeError::UnknownCommand) => error_response(
+            StatusCode::NOT_FOUND,
+            "remote_api_unknown_command",
+            Some(command),
+        ),
+        Err(InvokeError::InvalidArgs(message)) => error_response(
+            StatusCode::BAD_REQUEST,
+            "remote_api_invalid_args",
+            Some(message),
+        ),
+        Err(InvokeError::App(err)) => error_response(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            &err.code(),
+            Some(err.to_string()),
+        ),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountArgs {
+    account: GamesAccount,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ToggleIdleArgs {
+    account: GamesAccount,
+    app_id: u32,
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StartFarmingArgs {
+    account: GamesAccount,
+    manual_cookies: Option<SteamCookies>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StartUnlockerArgs {
+    account: GamesAccount,
+    max_concurrent_games: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SteamIdArgs {
+    steam_id: String,
+}
+
+fn parse<T: DeserializeOwned>(args: Value) -> Result<T, InvokeError> {
+    serde_json::from_value(args).map_err(|e| InvokeError::InvalidArgs(e.to_string()))
+}
+
+fn respond<T: Serialize>(result: AppResult<T>) -> Result<Value, InvokeError> {
+    let value = result.map_err(InvokeError::App)?;
+    serde_json::to_value(value).map_err(|e| InvokeError::App(AppError::RemoteApiIo(e.to_string())))
+}
+
+/// Calls the Tauri command of the same name directly, with its managed state looked up from the
+/// `AppHandle` - no parallel implementation that could drift from what the UI's buttons do.
+/// Deliberately an explicit allowlist of the idle/farming/unlocker controls rather than every
+/// registered command: nothing here can sign an account in or out, touch saved credentials, or
+/// install an update.
+async fn dispatch(app: &AppHandle, command: &str, args: Value) -> Result<Value, InvokeError> {
+    match command {
+        "get_idle_state" => {
+            let a: AccountArgs = parse(args)?;
+            respond(idling::commands::get_idle_state(app.state(), app.state(), a.account).await)
+        }
+        "get_idle_claims" => {
+            let a: AccountArgs = parse(args)?;
+            respond(idling::commands::get_idle_claims(app.state(), app.state(), a.account).await)
+        }
+        "toggle_manual_idle" => {
+            let a: ToggleIdleArgs = parse(args)?;
+            respond(
+                idling::commands::toggle_manual_idle(
+                    app.clone(),
+                    app.state(),
+                    app.state(),
+                    app.state(),
+                    app.state(),
+                    app.state(),
+                    app.state(),
+                    a.account,
+                    a.app_id,
+                    a.name,
+                )
+                .await,
+            )
+        }
+        "stop_all_idling" => {
+            let a: AccountArgs = parse(args)?;
+            respond(
+                idling::commands::stop_all_idling(
+                    app.clone(),
+                    app.state(),
+                    app.state(),
+                    app.state(),
+                    app.state(),
+                    app.state(),
+                    a.account,
+                )
+                .await,
+            )
+        }
+        "start_farming" => {
+            let a: StartFarmingArgs = parse(args)?;
+            respond(
+                card_farming::commands::start_farming(
+                    app.clone(),
+                    app.state(),
+                    app.state(),
+                    a.account,
+                    a.manual_cookies,
+                )
+                .await,
+            )
+        }
+        "stop_farming" => {
+            let a: AccountArgs = parse(args)?;
+            respond(card_farming::commands::stop_farming(app.state(), app.state(), a.account).await)
+        }
+        "get_farming_state" => {
+            let a: AccountArgs = parse(args)?;
+            respond(
+                card_farming::commands::get_farming_state(app.state(), app.state(), a.account)
+                    .await,
+            )
+        }
+        "start_achievement_unlocker" => {
+            let a: StartUnlockerArgs = parse(args)?;
+            respond(
+                achievement_unlocker::commands::start_achievement_unlocker(
+                    app.clone(),
+                    app.state(),
+                    app.state(),
+                    a.account,
+                    a.max_concurrent_games,
+                )
+                .await,
+            )
+        }
+        "stop_achievement_unlocker" => {
+            let a: AccountArgs = parse(args)?;
+            respond(
+                achievement_unlocker::commands::stop_achievement_unlocker(
+                    app.state(),
+                    app.state(),
+                    a.account,
+                )
+                .await,
+            )
+        }
+        "get_achievement_unlocker_state" => {
+            let a: AccountArgs = parse(args)?;
+            respond(
+                achievement_unlocker::commands::get_achievement_unlocker_state(
+                    app.state(),
+                    app.state(),
+                    a.account,
+                )
+                .await,
+            )
+        }
+        "get_owned_games_cache" => {
+            let a: SteamIdArgs = parse(args)?;
+            respond(crate::games::commands::get_owned_games_cache(
+                app.clone(),
+                a.steam_id,
+            ))
+        }
+        _ => Err(InvokeError::UnknownCommand),
+    }
+}
+
+async fn events(State(context): State<ApiContext>, upgrade: WebSocketUpgrade) -> Response {
+    let receiver = context.events.subscribe();
+    upgrade.on_upgrade(move |socket| forward_events(socket, receiver))
+}
+
+/// Pushes every forwarded event to one client until it disconnects. Incoming messages are read
+/// only to notice a close - the stream is one-way. A client too slow to keep up skips what it
+/// missed rather than stalling every other client (see `super::EVENT_BUFFER`).
+async fn forward_events(mut socket: WebSocket, mut receiver: broadcast::Receiver<String>) {
+    loop {
+        tokio::select! {
+            event = receiver.recv() => match event {
+                Ok(text) => {
+                    if socket.send(Message::Text(text.into())).await.is_err() {
+                        return;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "remote api: event stream client lagged");
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    const TOKEN: &str = "deadbeef";
+
+    /// [`guarded`] around stand-in handlers - the real ones need an `AppHandle`, and it's only the
+    /// auth rules under test here.
+    fn app(token: Option<&str>) -> Router {
+        let shared = SharedToken::default();
+        if let Some(token) = token {
+            shared.set(token.to_string());
+        }
+        guarded(
+            Router::new().route(EVENTS_PATH, get(|| async { "events" })),
+            Router::new().route(COMMAND_PATH, post(|| async { "ok" })),
+            shared,
+        )
+    }
+
+    async fn status(app: Router, method: &str, uri: &str, bearer: Option<&str>) -> StatusCode {
+        let mut request = axum::http::Request::builder().method(method).uri(uri);
+        if let Some(bearer) = bearer {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {bearer}"));
+        }
+        app.oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn rejects_a_missing_or_wrong_token() {
+        let cases = [
+            ("POST", "/api/get_idle_state", None),
+            ("POST", "/api/get_idle_state", Some("cafebabe")),
+            ("GET", "/api/events", None),
+            ("GET", "/api/events", Some("cafebabe")),
+            ("GET", "/api/events?token=cafebabe", None),
+        ];
+        for (method, uri, bearer) in cases {
+            assert_eq!(
+                status(app(Some(TOKEN)), method, uri, bearer).await,
+                StatusCode::UNAUTHORIZED,
+                "{method} {uri} {bearer:?}"
+            );
+        }
+        // No token loaded yet means nothing gets in, not everything.
+        assert_eq!(
+            status(app(None), "POST", "/api/get_idle_state", Some(TOKEN)).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn accepts_the_token() {
+        assert_eq!(
+            status(app(Some(TOKEN)), "POST", "/api/get_idle_state", Some(TOKEN)).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(app(Some(TOKEN)), "GET", "/api/events", Some(TOKEN)).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(app(Some(TOKEN)), "GET", "/api/events?token=deadbeef", None).await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn refuses_a_query_token_on_rest_routes() {
+        for bearer in [None, Some(TOKEN)] {
+            assert_eq!(
+                status(
+                    app(Some(TOKEN)),
+                    "POST",
+                    "/api/get_idle_state?token=deadbeef",
+                    bearer
+                )
+                .await,
+                StatusCode::UNAUTHORIZED
+            );
+        }
+    }
+}
@@ -104,6 +104,13 @@ pub fn set_close_to_tray(app_handle: AppHandle, enabled: bool) -> AppResult<Sett
         .and_then(build_response)
 }
 
+#[tauri::command]
+pub fn set_keep_agent_running(app_handle: AppHandle, enabled: bool) -> AppResult<SettingsResponse> {
+    super::set_keep_agent_running(&app_handle, enabled)
+        .map_err(AppError::SettingsIo)
+        .and_then(build_response)
+}
+
 #[tauri::command]
 pub fn set_auto_update_games_list(
     app_handle: AppHandle,
@@ -93,6 +93,21 @@ pub struct Settings {
     /// switching never needs a download. Defaults `"inter"`.
     #[serde(default = "default_font")]
     pub font: String,
+    /// Opt-in detached agent mode - whether agent-mode daemons are spawned listening on a named
+    /// pipe/Unix socket instead of over stdio, and left running (still idling, farming games still
+    /// announced) when the app exits rather than killed, to be reattached on next launch - see
+    /// `steam_agent::detached`. App-wide for the same reason as `close_to_tray`. Only takes effect
+    /// for daemons spawned after it changes. Defaults `false`.
+    #[serde(default)]
+    pub keep_agent_running: bool,
+    /// Opt-in LAN control API - whether `remote_api`'s embedded HTTP/WebSocket server runs. Its
+    /// bearer token deliberately lives in its own file next to this one, not here (see
+    /// `remote_api::auth`). Defaults `false`.
+    #[serde(default)]
+    pub remote_api_enabled: bool,
+    /// TCP port the remote API listens on, on every interface. Defaults `47600`.
+    #[serde(default = "default_remote_api_port")]
+    pub remote_api_port: u16,
 }
 
 fn default_close_to_tray() -> bool {
@@ -115,6 +130,10 @@ fn default_true() -> bool {
     true
 }
 
+fn default_remote_api_port() -> u16 {
+    47600
+}
+
 /// Not `#[derive(Default)]` - `close_to_tray` needs to default to `true` (see its own doc
 /// comment), which a derived `Default` can't express since it always uses `bool::default()`
 /// (`false`) per field regardless of this struct's own `#[serde(default = ...)]` attribute. Kept
@@ -135,6 +154,9 @@ impl Default for Settings {
             show_recommended_carousel: default_true(),
             show_recent_carousel: default_true(),
             font: default_font(),
+            keep_agent_running: false,
+            remote_api_enabled: false,
+            remote_api_port: default_remote_api_port(),
         }
     }
 }
@@ -238,6 +260,28 @@ pub fn set_close_to_tray(app_handle: &tauri::AppHandle, enabled: bool) -> Result
     mutate(app_handle, |settings| settings.close_to_tray = enabled)
 }
 
+/// Sets the detached-agent toggle. See [`Settings::keep_agent_running`] for why this only affects
+/// daemons spawned afterwards.
+pub fn set_keep_agent_running(
+    app_handle: &tauri::AppHandle,
+    enabled: bool,
+) -> Result<Settings, String> {
+    mutate(app_handle, |settings| settings.keep_agent_running = enabled)
+}
+
+/// Sets the remote API toggle and port together - the server is (re)started or stopped to match by
+/// `remote_api::commands::set_remote_api`, not here.
+pub fn set_remote_api(
+    app_handle: &tauri::AppHandle,
+    enabled: bool,
+    port: u16,
+) -> Result<Settings, String> {
+    mutate(app_handle, |settings| {
+        settings.remote_api_enabled = enabled;
+        settings.remote_api_port = port;
+    })
+}
+
 /// Sets the auto-update-games-list toggle. See [`Settings::auto_update_games_list`] - tier
 /// enforcement is the frontend's responsibility, not this command's.
 pub fn set_auto_update_games_list(
@@ -2,6 +2,7 @@ use tauri::{AppHandle, State};
 
 use crate::error::AppResult;
 
+use super::detached;
 use super::manager::{AgentManager, LoginOutcome, QrChallenge};
 use super::ownership_settings::{self, OwnershipSettings};
 use super::presence_settings::{self, PresenceSettings};
@@ -58,14 +59,22 @@ pub async fn agent_submit_guard_code(
 
 /// Resumes a session for `username` using its previously saved refresh token, skipping the
 /// interactive credential/guard-code flow. Returns whether the logon succeeded. Fails with
-/// `agent_no_saved_credentials` if no token was ever saved for this account.
+/// `agent_no_saved_credentials` if no token was ever saved for this account. In detached mode a
+/// successful resume may have been a reattach to a daemon left running by the previous app run -
+/// see `detached::restore_after_reattach` for restarting what was running alongside it.
 #[tauri::command]
 pub async fn agent_login_with_token(
     app_handle: AppHandle,
     manager: State<'_, AgentManager>,
     username: String,
 ) -> AppResult<bool> {
-    manager.login_with_token(&app_handle, username).await
+    let logged_on = manager
+        .login_with_token(&app_handle, username.clone())
+        .await?;
+    if logged_on {
+        detached::restore_after_reattach(&app_handle, &username).await;
+    }
+    Ok(logged_on)
 }
 
 /// Ends the live agent session for `username` and stops its `SteamUtility.exe` process. Does not
@@ -0,0 +1,567 @@
+//! Opt-in detached agent mode ([`crate::settings::Settings::keep_agent_running`]): instead of a
+//! stdio child that dies with this app, each agent-mode daemon is spawned as
+//! `SteamUtility agent --listen <endpoint>` - its own process group, no console, listening on a
+//! per-account named pipe (Windows) or Unix domain socket (everywhere else) - and simply
+//! disconnected from, not killed, on app exit. Its Steam session stays logged on and whatever it
+//! was last told to idle keeps idling; on the next launch `AgentManager::get_or_spawn` finds the
+//! endpoint still listening (and answering - see [`Endpoint`]), reattaches, and
+//! `login_with_token`'s `hello` handshake adopts the daemon's live session instead of logging in
+//! again.
+//!
+//! Idling survives on the daemon's side by itself, but card farming/achievement unlocker are loops
+//! running in *this* process, and the idle-claims registry that remembers which feature owns which
+//! game is in-memory only - so [`detach_on_exit`] writes a small per-account record of both to
+//! `platform::cache_dir/<steam_id>/agent_detached_session.json`, and [`restore_after_reattach`]
+//! consumes it to restart them and re-seed the registry.
+//!
+//! **Known gaps, deliberately unfixed for now**: a `refresh_token` rotation the daemon reports
+//! while nothing is attached is lost (the token saved on disk still works for a fresh login, just
+//! not the rotated one); `idling::auto_stop` timers aren't persisted, so an auto-stop scheduled
+//! before exit never fires; QR logins (`AgentManager::begin_qr_login`) always use a stdio daemon,
+//! which only becomes detached after the next launch's token login respawns it; and toggling the
+//! setting only changes how *later* daemons are spawned, never an already-running one.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader};
+use tokio::sync::Mutex;
+
+use crate::achievement_unlocker::AchievementUnlockerManager;
+use crate::card_farming::CardFarmingManager;
+use crate::error::{AppError, AppResult};
+use crate::fs_utils::atomic_write_json;
+use crate::games::commands::GamesAccount;
+use crate::idling::claims::{IdleClaimsRegistry, OWNER_AUTO_IDLE, OWNER_MANUAL};
+use crate::idling::{IdleTarget, IdlingManager};
+use crate::platform;
+use crate::steam_community::session;
+
+use super::ipc::{IpcLine, IpcMessage, IpcRequest};
+use super::process::IpcWriter;
+use super::AgentManager;
+
+const CACHE_FILE_NAME: &str = "agent_detached_session.json";
+/// Only the owners with no state of their own outside the claims registry - card farming and the
+/// achievement unlocker re-announce their own claims as soon as they're restarted.
+const RESTORED_OWNERS: [&str; 2] = [OWNER_MANUAL, OWNER_AUTO_IDLE];
+/// How long [`connect_with_retry`] waits for a freshly-spawned daemon to start listening - .NET
+/// startup plus SteamKit2 initialization, well under a second in practice.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const CONNECT_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+/// How long an endpoint that accepted a connection gets to answer [`handshake`] - a daemon already
+/// serving another host never reads from a second connection, so silence past this means busy.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(3);
+/// Request id for [`handshake`]'s `hello` - `AgentProcess` numbers its own requests from 1, so the
+/// two can never be confused on the same connection.
+const HANDSHAKE_ID: &str = "attach";
+/// Win32 `ERROR_PIPE_BUSY` - every instance of the pipe is already connected to a client.
+#[cfg(windows)]
+const ERROR_PIPE_BUSY: i32 = 231;
+
+static WRITE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+pub(super) type IpcReader = Box<dyn AsyncRead + Send + Unpin>;
+
+/// What [`connect`] found on an endpoint.
+pub(super) enum Endpoint {
+    /// Nothing listening - the only state a daemon may be spawned on (and, on Unix, a leftover
+    /// socket file unlinked for).
+    Vacant,
+    /// A daemon is there but serving another host (a second app instance, `sgi`). The daemon
+    /// accepts one host at a time: on Windows the pipe refuses the connection outright, while on
+    /// Unix it lands in the socket's listen backlog and only an unanswered [`handshake`] tells the
+    /// two apart.
+    Busy,
+    /// Connected, and the daemon answered [`handshake`].
+    Connected(IpcReader, IpcWriter),
+}
+
+/// What this process was doing for one account when it detached - see the module doc comment for
+/// why only these three things need recording.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DetachedSession {
+    /// owner -> claimed games, for [`RESTORED_OWNERS`] only.
+    claims: HashMap<String, Vec<ClaimedGame>>,
+    card_farming: bool,
+    /// `Some` (the session's live worker count) iff the unlocker was running - restarted through
+    /// `AchievementUnlockerManager::resume`, so it also keeps its pacing.
+    achievement_unlocker_max_concurrent_games: Option<u32>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClaimedGame {
+    app_id: u32,
+    name: String,
+}
+
+/// Read fresh on every spawn rather than cached, so the toggle applies to the very next daemon.
+pub(crate) fn enabled(app_handle: &AppHandle) -> bool {
+    crate::settings::load(app_handle)
+        .map(|s| s.keep_agent_running)
+        .unwrap_or(false)
+}
+
+/// The endpoint `account_key`'s daemon listens on - the bare pipe name on Windows (.NET's
+/// `NamedPipeServerStream` prepends `\\.\pipe\` itself; [`open`] adds it back for the client
+/// side), an absolute socket path under `platform::cache_dir/agent/` elsewhere. Deterministic per
+/// account so the next launch knows where to look without any record of its own.
+pub(super) fn endpoint(app_handle: &AppHandle, account_key: &str) -> AppResult<String> {
+    #[cfg(windows)]
+    {
+        let _ = app_handle;
+        Ok(pipe_name(account_key))
+    }
+    #[cfg(not(windows))]
+    {
+        let dir = platform::cache_dir(app_handle)?.join("agent");
+        fs::create_dir_all(&dir).map_err(|e| AppError::AgentDetachedConnect(e.to_string()))?;
+        Ok(socket_path(&dir, account_key))
+    }
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+fn pipe_name(account_key: &str) -> String {
+    format!("steam-game-idler-agent-{account_key}")
+}
+
+#[cfg_attr(windows, allow(dead_code))]
+fn socket_path(dir: &Path, account_key: &str) -> String {
+    dir.join(format!("{account_key}.sock"))
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// A Unix socket file outlives a daemon that crashed without cleaning it up, and .NET refuses to
+/// bind over an existing one - only ever called right before spawning a replacement. Re-checks
+/// that nothing accepts a connection first, so a live daemon's socket is never unlinked out from
+/// under it even if a caller's earlier [`connect`] raced it. Named pipes vanish with their last
+/// handle, so there's nothing to remove on Windows.
+pub(super) async fn remove_stale_endpoint(endpoint: &str) -> AppResult<()> {
+    if !matches!(open(endpoint).await, Endpoint::Vacant) {
+        return Err(AppError::AgentDetachedConnect(format!(
+            "{endpoint} is still accepting connections"
+        )));
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = fs::remove_file(endpoint);
+    }
+    Ok(())
+}
+
+/// Connects to `endpoint` and confirms a daemon is actually serving the connection - see
+/// [`Endpoint`] for why connecting alone isn't enough.
+pub(super) async fn connect(endpoint: &str) -> Endpoint {
+    match open(endpoint).await {
+        Endpoint::Connected(reader, mut writer) => match handshake(reader, &mut writer).await {
+            Some(reader) => Endpoint::Connected(reader, writer),
+            None => Endpoint::Busy,
+        },
+        other => other,
+    }
+}
+
+/// The bare transport, unverified - [`Endpoint::Connected`] here only means the OS accepted it.
+async fn open(endpoint: &str) -> Endpoint {
+    #[cfg(not(windows))]
+    {
+        let Ok(stream) = tokio::net::UnixStream::connect(endpoint).await else {
+            return Endpoint::Vacant;
+        };
+        let (reader, writer) = stream.into_split();
+        Endpoint::Connected(Box::new(reader), Box::new(writer))
+    }
+    #[cfg(windows)]
+    {
+        match tokio::net::windows::named_pipe::ClientOptions::new()
+            .open(format!(r"\\.\pipe\{endpoint}"))
+        {
+            Ok(pipe) => {
+                let (reader, writer) = tokio::io::split(pipe);
+                Endpoint::Connected(Box::new(reader), Box::new(writer))
+            }
+            Err(e) if e.raw_os_error() == Some(ERROR_PIPE_BUSY) => Endpoint::Busy,
+            Err(_) => Endpoint::Vacant,
+        }
+    }
+}
+
+/// Sends `hello` and waits up to [`HANDSHAKE_TIMEOUT`] for its response, skipping any event lines
+/// the daemon sends first. Returns the reader to keep using - buffered, since it may already hold
+/// lines past the response - or `None` if the daemon never answered.
+async fn handshake(reader: IpcReader, writer: &mut IpcWriter) -> Option<IpcReader> {
+    let mut request = serde_json::to_string(&IpcRequest::hello(HANDSHAKE_ID.to_string())).ok()?;
+    request.push('\n');
+    let mut reader = BufReader::new(reader);
+
+    let answered = async {
+        writer.write_all(request.as_bytes()).await.ok()?;
+        writer.flush().await.ok()?;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).await.ok()? == 0 {
+                return None;
+            }
+            let Ok(message) = serde_json::from_str::<IpcMessage>(&line) else {
+                continue;
+            };
+            if let IpcLine::Response { id: Some(id), .. } = message.classify() {
+                if id == HANDSHAKE_ID {
+                    return Some(());
+                }
+            }
+        }
+    };
+    tokio::time::timeout(HANDSHAKE_TIMEOUT, answered)
+        .await
+        .ok()??;
+    Some(Box::new(reader))
+}
+
+/// [`connect`] for a daemon that was only just spawned and may not be listening yet.
+pub(super) async fn connect_with_retry(endpoint: &str) -> AppResult<(IpcReader, IpcWriter)> {
+    let deadline = tokio::time::Instant::now() + CONNECT_TIMEOUT;
+    loop {
+        if let Endpoint::Connected(reader, writer) = connect(endpoint).await {
+            return Ok((reader, writer));
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return Err(AppError::AgentDetachedConnect(format!(
+                "nothing listening on {endpoint} after {}s",
+                CONNECT_TIMEOUT.as_secs()
+            )));
+        }
+        tokio::time::sleep(CONNECT_RETRY_INTERVAL).await;
+    }
+}
+
+fn cache_file_path(app_handle: &AppHandle, steam_id: &str) -> AppResult<PathBuf> {
+    Ok(platform::cache_dir(app_handle)?
+        .join(steam_id)
+        .join(CACHE_FILE_NAME))
+}
+
+async fn write_record(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    record: &DetachedSession,
+) -> AppResult<()> {
+    let _guard = WRITE_LOCK.lock().await;
+    write_record_to(&cache_file_path(app_handle, steam_id)?, record)
+}
+
+fn write_record_to(path: &Path, record: &DetachedSession) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::AgentDetachedSessionIo(e.to_string()))?;
+    }
+    atomic_write_json(path, record).map_err(|e| AppError::AgentDetachedSessionIo(e.to_string()))
+}
+
+/// Reads and deletes `steam_id`'s record - a record is only ever meaningful to the one reattach
+/// right after the exit that wrote it, never to a later fresh login.
+async fn take_record(app_handle: &AppHandle, steam_id: &str) -> AppResult<Option<DetachedSession>> {
+    let _guard = WRITE_LOCK.lock().await;
+    take_record_from(&cache_file_path(app_handle, steam_id)?)
+}
+
+/// The file is deleted even when it doesn't parse - a record a later launch can't read would
+/// otherwise fail every reattach from then on.
+fn take_record_from(path: &Path) -> AppResult<Option<DetachedSession>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents =
+        fs::read_to_string(path).map_err(|e| AppError::AgentDetachedSessionIo(e.to_string()))?;
+    let _ = fs::remove_file(path);
+    if contents.trim().is_empty() {
+        return Ok(None);
+    }
+    serde_json::from_str(&contents)
+        .map(Some)
+        .map_err(|e| AppError::AgentDetachedSessionIo(e.to_string()))
+}
+
+/// `RunEvent::Exit`'s detached-mode counterpart to `updater::kill_all_steam_utility_processes` -
+/// records each detachable session's state, then lets go of every daemon without killing it.
+/// CLI-mode idle processes and the claims registry are still torn down exactly as before: only
+/// the daemons are meant to outlive the app. Card farming/unlocker loops are deliberately *not*
+/// stopped first - a clean stop releases their claims, which would tell the daemon to stop idling
+/// their games right before we leave it running.
+pub async fn detach_on_exit(app_handle: &AppHandle) {
+    let agent_manager = app_handle.state::<AgentManager>();
+    let claims = app_handle.state::<IdleClaimsRegistry>();
+    let card_farming = app_handle.state::<CardFarmingManager>();
+    let unlocker = app_handle.state::<AchievementUnlockerManager>();
+
+    for (key, steam_id) in agent_manager.detachable_sessions().await {
+        let account = GamesAccount::Agent {
+            username: key.clone(),
+        };
+        let mut record = DetachedSession {
+            card_farming: card_farming.state(&steam_id).await.is_farming,
+            achievement_unlocker_max_concurrent_games: unlocker
+                .max_concurrent_games(&steam_id)
+                .await,
+            ..Default::default()
+        };
+        for owner in RESTORED_OWNERS {
+            let Ok(games) = claims.owner_claim(&agent_manager, &account, owner).await else {
+                continue;
+            };
+            if !games.is_empty() {
+                record.claims.insert(
+                    owner.to_string(),
+                    games
+                        .into_iter()
+                        .map(|(app_id, name)| ClaimedGame { app_id, name })
+                        .collect(),
+                );
+            }
+        }
+
+        if let Err(err) = write_record(app_handle, &steam_id, &record).await {
+            tracing::warn!(account = %key, ?err, "exit: failed to record detached agent session");
+        }
+    }
+
+    agent_manager.detach_all().await;
+    app_handle.state::<IdlingManager>().kill_all().await;
+    claims.clear().await;
+}
+
+/// Picks up where [`detach_on_exit`] left off, once `username`'s token login turned out to be a
+/// reattach - a no-op for a fresh login or when no record was written. Best-effort throughout:
+/// every step only logs on failure, since the login itself already succeeded and a feature that
+/// failed to come back is no worse than the user restarting it by hand.
+pub async fn restore_after_reattach(app_handle: &AppHandle, username: &str) {
+    let agent_manager = app_handle.state::<AgentManager>();
+    if !agent_manager.is_reattached(username).await {
+        return;
+    }
+    let Ok(steam_id) = agent_manager.steam_id(username).await else {
+        return;
+    };
+    let record = match take_record(app_handle, &steam_id).await {
+        Ok(Some(record)) => record,
+        Ok(None) => return,
+        Err(err) => {
+            tracing::warn!(
+                steam_id,
+                ?err,
+                "reattach: failed to read detached agent session"
+            );
+            return;
+        }
+    };
+    let account = GamesAccount::Agent {
+        username: username.to_string(),
+    };
+
+    // Seeded without announcing - the daemon is already idling these - and before either loop
+    // restarts, so the union each loop's first claim announces still includes them.
+    let idling = agent_manager.idle_state(username).await.unwrap_or_default();
+    let claims = app_handle.state::<IdleClaimsRegistry>();
+    for owner in RESTORED_OWNERS {
+        let targets: Vec<IdleTarget> = record
+            .claims
+            .get(owner)
+            .into_iter()
+            .flatten()
+            .filter(|game| idling.contains(&game.app_id))
+            .map(|game| IdleTarget {
+                app_id: game.app_id,
+                name: game.name.clone(),
+            })
+            .collect();
+        if !targets.is_empty() {
+            claims
+                .restore_owner_claim(&steam_id, &account, owner, targets)
+                .await;
+        }
+    }
+
+    if record.card_farming {
+        let restarted =
+            match session::resolve(app_handle, &agent_manager, &account, &steam_id, None).await {
+                Ok(cookies) => {
+                    app_handle
+                        .state::<CardFarmingManager>()
+                        .start(app_handle, steam_id.clone(), account.clone(), cookies)
+                        .await
+                }
+                Err(err) => Err(err),
+            };
+        if let Err(err) = restarted {
+            tracing::warn!(steam_id, ?err, "reattach: failed to restart card farming");
+        }
+    }
+
+    if let Some(max_concurrent_games) = record.achievement_unlocker_max_concurrent_games {
+        if let Err(err) = app_handle
+            .state::<AchievementUnlockerManager>()
+            .resume(app_handle, steam_id.clone(), account, max_concurrent_games)
+            .await
+        {
+            tracing::warn!(
+                steam_id,
+                ?err,
+                "reattach: failed to restart achievement unlocker"
+            );
+        }
+    }
+
+    tracing::info!(steam_id, "reattach: restored detached agent session");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> DetachedSession {
+        DetachedSession {
+            claims: HashMap::from([(
+                OWNER_MANUAL.to_string(),
+                vec![ClaimedGame {
+                    app_id: 440,
+                    name: "Team Fortress 2".to_string(),
+                }],
+            )]),
+            card_farming: true,
+            achievement_unlocker_max_concurrent_games: Some(3),
+        }
+    }
+
+    #[test]
+    fn names_endpoints_per_account() {
+        assert_eq!(pipe_name("alice"), "steam-game-idler-agent-alice");
+        let dir = Path::new("cache").join("agent");
+        assert_eq!(
+            socket_path(&dir, "alice"),
+            dir.join("alice.sock").to_string_lossy()
+        );
+    }
+
+    #[test]
+    fn round_trips_a_record_once() {
+        let dir = std::env::temp_dir().join(format!("sgi-detached-session-{}", std::process::id()));
+        let path = dir.join("76561198000000000").join(CACHE_FILE_NAME);
+
+        write_record_to(&path, &record()).unwrap();
+        assert_eq!(take_record_from(&path).unwrap(), Some(record()));
+        assert!(!path.exists());
+        assert_eq!(take_record_from(&path).unwrap(), None);
+
+        let idle_only = DetachedSession {
+            achievement_unlocker_max_concurrent_games: None,
+            ..record()
+        };
+        write_record_to(&path, &idle_only).unwrap();
+        assert_eq!(take_record_from(&path).unwrap(), Some(idle_only));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn discards_a_blank_or_unreadable_record() {
+        let dir = std::env::temp_dir().join(format!("sgi-detached-stale-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(CACHE_FILE_NAME);
+
+        fs::write(&path, "  \n").unwrap();
+        assert_eq!(take_record_from(&path).unwrap(), None);
+        assert!(!path.exists());
+
+        fs::write(&path, "{\"claims\": [").unwrap();
+        assert!(matches!(
+            take_record_from(&path),
+            Err(AppError::AgentDetachedSessionIo(_))
+        ));
+        assert!(!path.exists());
+
+        // Written before the unlocker was recorded - still restores idling and card farming.
+        fs::write(&path, r#"{"claims": {}, "cardFarming": true}"#).unwrap();
+        assert_eq!(
+            take_record_from(&path).unwrap(),
+            Some(DetachedSession {
+                card_farming: true,
+                ..Default::default()
+            })
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn a_socket_that_never_answers_is_busy_and_kept() {
+        let dir = std::env::temp_dir().join(format!("sgi-detached-busy-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let endpoint = socket_path(&dir, "alice");
+        let _ = fs::remove_file(&endpoint);
+
+        // Bound but never accepting - the kernel still completes the connect into the backlog,
+        // exactly like a daemon that's busy with another host.
+        let listener = tokio::net::UnixListener::bind(&endpoint).unwrap();
+        assert!(matches!(connect(&endpoint).await, Endpoint::Busy));
+        assert!(matches!(
+            remove_stale_endpoint(&endpoint).await,
+            Err(AppError::AgentDetachedConnect(_))
+        ));
+        assert!(Path::new(&endpoint).exists());
+
+        drop(listener);
+        assert!(matches!(connect(&endpoint).await, Endpoint::Vacant));
+        remove_stale_endpoint(&endpoint).await.unwrap();
+        assert!(!Path::new(&endpoint).exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn handshake_skips_events_and_keeps_what_follows() {
+        let dir = std::env::temp_dir().join(format!("sgi-detached-hello-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let endpoint = socket_path(&dir, "alice");
+        let _ = fs::remove_file(&endpoint);
+
+        let listener = tokio::net::UnixListener::bind(&endpoint).unwrap();
+        let daemon = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            let mut request = String::new();
+            BufReader::new(reader)
+                .read_line(&mut request)
+                .await
+                .unwrap();
+            assert!(request.contains(r#""cmd":"hello""#));
+            writer
+                .write_all(
+                    b"{\"event\":\"idle_state\",\"appIds\":[]}\n\
+                      {\"id\":\"attach\",\"ok\":true,\"result\":{}}\n\
+                      {\"id\":\"1\",\"ok\":true}\n",
+                )
+                .await
+                .unwrap();
+        });
+
+        let Endpoint::Connected(reader, _writer) = connect(&endpoint).await else {
+            panic!("expected the handshake to succeed");
+        };
+        daemon.await.unwrap();
+        let mut next = String::new();
+        BufReader::new(reader).read_line(&mut next).await.unwrap();
+        assert_eq!(next.trim(), r#"{"id":"1","ok":true}"#);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
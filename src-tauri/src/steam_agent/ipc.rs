@@ -167,6 +167,54 @@ impl IpcRequest {
         }
     }
 
+    /// Reattach handshake for a detached daemon - responds with `{loggedOn, steamId, appIds}` so a
+    /// freshly-launched app can adopt the still-live session instead of logging in again. See
+    /// `super::detached`'s doc comment.
+    pub fn hello(id: String) -> Self {
+        Self {
+            id,
+            cmd: "hello",
+            user: None,
+            pass_b64: None,
+            refresh_token_b64: None,
+            code: None,
+            app_ids: None,
+            app_id: None,
+            achievement_id: None,
+            unlock: None,
+            stats: None,
+            achievement_changes: None,
+            persona_state: None,
+            game_extra_info: None,
+            language: None,
+            games_only: None,
+        }
+    }
+
+    /// Ends a detached daemon's session and process outright - the detached-mode equivalent of
+    /// killing the child, for a host that reattached to a daemon it never spawned (and so holds no
+    /// `Child` handle to kill). See `AgentProcess::kill`.
+    pub fn shutdown(id: String) -> Self {
+        Self {
+            id,
+            cmd: "shutdown",
+            user: None,
+            pass_b64: None,
+            refresh_token_b64: None,
+            code: None,
+            app_ids: None,
+            app_id: None,
+            achievement_id: None,
+            unlock: None,
+            stats: None,
+            achievement_changes: None,
+            persona_state: None,
+            game_extra_info: None,
+            language: None,
+            games_only: None,
+        }
+    }
+
     /// Derives a Steam Community web session from the daemon's already-authenticated connection
     /// via the `get_web_session` command - see `Daemon/Bot/AuthFlow.cs::GetWebSessionAsync`. No
     /// params: the daemon mints it from whatever session/refresh-token it already holds.
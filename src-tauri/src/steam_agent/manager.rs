@@ -10,6 +10,7 @@ use crate::credential_store;
 use crate::error::{AppError, AppResult};
 use crate::settings;
 
+use super::detached;
 use super::ipc::{AchievementChange, IpcRequest, IpcResponse};
 use super::process::{AgentProcess, OWNED_APPS_REQUEST_TIMEOUT};
 
@@ -118,17 +119,33 @@ impl AgentManager {
 
     /// Kills any existing process for this account and spawns a fresh one. Used for `login`
     /// (credential flow) so a retried login never reuses a process that's mid-auth-flow (or
-    /// mid-guard-code-prompt) from an earlier, possibly-abandoned attempt.
+    /// mid-guard-code-prompt) from an earlier, possibly-abandoned attempt. In detached mode that
+    /// includes a daemon a previous app run left behind for this account, which isn't in
+    /// `sessions` yet - it's attached to just long enough to shut it down. One that's busy serving
+    /// another host (see `detached::Endpoint::Busy`) fails the login instead, left untouched.
     async fn respawn(&self, app_handle: &AppHandle, key: &str) -> AppResult<Arc<AgentProcess>> {
         let mut sessions = self.sessions.lock().await;
         if let Some(old) = sessions.remove(key) {
             old.kill().await;
         }
-        let process = Arc::new(AgentProcess::spawn(app_handle.clone(), key.to_string())?);
+        let process = if detached::enabled(app_handle) {
+            let endpoint = detached::endpoint(app_handle, key)?;
+            if let Some(leftover) =
+                AgentProcess::attach(app_handle.clone(), key.to_string(), &endpoint).await?
+            {
+                leftover.kill().await;
+            }
+            AgentProcess::spawn_detached(app_handle.clone(), key.to_string(), &endpoint).await?
+        } else {
+            AgentProcess::spawn(app_handle.clone(), key.to_string())?
+        };
+        let process = Arc::new(process);
         sessions.insert(key.to_string(), process.clone());
         Ok(process)
     }
 
+    /// In detached mode (see `super::detached`), prefers reattaching to a daemon a previous app run
+    /// left running for this account over spawning a new one.
     async fn get_or_spawn(
         &self,
         app_handle: &AppHandle,
@@ -138,7 +155,19 @@ impl AgentManager {
         if let Some(existing) = sessions.get(key) {
             return Ok(existing.clone());
         }
-        let process = Arc::new(AgentProcess::spawn(app_handle.clone(), key.to_string())?);
+        let process = if detached::enabled(app_handle) {
+            let endpoint = detached::endpoint(app_handle, key)?;
+            match AgentProcess::attach(app_handle.clone(), key.to_string(), &endpoint).await? {
+                Some(process) => process,
+                None => {
+                    AgentProcess::spawn_detached(app_handle.clone(), key.to_string(), &endpoint)
+                        .await?
+                }
+            }
+        } else {
+            AgentProcess::spawn(app_handle.clone(), key.to_string())?
+        };
+        let process = Arc::new(process);
         sessions.insert(key.to_string(), process.clone());
         Ok(process)
     }
@@ -319,6 +348,10 @@ impl AgentManager {
             credential_store::load_refresh_token(&key)?.ok_or(AppError::NoSavedAccount)?;
 
         let process = self.get_or_spawn(app_handle, &key).await?;
+        if process.is_reattached() && Self::adopt_detached_session(&process, &key).await {
+            return Ok(true);
+        }
+
         let response = process
             .send_request(move |id| IpcRequest::login_with_token(id, saved_username, token_b64))
             .await?;
@@ -331,6 +364,50 @@ impl AgentManager {
         Ok(response.ok)
     }
 
+    /// Reattach handshake with a detached daemon a previous app run left running - `true` if its
+    /// session is still logged on, in which case its SteamID64 and idle set are adopted as-is and
+    /// no login is sent at all (the daemon never logged off, so there's nothing to resume). `false`
+    /// (daemon mid-reconnect, or logged off) falls through to a normal token login on the same
+    /// connection.
+    async fn adopt_detached_session(process: &AgentProcess, key: &str) -> bool {
+        let result = match process
+            .send_request(IpcRequest::hello)
+            .await
+            .and_then(ok_or_agent_error_with_result)
+        {
+            Ok(result) => result,
+            Err(e) => {
+                tracing::warn!(account = %key, error = %e, "detached agent handshake failed, logging in again");
+                return false;
+            }
+        };
+
+        let logged_on = result
+            .get("loggedOn")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let steam_id = result
+            .get("steamId")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        if !logged_on || steam_id.is_none() {
+            return false;
+        }
+
+        let app_ids: Vec<u32> = result
+            .get("appIds")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_u64().map(|n| n as u32))
+                    .collect()
+            })
+            .unwrap_or_default();
+        tracing::info!(account = %key, idling = app_ids.len(), "adopted detached agent session");
+        process.adopt(steam_id, app_ids);
+        true
+    }
+
     /// Re-applies this account's saved persona state (see `presence_settings`) after a fresh
     /// (re)login. `PresenceManager` on the daemon side always defaults a freshly spawned process to
     /// `Online` (see `Daemon/Bot/PresenceManager.cs`) and only learns otherwise from an explicit
@@ -378,6 +455,7 @@ impl AgentManager {
             sessions.remove(&key).ok_or(AppError::SessionNotFound)?
         };
 
+        process.end_session();
         let response = process.send_request(IpcRequest::logout).await;
         process.kill().await;
 
@@ -726,6 +804,62 @@ impl AgentManager {
         serde_json::from_value(result).map_err(AppError::from)
     }
 
+    /// Whether `username`'s live session adopted a detached daemon from a previous app run rather
+    /// than spawning a fresh one - see `detached::restore_after_reattach`.
+    pub async fn is_reattached(&self, username: &str) -> bool {
+        let key = Self::key_for(username);
+        self.existing(&key)
+            .await
+            .is_ok_and(|process| process.is_reattached())
+    }
+
+    /// `(account key, SteamID64)` for every logged-on session whose daemon will outlive this app -
+    /// what `detached::detach_on_exit` snapshots before calling [`Self::detach_all`].
+    pub async fn detachable_sessions(&self) -> Vec<(String, String)> {
+        self.sessions
+            .lock()
+            .await
+            .iter()
+            .filter(|(_, process)| process.is_detached())
+            .filter_map(|(key, process)| Some((key.clone(), process.steam_id()?)))
+            .collect()
+    }
+
+    /// App-exit counterpart to [`Self::kill_all`] for detached mode: forgets every session, but
+    /// only kills the ones that can't outlive us (stdio children, pending QR attempts) - a detached
+    /// daemon's connection is just dropped, leaving its Steam session and idle set running.
+    pub async fn detach_all(&self) {
+        let mut sessions = self.sessions.lock().await;
+        for (key, process) in sessions.drain() {
+            if process.is_detached() {
+                tracing::info!(account = %key, "leaving detached agent session running");
+            } else {
+                process.kill().await;
+            }
+        }
+        for (_, process) in self.pending_qr.lock().await.drain() {
+            process.kill().await;
+        }
+    }
+
+    /// `sgi`'s exit counterpart to [`Self::detach_all`]: forgets every session, killing each daemon
+    /// this process spawned itself (detached or not) but only dropping the connection to one it
+    /// reattached to - that daemon belongs to whichever app run left it running, and keeps its
+    /// Steam session and idle set exactly as it found them.
+    pub async fn release_all(&self) {
+        let mut sessions = self.sessions.lock().await;
+        for (key, process) in sessions.drain() {
+            if process.is_reattached() {
+                tracing::info!(account = %key, "detaching from reattached agent session");
+            } else {
+                process.kill().await;
+            }
+        }
+        for (_, process) in self.pending_qr.lock().await.drain() {
+            process.kill().await;
+        }
+    }
+
     /// Kills every tracked agent session's process and forgets about all of them. Used by the
     /// pre-install update cleanup (see `updater::kill_all_steam_utility_processes`) - unlike
     /// `logout`, this doesn't ask SteamUtility to log off cleanly first, since an update install
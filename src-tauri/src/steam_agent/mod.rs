@@ -1,8 +1,10 @@
 //! Agent-mode (SteamKit2/daemon-backed) sign-in integration - spawns `libs/SteamUtility.exe agent`
 //! per account and speaks its newline-delimited JSON IPC protocol (see
-//! `libs/SteamUtility/Daemon/DaemonHost.cs`).
+//! `libs/SteamUtility/Daemon/DaemonHost.cs`) - over stdio by default, or over a named pipe/Unix
+//! socket to a daemon that outlives the app in opt-in detached mode (see [`detached`]).
 
 pub mod commands;
+pub mod detached;
 mod ipc;
 mod manager;
 pub mod ownership_settings;
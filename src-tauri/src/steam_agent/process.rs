@@ -1,15 +1,16 @@
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex as StdMutex};
 use std::time::Duration;
 
 use tauri::{AppHandle, Emitter, Manager};
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::process::{Child, ChildStdin, Command};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::process::{Child, Command};
 use tokio::sync::{oneshot, Mutex};
 
 use crate::error::{AppError, AppResult};
 
+use super::detached;
 use super::ipc::{IpcLine, IpcMessage, IpcRequest, IpcResponse};
 
 /// Conservative upper bound for a Steam network round trip; not derived from any specific
@@ -38,15 +39,34 @@ pub const AGENT_EVENT: &str = "steam-agent-event";
 /// - a spawned child there has no console window to flash in the first place.
 #[cfg(windows)]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
+/// Win32 `DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP` - a detached-mode daemon (see
+/// `super::detached`) must survive this app's own process exiting, so it gets no console and no
+/// shared process group/Ctrl+C delivery with us.
+#[cfg(windows)]
+const DETACHED_PROCESS_FLAGS: u32 = 0x00000008 | 0x00000200;
+/// How long a `shutdown` request gets before `kill` gives up on it - the daemon exits right after
+/// responding, so anything slower means it's already gone or wedged.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 
 type PendingMap = Arc<StdMutex<HashMap<String, oneshot::Sender<IpcResponse>>>>;
+pub(super) type IpcWriter = Box<dyn AsyncWrite + Send + Unpin>;
 
-/// One spawned `SteamUtility.exe agent` child process plus its IPC plumbing. One `AgentProcess`
-/// exists per logged-in-or-logging-in account - see `AgentManager` for the account-keyed map this
-/// lives behind.
+/// One `SteamUtility.exe agent` daemon plus its IPC plumbing. One `AgentProcess` exists per
+/// logged-in-or-logging-in account - see `AgentManager` for the account-keyed map this lives
+/// behind. Normally the daemon is our own child speaking over stdio; in detached mode (see
+/// `super::detached`) it's a free-standing process speaking the exact same newline-delimited JSON
+/// over a named pipe/Unix socket, which we may or may not have spawned ourselves.
 pub struct AgentProcess {
-    child: Mutex<Child>,
-    stdin: Mutex<ChildStdin>,
+    /// `None` when this process reattached to a detached daemon a previous app run spawned - see
+    /// [`Self::kill`] for how that case is still terminated.
+    child: Mutex<Option<Child>>,
+    writer: Mutex<IpcWriter>,
+    /// Whether the transport is a detached daemon's pipe/socket rather than a child's stdio -
+    /// dropping this `AgentProcess` then leaves the daemon (and its Steam session) running.
+    detached: bool,
+    /// Whether this connection adopted an already-running detached daemon instead of spawning one
+    /// - `AgentManager::login_with_token` uses this to handshake via `hello` instead of logging in.
+    reattached: bool,
     pending: PendingMap,
     next_id: AtomicU64,
     /// The key this process's stdout/stderr reader tasks tag every log line and emitted event
@@ -66,6 +86,10 @@ pub struct AgentProcess {
     /// reads this directly; `AgentManager::set_idle_games` doesn't wait for it (see that method's
     /// doc comment for why).
     idle_app_ids: Arc<StdMutex<Vec<u32>>>,
+    /// Set once this app asks the session to end ([`Self::end_session`], [`Self::kill`]) - the
+    /// `status_changed` that follows is then the logout we asked for, not the unexpected drop
+    /// the `agentSessionDropped` webhook is for (see `handle_line`).
+    ending: Arc<AtomicBool>,
 }
 
 impl AgentProcess {
@@ -88,33 +112,147 @@ impl AgentProcess {
         let stdout = child.stdout.take().expect("stdout was piped at spawn");
         let stderr = child.stderr.take().expect("stderr was piped at spawn");
 
+        let process = Self::from_transport(
+            app_handle,
+            account_key,
+            stdout,
+            Box::new(stdin),
+            Some(child),
+            false,
+            false,
+        );
+        spawn_stderr_forwarder(stderr, process.account_key.clone());
+
+        let logged_key = process.account_key.lock().unwrap().clone();
+        tracing::info!(account = %logged_key, exe = %exe_path.display(), "spawned SteamUtility agent process");
+
+        Ok(process)
+    }
+
+    /// Spawns a detached-mode daemon listening on `endpoint` and connects to it. Not
+    /// `kill_on_drop`, and in its own process group/with no console - it has to outlive this app.
+    /// Its stderr goes nowhere: SteamUtility's own rotating log file (`Core/Logging/FileLogSink`)
+    /// still captures everything, and there's no parent left to forward to once we exit.
+    pub async fn spawn_detached(
+        app_handle: AppHandle,
+        account_key: String,
+        endpoint: &str,
+    ) -> AppResult<Self> {
+        let exe_path = crate::steam_utility_exe::locate_for_agent(&app_handle)?;
+        detached::remove_stale_endpoint(endpoint).await?;
+
+        let mut command = Command::new(&exe_path);
+        command
+            .arg("agent")
+            .arg("--listen")
+            .arg(endpoint)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null());
+        #[cfg(windows)]
+        command.creation_flags(DETACHED_PROCESS_FLAGS);
+        #[cfg(unix)]
+        command.process_group(0);
+
+        let child = command.spawn()?;
+        let (reader, writer) = detached::connect_with_retry(endpoint).await?;
+
+        tracing::info!(account = %account_key, exe = %exe_path.display(), endpoint, "spawned detached SteamUtility agent process");
+        Ok(Self::from_transport(
+            app_handle,
+            account_key,
+            reader,
+            writer,
+            Some(child),
+            true,
+            false,
+        ))
+    }
+
+    /// Connects to a detached daemon a previous app run left listening on `endpoint`, if there is
+    /// one - `Ok(None)` just means nothing is listening, the normal case on a first launch. A
+    /// daemon busy serving another host is an error rather than `None`: callers spawn a
+    /// replacement on `None`, and this one is very much alive.
+    pub async fn attach(
+        app_handle: AppHandle,
+        account_key: String,
+        endpoint: &str,
+    ) -> AppResult<Option<Self>> {
+        let (reader, writer) = match detached::connect(endpoint).await {
+            detached::Endpoint::Connected(reader, writer) => (reader, writer),
+            detached::Endpoint::Vacant => return Ok(None),
+            detached::Endpoint::Busy => {
+                return Err(AppError::AgentDetachedConnect(format!(
+                    "{endpoint} is already serving another host"
+                )))
+            }
+        };
+        tracing::info!(account = %account_key, endpoint, "reattached to detached SteamUtility agent process");
+        Ok(Some(Self::from_transport(
+            app_handle,
+            account_key,
+            reader,
+            writer,
+            None,
+            true,
+            true,
+        )))
+    }
+
+    fn from_transport(
+        app_handle: AppHandle,
+        account_key: String,
+        reader: impl AsyncRead + Send + Unpin + 'static,
+        writer: IpcWriter,
+        child: Option<Child>,
+        detached: bool,
+        reattached: bool,
+    ) -> Self {
         let pending: PendingMap = Arc::new(StdMutex::new(HashMap::new()));
         let account_key: Arc<StdMutex<String>> = Arc::new(StdMutex::new(account_key));
         let steam_id: Arc<StdMutex<Option<String>>> = Arc::new(StdMutex::new(None));
         let idle_app_ids: Arc<StdMutex<Vec<u32>>> = Arc::new(StdMutex::new(Vec::new()));
+        let ending = Arc::new(AtomicBool::new(false));
 
         spawn_stdout_reader(
-            stdout,
+            reader,
             pending.clone(),
             app_handle,
             account_key.clone(),
             steam_id.clone(),
             idle_app_ids.clone(),
+            ending.clone(),
         );
-        spawn_stderr_forwarder(stderr, account_key.clone());
-
-        let logged_key = account_key.lock().unwrap().clone();
-        tracing::info!(account = %logged_key, exe = %exe_path.display(), "spawned SteamUtility agent process");
 
-        Ok(Self {
+        Self {
             child: Mutex::new(child),
-            stdin: Mutex::new(stdin),
+            writer: Mutex::new(writer),
+            detached,
+            reattached,
             pending,
             next_id: AtomicU64::new(1),
             account_key,
             steam_id,
             idle_app_ids,
-        })
+            ending,
+        }
+    }
+
+    /// See the `detached` field doc comment.
+    pub fn is_detached(&self) -> bool {
+        self.detached
+    }
+
+    /// See the `reattached` field doc comment.
+    pub fn is_reattached(&self) -> bool {
+        self.reattached
+    }
+
+    /// Seeds the cached SteamID64/idle set from a reattach `hello` response - a reattached daemon
+    /// won't resend the `status_changed`/`idle_state` events it already sent to the previous host.
+    pub fn adopt(&self, steam_id: Option<String>, idle_app_ids: Vec<u32>) {
+        *self.steam_id.lock().unwrap() = steam_id;
+        *self.idle_app_ids.lock().unwrap() = idle_app_ids;
     }
 
     /// The account's SteamID64, once a `status_changed` event has resolved one - see the `steam_id`
@@ -128,6 +266,12 @@ impl AgentProcess {
         self.idle_app_ids.lock().unwrap().clone()
     }
 
+    /// Marks the session as ending at this app's request - call before sending `logout`, so the
+    /// disconnect it causes isn't reported as a dropped session. See the `ending` field doc comment.
+    pub fn end_session(&self) {
+        self.ending.store(true, Ordering::Relaxed);
+    }
+
     /// Updates the key this process's reader tasks tag every subsequent log line/emitted event
     /// with - see the `account_key` field doc comment. Called by `AgentManager::
     /// promote_pending_qr_session` once a QR login resolves a real username.
@@ -165,8 +309,12 @@ impl AgentProcess {
         line.push('\n');
 
         {
-            let mut stdin = self.stdin.lock().await;
-            if let Err(e) = stdin.write_all(line.as_bytes()).await {
+            let mut writer = self.writer.lock().await;
+            let written = match writer.write_all(line.as_bytes()).await {
+                Ok(()) => writer.flush().await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = written {
                 self.pending.lock().unwrap().remove(&id);
                 return Err(AppError::ProcessSpawn(e));
             }
@@ -182,19 +330,29 @@ impl AgentProcess {
         }
     }
 
+    /// A detached daemon is asked to `shutdown` first - killing our `Child` handle alone wouldn't
+    /// reach one we reattached to (no handle), and a clean exit also logs the session off.
     pub async fn kill(&self) {
-        let mut child = self.child.lock().await;
-        let _ = child.kill().await;
+        self.end_session();
+        if self.detached {
+            let _ = self
+                .send_request_with_timeout(IpcRequest::shutdown, SHUTDOWN_TIMEOUT)
+                .await;
+        }
+        if let Some(child) = self.child.lock().await.as_mut() {
+            let _ = child.kill().await;
+        }
     }
 }
 
 fn spawn_stdout_reader(
-    stdout: tokio::process::ChildStdout,
+    stdout: impl AsyncRead + Send + Unpin + 'static,
     pending: PendingMap,
     app_handle: AppHandle,
     account_key: Arc<StdMutex<String>>,
     steam_id: Arc<StdMutex<Option<String>>>,
     idle_app_ids: Arc<StdMutex<Vec<u32>>>,
+    ending: Arc<AtomicBool>,
 ) {
     tokio::spawn(async move {
         let mut lines = BufReader::new(stdout).lines();
@@ -211,6 +369,7 @@ fn spawn_stdout_reader(
                         &account_key,
                         &steam_id,
                         &idle_app_ids,
+                        &ending,
                     )
                     .await;
                 }
@@ -253,6 +412,7 @@ async fn handle_line(
     account_key: &Arc<StdMutex<String>>,
     steam_id: &Arc<StdMutex<Option<String>>>,
     idle_app_ids: &Arc<StdMutex<Vec<u32>>>,
+    ending: &AtomicBool,
 ) {
     let message: IpcMessage = match serde_json::from_str(line) {
         Ok(m) => m,
@@ -343,7 +503,32 @@ async fn handle_line(
                 }
 
                 if should_update_steam_id(resolved, reconnecting) {
-                    *steam_id.lock().unwrap() = resolved.map(|s| s.to_string());
+                    let previous = std::mem::replace(
+                        &mut *steam_id.lock().unwrap(),
+                        resolved.map(|s| s.to_string()),
+                    );
+                    // A logged-on session (one that had resolved a SteamID64) losing it for good
+                    // - kicked or otherwise disconnected, but not a transient "Reconnecting" (which
+                    // `should_update_steam_id` already excludes) nor a logout/kill this app asked
+                    // for - is the `agentSessionDropped` webhook. Keyed by the previous SteamID64,
+                    // the only one this account had.
+                    if let Some(previous) = previous
+                        .filter(|_| is_unexpected_drop(resolved, ending.load(Ordering::Relaxed)))
+                    {
+                        let reason = payload
+                            .get("result")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("Unknown")
+                            .to_string();
+                        crate::webhooks::notify(
+                            app_handle,
+                            &previous,
+                            crate::webhooks::WebhookEvent::AgentSessionDropped {
+                                account: key.clone(),
+                                reason,
+                            },
+                        );
+                    }
                 }
             }
 
@@ -455,6 +640,13 @@ fn should_update_steam_id(resolved: Option<&str>, reconnecting: bool) -> bool {
     resolved.is_some() || !reconnecting
 }
 
+/// Whether a logged-on session's `status_changed` (one `should_update_steam_id` let through) is a
+/// drop nobody asked for - it lost its SteamID64, and not because this app ended the session (see
+/// `AgentProcess::ending`'s doc comment).
+fn is_unexpected_drop(resolved: Option<&str>, ending: bool) -> bool {
+    resolved.is_none() && !ending
+}
+
 /// `AuthFlow.LoginWithCredentialsAsync`/`LoginWithQrAsync` emit a `refresh_token` event
 /// (`{username, tokenB64}`) right after a successful logon - captured here so `login_with_token`
 /// can resume the session on a later app start without the frontend having to shuttle the token
@@ -517,4 +709,11 @@ mod tests {
     fn a_genuine_disconnect_or_failed_logon_clears_the_cache() {
         assert!(should_update_steam_id(None, false));
     }
+
+    #[test]
+    fn only_a_disconnect_nobody_asked_for_is_a_dropped_session() {
+        assert!(is_unexpected_drop(None, false));
+        assert!(!is_unexpected_drop(None, true));
+        assert!(!is_unexpected_drop(Some("76561198000000000"), false));
+    }
 }
@@ -0,0 +1,162 @@
+use std::collections::{HashMap, HashSet};
+
+use tauri::{AppHandle, State};
+
+use crate::achievement_unlocker::{self, AchievementUnlockerEntry};
+use crate::auto_idle::{self, AutoIdleEntry};
+use crate::card_farming::{
+    blacklist, whitelist, CardFarmingBlacklistEntry, CardFarmingWhitelistEntry,
+};
+use crate::error::{AppError, AppResult};
+use crate::favorites::{self, FavoriteEntry};
+use crate::games::{
+    self,
+    commands::{resolve_steam_id, GamesAccount},
+};
+use crate::local_steam::collections::{self, SteamCollection};
+use crate::steam_agent::AgentManager;
+
+use super::{plan, CollectionImportReport, CollectionImportTarget};
+
+async fn read_collections(steam_id: String) -> AppResult<Vec<SteamCollection>> {
+    tokio::task::spawn_blocking(move || collections::read(&steam_id))
+        .await
+        .map_err(|e| AppError::SteamCollections(e.to_string()))?
+}
+
+/// The account's Steam library collections as this machine's client last synced them - empty
+/// when Steam isn't installed here or the account never opened its library on it. Works for
+/// agent-mode accounts too, as long as the same account has also used the local client.
+#[tauri::command]
+pub async fn get_steam_collections(
+    agent_manager: State<'_, AgentManager>,
+    account: GamesAccount,
+) -> AppResult<Vec<SteamCollection>> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    read_collections(steam_id).await
+}
+
+/// Copies collection `collection_id`'s games into `target`, skipping games already in it and
+/// games the account doesn't own - see the module doc comment. Safe to re-run after the
+/// collection changes in the client: only its new games are added.
+#[tauri::command]
+pub async fn import_steam_collection(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    account: GamesAccount,
+    collection_id: String,
+    target: CollectionImportTarget,
+) -> AppResult<CollectionImportReport> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    let collection = read_collections(steam_id.clone())
+        .await?
+        .into_iter()
+        .find(|c| c.id == collection_id)
+        .ok_or_else(|| AppError::SteamCollectionNotFound(collection_id.clone()))?;
+
+    let owned_names: HashMap<u32, String> =
+        games::commands::get_owned_games_cache(app_handle.clone(), steam_id.clone())?
+            .into_iter()
+            .map(|g| (g.app_id, g.name.unwrap_or_else(|| g.app_id.to_string())))
+            .collect();
+    let existing = existing_app_ids(&app_handle, &steam_id, target).await?;
+    let (to_add, report) = plan(&collection.app_ids, &existing, &owned_names);
+
+    for (app_id, name) in to_add {
+        let result = add(&app_handle, &steam_id, target, app_id, name).await;
+        if let Err(e) = &result {
+            tracing::warn!(
+                steam_id,
+                collection_id,
+                ?target,
+                app_id,
+                error = %e,
+                "steam collections: import failed partway"
+            );
+        }
+        result?;
+    }
+    tracing::info!(
+        steam_id,
+        collection_id,
+        ?target,
+        added = report.added.len(),
+        already_present = report.already_present.len(),
+        not_owned = report.not_owned.len(),
+        "steam collections: imported collection"
+    );
+    Ok(report)
+}
+
+async fn existing_app_ids(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    target: CollectionImportTarget,
+) -> AppResult<HashSet<u32>> {
+    Ok(match target {
+        CollectionImportTarget::Favorites => favorites::cache::read(app_handle, steam_id)
+            .await?
+            .into_iter()
+            .map(|g| g.app_id)
+            .collect(),
+        CollectionImportTarget::AutoIdle => auto_idle::cache::read(app_handle, steam_id)
+            .await?
+            .into_iter()
+            .map(|g| g.app_id)
+            .collect(),
+        CollectionImportTarget::CardFarmingWhitelist => whitelist::read(app_handle, steam_id)
+            .await?
+            .into_iter()
+            .map(|g| g.app_id)
+            .collect(),
+        CollectionImportTarget::CardFarmingBlacklist => blacklist::read(app_handle, steam_id)
+            .await?
+            .into_iter()
+            .map(|g| g.app_id)
+            .collect(),
+        CollectionImportTarget::AchievementUnlocker => {
+            achievement_unlocker::cache::read(app_handle, steam_id)
+                .await?
+                .into_iter()
+                .map(|g| g.app_id)
+                .collect()
+        }
+    })
+}
+
+/// Goes through the target's own idempotent `add`, so a game added from its page while the
+/// import runs is simply left as it is.
+async fn add(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    target: CollectionImportTarget,
+    app_id: u32,
+    name: String,
+) -> AppResult<()> {
+    match target {
+        CollectionImportTarget::Favorites => {
+            favorites::cache::add(app_handle, steam_id, FavoriteEntry { app_id, name }).await?;
+        }
+        CollectionImportTarget::AutoIdle => {
+            let entry = AutoIdleEntry {
+                app_id,
+                name,
+                enabled: true,
+            };
+            auto_idle::cache::add(app_handle, steam_id, entry).await?;
+        }
+        CollectionImportTarget::CardFarmingWhitelist => {
+            let entry = CardFarmingWhitelistEntry { app_id, name };
+            whitelist::add(app_handle, steam_id, entry).await?;
+        }
+        CollectionImportTarget::CardFarmingBlacklist => {
+            let entry = CardFarmingBlacklistEntry { app_id, name };
+            blacklist::add(app_handle, steam_id, entry).await?;
+        }
+        CollectionImportTarget::AchievementUnlocker => {
+            let entry = AchievementUnlockerEntry { app_id, name };
+            achievement_unlocker::cache::add(app_handle, steam_id, entry).await?;
+        }
+    }
+    Ok(())
+}
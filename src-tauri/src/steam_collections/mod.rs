@@ -0,0 +1,89 @@
+//! Importing the local Steam client's library collections (`local_steam::collections`) into this
+//! app's own per-account lists - `favorites`, `auto_idle`, the `card_farming` whitelist/blacklist
+//! or the `achievement_unlocker` queue. A one-shot copy, not a sync: nothing here watches the
+//! client's collections afterwards, and removing a game from either side never touches the other.
+//!
+//! Each target keeps owning its own file and lock - the import only ever goes through that list's
+//! existing idempotent `add`, one game at a time, so it can't interleave badly with a click in the
+//! list's own page, and running the same import twice adds nothing the second time. No shared
+//! "custom list" abstraction sneaks in here either (see `favorites`' module doc comment): the
+//! target is a closed enum matched once in `commands`, and each arm calls that module's own API.
+//!
+//! Names come from the owned-games cache, the same source every list's "add" button takes them
+//! from on the frontend. A collection can hold games the account no longer owns (refunds, revoked
+//! family sharing, another account's shelf synced down), so anything missing from that cache is
+//! reported and skipped rather than stored under a made-up name. An owned game the cache has no
+//! name for is stored under its app id, the same fallback `useIdling` uses.
+
+pub mod commands;
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+/// Which of the app's lists a collection is imported into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CollectionImportTarget {
+    Favorites,
+    AutoIdle,
+    CardFarmingWhitelist,
+    CardFarmingBlacklist,
+    AchievementUnlocker,
+}
+
+/// What an import did with each of the collection's games - every id lands in exactly one list.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionImportReport {
+    pub added: Vec<u32>,
+    pub already_present: Vec<u32>,
+    pub not_owned: Vec<u32>,
+}
+
+/// Splits `app_ids` against the target list's current ids and the owned-games names - the
+/// returned `(app_id, name)` pairs are the ones to add, already counted in the report's `added`.
+fn plan(
+    app_ids: &[u32],
+    existing: &HashSet<u32>,
+    owned_names: &HashMap<u32, String>,
+) -> (Vec<(u32, String)>, CollectionImportReport) {
+    let mut to_add = Vec::new();
+    let mut report = CollectionImportReport::default();
+    for &app_id in app_ids {
+        if existing.contains(&app_id) {
+            report.already_present.push(app_id);
+        } else if let Some(name) = owned_names.get(&app_id) {
+            report.added.push(app_id);
+            to_add.push((app_id, name.clone()));
+        } else {
+            report.not_owned.push(app_id);
+        }
+    }
+    (to_add, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plans_only_owned_games_missing_from_the_list() {
+        let existing = HashSet::from([570]);
+        let owned = HashMap::from([
+            (440, "Team Fortress 2".to_string()),
+            (570, "Dota 2".to_string()),
+        ]);
+        let (to_add, report) = plan(&[440, 570, 730], &existing, &owned);
+
+        assert_eq!(to_add, vec![(440, "Team Fortress 2".to_string())]);
+        assert_eq!(
+            report,
+            CollectionImportReport {
+                added: vec![440],
+                already_present: vec![570],
+                not_owned: vec![730],
+            }
+        );
+    }
+}
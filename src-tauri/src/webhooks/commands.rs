@@ -0,0 +1,43 @@
+use tauri::{AppHandle, State};
+
+use crate::error::AppResult;
+use crate::games::commands::{resolve_steam_id, GamesAccount};
+use crate::steam_agent::AgentManager;
+
+use super::settings::{self, WebhookEndpoint, WebhookSettings};
+use super::{delivery, WebhookEvent};
+
+#[tauri::command]
+pub async fn get_webhook_settings(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    account: GamesAccount,
+) -> AppResult<WebhookSettings> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    settings::get(&app_handle, &steam_id).await
+}
+
+/// Whole-struct replace - see `settings::set`'s doc comment.
+#[tauri::command]
+pub async fn set_webhook_settings(
+    app_handle: AppHandle,
+    agent_manager: State<'_, AgentManager>,
+    account: GamesAccount,
+    settings: WebhookSettings,
+) -> AppResult<WebhookSettings> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    settings::set(&app_handle, &steam_id, settings).await
+}
+
+/// Sends a `test` event to `endpoint` right now and waits for the outcome (retries included) -
+/// the one delivery whose failure reaches the frontend instead of just the log. `endpoint` is
+/// taken as-is rather than looked up from saved settings, so an edit can be tested before saving.
+#[tauri::command]
+pub async fn send_test_webhook(
+    agent_manager: State<'_, AgentManager>,
+    account: GamesAccount,
+    endpoint: WebhookEndpoint,
+) -> AppResult<()> {
+    let steam_id = resolve_steam_id(&account, &agent_manager).await?;
+    delivery::deliver(&endpoint, &steam_id, &WebhookEvent::Test).await
+}
@@ -0,0 +1,294 @@
+//! Rendering and sending one webhook request, with retry.
+//!
+//! **Retries** - up to [`MAX_ATTEMPTS`] tries with exponential backoff ([`BACKOFF_BASE`], doubling)
+//! on anything that looks transient: a connection/timeout error, a 5xx, or a 429 (whose
+//! `Retry-After` is honored when it's a plain number of seconds, capped at [`MAX_RETRY_AFTER`] so a
+//! misbehaving endpoint can't park a delivery task for an hour). Any other 4xx fails immediately -
+//! a wrong URL or a template the service rejects won't start working on the third try.
+
+use std::time::Duration;
+
+use reqwest::header::{CONTENT_TYPE, RETRY_AFTER};
+use reqwest::{Client, StatusCode};
+use serde_json::Value;
+
+use crate::error::{AppError, AppResult};
+
+use super::settings::WebhookEndpoint;
+use super::WebhookEvent;
+
+const MAX_ATTEMPTS: u32 = 4;
+const BACKOFF_BASE: Duration = Duration::from_secs(2);
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub async fn deliver(
+    endpoint: &WebhookEndpoint,
+    steam_id: &str,
+    event: &WebhookEvent,
+) -> AppResult<()> {
+    let body = render(endpoint.template.as_deref(), steam_id, event)?;
+    let client = Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::WebhookDeliveryFailed(e.to_string()))?;
+    send_with_retry(&client, &endpoint.url, body, BACKOFF_BASE).await
+}
+
+/// Delay before retry number `attempt` (1-based: the wait after the first failure is `attempt` 1).
+fn backoff_delay(base: Duration, attempt: u32) -> Duration {
+    base * 2u32.pow(attempt.saturating_sub(1))
+}
+
+async fn send_with_retry(
+    client: &Client,
+    url: &str,
+    body: String,
+    backoff_base: Duration,
+) -> AppResult<()> {
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let (error, retry_after) = match client
+            .post(url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(body.clone())
+            .send()
+            .await
+        {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) => {
+                let status = response.status();
+                let retryable = status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS;
+                if !retryable {
+                    return Err(AppError::WebhookDeliveryFailed(format!("HTTP {status}")));
+                }
+                let retry_after = response
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.trim().parse::<u64>().ok())
+                    .map(|secs| Duration::from_secs(secs).min(MAX_RETRY_AFTER));
+                (format!("HTTP {status}"), retry_after)
+            }
+            Err(e) => (e.without_url().to_string(), None),
+        };
+
+        if attempt >= MAX_ATTEMPTS {
+            return Err(AppError::WebhookDeliveryFailed(format!(
+                "{error} (gave up after {attempt} attempts)"
+            )));
+        }
+        let delay = retry_after.unwrap_or_else(|| backoff_delay(backoff_base, attempt));
+        tracing::info!(
+            url = %redact_url(url),
+            attempt,
+            error,
+            retry_in_ms = delay.as_millis() as u64,
+            "webhooks: delivery attempt failed, retrying"
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// The request body: the event's own JSON (plus `steamId` and `message`) when there's no
+/// template, otherwise the template with every `{{key}}` replaced by that key's value - `message`,
+/// `steamId`, or any of the event's own (camelCase) fields. Substituted values are JSON-string-escaped without surrounding
+/// quotes, so they go *inside* a quoted string in the template (`{"content": "{{message}}"}`); an
+/// unknown key renders as empty. The result must still be valid JSON - checked here rather than
+/// letting the endpoint reject it, so the log says which side is wrong.
+pub(super) fn render(
+    template: Option<&str>,
+    steam_id: &str,
+    event: &WebhookEvent,
+) -> AppResult<String> {
+    let mut fields = match serde_json::to_value(event) {
+        Ok(Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    fields.insert("steamId".to_string(), Value::String(steam_id.to_string()));
+    fields.insert("message".to_string(), Value::String(event.message()));
+
+    let Some(template) = template.filter(|t| !t.trim().is_empty()) else {
+        return Ok(Value::Object(fields).to_string());
+    };
+
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            rendered.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+        let key = after[..end].trim();
+        if let Some(value) = fields.get(key) {
+            rendered.push_str(&escaped(value));
+        }
+        rest = &after[end + 2..];
+    }
+    rendered.push_str(rest);
+
+    serde_json::from_str::<Value>(&rendered)
+        .map_err(|e| AppError::WebhookTemplateInvalid(e.to_string()))?;
+    Ok(rendered)
+}
+
+/// A field's value as it should appear inside a JSON string literal.
+fn escaped(value: &Value) -> String {
+    let text = match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    };
+    let quoted = Value::String(text).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+/// Scheme and host only - a Discord/Slack webhook URL's path *is* its secret.
+pub(super) fn redact_url(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) => format!(
+            "{}://{}/...",
+            parsed.scheme(),
+            parsed.host_str().unwrap_or("?")
+        ),
+        Err(_) => "<invalid url>".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn drop_event() -> WebhookEvent {
+        WebhookEvent::CardDropped {
+            app_id: 440,
+            name: "Team \"Fortress\" 2".to_string(),
+            dropped: 1,
+            remaining: 2,
+        }
+    }
+
+    #[test]
+    fn renders_default_body_without_template() {
+        let body: Value =
+            serde_json::from_str(&render(None, "7656", &drop_event()).unwrap()).unwrap();
+        assert_eq!(body["event"], "cardDropped");
+        assert_eq!(body["appId"], 440);
+        assert_eq!(body["steamId"], "7656");
+    }
+
+    #[test]
+    fn renders_template_with_escaped_values() {
+        let template = r#"{"content": "{{message}} [{{ appId }}]{{unknown}}"}"#;
+        let body: Value =
+            serde_json::from_str(&render(Some(template), "7656", &drop_event()).unwrap()).unwrap();
+        assert_eq!(
+            body["content"],
+            "Team \"Fortress\" 2: 1 card dropped, 2 remaining [440]"
+        );
+    }
+
+    #[test]
+    fn rejects_template_that_renders_invalid_json() {
+        assert!(render(Some("{\"content\": {{message}}}"), "7656", &drop_event()).is_err());
+    }
+
+    #[test]
+    fn backoff_doubles() {
+        let base = Duration::from_secs(2);
+        assert_eq!(backoff_delay(base, 1), Duration::from_secs(2));
+        assert_eq!(backoff_delay(base, 2), Duration::from_secs(4));
+        assert_eq!(backoff_delay(base, 3), Duration::from_secs(8));
+    }
+
+    #[test]
+    fn redacts_url_path() {
+        assert_eq!(
+            redact_url("https://discord.com/api/webhooks/123/secret"),
+            "https://discord.com/..."
+        );
+        assert_eq!(redact_url("not a url"), "<invalid url>");
+    }
+
+    /// Local stand-in for a webhook receiver: answers each request with the next status in
+    /// `statuses`, returning every body it received once they're used up.
+    async fn stand_in(statuses: Vec<u16>) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let mut bodies = Vec::new();
+            for status in statuses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                let body = loop {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request).to_string();
+                    let Some((head, body)) = text.split_once("\r\n\r\n") else {
+                        continue;
+                    };
+                    let length = head
+                        .lines()
+                        .find_map(|l| {
+                            l.to_ascii_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if body.len() >= length {
+                        break body.to_string();
+                    }
+                };
+                bodies.push(body);
+                let response = format!(
+                    "HTTP/1.1 {status} X\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+            bodies
+        });
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_until_success() {
+        let (url, server) = stand_in(vec![500, 503, 200]).await;
+        let client = Client::new();
+        send_with_retry(
+            &client,
+            &url,
+            "{\"a\":1}".to_string(),
+            Duration::from_millis(1),
+        )
+        .await
+        .unwrap();
+        assert_eq!(server.await.unwrap(), vec!["{\"a\":1}"; 3]);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_client_errors() {
+        let (url, server) = stand_in(vec![404]).await;
+        let client = Client::new();
+        let result =
+            send_with_retry(&client, &url, "{}".to_string(), Duration::from_millis(1)).await;
+        assert!(result.is_err());
+        assert_eq!(server.await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_attempts() {
+        let (url, server) = stand_in(vec![500; MAX_ATTEMPTS as usize]).await;
+        let client = Client::new();
+        let result =
+            send_with_retry(&client, &url, "{}".to_string(), Duration::from_millis(1)).await;
+        assert!(result.is_err());
+        assert_eq!(server.await.unwrap().len(), MAX_ATTEMPTS as usize);
+    }
+}
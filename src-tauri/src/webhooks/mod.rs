@@ -0,0 +1,143 @@
+//! Outgoing webhooks: a generic JSON POST per notable event - a card drop, a `CompletedFarm`/
+//! `CompletedUnlock` entry being recorded, a granted free-game claim, an agent session dropping -
+//! to whichever endpoints the account has subscribed to that event kind (`settings`). Each endpoint
+//! can carry a JSON body template (`delivery::render`), which is all Discord (`{"content": ...}`)
+//! and Slack (`{"text": ...}`) incoming webhooks need - no per-service integration code.
+//!
+//! **Fire-and-forget from the caller's side** - [`notify`] spawns the whole load-settings/render/
+//! deliver/retry sequence and returns immediately, so the farming/unlocker loops that call it never
+//! wait on a slow or dead endpoint. The flip side is that there's no delivery result to hand back
+//! to anyone: failures (after `delivery`'s retries) only show up in the log, which is what the
+//! Settings page's "Send test" button (`commands::send_test_webhook`) exists to make up for.
+
+pub mod commands;
+mod delivery;
+pub mod settings;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::achievement_unlocker::CompletedUnlock;
+use crate::card_farming::CompletedFarm;
+
+use settings::WebhookEndpoint;
+
+/// What an endpoint subscribes to - one per [`WebhookEvent`] variant except `Test`, which is only
+/// ever sent on demand to the one endpoint being tested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookEventKind {
+    CardDropped,
+    FarmCompleted,
+    UnlockCompleted,
+    FreeGameClaimed,
+    AgentSessionDropped,
+}
+
+/// One event's payload - serialized (tagged by `event`) as the default request body, and flattened
+/// into template placeholders otherwise.
+#[derive(Debug, Clone, Serialize)]
+#[serde(
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase",
+    tag = "event"
+)]
+pub enum WebhookEvent {
+    /// A farmed game's remaining drop count went down between two polls - `dropped` is by how much
+    /// (usually 1, more if several dropped within one poll interval).
+    CardDropped {
+        app_id: u32,
+        name: String,
+        dropped: u32,
+        remaining: u32,
+    },
+    FarmCompleted(CompletedFarm),
+    UnlockCompleted(CompletedUnlock),
+    FreeGameClaimed {
+        app_id: u32,
+    },
+    /// `reason` is the daemon's `status_changed` result (e.g. `"LoggedInElsewhere"`).
+    AgentSessionDropped {
+        account: String,
+        reason: String,
+    },
+    Test,
+}
+
+impl WebhookEvent {
+    fn kind(&self) -> Option<WebhookEventKind> {
+        match self {
+            WebhookEvent::CardDropped { .. } => Some(WebhookEventKind::CardDropped),
+            WebhookEvent::FarmCompleted(_) => Some(WebhookEventKind::FarmCompleted),
+            WebhookEvent::UnlockCompleted(_) => Some(WebhookEventKind::UnlockCompleted),
+            WebhookEvent::FreeGameClaimed { .. } => Some(WebhookEventKind::FreeGameClaimed),
+            WebhookEvent::AgentSessionDropped { .. } => Some(WebhookEventKind::AgentSessionDropped),
+            WebhookEvent::Test => None,
+        }
+    }
+
+    /// One human-readable line - the `{{message}}` placeholder, i.e. what a Discord/Slack template
+    /// actually shows.
+    fn message(&self) -> String {
+        match self {
+            WebhookEvent::CardDropped {
+                name,
+                dropped,
+                remaining,
+                ..
+            } => {
+                let cards = if *dropped == 1 { "card" } else { "cards" };
+                format!("{name}: {dropped} {cards} dropped, {remaining} remaining")
+            }
+            WebhookEvent::FarmCompleted(farm) => {
+                format!("{}: card farming finished ({:?})", farm.name, farm.reason)
+            }
+            WebhookEvent::UnlockCompleted(unlock) => format!(
+                "{}: {} of {} achievements unlocked ({:?})",
+                unlock.name, unlock.unlocked, unlock.total, unlock.reason
+            ),
+            WebhookEvent::FreeGameClaimed { app_id } => {
+                format!("Claimed free game {app_id}")
+            }
+            WebhookEvent::AgentSessionDropped { account, reason } => {
+                format!("Steam session for {account} dropped ({reason})")
+            }
+            WebhookEvent::Test => "Test notification from Steam Game Idler".to_string(),
+        }
+    }
+}
+
+/// Sends `event` to every enabled endpoint `steam_id` has subscribed to its kind - in the
+/// background, see this module's doc comment. A no-op (no task spawned) for an account with no
+/// webhooks configured, which is every account until the user adds one.
+pub fn notify(app_handle: &AppHandle, steam_id: &str, event: WebhookEvent) {
+    let Some(kind) = event.kind() else {
+        return;
+    };
+    let app_handle = app_handle.clone();
+    let steam_id = steam_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        let endpoints: Vec<WebhookEndpoint> = match settings::get(&app_handle, &steam_id).await {
+            Ok(settings) => settings
+                .endpoints
+                .into_iter()
+                .filter(|e| e.enabled && e.events.contains(&kind))
+                .collect(),
+            Err(e) => {
+                tracing::warn!(steam_id, error = %e, "webhooks: failed to read settings");
+                return;
+            }
+        };
+        for endpoint in endpoints {
+            if let Err(e) = delivery::deliver(&endpoint, &steam_id, &event).await {
+                tracing::warn!(
+                    steam_id,
+                    kind = ?kind,
+                    url = %delivery::redact_url(&endpoint.url),
+                    error = %e,
+                    "webhooks: delivery failed"
+                );
+            }
+        }
+    });
+}
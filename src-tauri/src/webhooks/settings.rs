@@ -0,0 +1,112 @@
+//! Per-account webhook endpoints - own file in the per-SteamID64 directory, whole-struct get/set,
+//! same pattern as `card_farming::settings`. Per account rather than app-wide since the events
+//! themselves are (one account's farm finishing shouldn't ping a channel set up for another's).
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tokio::sync::Mutex;
+
+use crate::error::{AppError, AppResult};
+use crate::fs_utils::atomic_write_json;
+use crate::platform;
+
+use super::WebhookEventKind;
+
+const SETTINGS_FILE_NAME: &str = "webhook_settings.json";
+
+static WRITE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSettings {
+    #[serde(default)]
+    pub endpoints: Vec<WebhookEndpoint>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookEndpoint {
+    /// Full URL, secret path included (Discord/Slack webhook URLs are themselves the credential) -
+    /// never logged as-is, see `delivery::redact_url`.
+    pub url: String,
+    /// Which event kinds this endpoint receives - empty means none, not all.
+    #[serde(default)]
+    pub events: Vec<WebhookEventKind>,
+    /// JSON body template with `{{placeholder}}`s (see `delivery::render`), or `None` for the
+    /// event's own default JSON shape.
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Lets an endpoint be paused without losing its URL/template. Defaults `true`.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn settings_file_path(app_handle: &AppHandle, steam_id: &str) -> AppResult<PathBuf> {
+    Ok(platform::cache_dir(app_handle)?
+        .join(steam_id)
+        .join(SETTINGS_FILE_NAME))
+}
+
+/// Same self-heal-to-defaults-on-unparseable-file behavior as every other per-account settings
+/// module's `read_unlocked` (see `card_farming::settings::read_unlocked`'s comment).
+fn read_unlocked(app_handle: &AppHandle, steam_id: &str) -> AppResult<WebhookSettings> {
+    let path = settings_file_path(app_handle, steam_id)?;
+    if !path.exists() {
+        return Ok(WebhookSettings::default());
+    }
+
+    let contents =
+        fs::read_to_string(&path).map_err(|e| AppError::WebhookSettingsIo(e.to_string()))?;
+    if contents.trim().is_empty() {
+        return Ok(WebhookSettings::default());
+    }
+
+    match serde_json::from_str::<WebhookSettings>(&contents) {
+        Ok(settings) => Ok(settings),
+        Err(_) => {
+            tracing::warn!(
+                steam_id,
+                "webhooks: webhook_settings.json failed to parse, resetting to defaults"
+            );
+            let defaults = WebhookSettings::default();
+            write_unlocked(app_handle, steam_id, &defaults)?;
+            Ok(defaults)
+        }
+    }
+}
+
+fn write_unlocked(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    settings: &WebhookSettings,
+) -> AppResult<()> {
+    let path = settings_file_path(app_handle, steam_id)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::WebhookSettingsIo(e.to_string()))?;
+    }
+    atomic_write_json(&path, settings).map_err(|e| AppError::WebhookSettingsIo(e.to_string()))
+}
+
+pub async fn get(app_handle: &AppHandle, steam_id: &str) -> AppResult<WebhookSettings> {
+    let _guard = WRITE_LOCK.lock().await;
+    read_unlocked(app_handle, steam_id)
+}
+
+/// Whole-struct replace - see `achievement_unlocker::settings::set`'s doc comment for why.
+pub async fn set(
+    app_handle: &AppHandle,
+    steam_id: &str,
+    settings: WebhookSettings,
+) -> AppResult<WebhookSettings> {
+    let _guard = WRITE_LOCK.lock().await;
+    write_unlocked(app_handle, steam_id, &settings)?;
+    Ok(settings)
+}
@@ -21,6 +21,7 @@ interface AchievementManagerHeaderProps {
   query: string
   onQueryChange: (query: string) => void
   hasProtectedItems: boolean
+  staleSinceMs?: number
   onClose: () => void
 }
 
@@ -37,6 +38,7 @@ export const AchievementManagerHeader = ({
   query,
   onQueryChange,
   hasProtectedItems,
+  staleSinceMs,
   onClose,
 }: AchievementManagerHeaderProps) => {
   const { t } = useTranslation()
@@ -126,6 +128,20 @@ export const AchievementManagerHeader = ({
         </InputGroup>
       </div>
 
+      {staleSinceMs !== undefined && (
+        <Alert status='warning'>
+          <Alert.Indicator />
+          <Alert.Content>
+            <Alert.Title>{t('dashboard.achievements.staleAlert.title')}</Alert.Title>
+            <Alert.Description>
+              {t('dashboard.achievements.staleAlert.description', {
+                date: new Date(staleSinceMs).toLocaleString(),
+              })}
+            </Alert.Description>
+          </Alert.Content>
+        </Alert>
+      )}
+
       {hasProtectedItems && (
         <Alert status='warning'>
           <Alert.Indicator />
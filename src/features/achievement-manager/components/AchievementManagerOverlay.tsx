@@ -95,6 +95,7 @@ export const AchievementManagerOverlay = () => {
                   hasProtectedItems={hasProtectedItems}
                   name={openGame.name}
                   query={query}
+                  staleSinceMs={data?.staleSinceMs}
                   onClose={() => handleOpenChange(false)}
                   onQueryChange={setQuery}
                 />
@@ -1,4 +1,4 @@
-﻿import type { AchievementData, BulkAchievementResult } from '../types'
+﻿import type { AchievementDataResponse, BulkAchievementResult } from '../types'
 import { useCallback, useEffect, useState } from 'react'
 import { useTranslation } from 'react-i18next'
 import { errorMessageKey } from '../utils/errorMessageKey'
@@ -23,6 +23,10 @@ interface OpenGame {
 // result instead of refetching - the command already tells us exactly what changed.
 // `reset_all_stats` is the one action that refetches, since it doesn't report the new values.
 //
+// `data.staleSinceMs` is set when the backend couldn't fetch live and served its offline copy
+// instead (Steam closed, daemon unreachable) - the overlay shows it as a banner rather than an
+// error, since the data is still worth browsing.
+//
 // `loadErrorCode` only ever comes from `load()` - it's what AchievementManagerOverlay uses to
 // replace the whole tab with a blocking error state. Mutation failures (toggle/bulk-set/
 // save-stats/reset-stats) fire a toast instead of touching this state, so a single failed toggle
@@ -31,7 +35,7 @@ export const useAchievementManager = (openGame: OpenGame | null) => {
   const { t, i18n } = useTranslation()
   const account = useSessionStore(state => state.account)
   const currentOs = usePlatformStore(state => state.currentOs)
-  const [data, setData] = useState<AchievementData | null>(null)
+  const [data, setData] = useState<AchievementDataResponse | null>(null)
   const [isLoading, setIsLoading] = useState(false)
   const [isMutating, setIsMutating] = useState(false)
   const [loadErrorCode, setLoadErrorCode] = useState<string | null>(null)
@@ -42,7 +46,7 @@ export const useAchievementManager = (openGame: OpenGame | null) => {
     setLoadErrorCode(null)
     try {
       setData(
-        await invoke<AchievementData>('get_achievement_data', {
+        await invoke<AchievementDataResponse>('get_achievement_data', {
           account,
           appId: openGame.appId,
           locale: i18n.language,
@@ -119,7 +123,8 @@ export const useAchievementManager = (openGame: OpenGame | null) => {
   // There's no single backend command for an arbitrary unlock+lock mix (unlike Unlock/Lock all's
   // `unlock_all_achievements`/`lock_all_achievements`), so this replays `set_achievement` per id -
   // a per-item failure is logged and the loop continues, never aborting the rest of the staged
-  // batch.
+  // batch. The automatic snapshot the bulk backend commands take for themselves is requested
+  // explicitly first (see src-tauri/src/achievements/snapshots.rs) - if it fails, nothing is applied.
   const applyStagedChanges = useCallback(
     async (unlockIds: string[], lockIds: string[]) => {
       if (!account || !openGame) return
@@ -127,6 +132,17 @@ export const useAchievementManager = (openGame: OpenGame | null) => {
       const unlockedIds = new Set<string>()
       const lockedIds = new Set<string>()
       try {
+        try {
+          await invoke('take_achievement_snapshot', {
+            account,
+            appId: openGame.appId,
+            reason: 'beforeBulkEdit',
+          })
+        } catch (error) {
+          console.error('Error in (take_achievement_snapshot) during applyStagedChanges:', error)
+          toastActionError(error)
+          return null
+        }
         for (const achievementId of unlockIds) {
           try {
             await invoke('set_achievement', {
@@ -187,7 +203,7 @@ export const useAchievementManager = (openGame: OpenGame | null) => {
         setIsMutating(false)
       }
     },
-    [account, openGame, t],
+    [account, openGame, t, toastActionError],
   )
 
   const bulkSetAchievements = useCallback(
@@ -249,6 +265,14 @@ export const useAchievementManager = (openGame: OpenGame | null) => {
       if (stats.length === 0) return true
       setIsMutating(true)
       try {
+        // `update_stats` takes no snapshot of its own (see its doc comment) - same explicit
+        // `beforeBulkEdit` request as `applyStagedChanges`, and the same "nothing saved if it
+        // fails" rule, via the catch below.
+        await invoke('take_achievement_snapshot', {
+          account,
+          appId: openGame.appId,
+          reason: 'beforeBulkEdit',
+        })
         await invoke('update_stats', { account, appId: openGame.appId, stats })
         setData(
           prev =>
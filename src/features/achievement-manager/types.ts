@@ -15,6 +15,15 @@ export interface AchievementDto {
   percent?: number
   protectedAchievement: boolean
   flags: string
+  // Only on progress-based achievements - the stat threshold the unlocker walks up to (see
+  // AchievementProgress in src-tauri/src/achievements/mod.rs). Omitted from the wire otherwise.
+  progress?: AchievementProgress
+}
+
+export interface AchievementProgress {
+  statId: string
+  minValue: number
+  maxValue: number
 }
 
 // `value` is a raw JSON number on the wire (int or float depending on `statType`) - typed as
@@ -37,6 +46,15 @@ export interface AchievementData {
   stats: StatDto[]
 }
 
+// Mirrors src-tauri/src/achievements/data_cache.rs::AchievementDataResponse - what
+// get_achievement_data actually returns. `staleSinceMs`/`staleReason` are only set when the live
+// fetch failed and this is the last cached copy; `staleReason` is the failed fetch's AppError code.
+export interface AchievementDataResponse extends AchievementData {
+  fetchedAtMs: number
+  staleSinceMs?: number
+  staleReason?: string
+}
+
 // `name` must be a `StatDto.id`, not its display `name` - see StatUpdate's doc comment in
 // src-tauri/src/achievements/mod.rs for the full explanation (confirmed live: the display name
 // fails with `stat_not_found`).
@@ -50,3 +68,108 @@ export interface BulkAchievementResult {
   skipped: string[]
   failed: string[]
 }
+
+// Mirrors src-tauri/src/achievements/snapshots.rs::SnapshotReason.
+export type SnapshotReason =
+  | 'manual'
+  | 'beforeUnlockAll'
+  | 'beforeLockAll'
+  | 'beforeUpdateStats'
+  | 'beforeResetStats'
+  | 'beforeBulkEdit'
+  | 'beforeRestore'
+
+// Mirrors src-tauri/src/achievements/snapshots.rs::SnapshotSummary - what
+// list_achievement_snapshots returns, newest first.
+export interface SnapshotSummary {
+  id: string
+  appId: number
+  takenAtMs: number
+  reason: SnapshotReason
+  label?: string
+  batchId?: string
+  achievedCount: number
+  achievementCount: number
+  statCount: number
+}
+
+// Mirrors src-tauri/src/achievements/snapshots.rs::SnapshotFailure.
+export interface SnapshotFailure {
+  appId: number
+  error: string
+}
+
+// Mirrors src-tauri/src/achievements/snapshots.rs::SelectionSnapshotResult.
+export interface SelectionSnapshotResult {
+  batchId: string
+  taken: SnapshotSummary[]
+  failed: SnapshotFailure[]
+}
+
+// Mirrors src-tauri/src/achievements/snapshots.rs::RestoreResult. `undoSnapshotId` is the
+// snapshot of the state the restore replaced; `error` is set only when the whole game failed.
+export interface RestoreResult {
+  appId: number
+  undoSnapshotId?: string
+  unlocked: string[]
+  locked: string[]
+  statsUpdated: string[]
+  skipped: string[]
+  failed: string[]
+  error?: string
+}
+
+// Mirrors src-tauri/src/achievements/library.rs::GameCompletion. `completion` is 0-100.
+export interface GameCompletion {
+  appId: number
+  name: string | null
+  unlocked: number
+  total: number
+  completion: number
+}
+
+// Mirrors src-tauri/src/achievements/library.rs::RareAchievement.
+export interface RareAchievement {
+  appId: number
+  gameName: string | null
+  id: string
+  name: string
+  iconNormal: string
+  percent: number
+}
+
+// Mirrors src-tauri/src/achievements/library.rs::LibraryStats. `averageCompletion` only counts
+// games with at least one achievement unlocked, like Steam's own profile stat.
+export interface LibraryStats {
+  games: GameCompletion[]
+  perfectGames: GameCompletion[]
+  averageCompletion: number | null
+  achievementsUnlocked: number
+  rarest: RareAchievement[]
+  lastScannedAtMs?: number
+}
+
+// Mirrors src-tauri/src/achievements/library.rs::LibraryScanFailure. `reason` is an AppError code.
+export interface LibraryScanFailure {
+  appId: number
+  name: string | null
+  reason: string
+}
+
+// Mirrors src-tauri/src/achievements/library.rs::LibraryScanResult.
+export interface LibraryScanResult {
+  scanned: number
+  unchanged: number
+  failed: LibraryScanFailure[]
+  stats: LibraryStats
+}
+
+// Payload of the `achievement-library-scan-progress` event -
+// src-tauri/src/achievements/library.rs::ACHIEVEMENT_LIBRARY_SCAN_EVENT.
+export interface LibraryScanProgressEvent {
+  steamId: string
+  progress: {
+    checked: number
+    total: number
+  }
+}
@@ -0,0 +1,113 @@
+import type { AchievementUnlockerSettings } from '../types'
+import { useState } from 'react'
+import { useTranslation } from 'react-i18next'
+import { Alert, Button, Modal, Typography } from '@heroui/react'
+import { errorMessageKey } from '../utils/errorMessageKey'
+import { resolveMaxConcurrentGames } from '../utils/resolveMaxConcurrentGames'
+import { useAchievementUnlockerResumeStore } from '@/shared/stores/achievementUnlockerResumeStore'
+import { useSessionStore } from '@/shared/stores/sessionStore'
+import { useSubscriptionStore } from '@/shared/stores/subscriptionStore'
+import { invoke } from '@/shared/utils/invoke'
+
+type ResumeAction = 'resume' | 'discard'
+
+// Rendered once in DashboardShell, driven entirely by achievementUnlockerResumeStore (opened by
+// useAchievementUnlockerResume when `autoResume` is off). Resolves the account from sessionStore
+// by the offer's key rather than using whichever account is active now, so answering after an
+// account switch still resumes/discards the run it was actually offered for. Closing without
+// answering leaves the checkpoint on disk - it's offered again on the next launch.
+export const AchievementUnlockerResumeModal = () => {
+  const { t } = useTranslation()
+  const offer = useAchievementUnlockerResumeStore(state => state.offer)
+  const close = useAchievementUnlockerResumeStore(state => state.close)
+  const account = useSessionStore(state => (offer ? state.accounts[offer.accountKey] : undefined))
+  const subscriptionTier = useSubscriptionStore(state => state.subscriptionTier)
+  const [pending, setPending] = useState<ResumeAction | null>(null)
+  const [errorCode, setErrorCode] = useState<string | null>(null)
+
+  const handleClose = () => {
+    setErrorCode(null)
+    close()
+  }
+
+  const run = async (action: ResumeAction) => {
+    if (!account) return
+    setPending(action)
+    setErrorCode(null)
+    try {
+      if (action === 'resume') {
+        const settings = await invoke<AchievementUnlockerSettings>(
+          'get_achievement_unlocker_settings',
+          { account },
+        )
+        const maxConcurrentGames = resolveMaxConcurrentGames(
+          subscriptionTier,
+          settings.multipleGames,
+        )
+        await invoke('resume_achievement_unlocker', { account, maxConcurrentGames })
+      } else {
+        await invoke('discard_achievement_unlocker_checkpoint', { account })
+      }
+      handleClose()
+    } catch (error) {
+      console.error(`Error in (${action}_achievement_unlocker checkpoint):`, error)
+      setErrorCode(String(error))
+    } finally {
+      setPending(null)
+    }
+  }
+
+  return (
+    <Modal
+      isOpen={Boolean(offer) && Boolean(account)}
+      onOpenChange={open => !open && handleClose()}
+    >
+      <Modal.Backdrop>
+        <Modal.Container>
+          <Modal.Dialog>
+            <Modal.Header>
+              <Modal.Heading>{t('dashboard.achievementUnlocker.resume.title')}</Modal.Heading>
+              <Modal.CloseTrigger />
+            </Modal.Header>
+            <Modal.Body>
+              <div className='flex flex-col gap-3'>
+                <Typography color='muted' type='body-sm'>
+                  {t('dashboard.achievementUnlocker.resume.description', {
+                    date: new Date(offer?.checkpoint.savedAtMs ?? 0).toLocaleString(),
+                  })}
+                </Typography>
+                {errorCode ? (
+                  <Alert className='w-full' status='danger'>
+                    <Alert.Indicator />
+                    <Alert.Content>
+                      <Alert.Description>
+                        {t(errorMessageKey(errorCode), { code: errorCode })}
+                      </Alert.Description>
+                    </Alert.Content>
+                  </Alert>
+                ) : null}
+              </div>
+            </Modal.Body>
+            <Modal.Footer>
+              <Button
+                isDisabled={pending === 'resume'}
+                isPending={pending === 'discard'}
+                variant='secondary'
+                onPress={() => void run('discard')}
+              >
+                {t('dashboard.achievementUnlocker.resume.discard')}
+              </Button>
+              <Button
+                isDisabled={pending === 'discard'}
+                isPending={pending === 'resume'}
+                onPress={() => void run('resume')}
+              >
+                {t('dashboard.achievementUnlocker.resume.resume')}
+              </Button>
+            </Modal.Footer>
+          </Modal.Dialog>
+        </Modal.Container>
+      </Modal.Backdrop>
+    </Modal>
+  )
+}
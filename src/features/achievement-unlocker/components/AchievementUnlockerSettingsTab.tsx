@@ -169,6 +169,17 @@ export const AchievementUnlockerSettingsTab = ({
           onChange={value => commit({ ...draft, hidden: value })}
         />
       </SettingsRow>
+
+      <SettingsRow
+        description={t('dashboard.achievementUnlocker.settings.autoResume.description')}
+        showDivider={false}
+        title={t('dashboard.achievementUnlocker.settings.autoResume.title')}
+      >
+        <ToggleSwitch
+          isSelected={draft.autoResume}
+          onChange={value => commit({ ...draft, autoResume: value })}
+        />
+      </SettingsRow>
       <Separator className='border-t border-border my-2' />
 
       <SettingsRow
@@ -0,0 +1,72 @@
+import type { AchievementUnlockerSettings, SessionCheckpoint } from '../types'
+import { useEffect } from 'react'
+import { resolveMaxConcurrentGames } from '../utils/resolveMaxConcurrentGames'
+import { useAchievementUnlockerResumeStore } from '@/shared/stores/achievementUnlockerResumeStore'
+import { getAccountKey, useSessionStore } from '@/shared/stores/sessionStore'
+import { useSubscriptionStore } from '@/shared/stores/subscriptionStore'
+import { logFrontendInfo } from '@/shared/utils/frontendLogging'
+import { invoke } from '@/shared/utils/invoke'
+
+// Accounts already checked this app session - module-level, not a ref, so an account switch back
+// and forth doesn't re-offer a checkpoint the user already declined to answer.
+const checkedAccountKeys = new Set<string>()
+
+// Picks up an achievement-unlocker run that an app exit, crash, sign-out or Steam closing
+// interrupted (see `achievement_unlocker::checkpoint`'s module doc comment). Mounted once from
+// `DashboardShell`; runs once per account per app session, as soon as that account is the active
+// one. `get_achievement_unlocker_checkpoint` already returns nothing while a session is running,
+// so a detached-agent reattach that restored its own run is never offered a second time.
+//
+// With `autoResume` on, the run resumes straight away through `resume_achievement_unlocker`, with
+// `maxConcurrentGames` resolved the same way `useAchievementUnlockerRun.start` does; otherwise
+// `AchievementUnlockerResumeModal` asks first. A CLI-mode account only counts as ready once Steam
+// is running (`resume_achievement_unlocker` requires it) - if it isn't, the account is left
+// unchecked and the saved checkpoint simply waits for the next activation or launch.
+export const useAchievementUnlockerResume = () => {
+  const account = useSessionStore(state => state.account)
+
+  useEffect(() => {
+    if (!account) return
+    const key = getAccountKey(account)
+    if (checkedAccountKeys.has(key)) return
+    let cancelled = false
+
+    const check = async () => {
+      try {
+        if (account.mode === 'local' && !(await invoke<boolean>('is_steam_running'))) return
+        if (cancelled) return
+        checkedAccountKeys.add(key)
+
+        const checkpoint = await invoke<SessionCheckpoint | null>(
+          'get_achievement_unlocker_checkpoint',
+          { account },
+        )
+        if (!checkpoint || cancelled) return
+
+        const settings = await invoke<AchievementUnlockerSettings>(
+          'get_achievement_unlocker_settings',
+          { account },
+        )
+        if (!settings.autoResume) {
+          useAchievementUnlockerResumeStore.getState().open(key, checkpoint)
+          return
+        }
+
+        const subscriptionTier = useSubscriptionStore.getState().subscriptionTier
+        const maxConcurrentGames = resolveMaxConcurrentGames(
+          subscriptionTier,
+          settings.multipleGames,
+        )
+        await invoke('resume_achievement_unlocker', { account, maxConcurrentGames })
+        logFrontendInfo('useAchievementUnlockerResume', 'auto-resumed achievement unlocker')
+      } catch (error) {
+        console.error('Error in (useAchievementUnlockerResume check):', error)
+      }
+    }
+
+    check()
+    return () => {
+      cancelled = true
+    }
+  }, [account])
+}
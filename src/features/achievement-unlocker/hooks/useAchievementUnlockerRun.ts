@@ -61,7 +61,8 @@ export const useAchievementUnlockerRun = () => {
     setIsStopping(true)
     setErrorCode(null)
     try {
-      await invoke('stop_achievement_unlocker', { account })
+      // A deliberate stop - nothing to offer to resume afterwards.
+      await invoke('stop_achievement_unlocker', { account, discardCheckpoint: true })
     } catch (error) {
       console.error('Error in (stop_achievement_unlocker):', error)
       setErrorCode(String(error))
@@ -11,6 +11,31 @@ export interface ScheduleTime {
   minute: number
 }
 
+// Mirrors src-tauri/src/achievement_unlocker/settings.rs::ScheduleWeekday.
+export type ScheduleWeekday =
+  | 'monday'
+  | 'tuesday'
+  | 'wednesday'
+  | 'thursday'
+  | 'friday'
+  | 'saturday'
+  | 'sunday'
+
+// Mirrors src-tauri/src/achievement_unlocker/settings.rs::ScheduleWindow. Empty `weekdays` means
+// every day. `timeZone` (an IANA name like 'America/New_York') wins over `utcOffsetMinutes`, and
+// missing/null for both means the machine's local time.
+export interface ScheduleWindow {
+  from: ScheduleTime
+  to: ScheduleTime
+  weekdays: ScheduleWeekday[]
+  // Follows the zone's DST changes. The backend rejects a name it doesn't know with
+  // `achievement_unlocker_time_zone_invalid`.
+  timeZone?: string | null
+  // A fixed offset that never follows DST - a window set as -300 for New York runs an hour off the
+  // local clock all summer, so a UI offering this should warn and prefer `timeZone`.
+  utcOffsetMinutes?: number | null
+}
+
 // Mirrors src-tauri/src/achievement_unlocker/settings.rs::AchievementUnlockerSettings.
 export interface AchievementUnlockerSettings {
   hidden: boolean
@@ -20,10 +45,28 @@ export interface AchievementUnlockerSettings {
   schedule: boolean
   scheduleFrom: ScheduleTime
   scheduleTo: ScheduleTime
+  // Replaces scheduleFrom/scheduleTo once non-empty - see `achievement_unlocker::schedule`.
+  scheduleWindows: ScheduleWindow[]
   nextTaskCheckbox: boolean
   nextTask: string | null
+  // See `achievement_unlocker::natural`'s module doc comment.
+  ordering: UnlockOrdering
+  delayDistribution: DelayDistribution
+  // Delay samples (minutes) resampled by `'empirical'`.
+  empiricalDelays: number[]
+  // Resume an interrupted run without asking - see `achievement_unlocker::checkpoint`.
+  autoResume: boolean
+  // Walk progress-based achievements' stats up to their thresholds before unlocking - see
+  // `achievements::progress`.
+  statProgress: boolean
 }
 
+// Mirrors src-tauri/src/achievement_unlocker/settings.rs::UnlockOrdering.
+export type UnlockOrdering = 'percent' | 'natural'
+
+// Mirrors src-tauri/src/achievement_unlocker/settings.rs::DelayDistribution.
+export type DelayDistribution = 'uniform' | 'logNormal' | 'empirical'
+
 // Mirrors src-tauri/src/achievement_unlocker/order.rs::AchievementOrderEntry. `id` matches
 // AchievementDto::id (achievement-manager's types.ts), not the display name - see that Rust
 // struct's doc comment for why.
@@ -39,12 +82,45 @@ export interface AchievementOrder {
   delayBeforeFirstUnlock?: number
 }
 
+// Mirrors src-tauri/src/achievement_unlocker/plan_file.rs::PlanImportReport - what
+// import_achievement_order found. `saved` is false when the plan had unknown/duplicate ids and
+// wasn't forced; `missingIds` alone never blocks a save.
+export interface PlanImportReport {
+  appId: number
+  saved: boolean
+  order: AchievementOrder
+  unknownIds: string[]
+  duplicateIds: string[]
+  missingIds: string[]
+}
+
 // Mirrors src-tauri/src/achievement_unlocker/import_timings.rs::AchievementTiming.
 export interface AchievementTiming {
   id: string
   unlockTime: number
 }
 
+// Mirrors src-tauri/src/achievement_unlocker/aggregate_timings.rs::TimingSource.
+export type TimingSource =
+  | { kind: 'profiles'; profiles: string[] }
+  | { kind: 'topCompletionists'; count: number }
+
+// Mirrors src-tauri/src/achievement_unlocker/aggregate_timings.rs::AggregationMethod.
+export type AggregationMethod = 'median' | 'trimmedMean'
+
+// Mirrors src-tauri/src/achievement_unlocker/aggregate_timings.rs::SkippedProfile.
+export interface SkippedProfile {
+  profile: string
+  reason: string
+}
+
+// Mirrors src-tauri/src/achievement_unlocker/aggregate_timings.rs::AggregateTimingsReport.
+export interface AggregateTimingsReport {
+  order: AchievementOrder | null
+  usedProfiles: string[]
+  skipped: SkippedProfile[]
+}
+
 // Gamer-tier concurrency cap - mirrors src-tauri/src/achievement_unlocker/manager.rs::MAX_CONCURRENT_GAMES.
 export const MAX_CONCURRENT_GAMES = 32
 
@@ -64,6 +140,34 @@ export interface UpcomingAchievement {
   unlockAtMs: number
 }
 
+// Mirrors src-tauri/src/achievement_unlocker/simulate.rs::SimulatedOutcome.
+export type SimulatedOutcome =
+  | 'finished'
+  | 'maxUnlocksReached'
+  | 'maxPlaytime'
+  | 'nothingToUnlock'
+  | 'noAchievementData'
+  | 'scheduleNeverOpens'
+
+// Mirrors src-tauri/src/achievement_unlocker/simulate.rs::SimulatedGame.
+export interface SimulatedGame {
+  appId: number
+  name: string
+  outcome: SimulatedOutcome
+  worker?: number
+  startedAtMs?: number
+  finishedAtMs?: number
+  unlocks: UpcomingAchievement[]
+}
+
+// Mirrors src-tauri/src/achievement_unlocker/simulate.rs::UnlockSimulation.
+export interface UnlockSimulation {
+  workerCount: number
+  startedAtMs: number
+  finishedAtMs: number
+  games: SimulatedGame[]
+}
+
 // Mirrors src-tauri/src/achievement_unlocker/mod.rs::ActiveGameProgress.
 export interface ActiveGameProgress {
   appId: number
@@ -94,6 +198,28 @@ export interface CompletedUnlock {
   reason: CompletedUnlockReason
 }
 
+// Mirrors src-tauri/src/achievement_unlocker/checkpoint.rs::ScheduledUnlock.
+export interface ScheduledUnlock {
+  id: string
+  unlockAtMs: number
+}
+
+// Mirrors src-tauri/src/achievement_unlocker/checkpoint.rs::GameCheckpoint.
+export interface GameCheckpoint {
+  appId: number
+  name: string
+  schedule: ScheduledUnlock[]
+}
+
+// Mirrors src-tauri/src/achievement_unlocker/checkpoint.rs::SessionCheckpoint - what
+// get_achievement_unlocker_checkpoint offers to resume.
+export interface SessionCheckpoint {
+  savedAtMs: number
+  maxConcurrentGames: number
+  games: GameCheckpoint[]
+  completed: CompletedUnlock[]
+}
+
 // Mirrors src-tauri/src/achievement_unlocker/mod.rs::AchievementUnlockerState.
 export interface AchievementUnlockerState {
   isRunning: boolean
@@ -14,6 +14,7 @@ const KNOWN_ERROR_KEYS: Record<string, TranslationKey> = {
   achievement_unlocker_queue_cache_io_failed:
     'dashboard.achievementUnlocker.errors.queueCacheIoFailed',
   achievement_unlocker_settings_io_failed: 'dashboard.achievementUnlocker.errors.settingsIoFailed',
+  achievement_unlocker_time_zone_invalid: 'dashboard.achievementUnlocker.errors.timeZoneInvalid',
   achievement_order_io_failed: 'dashboard.achievementUnlocker.errors.orderIoFailed',
   steam_not_running: 'common.errors.steamNotRunning',
 }
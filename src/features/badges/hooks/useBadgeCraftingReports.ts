@@ -0,0 +1,40 @@
+import type { CraftReport } from '../types'
+import { listen } from '@tauri-apps/api/event'
+import { useEffect } from 'react'
+import { useTranslation } from 'react-i18next'
+import { toast } from '@heroui/react'
+
+interface BadgeCraftingFinishedPayload {
+  steamId: string
+  report: CraftReport
+}
+
+// Matches src-tauri/src/badges/mod.rs's BADGE_CRAFTING_FINISHED_EVENT constant.
+const BADGE_CRAFTING_FINISHED_EVENT = 'badge-crafting-finished'
+
+// Mounted once in DashboardShell alongside the other global sync hooks - the post-farming
+// auto-craft (card_farming::manager::run_cycle) runs after the farming session has already ended,
+// so nothing is awaiting its report but this listener. A run that found nothing to craft stays
+// silent rather than toasting after every finished farm.
+export const useBadgeCraftingReports = () => {
+  const { t } = useTranslation()
+
+  useEffect(() => {
+    const unlisten = listen<BadgeCraftingFinishedPayload>(BADGE_CRAFTING_FINISHED_EVENT, event => {
+      const { results } = event.payload.report
+      const levels = results.reduce((sum, result) => sum + result.levelsCrafted, 0)
+      const failed = results.filter(result => !result.success).length
+
+      if (levels > 0) {
+        toast.success(t('dashboard.badges.autoCraft.crafted', { count: levels }))
+      }
+      if (failed > 0) {
+        toast.warning(t('dashboard.badges.autoCraft.failed', { count: failed }))
+      }
+    })
+
+    return () => {
+      unlisten.then(fn => fn())
+    }
+  }, [t])
+}
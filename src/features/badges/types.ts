@@ -0,0 +1,30 @@
+// Mirrors src-tauri/src/badges/mod.rs's `CraftableBadge`/`CraftResult`/`CraftReport` (serde
+// `rename_all = "camelCase"`).
+export interface CraftableBadge {
+  appId: number
+  name: string
+  foil: boolean
+  level: number
+  sets: number
+  craftableLevels: number
+}
+
+export interface CraftResult {
+  badge: CraftableBadge
+  success: boolean
+  levelsCrafted: number
+  newLevel: number | null
+  message: string | null
+}
+
+export interface CraftReport {
+  dryRun: boolean
+  results: CraftResult[]
+  excluded: CraftableBadge[]
+}
+
+// Mirrors src-tauri/src/badges/settings.rs's `BadgeCraftingSettings`.
+export interface BadgeCraftingSettings {
+  autoCraftAfterFarming: boolean
+  excludedAppIds: number[]
+}
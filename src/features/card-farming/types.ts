@@ -11,6 +11,9 @@ export interface GameWithDrops {
   name: string
   remaining: number
   playtimeHours: number
+  // Market value of every remaining drop, in the account's inventory currency - null until the
+  // game's cards have been priced (see `card_farming::market_value`'s module doc comment).
+  expectedValue: number | null
 }
 
 // Mirrors `card_farming::CardFarmingWhitelistEntry` - one entry in the account's card-farming
@@ -61,6 +64,50 @@ export interface CompletedFarm {
   farmableAt: number | null
 }
 
+// Mirrors src-tauri/src/card_farming/ledger.rs::CardDropRecord.
+export interface CardDropRecord {
+  appId: number
+  name: string
+  // Unix millis of the poll that observed the drop.
+  droppedAt: number
+  count: number
+  remaining: number
+  phase: FarmingPhase
+  strategy: FarmingStrategy
+  idleMs: number
+}
+
+// Mirrors src-tauri/src/card_farming/ledger.rs::CardDropLedgerQuery - unix millis, `from`
+// inclusive and `to` exclusive.
+export interface CardDropLedgerQuery {
+  from?: number
+  to?: number
+  appId?: number
+  strategy?: FarmingStrategy
+}
+
+export type CardDropLedgerExportFormat = 'csv' | 'json'
+
+// Mirrors src-tauri/src/card_farming/eta.rs::DropRateSource - `'default'` means no drop history
+// exists for this account yet, so the estimate is a guess.
+export type DropRateSource = 'game' | 'account' | 'default'
+
+// Mirrors src-tauri/src/card_farming/eta.rs::GameEta.
+export interface GameEta {
+  appId: number
+  remaining: number
+  msPerDrop: number
+  rateSource: DropRateSource
+  // Unix millis.
+  completesAt: number
+}
+
+// Mirrors src-tauri/src/card_farming/eta.rs::FarmingEta.
+export interface FarmingEta {
+  games: GameEta[]
+  libraryCompletesAt: number | null
+}
+
 export interface FarmingState {
   isFarming: boolean
   // Which phase produced `active` - `null` only when the cycle isn't running at all.
@@ -72,6 +119,8 @@ export interface FarmingState {
   // `FarmingState::session_expired`'s doc comment. A hard stop, distinct from the cycle just
   // finishing normally.
   sessionExpired: boolean
+  // Completion estimates, recomputed once per cycle iteration - `null` while nothing is running.
+  eta: FarmingEta | null
 }
 
 export const DEFAULT_FARMING_STATE: FarmingState = {
@@ -81,13 +130,13 @@ export const DEFAULT_FARMING_STATE: FarmingState = {
   queue: [],
   completed: [],
   sessionExpired: false,
+  eta: null,
 }
 
 // Mirrors src-tauri/src/card_farming/settings.rs::CardFarmingSettings. Blacklisting/whitelisting
 // each live in their own list (own file, own commands) - see those Rust structs' doc comments for
-// why they're not fields here. Ordering is never user-configurable - the app always picks
-// automatically (fewest drops remaining first while farming, closest to `hoursUntilFarmable` first
-// while accumulating playtime).
+// why they're not fields here. Farming order is `priority` (fewest drops remaining first by
+// default); accumulating playtime is always closest to `hoursUntilFarmable` first.
 export interface CardFarmingSettings {
   skipNoPlaytime: boolean
   farmUnplayedOnly: boolean
@@ -107,4 +156,27 @@ export interface CardFarmingSettings {
   allowMultiGameFarming: boolean
   // Hours of playtime a game needs before its card drops are considered reachable.
   hoursUntilFarmable: number
+  // See `card_farming::strategy`'s module doc comment.
+  strategy: FarmingStrategy
+  // Only used while `strategy` is `'custom'`.
+  customStrategy: CustomFarmingStrategy
+  priority: FarmingPriority
+  // How long a game's card prices stay cached before `'marketValue'` priority re-prices it.
+  priceCacheHours: number
+}
+
+// Mirrors src-tauri/src/card_farming/settings.rs::FarmingPriority.
+export type FarmingPriority = 'fewestDrops' | 'marketValue'
+
+// Mirrors src-tauri/src/card_farming/strategy.rs::FarmingStrategy.
+export type FarmingStrategy = 'twoPhase' | 'simple' | 'fastRestart' | 'custom'
+
+// Mirrors src-tauri/src/card_farming/strategy.rs::CustomStrategy. The main window is floored at
+// 60s and the pause/sweep capped at 60s on the Rust side.
+export interface CustomFarmingStrategy {
+  mainWindowSecs: number
+  restart: boolean
+  pauseSecs: number
+  sweepSecsPerGame: number
+  bulkIdle: boolean
 }
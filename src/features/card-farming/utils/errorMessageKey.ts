@@ -22,6 +22,7 @@ const KNOWN_ERROR_KEYS: Record<string, TranslationKey> = {
   // "check your cookies" (see AppError::SteamCommunitySessionExpired's doc comment).
   steam_community_session_expired: 'dashboard.cardFarming.errors.sessionExpired',
   card_farming_scrape_failed: 'dashboard.cardFarming.errors.scrapeFailed',
+  card_farming_scraper_drift: 'dashboard.cardFarming.errors.scraperDrift',
 }
 
 // A separate typed constant, not an inline literal - an explicit return-type annotation on an
@@ -6,4 +6,6 @@ export interface OwnedGame {
   name: string | null
   playtimeForeverMinutes: number
   rtimeLastPlayed: number
+  // CLI mode only, and only for apps the local Steam client has cached - see that field's doc comment.
+  appType?: string | null
 }
@@ -22,7 +22,7 @@ function stopOwnerCommand(owner: IdleOwner, account: SignedInAccount) {
     case 'card_farming':
       return { command: 'stop_farming', params: { account } }
     case 'achievement_unlocker':
-      return { command: 'stop_achievement_unlocker', params: { account } }
+      return { command: 'stop_achievement_unlocker', params: { account, discardCheckpoint: true } }
     case 'manual':
     case 'auto_idle':
       return { command: 'stop_owner_idling', params: { account, owner } }
@@ -18,6 +18,16 @@ export interface Settings {
   disableTooltips: boolean
   showRecommendedCarousel: boolean
   showRecentCarousel: boolean
+  keepAgentRunning: boolean
+  remoteApiEnabled: boolean
+  remoteApiPort: number
+}
+
+// Mirrors src-tauri/src/remote_api/mod.rs::RemoteApiStatus.
+export interface RemoteApiStatus {
+  running: boolean
+  port: number | null
+  token: string
 }
 
 // Mirrors src-tauri/src/debug/commands.rs::SystemInfo.
@@ -69,3 +79,25 @@ export interface PresenceSettings {
 export interface OwnershipSettings {
   gamesOnly: boolean
 }
+
+// Mirrors src-tauri/src/webhooks/mod.rs's `WebhookEventKind` (serde `rename_all = "camelCase"`).
+export type WebhookEventKind =
+  | 'cardDropped'
+  | 'farmCompleted'
+  | 'unlockCompleted'
+  | 'freeGameClaimed'
+  | 'agentSessionDropped'
+
+// Mirrors src-tauri/src/webhooks/settings.rs's `WebhookEndpoint` (serde `rename_all = "camelCase"`).
+export interface WebhookEndpoint {
+  url: string
+  events: WebhookEventKind[]
+  template: string | null
+  enabled: boolean
+}
+
+// Mirrors src-tauri/src/webhooks/settings.rs's `WebhookSettings`. Per account - see that module's
+// doc comment.
+export interface WebhookSettings {
+  endpoints: WebhookEndpoint[]
+}
@@ -0,0 +1,24 @@
+// Mirrors `SteamCollection` in src-tauri/src/local_steam/collections.rs (serde
+// `rename_all = "camelCase"`). `name` is empty for the client's built-in collections
+// (`id` `"favorite"`/`"hidden"`) - label those locally.
+export interface SteamCollection {
+  id: string
+  name: string
+  appIds: number[]
+  dynamic: boolean
+}
+
+// Mirrors src-tauri/src/steam_collections/mod.rs's `CollectionImportTarget`.
+export type CollectionImportTarget =
+  | 'favorites'
+  | 'autoIdle'
+  | 'cardFarmingWhitelist'
+  | 'cardFarmingBlacklist'
+  | 'achievementUnlocker'
+
+// Mirrors src-tauri/src/steam_collections/mod.rs's `CollectionImportReport`.
+export interface CollectionImportReport {
+  added: number[]
+  alreadyPresent: number[]
+  notOwned: number[]
+}
@@ -5,8 +5,11 @@ import { ReauthModal } from '@/features/account-switcher/components/ReauthModal'
 import { useAgentAccountCapEnforcement } from '@/features/account-switcher/hooks/useAgentAccountCapEnforcement'
 import { AchievementManagerOverlay } from '@/features/achievement-manager/components/AchievementManagerOverlay'
 import { AchievementOrderOverlay } from '@/features/achievement-unlocker/components/AchievementOrderOverlay'
+import { AchievementUnlockerResumeModal } from '@/features/achievement-unlocker/components/AchievementUnlockerResumeModal'
+import { useAchievementUnlockerResume } from '@/features/achievement-unlocker/hooks/useAchievementUnlockerResume'
 import { useAchievementUnlockerSync } from '@/features/achievement-unlocker/hooks/useAchievementUnlockerSync'
 import { useAutoIdleStartup } from '@/features/auto-idle/hooks/useAutoIdleStartup'
+import { useBadgeCraftingReports } from '@/features/badges/hooks/useBadgeCraftingReports'
 import { useAutoFarmCards } from '@/features/card-farming/hooks/useAutoFarmCards'
 import { useCardFarmingSync } from '@/features/card-farming/hooks/useCardFarmingSync'
 import { useFreeGameClaimCorrections } from '@/features/free-games/hooks/useFreeGameClaimCorrections'
@@ -51,6 +54,7 @@ export const DashboardShell = ({ children }: DashboardShellProps) => {
   useCardFarmingSync()
   useAutoFarmCards()
   useAchievementUnlockerSync()
+  useAchievementUnlockerResume()
   useAutoIdleStartup()
   useSubscriptionCacheSync()
   useCheckSubscription()
@@ -65,6 +69,7 @@ export const DashboardShell = ({ children }: DashboardShellProps) => {
   useAutoUpdateGamesListStatus()
   useFreeGamesWatcher()
   useFreeGameClaimCorrections()
+  useBadgeCraftingReports()
   useDisableTooltipsSync()
   useCarouselSettingsSync()
   useSteamMonitor()
@@ -87,6 +92,7 @@ export const DashboardShell = ({ children }: DashboardShellProps) => {
       <ReauthModal />
       <AchievementManagerOverlay />
       <AchievementOrderOverlay />
+      <AchievementUnlockerResumeModal />
       <GlobalSearchModal />
       <SteamWarning />
       <Banner />
@@ -0,0 +1,20 @@
+import type { SessionCheckpoint } from '@/features/achievement-unlocker/types'
+import type { AccountKey } from './sessionStore'
+import { create } from 'zustand'
+
+interface AchievementUnlockerResumeStore {
+  // The account whose interrupted run is being offered, with the checkpoint it was found with, or
+  // null when closed - keyed like reauthModalStore, since the offer belongs to one specific account
+  // even if the user switches away before answering.
+  offer: { accountKey: AccountKey; checkpoint: SessionCheckpoint } | null
+  open: (accountKey: AccountKey, checkpoint: SessionCheckpoint) => void
+  close: () => void
+}
+
+// Drives AchievementUnlockerResumeModal, opened by useAchievementUnlockerResume when a checkpoint
+// is found and `autoResume` is off.
+export const useAchievementUnlockerResumeStore = create<AchievementUnlockerResumeStore>(set => ({
+  offer: null,
+  open: (accountKey, checkpoint) => set({ offer: { accountKey, checkpoint } }),
+  close: () => set({ offer: null }),
+}))